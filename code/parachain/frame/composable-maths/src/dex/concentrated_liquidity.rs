@@ -0,0 +1,353 @@
+//! Math for concentrated liquidity pools.
+//!
+//! Prices are expressed as the amount of quote asset per base asset. The price range is split in
+//! ticks, the price at tick `i` is `1.0001^i`. Pools track the square root of the price, stored as
+//! a [`FixedU128`].
+//!
+//! From https://uniswap.org/whitepaper-v3.pdf
+
+use frame_support::ensure;
+use sp_arithmetic::{FixedPointNumber, FixedU128, Rounding};
+use sp_core::U256;
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{CheckedDiv, CheckedMul, One, Zero},
+	ArithmeticError, PerThing, Permill,
+};
+
+/// Lowest tick a position can be opened at.
+pub const MIN_TICK: i32 = -MAX_TICK;
+/// Highest tick a position can be opened at. The square root price at this tick is roughly
+/// `4.85e8`.
+pub const MAX_TICK: i32 = 400_000;
+
+/// `sqrt(1.0001)`, rounded down to the precision of [`FixedU128`].
+const SQRT_TICK_BASE: FixedU128 = FixedU128::from_inner(1_000_049_998_750_062_496);
+
+fn mul_div(a: u128, b: u128, c: u128, rounding: Rounding) -> Result<u128, ArithmeticError> {
+	ensure!(!c.is_zero(), ArithmeticError::DivisionByZero);
+	multiply_by_rational_with_rounding(a, b, c, rounding).ok_or(ArithmeticError::Overflow)
+}
+
+fn sorted(sqrt_price_a: FixedU128, sqrt_price_b: FixedU128) -> (FixedU128, FixedU128) {
+	if sqrt_price_a > sqrt_price_b {
+		(sqrt_price_b, sqrt_price_a)
+	} else {
+		(sqrt_price_a, sqrt_price_b)
+	}
+}
+
+/// Computes `sqrt(1.0001^tick)`.
+pub fn sqrt_price_at_tick(tick: i32) -> Result<FixedU128, ArithmeticError> {
+	ensure!((MIN_TICK..=MAX_TICK).contains(&tick), ArithmeticError::Overflow);
+
+	let mut exponent = tick.unsigned_abs();
+	let mut base = SQRT_TICK_BASE;
+	let mut result = FixedU128::one();
+	while exponent > 0 {
+		if exponent & 1 == 1 {
+			result = result.checked_mul(&base).ok_or(ArithmeticError::Overflow)?;
+		}
+		exponent >>= 1;
+		if exponent > 0 {
+			base = base.checked_mul(&base).ok_or(ArithmeticError::Overflow)?;
+		}
+	}
+
+	if tick < 0 {
+		FixedU128::one().checked_div(&result).ok_or(ArithmeticError::DivisionByZero)
+	} else {
+		Ok(result)
+	}
+}
+
+/// Computes the greatest tick such that `sqrt_price_at_tick(tick) <= sqrt_price`.
+pub fn tick_at_sqrt_price(sqrt_price: FixedU128) -> Result<i32, ArithmeticError> {
+	ensure!(sqrt_price >= sqrt_price_at_tick(MIN_TICK)?, ArithmeticError::Underflow);
+	ensure!(sqrt_price <= sqrt_price_at_tick(MAX_TICK)?, ArithmeticError::Overflow);
+
+	let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+	while low < high {
+		// round towards the upper bound so that the search always makes progress
+		let mid = low + (high - low + 1) / 2;
+		if sqrt_price_at_tick(mid)? <= sqrt_price {
+			low = mid;
+		} else {
+			high = mid - 1;
+		}
+	}
+
+	Ok(low)
+}
+
+/// Computes the amount of base asset between two prices for the given liquidity,
+/// `L * (sqrt_b - sqrt_a) / (sqrt_a * sqrt_b)`.
+pub fn base_amount_delta(
+	sqrt_price_a: FixedU128,
+	sqrt_price_b: FixedU128,
+	liquidity: u128,
+	rounding: Rounding,
+) -> Result<u128, ArithmeticError> {
+	let (sqrt_price_a, sqrt_price_b) = sorted(sqrt_price_a, sqrt_price_b);
+	let difference = sqrt_price_b.into_inner().saturating_sub(sqrt_price_a.into_inner());
+	// computed in a single division so that the rounding error isn't scaled by the price
+	let numerator = U256::from(liquidity)
+		.checked_mul(U256::from(difference))
+		.and_then(|x| x.checked_mul(U256::from(FixedU128::accuracy())))
+		.ok_or(ArithmeticError::Overflow)?;
+	let denominator = U256::from(sqrt_price_a.into_inner())
+		.checked_mul(U256::from(sqrt_price_b.into_inner()))
+		.ok_or(ArithmeticError::Overflow)?;
+	ensure!(!denominator.is_zero(), ArithmeticError::DivisionByZero);
+	let (quotient, remainder) = numerator.div_mod(denominator);
+	let quotient = match rounding {
+		Rounding::Up if !remainder.is_zero() => quotient + U256::one(),
+		_ => quotient,
+	};
+	u128::try_from(quotient).map_err(|_| ArithmeticError::Overflow)
+}
+
+/// Computes the amount of quote asset between two prices for the given liquidity,
+/// `L * (sqrt_b - sqrt_a)`.
+pub fn quote_amount_delta(
+	sqrt_price_a: FixedU128,
+	sqrt_price_b: FixedU128,
+	liquidity: u128,
+	rounding: Rounding,
+) -> Result<u128, ArithmeticError> {
+	let (sqrt_price_a, sqrt_price_b) = sorted(sqrt_price_a, sqrt_price_b);
+	let difference = sqrt_price_b.into_inner().saturating_sub(sqrt_price_a.into_inner());
+	mul_div(liquidity, difference, FixedU128::accuracy(), rounding)
+}
+
+/// Computes the maximum liquidity that can be provided in the `sqrt_price_a..sqrt_price_b` range
+/// with at most `base_amount` and `quote_amount`, given the current price of the pool.
+pub fn liquidity_for_amounts(
+	sqrt_price: FixedU128,
+	sqrt_price_a: FixedU128,
+	sqrt_price_b: FixedU128,
+	base_amount: u128,
+	quote_amount: u128,
+) -> Result<u128, ArithmeticError> {
+	let (sqrt_price_a, sqrt_price_b) = sorted(sqrt_price_a, sqrt_price_b);
+
+	// L = x * sqrt_a * sqrt_b / (sqrt_b - sqrt_a)
+	let liquidity_for_base = |sqrt_price_a: FixedU128, sqrt_price_b: FixedU128| {
+		let difference = sqrt_price_b.into_inner().saturating_sub(sqrt_price_a.into_inner());
+		mul_div(
+			mul_div(base_amount, sqrt_price_a.into_inner(), difference, Rounding::Down)?,
+			sqrt_price_b.into_inner(),
+			FixedU128::accuracy(),
+			Rounding::Down,
+		)
+	};
+	// L = y / (sqrt_b - sqrt_a)
+	let liquidity_for_quote = |sqrt_price_a: FixedU128, sqrt_price_b: FixedU128| {
+		let difference = sqrt_price_b.into_inner().saturating_sub(sqrt_price_a.into_inner());
+		mul_div(quote_amount, FixedU128::accuracy(), difference, Rounding::Down)
+	};
+
+	if sqrt_price <= sqrt_price_a {
+		liquidity_for_base(sqrt_price_a, sqrt_price_b)
+	} else if sqrt_price < sqrt_price_b {
+		Ok(liquidity_for_base(sqrt_price, sqrt_price_b)?
+			.min(liquidity_for_quote(sqrt_price_a, sqrt_price)?))
+	} else {
+		liquidity_for_quote(sqrt_price_a, sqrt_price_b)
+	}
+}
+
+/// Computes the amounts of base and quote asset backing `liquidity` in the
+/// `sqrt_price_a..sqrt_price_b` range, given the current price of the pool.
+pub fn amounts_for_liquidity(
+	sqrt_price: FixedU128,
+	sqrt_price_a: FixedU128,
+	sqrt_price_b: FixedU128,
+	liquidity: u128,
+	rounding: Rounding,
+) -> Result<(u128, u128), ArithmeticError> {
+	let (sqrt_price_a, sqrt_price_b) = sorted(sqrt_price_a, sqrt_price_b);
+
+	if sqrt_price <= sqrt_price_a {
+		Ok((base_amount_delta(sqrt_price_a, sqrt_price_b, liquidity, rounding)?, 0))
+	} else if sqrt_price < sqrt_price_b {
+		Ok((
+			base_amount_delta(sqrt_price, sqrt_price_b, liquidity, rounding)?,
+			quote_amount_delta(sqrt_price_a, sqrt_price, liquidity, rounding)?,
+		))
+	} else {
+		Ok((0, quote_amount_delta(sqrt_price_a, sqrt_price_b, liquidity, rounding)?))
+	}
+}
+
+/// Price after adding (`add == true`) or removing `base_amount` from the pool,
+/// `L * sqrt_p / (L +- x * sqrt_p)`. Always rounded up.
+fn next_sqrt_price_from_base(
+	sqrt_price: FixedU128,
+	liquidity: u128,
+	base_amount: u128,
+	add: bool,
+) -> Result<FixedU128, ArithmeticError> {
+	let product =
+		mul_div(base_amount, sqrt_price.into_inner(), FixedU128::accuracy(), Rounding::Up)?;
+	let denominator = if add {
+		liquidity.checked_add(product).ok_or(ArithmeticError::Overflow)?
+	} else {
+		liquidity.checked_sub(product).ok_or(ArithmeticError::Underflow)?
+	};
+	mul_div(liquidity, sqrt_price.into_inner(), denominator, Rounding::Up)
+		.map(FixedU128::from_inner)
+}
+
+/// Price after adding (`add == true`) or removing `quote_amount` from the pool,
+/// `sqrt_p +- y / L`. Always rounded down.
+fn next_sqrt_price_from_quote(
+	sqrt_price: FixedU128,
+	liquidity: u128,
+	quote_amount: u128,
+	add: bool,
+) -> Result<FixedU128, ArithmeticError> {
+	if add {
+		let quotient = mul_div(quote_amount, FixedU128::accuracy(), liquidity, Rounding::Down)?;
+		sqrt_price.into_inner().checked_add(quotient).ok_or(ArithmeticError::Overflow)
+	} else {
+		let quotient = mul_div(quote_amount, FixedU128::accuracy(), liquidity, Rounding::Up)?;
+		ensure!(sqrt_price.into_inner() > quotient, ArithmeticError::Underflow);
+		Ok(sqrt_price.into_inner() - quotient)
+	}
+	.map(FixedU128::from_inner)
+}
+
+/// Result of a single step of a swap, within a range of constant liquidity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapStep {
+	/// Price of the pool after the step
+	pub sqrt_price_next: FixedU128,
+	/// Amount sent to the pool, excluding the fee
+	pub amount_in: u128,
+	/// Amount received from the pool
+	pub amount_out: u128,
+	/// Fee paid on top of `amount_in`
+	pub fee_amount: u128,
+}
+
+/// Computes a single step of a swap, moving the price from `sqrt_price_current` towards
+/// `sqrt_price_target` with constant `liquidity`.
+///
+/// The base asset is sold if the target price is lower than the current one, the quote asset
+/// otherwise. `amount_remaining` is the amount left to be sent to the pool (fees included) if
+/// `exact_in`, the amount left to be received from the pool otherwise.
+pub fn compute_swap_step(
+	sqrt_price_current: FixedU128,
+	sqrt_price_target: FixedU128,
+	liquidity: u128,
+	amount_remaining: u128,
+	fee: Permill,
+	exact_in: bool,
+) -> Result<SwapStep, ArithmeticError> {
+	let base_for_quote = sqrt_price_current >= sqrt_price_target;
+	let fee_parts = u128::from(fee.deconstruct());
+	let accuracy = u128::from(Permill::ACCURACY);
+
+	let amount_in_to_target = || {
+		if base_for_quote {
+			base_amount_delta(sqrt_price_target, sqrt_price_current, liquidity, Rounding::Up)
+		} else {
+			quote_amount_delta(sqrt_price_current, sqrt_price_target, liquidity, Rounding::Up)
+		}
+	};
+	let amount_out_to_target = || {
+		if base_for_quote {
+			quote_amount_delta(sqrt_price_target, sqrt_price_current, liquidity, Rounding::Down)
+		} else {
+			base_amount_delta(sqrt_price_current, sqrt_price_target, liquidity, Rounding::Down)
+		}
+	};
+
+	let (sqrt_price_next, amount_to_target) = if exact_in {
+		let amount_remaining_less_fee =
+			mul_div(amount_remaining, accuracy - fee_parts, accuracy, Rounding::Down)?;
+		let amount_in = amount_in_to_target()?;
+		if amount_remaining_less_fee >= amount_in {
+			(sqrt_price_target, amount_in)
+		} else if base_for_quote {
+			(
+				next_sqrt_price_from_base(
+					sqrt_price_current,
+					liquidity,
+					amount_remaining_less_fee,
+					true,
+				)?,
+				amount_in,
+			)
+		} else {
+			(
+				next_sqrt_price_from_quote(
+					sqrt_price_current,
+					liquidity,
+					amount_remaining_less_fee,
+					true,
+				)?,
+				amount_in,
+			)
+		}
+	} else {
+		let amount_out = amount_out_to_target()?;
+		if amount_remaining >= amount_out {
+			(sqrt_price_target, amount_out)
+		} else if base_for_quote {
+			(
+				next_sqrt_price_from_quote(sqrt_price_current, liquidity, amount_remaining, false)?,
+				amount_out,
+			)
+		} else {
+			(
+				next_sqrt_price_from_base(sqrt_price_current, liquidity, amount_remaining, false)?,
+				amount_out,
+			)
+		}
+	};
+	let reached_target = sqrt_price_next == sqrt_price_target;
+
+	let (amount_in, mut amount_out) = if base_for_quote {
+		(
+			if reached_target && exact_in {
+				amount_to_target
+			} else {
+				base_amount_delta(sqrt_price_next, sqrt_price_current, liquidity, Rounding::Up)?
+			},
+			if reached_target && !exact_in {
+				amount_to_target
+			} else {
+				quote_amount_delta(sqrt_price_next, sqrt_price_current, liquidity, Rounding::Down)?
+			},
+		)
+	} else {
+		(
+			if reached_target && exact_in {
+				amount_to_target
+			} else {
+				quote_amount_delta(sqrt_price_current, sqrt_price_next, liquidity, Rounding::Up)?
+			},
+			if reached_target && !exact_in {
+				amount_to_target
+			} else {
+				base_amount_delta(sqrt_price_current, sqrt_price_next, liquidity, Rounding::Down)?
+			},
+		)
+	};
+
+	// the price can move a bit too far due to rounding, never hand out more than requested
+	if !exact_in && amount_out > amount_remaining {
+		amount_out = amount_remaining;
+	}
+
+	let fee_amount = if exact_in && !reached_target {
+		// the remainder of the input is taken as fee
+		amount_remaining.saturating_sub(amount_in)
+	} else {
+		mul_div(amount_in, fee_parts, accuracy - fee_parts, Rounding::Up)?
+	};
+
+	Ok(SwapStep { sqrt_price_next, amount_in, amount_out, fee_amount })
+}
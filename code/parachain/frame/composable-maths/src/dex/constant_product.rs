@@ -242,6 +242,44 @@ pub fn compute_redeemed_for_lp<T: PerThing>(
 	Ok(a_k.safe_to_u128()?)
 }
 
+/// Calculates `a_k` when redeeming LP tokens for a single asset `k` of the pool.
+///
+/// If `Ok`, returns a `ConstantProductAmmValueFeePair` containing the `a_k` and the `fee`.
+///
+/// Only the part of the withdrawal that is not proportional to the pool (`1 - w_k`) is implicitly
+/// swapped, thus the fee is only charged on that part.
+///
+/// # Parameters
+/// * `p_supply` - Existing supply of LP
+/// * `p_redeemed` - Redeemed LP tokens
+/// * `b_k` - balance of token `k`
+/// * `w_k` - weight of token `k`
+/// * `f` - Total swap fee
+pub fn compute_single_asset_redeemed_for_lp<T: PerThing>(
+	p_supply: u128,
+	p_redeemed: u128,
+	b_k: u128,
+	w_k: T,
+	f: T,
+) -> ConstantProductAmmResult<ConstantProductAmmValueFeePair> {
+	let a_k = Decimal::safe_from_u128(compute_redeemed_for_lp(p_supply, p_redeemed, b_k, w_k)?)?;
+
+	let fee = if f.is_zero() {
+		Decimal::ZERO
+	} else {
+		let f = Decimal::safe_from_per_thing(f)?;
+		let not_proportional = Decimal::safe_from_per_thing(w_k.left_from_one())?;
+		a_k.safe_mul(&not_proportional)?.safe_mul(&f)?.round_up()
+	};
+
+	let a_k_left_from_fee = a_k.safe_sub(&fee)?;
+
+	Ok(ConstantProductAmmValueFeePair {
+		value: a_k_left_from_fee.safe_to_u128()?,
+		fee: fee.safe_to_u128()?,
+	})
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum ConstantProductAmmError {
 	ArithmeticError(ArithmeticError),
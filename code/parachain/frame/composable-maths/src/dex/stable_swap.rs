@@ -0,0 +1,340 @@
+use crate::dex::constant_product::{
+	ConstantProductAmmError, ConstantProductAmmResult, ConstantProductAmmValueFeePair,
+};
+use composable_support::math::safe::{SafeAdd, SafeMul, SafeSub};
+use frame_support::ensure;
+use sp_arithmetic::Rounding;
+use sp_core::U256;
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding, traits::Zero, ArithmeticError,
+	FixedPointNumber, FixedU128, PerThing,
+};
+use sp_std::vec::Vec;
+
+/// Maximum number of Newton iterations before giving up on convergence.
+const MAX_ITERATIONS: u8 = 255;
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+	if a > b {
+		a - b
+	} else {
+		b - a
+	}
+}
+
+/// Computes the StableSwap invariant `D` for the given pool balances.
+///
+/// Solves `A * n^n * S + D = A * D * n^n + D^(n + 1) / (n^n * P)` for `D` using Newton's method,
+/// where `S` is the sum and `P` the product of the balances.
+///
+/// # Parameters
+/// * `amp` - Amplification coefficient `A`
+/// * `balances` - Balances of all of the assets in the pool
+///
+/// From https://curve.fi/files/stableswap-paper.pdf
+pub fn compute_d(amp: u128, balances: &[u128]) -> Result<u128, ArithmeticError> {
+	let n = U256::from(balances.len());
+	let sum = balances.iter().try_fold(0_u128, |sum, balance| sum.safe_add(balance))?;
+	if sum.is_zero() {
+		return Ok(0)
+	}
+
+	let sum = U256::from(sum);
+	let ann = U256::from(amp).checked_mul(n.pow(n)).ok_or(ArithmeticError::Overflow)?;
+	ensure!(!ann.is_zero(), ArithmeticError::DivisionByZero);
+
+	let mut d = sum;
+	for _ in 0..MAX_ITERATIONS {
+		let d_p = balances.iter().try_fold(d, |d_p, balance| -> Result<_, ArithmeticError> {
+			let denominator =
+				U256::from(*balance).checked_mul(n).ok_or(ArithmeticError::Overflow)?;
+			ensure!(!denominator.is_zero(), ArithmeticError::DivisionByZero);
+			d_p.checked_mul(d)
+				.ok_or(ArithmeticError::Overflow)?
+				.checked_div(denominator)
+				.ok_or(ArithmeticError::DivisionByZero)
+		})?;
+		let d_prev = d;
+
+		// D = (Ann * S + D_P * n) * D / ((Ann - 1) * D + (n + 1) * D_P)
+		let numerator = ann
+			.checked_mul(sum)
+			.and_then(|x| x.checked_add(d_p.checked_mul(n)?))
+			.and_then(|x| x.checked_mul(d))
+			.ok_or(ArithmeticError::Overflow)?;
+		let denominator = (ann - U256::one())
+			.checked_mul(d)
+			.and_then(|x| x.checked_add((n + U256::one()).checked_mul(d_p)?))
+			.ok_or(ArithmeticError::Overflow)?;
+		d = numerator.checked_div(denominator).ok_or(ArithmeticError::DivisionByZero)?;
+
+		if abs_diff(d, d_prev) <= U256::one() {
+			return u128::try_from(d).map_err(|_| ArithmeticError::Overflow)
+		}
+	}
+
+	Err(ArithmeticError::Overflow)
+}
+
+/// Computes the balance of the remaining asset in the pool such that the invariant `D` holds.
+///
+/// Solves `y^2 + y * (b - D) = c` for `y` using Newton's method, where
+/// `b = S' + D / (A * n^n)` and `c = D^(n + 1) / (n^n * P' * A * n^n)`; `S'` and `P'` are the
+/// sum and product of all balances except `y`.
+///
+/// # Parameters
+/// * `amp` - Amplification coefficient `A`
+/// * `d` - The invariant `D`
+/// * `other_balances` - Balances of all of the assets in the pool, except the one being solved for
+///
+/// From https://github.com/curvefi/curve-contract/blob/master/contracts/pool-templates/base/SwapTemplateBase.vy
+/// `get_y`
+pub fn compute_y(amp: u128, d: u128, other_balances: &[u128]) -> Result<u128, ArithmeticError> {
+	let n = U256::from(other_balances.len().safe_add(&1)?);
+	let d = U256::from(d);
+	let ann = U256::from(amp).checked_mul(n.pow(n)).ok_or(ArithmeticError::Overflow)?;
+	ensure!(!ann.is_zero(), ArithmeticError::DivisionByZero);
+
+	let (c, sum) = other_balances.iter().try_fold(
+		(d, U256::zero()),
+		|(c, sum), balance| -> Result<_, ArithmeticError> {
+			let balance = U256::from(*balance);
+			let denominator = balance.checked_mul(n).ok_or(ArithmeticError::Overflow)?;
+			ensure!(!denominator.is_zero(), ArithmeticError::DivisionByZero);
+			let c = c.checked_mul(d).ok_or(ArithmeticError::Overflow)? / denominator;
+			let sum = sum.checked_add(balance).ok_or(ArithmeticError::Overflow)?;
+			Ok((c, sum))
+		},
+	)?;
+	let c = c
+		.checked_mul(d)
+		.ok_or(ArithmeticError::Overflow)?
+		.checked_div(ann.checked_mul(n).ok_or(ArithmeticError::Overflow)?)
+		.ok_or(ArithmeticError::DivisionByZero)?;
+	let b = sum.checked_add(d / ann).ok_or(ArithmeticError::Overflow)?;
+
+	let mut y = d;
+	for _ in 0..MAX_ITERATIONS {
+		let y_prev = y;
+
+		// y = (y^2 + c) / (2 * y + b - D)
+		let numerator = y
+			.checked_mul(y)
+			.and_then(|x| x.checked_add(c))
+			.ok_or(ArithmeticError::Overflow)?;
+		let denominator = y
+			.checked_mul(U256::from(2_u8))
+			.and_then(|x| x.checked_add(b))
+			.ok_or(ArithmeticError::Overflow)?
+			.checked_sub(d)
+			.ok_or(ArithmeticError::Underflow)?;
+		y = numerator.checked_div(denominator).ok_or(ArithmeticError::DivisionByZero)?;
+
+		if abs_diff(y, y_prev) <= U256::one() {
+			return u128::try_from(y).map_err(|_| ArithmeticError::Overflow)
+		}
+	}
+
+	Err(ArithmeticError::Overflow)
+}
+
+/// Computes the spot price of the input token in terms of the output token, i.e. the amount of the
+/// output token received per unit of the input token for an infinitesimally small swap.
+///
+/// Differentiating the invariant of a two asset pool gives
+/// `-dy/dx = (4 * A * n^n * x * y + D^3 / x) / (4 * A * n^n * x * y + D^3 / y)`, where `x` and `y`
+/// are the balances of the input and output token.
+///
+/// # Parameters
+/// * `amp` - Amplification coefficient
+/// * `b_i` - Balance of the input token
+/// * `b_o` - Balance of the output token
+pub fn compute_spot_price(amp: u128, b_i: u128, b_o: u128) -> Result<FixedU128, ArithmeticError> {
+	ensure!(!b_i.is_zero() && !b_o.is_zero(), ArithmeticError::DivisionByZero);
+
+	let d = U256::from(compute_d(amp, &[b_i, b_o])?);
+	let (x, y) = (U256::from(b_i), U256::from(b_o));
+
+	// 4 * A * n^n * x * y, with n = 2
+	let axy = U256::from(amp)
+		.checked_mul(U256::from(16_u8))
+		.and_then(|a| a.checked_mul(x))
+		.and_then(|ax| ax.checked_mul(y))
+		.ok_or(ArithmeticError::Overflow)?;
+	// D^3 / b, dividing before the last multiplication to stay within bounds
+	let d_cubed_over = |b: U256| -> Result<U256, ArithmeticError> {
+		d.checked_mul(d)
+			.ok_or(ArithmeticError::Overflow)?
+			.checked_div(b)
+			.ok_or(ArithmeticError::DivisionByZero)?
+			.checked_mul(d)
+			.ok_or(ArithmeticError::Overflow)
+	};
+	let numerator = axy.checked_add(d_cubed_over(x)?).ok_or(ArithmeticError::Overflow)?;
+	let denominator = axy.checked_add(d_cubed_over(y)?).ok_or(ArithmeticError::Overflow)?;
+
+	// scale both terms down to fit into a u128, the ratio is kept
+	let shift = numerator.bits().max(denominator.bits()).saturating_sub(128);
+	FixedU128::checked_from_rational(
+		(numerator >> shift).as_u128(),
+		(denominator >> shift).as_u128(),
+	)
+	.ok_or(ArithmeticError::Overflow)
+}
+
+/// Compute the amount of the output token given the amount of the input token.
+///
+/// If `Ok`, returns a `ConstantProductAmmValueFeePair` containing the `a_out` and the `fee`.
+/// To get `a_out` without accounting for the fee, set `f = 0`.
+/// Amount out, round down results.
+///
+/// # Parameters
+/// * `amp` - Amplification coefficient
+/// * `b_i` - Balance of the input token
+/// * `b_o` - Balance of the output token
+/// * `a_sent` - Amount of the input token sent by the user
+/// * `f` - Total swap fee
+pub fn compute_out_given_in<T: PerThing>(
+	amp: u128,
+	b_i: u128,
+	b_o: u128,
+	a_sent: u128,
+	f: T,
+) -> ConstantProductAmmResult<ConstantProductAmmValueFeePair> {
+	let a_sent_fee_cut = f.left_from_one().mul_floor(a_sent);
+	let fee = a_sent.safe_sub(&a_sent_fee_cut)?;
+
+	let d = compute_d(amp, &[b_i, b_o])?;
+	let y = compute_y(amp, d, &[b_i.safe_add(&a_sent_fee_cut)?])?;
+
+	// subtract 1 to account for the rounding error in `compute_y`, in favour of the pool
+	let a_out = b_o.safe_sub(&y)?.saturating_sub(1);
+
+	Ok(ConstantProductAmmValueFeePair { value: a_out, fee })
+}
+
+/// Compute the amount of the input token given the amount of the output token.
+///
+/// If `Ok`, returns a `ConstantProductAmmValueFeePair` containing the `a_sent` and the `fee`.
+/// To get `a_sent` without accounting for the fee, set `f = 0`.
+/// Amount in, round up results.
+///
+/// # Parameters
+/// * `amp` - Amplification coefficient
+/// * `b_i` - Balance of the input token
+/// * `b_o` - Balance of the output token
+/// * `a_out` - Amount of the output token desired by the user
+/// * `f` - Total swap fee
+pub fn compute_in_given_out<T: PerThing>(
+	amp: u128,
+	b_i: u128,
+	b_o: u128,
+	a_out: u128,
+	f: T,
+) -> ConstantProductAmmResult<ConstantProductAmmValueFeePair> {
+	ensure!(a_out < b_o, ConstantProductAmmError::CannotTakeMoreThanAvailable);
+
+	let d = compute_d(amp, &[b_i, b_o])?;
+	let x = compute_y(amp, d, &[b_o.safe_sub(&a_out)?])?;
+
+	// add 1 to account for the rounding error in `compute_y`, in favour of the pool
+	let a_sent_fee_cut = x.safe_sub(&b_i)?.safe_add(&1)?;
+	let a_sent = multiply_by_rational_with_rounding(
+		a_sent_fee_cut,
+		T::ACCURACY.into(),
+		f.left_from_one().deconstruct().into(),
+		Rounding::Up,
+	)
+	.ok_or(ArithmeticError::Overflow)?;
+	let fee = a_sent.safe_sub(&a_sent_fee_cut)?;
+
+	Ok(ConstantProductAmmValueFeePair { value: a_sent, fee })
+}
+
+/// Computes the LP to mint on first deposit, which is equal to the invariant `D` of the deposited
+/// amounts.
+///
+/// Fees are always 0 as there is no existing balance to be imbalanced.
+///
+/// # Parameters
+/// * `amp` - Amplification coefficient
+/// * `deposits` - Deposits of all of the assets in the pool
+pub fn compute_first_deposit_lp(
+	amp: u128,
+	deposits: &[u128],
+) -> ConstantProductAmmResult<ConstantProductAmmValueFeePair> {
+	ensure!(!deposits.is_empty(), ConstantProductAmmError::InvalidTokensList);
+	ensure!(deposits.iter().all(|d| !d.is_zero()), ArithmeticError::DivisionByZero);
+
+	Ok(ConstantProductAmmValueFeePair { value: compute_d(amp, deposits)?, fee: 0 })
+}
+
+/// Computes the LP to mint on an existing deposit.
+///
+/// Deposits that move the pool away from its current ratio are charged `f * n / (4 * (n - 1))`
+/// on the imbalanced part, as in Curve. The charged amount stays in the pool.
+///
+/// If `Ok`, returns a `ConstantProductAmmValueFeePair` containing the `lp_to_mint` and the `fee`
+/// expressed in LP tokens that were not minted because of the imbalance.
+///
+/// # Parameters
+/// * `p_supply` - Existing supply of LP tokens
+/// * `amp` - Amplification coefficient
+/// * `balances_and_deposits` - `(b_k, d_k)` for every asset `k` in the pool; `d_k` may be zero
+/// * `f` - Fee
+pub fn compute_deposit_lp<T: PerThing>(
+	p_supply: u128,
+	amp: u128,
+	balances_and_deposits: &[(u128, u128)],
+	f: T,
+) -> ConstantProductAmmResult<ConstantProductAmmValueFeePair> {
+	let n: u128 = balances_and_deposits.len().try_into().map_err(|_| ArithmeticError::Overflow)?;
+	ensure!(n > 1, ConstantProductAmmError::InvalidTokensList);
+
+	let old_balances = balances_and_deposits.iter().map(|(b_k, _)| *b_k).collect::<Vec<_>>();
+	let new_balances = balances_and_deposits
+		.iter()
+		.map(|(b_k, d_k)| b_k.safe_add(d_k))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let d_0 = compute_d(amp, &old_balances)?;
+	let d_1 = compute_d(amp, &new_balances)?;
+	ensure!(d_1 > d_0, ConstantProductAmmError::InvalidTokensList);
+
+	// the fee charged on the imbalanced part is `f * n / (4 * (n - 1))`
+	let f_parts: u128 = f.deconstruct().into();
+	let accuracy: u128 = T::ACCURACY.into();
+	let fee_numerator = f_parts.safe_mul(&n)?;
+	let fee_denominator = accuracy.safe_mul(&4_u128.safe_mul(&n.safe_sub(&1)?)?)?;
+
+	let balances_after_fees = old_balances
+		.iter()
+		.zip(new_balances.iter())
+		.map(|(old_balance, new_balance)| {
+			let ideal_balance =
+				multiply_by_rational_with_rounding(d_1, *old_balance, d_0, Rounding::Down)
+					.ok_or(ArithmeticError::Overflow)?;
+			let difference = ideal_balance.abs_diff(*new_balance);
+			let fee = multiply_by_rational_with_rounding(
+				difference,
+				fee_numerator,
+				fee_denominator,
+				Rounding::Up,
+			)
+			.ok_or(ArithmeticError::Overflow)?;
+			new_balance.safe_sub(&fee)
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let d_2 = compute_d(amp, &balances_after_fees)?;
+
+	let issue = |d: u128| -> Result<u128, ArithmeticError> {
+		multiply_by_rational_with_rounding(p_supply, d.safe_sub(&d_0)?, d_0, Rounding::Down)
+			.ok_or(ArithmeticError::Overflow)
+	};
+
+	let issued = issue(d_2)?;
+	let fee = issue(d_1)?.safe_sub(&issued)?;
+
+	Ok(ConstantProductAmmValueFeePair { value: issued, fee })
+}
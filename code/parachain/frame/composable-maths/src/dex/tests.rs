@@ -157,6 +157,76 @@ mod constant_product {
 		}
 	}
 
+	/// Tests related to the function `compute_single_asset_redeemed_for_lp`
+	mod compute_single_asset_redeemed_for_lp {
+		use super::*;
+
+		#[test]
+		fn should_match_compute_redeemed_for_lp_without_fee() {
+			let p_supply = 512_000_000_000_000;
+			let p_redeemed = 128_000_000_000_000;
+			let b_k = 2_048_000_000_000_000;
+			let w_k = Permill::from_percent(50);
+
+			let res = compute_single_asset_redeemed_for_lp(
+				p_supply,
+				p_redeemed,
+				b_k,
+				w_k,
+				Permill::zero(),
+			)
+			.expect("Inputs are valid; QED");
+
+			assert_eq!(
+				res,
+				ConstantProductAmmValueFeePair {
+					value: compute_redeemed_for_lp(p_supply, p_redeemed, b_k, w_k)
+						.expect("Inputs are valid; QED"),
+					fee: 0
+				}
+			);
+		}
+
+		#[test]
+		fn should_charge_fee_on_non_proportional_part() {
+			let p_supply = 512_000_000_000_000;
+			let p_redeemed = 128_000_000_000_000;
+			let b_k = 2_048_000_000_000_000;
+			let w_k = Permill::from_percent(50);
+			let f = Permill::from_percent(1);
+
+			let res = compute_single_asset_redeemed_for_lp(p_supply, p_redeemed, b_k, w_k, f)
+				.expect("Inputs are valid; QED");
+
+			// 896 * (1 - 0.5) * 0.01 = 4.48
+			assert_eq!(
+				res,
+				ConstantProductAmmValueFeePair {
+					value: 891_520_000_000_000,
+					fee: 4_480_000_000_000
+				}
+			);
+		}
+
+		#[test]
+		fn should_not_charge_fee_when_w_k_is_one() {
+			let p_supply = 512_000_000_000_000;
+			let p_redeemed = 128_000_000_000_000;
+			let b_k = 2_048_000_000_000_000;
+
+			let res = compute_single_asset_redeemed_for_lp(
+				p_supply,
+				p_redeemed,
+				b_k,
+				Permill::one(),
+				Permill::from_percent(1),
+			)
+			.expect("Inputs are valid; QED");
+
+			assert_eq!(res, ConstantProductAmmValueFeePair { value: 512_000_000_000_000, fee: 0 });
+		}
+	}
+
 	/// Tests related to the function `compute_first_deposit_lp`
 	mod compute_first_deposit_lp {
 		use super::*;
@@ -914,3 +984,418 @@ mod constant_product {
 		}
 	}
 }
+
+/// Tests related to stable swap math functions
+mod stable_swap {
+	use crate::dex::{
+		constant_product::{ConstantProductAmmError, ConstantProductAmmValueFeePair},
+		stable_swap::*,
+	};
+	use composable_tests_helpers::test::helper::default_acceptable_computation_error;
+	use proptest::prelude::*;
+	use sp_runtime::{ArithmeticError, Permill};
+
+	const AMP: u128 = 100;
+	const BALANCE: u128 = 1_000_000_000_000_000;
+
+	/// Tests related to the function `compute_d`
+	mod compute_d {
+		use super::*;
+
+		#[test]
+		fn should_equal_sum_when_balanced() {
+			assert_eq!(compute_d(AMP, &[BALANCE, BALANCE]), Ok(2 * BALANCE));
+		}
+
+		#[test]
+		fn should_be_zero_when_pool_is_empty() {
+			assert_eq!(compute_d(AMP, &[0, 0]), Ok(0));
+		}
+
+		#[test]
+		fn should_be_less_than_sum_when_imbalanced() {
+			let d = compute_d(AMP, &[BALANCE, BALANCE / 2]).expect("Inputs are valid; QED");
+
+			assert_eq!(d, 1_499_534_015_561_310);
+		}
+
+		#[test]
+		fn should_error_when_amp_is_zero() {
+			assert_eq!(compute_d(0, &[BALANCE, BALANCE]), Err(ArithmeticError::DivisionByZero));
+		}
+	}
+
+	/// Tests related to the function `compute_out_given_in`
+	mod compute_out_given_in {
+		use super::*;
+
+		#[test]
+		fn should_have_low_slippage_when_balanced() {
+			let res = compute_out_given_in(
+				AMP,
+				BALANCE,
+				BALANCE,
+				100_000_000_000_000,
+				Permill::from_percent(1),
+			)
+			.expect("Inputs are valid; QED");
+
+			assert_eq!(
+				res,
+				ConstantProductAmmValueFeePair {
+					value: 98_950_785_745_244,
+					fee: 1_000_000_000_000
+				}
+			);
+		}
+
+		#[test]
+		fn should_be_inverse_of_compute_in_given_out() {
+			let a_sent = 100_000_000_000_000;
+			let f = Permill::from_percent(1);
+
+			let out = compute_out_given_in(AMP, BALANCE, BALANCE, a_sent, f)
+				.expect("Inputs are valid; QED");
+			let res = compute_in_given_out(AMP, BALANCE, BALANCE, out.value, f)
+				.expect("Inputs are valid; QED");
+
+			assert_eq!(res, ConstantProductAmmValueFeePair { value: a_sent, fee: out.fee });
+		}
+	}
+
+	/// Tests related to the function `compute_spot_price`
+	mod compute_spot_price {
+		use super::*;
+		use sp_runtime::{FixedPointNumber, FixedU128};
+
+		#[test]
+		fn should_be_one_when_balanced() {
+			assert_eq!(compute_spot_price(AMP, BALANCE, BALANCE), Ok(FixedU128::one()));
+		}
+
+		#[test]
+		fn should_be_between_one_and_balance_ratio_when_imbalanced() {
+			let price =
+				compute_spot_price(AMP, BALANCE, BALANCE / 2).expect("Inputs are valid; QED");
+
+			assert!(price < FixedU128::one());
+			assert!(price > FixedU128::saturating_from_rational(1_u128, 2_u128));
+		}
+
+		#[test]
+		fn should_match_a_small_swap() {
+			let a_sent = BALANCE / 1_000_000;
+			let price =
+				compute_spot_price(AMP, BALANCE / 2, BALANCE).expect("Inputs are valid; QED");
+			let out = compute_out_given_in(AMP, BALANCE / 2, BALANCE, a_sent, Permill::zero())
+				.expect("Inputs are valid; QED");
+
+			assert!(default_acceptable_computation_error(
+				price.saturating_mul_int(a_sent),
+				out.value
+			)
+			.is_ok());
+		}
+
+		#[test]
+		fn should_error_when_pool_is_empty() {
+			assert_eq!(compute_spot_price(AMP, BALANCE, 0), Err(ArithmeticError::DivisionByZero));
+		}
+	}
+
+	/// Tests related to the function `compute_in_given_out`
+	mod compute_in_given_out {
+		use super::*;
+
+		#[test]
+		fn should_error_when_taking_whole_balance() {
+			assert_eq!(
+				compute_in_given_out(AMP, BALANCE, BALANCE, BALANCE, Permill::zero()),
+				Err(ConstantProductAmmError::CannotTakeMoreThanAvailable)
+			);
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(1_000))]
+
+			#[test]
+			fn should_be_close_to_one_to_one_when_balanced(a_out in 1_000_000..BALANCE / 100) {
+				let res = compute_in_given_out(AMP, BALANCE, BALANCE, a_out, Permill::zero())
+					.expect("Inputs are valid; QED");
+
+				prop_assert!(default_acceptable_computation_error(res.value, a_out).is_ok());
+			}
+		}
+	}
+
+	/// Tests related to the functions `compute_first_deposit_lp` and `compute_deposit_lp`
+	mod compute_deposit_lp {
+		use super::*;
+
+		#[test]
+		fn first_deposit_should_mint_invariant() {
+			assert_eq!(
+				compute_first_deposit_lp(AMP, &[BALANCE, BALANCE]),
+				Ok(ConstantProductAmmValueFeePair { value: 2 * BALANCE, fee: 0 })
+			);
+		}
+
+		#[test]
+		fn first_deposit_should_error_when_missing_asset() {
+			assert_eq!(
+				compute_first_deposit_lp(AMP, &[BALANCE, 0]),
+				Err(ConstantProductAmmError::ArithmeticError(ArithmeticError::DivisionByZero))
+			);
+		}
+
+		#[test]
+		fn balanced_deposit_should_not_charge_fees() {
+			let res = compute_deposit_lp(
+				2 * BALANCE,
+				AMP,
+				&[(BALANCE, BALANCE / 10), (BALANCE, BALANCE / 10)],
+				Permill::from_percent(1),
+			)
+			.expect("Inputs are valid; QED");
+
+			assert_eq!(res, ConstantProductAmmValueFeePair { value: 2 * BALANCE / 10, fee: 0 });
+		}
+
+		#[test]
+		fn single_sided_deposit_should_charge_fees() {
+			let res = compute_deposit_lp(
+				2 * BALANCE,
+				AMP,
+				&[(BALANCE, BALANCE / 10), (BALANCE, 0)],
+				Permill::from_percent(1),
+			)
+			.expect("Inputs are valid; QED");
+
+			assert_eq!(
+				res,
+				ConstantProductAmmValueFeePair { value: 99_488_124_918_924, fee: 500_002_826_032 }
+			);
+		}
+	}
+}
+
+/// Tests related to concentrated liquidity math functions
+mod concentrated_liquidity {
+	use crate::dex::concentrated_liquidity::*;
+	use proptest::prelude::*;
+	use sp_arithmetic::{FixedU128, Rounding};
+	use sp_runtime::{ArithmeticError, Permill};
+
+	const AMOUNT: u128 = 1_000_000_000_000;
+
+	mod sqrt_price_at_tick {
+		use super::*;
+
+		#[test]
+		fn should_compute_known_prices() {
+			assert_eq!(sqrt_price_at_tick(0), Ok(FixedU128::from_inner(1_000_000_000_000_000_000)));
+			assert_eq!(sqrt_price_at_tick(1), Ok(FixedU128::from_inner(1_000_049_998_750_062_496)));
+			assert_eq!(sqrt_price_at_tick(-1), Ok(FixedU128::from_inner(999_950_003_749_687_527)));
+			// price of ~10
+			assert_eq!(
+				sqrt_price_at_tick(46_054),
+				Ok(FixedU128::from_inner(9_999_997_796_810_265_608))
+			);
+		}
+
+		#[test]
+		fn should_fail_outside_of_tick_range() {
+			assert_eq!(sqrt_price_at_tick(MAX_TICK + 1), Err(ArithmeticError::Overflow));
+			assert_eq!(sqrt_price_at_tick(MIN_TICK - 1), Err(ArithmeticError::Overflow));
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(1_000))]
+
+			#[test]
+			fn should_be_monotonic(tick in MIN_TICK..MAX_TICK) {
+				prop_assert!(
+					sqrt_price_at_tick(tick).expect("Input is valid; QED") <
+						sqrt_price_at_tick(tick + 1).expect("Input is valid; QED")
+				);
+			}
+
+			#[test]
+			fn tick_at_sqrt_price_should_be_inverse(tick in MIN_TICK..=MAX_TICK) {
+				let sqrt_price = sqrt_price_at_tick(tick).expect("Input is valid; QED");
+				prop_assert_eq!(tick_at_sqrt_price(sqrt_price), Ok(tick));
+			}
+		}
+	}
+
+	mod liquidity {
+		use super::*;
+
+		#[test]
+		fn should_compute_liquidity_for_balanced_range() {
+			let sqrt_price = sqrt_price_at_tick(0).unwrap();
+			let (lower, upper) =
+				(sqrt_price_at_tick(-1_000).unwrap(), sqrt_price_at_tick(1_000).unwrap());
+
+			let liquidity =
+				liquidity_for_amounts(sqrt_price, lower, upper, AMOUNT, AMOUNT).unwrap();
+
+			assert_eq!(liquidity, 20_505_166_268_106);
+			assert_eq!(
+				amounts_for_liquidity(sqrt_price, lower, upper, liquidity, Rounding::Up),
+				Ok((AMOUNT, AMOUNT))
+			);
+		}
+
+		#[test]
+		fn ranges_outside_of_current_price_should_hold_a_single_asset() {
+			let sqrt_price = sqrt_price_at_tick(0).unwrap();
+
+			let above = (sqrt_price_at_tick(10).unwrap(), sqrt_price_at_tick(20).unwrap());
+			let liquidity =
+				liquidity_for_amounts(sqrt_price, above.0, above.1, AMOUNT, AMOUNT).unwrap();
+			let (base, quote) =
+				amounts_for_liquidity(sqrt_price, above.0, above.1, liquidity, Rounding::Up)
+					.unwrap();
+			assert!(base > 0);
+			assert_eq!(quote, 0);
+
+			let below = (sqrt_price_at_tick(-20).unwrap(), sqrt_price_at_tick(-10).unwrap());
+			let liquidity =
+				liquidity_for_amounts(sqrt_price, below.0, below.1, AMOUNT, AMOUNT).unwrap();
+			let (base, quote) =
+				amounts_for_liquidity(sqrt_price, below.0, below.1, liquidity, Rounding::Up)
+					.unwrap();
+			assert_eq!(base, 0);
+			assert!(quote > 0);
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(10_000))]
+
+			#[test]
+			fn deposited_amounts_should_not_exceed_provided_amounts(
+				lower_tick in -300_000_i32..200_000,
+				width in 1_i32..100_000,
+				offset in -50_000_i32..150_000,
+				base_amount in 1_u128..u64::MAX as u128,
+				quote_amount in 1_u128..u64::MAX as u128,
+			) {
+				let upper_tick = lower_tick + width;
+				let sqrt_price =
+					sqrt_price_at_tick(lower_tick + offset).expect("Input is valid; QED");
+				let lower = sqrt_price_at_tick(lower_tick).expect("Input is valid; QED");
+				let upper = sqrt_price_at_tick(upper_tick).expect("Input is valid; QED");
+
+				let liquidity =
+					liquidity_for_amounts(sqrt_price, lower, upper, base_amount, quote_amount)
+						.expect("Inputs are valid; QED");
+				let (base, quote) =
+					amounts_for_liquidity(sqrt_price, lower, upper, liquidity, Rounding::Up)
+						.expect("Inputs are valid; QED");
+
+				prop_assert!(base <= base_amount);
+				prop_assert!(quote <= quote_amount);
+			}
+		}
+	}
+
+	mod compute_swap_step {
+		use super::*;
+
+		fn liquidity() -> u128 {
+			liquidity_for_amounts(
+				sqrt_price_at_tick(0).unwrap(),
+				sqrt_price_at_tick(-1_000).unwrap(),
+				sqrt_price_at_tick(1_000).unwrap(),
+				AMOUNT,
+				AMOUNT,
+			)
+			.unwrap()
+		}
+
+		#[test]
+		fn exact_in_within_range() {
+			let step = compute_swap_step(
+				sqrt_price_at_tick(0).unwrap(),
+				sqrt_price_at_tick(-1_000).unwrap(),
+				liquidity(),
+				AMOUNT / 100,
+				Permill::from_perthousand(3),
+				true,
+			)
+			.unwrap();
+
+			assert_eq!(
+				step,
+				SwapStep {
+					sqrt_price_next: FixedU128::from_inner(999_514_017_364_071_819),
+					amount_in: 9_970_000_000,
+					amount_out: 9_965_154_753,
+					fee_amount: 30_000_000,
+				}
+			);
+		}
+
+		#[test]
+		fn exact_out_within_range() {
+			let step = compute_swap_step(
+				sqrt_price_at_tick(0).unwrap(),
+				sqrt_price_at_tick(1_000).unwrap(),
+				liquidity(),
+				AMOUNT / 100,
+				Permill::from_perthousand(3),
+				false,
+			)
+			.unwrap();
+
+			assert_eq!(
+				step,
+				SwapStep {
+					sqrt_price_next: FixedU128::from_inner(1_000_487_919_925_566_143),
+					amount_in: 10_004_879_200,
+					amount_out: 10_000_000_000,
+					fee_amount: 30_104_953,
+				}
+			);
+		}
+
+		#[test]
+		fn should_stop_at_target_price() {
+			let target = sqrt_price_at_tick(-1_000).unwrap();
+			let step = compute_swap_step(
+				sqrt_price_at_tick(0).unwrap(),
+				target,
+				liquidity(),
+				10 * AMOUNT,
+				Permill::from_perthousand(3),
+				true,
+			)
+			.unwrap();
+
+			assert_eq!(step.sqrt_price_next, target);
+			// the whole base side of the range can be bought
+			assert_eq!(step.amount_out, AMOUNT - 1);
+			assert!(step.amount_in + step.fee_amount < 10 * AMOUNT);
+		}
+
+		#[test]
+		fn zero_liquidity_should_move_to_target() {
+			let target = sqrt_price_at_tick(-1_000).unwrap();
+			let step = compute_swap_step(
+				sqrt_price_at_tick(0).unwrap(),
+				target,
+				0,
+				AMOUNT,
+				Permill::from_perthousand(3),
+				true,
+			)
+			.unwrap();
+
+			assert_eq!(
+				step,
+				SwapStep { sqrt_price_next: target, amount_in: 0, amount_out: 0, fee_amount: 0 }
+			);
+		}
+	}
+}
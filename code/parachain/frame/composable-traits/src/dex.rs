@@ -1,4 +1,9 @@
-use crate::{currency::BalanceLike, defi::CurrencyPair, prelude::*};
+use crate::{
+	currency::BalanceLike,
+	defi::{CurrencyPair, Rate},
+	prelude::*,
+	time::DurationSeconds,
+};
 
 use frame_support::{
 	ensure,
@@ -7,8 +12,9 @@ use frame_support::{
 };
 
 use sp_runtime::{
-	helpers_128bit::multiply_by_rational_with_rounding, traits::Zero, BoundedBTreeMap,
-	DispatchError, Permill, Rational128,
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{AtLeast32BitUnsigned, Saturating, Zero},
+	BoundedBTreeMap, DispatchError, PerThing, Permill, Rational128, SaturatedConversion,
 };
 use sp_std::collections::btree_map::BTreeMap;
 
@@ -255,6 +261,15 @@ pub trait Amm {
 		calculate_with_fees: bool,
 	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError>;
 
+	/// Time weighted average price between `base_asset_id` and `quote_asset_id` of the pool over
+	/// the last `window` seconds, harder to manipulate than `spot_price`.
+	fn twap_over_window(
+		pool_id: Self::PoolId,
+		base_asset_id: Self::AssetId,
+		quote_asset_id: Self::AssetId,
+		window: DurationSeconds,
+	) -> Result<Rate, DispatchError>;
+
 	/// Deposit coins into the pool
 	/// `amounts` - list of amounts of coins to deposit,
 	/// `min_mint_amount` - minimum amount of LP tokens to mint from the deposit.
@@ -303,6 +318,7 @@ pub const REWARD_PERCENTAGE: u32 = 10;
 #[derive(
 	Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, Copy, RuntimeDebug,
 )]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Fee<AssetId, Balance> {
 	// total fee
 	pub fee: Balance,
@@ -422,6 +438,127 @@ pub struct BasicPoolInfo<
 	pub fee_config: FeeConfig,
 }
 
+/// Pool info for a StableSwap (Curve) pool. The weights in `pool_info` are always equal, the
+/// curvature of the invariant is set by the `amplification_coefficient` instead.
+#[derive(
+	Encode,
+	Decode,
+	MaxEncodedLen,
+	TypeInfo,
+	CloneNoBound,
+	Default,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+)]
+#[scale_info(skip_type_params(MaxAssets))]
+pub struct StableSwapPoolInfo<
+	AccountId: Clone + PartialEq + Debug,
+	AssetId: Ord + Clone + Debug,
+	MaxAssets: Get<u32>,
+> {
+	pub pool_info: BasicPoolInfo<AccountId, AssetId, MaxAssets>,
+	/// Amplification coefficient (`A`) of the invariant; higher values mean lower slippage
+	/// around the balanced point.
+	pub amplification_coefficient: u16,
+}
+
+/// State of a liquidity bootstrapping sale at a given block.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SaleState {
+	NotStarted,
+	Ongoing,
+	Ended,
+}
+
+/// What happens to a liquidity bootstrapping pool once the sale ended.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SaleEndBehaviour {
+	/// Trading is halted, only the owner can withdraw the remaining liquidity.
+	Close,
+	/// Trading continues with the final weights until the owner withdraws the liquidity.
+	Continue,
+}
+
+/// Weight schedule of a liquidity bootstrapping sale. The weight of the project token moves
+/// linearly from `initial_weight` to `final_weight` between the `start` and `end` blocks.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Sale<BlockNumber> {
+	/// Block at which the sale starts.
+	pub start: BlockNumber,
+	/// Block at which the sale ends.
+	pub end: BlockNumber,
+	/// Weight of the project token at `start`.
+	pub initial_weight: Permill,
+	/// Weight of the project token at `end`.
+	pub final_weight: Permill,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> Sale<BlockNumber> {
+	pub fn state(&self, current_block: BlockNumber) -> SaleState {
+		if current_block < self.start {
+			SaleState::NotStarted
+		} else if current_block < self.end {
+			SaleState::Ongoing
+		} else {
+			SaleState::Ended
+		}
+	}
+
+	/// Weights of the project and the collateral token at `current_block`, in that order.
+	pub fn current_weights(&self, current_block: BlockNumber) -> (Permill, Permill) {
+		let current_block = current_block.max(self.start).min(self.end);
+		let elapsed: u64 = current_block.saturating_sub(self.start).saturated_into();
+		let duration: u64 = self.end.saturating_sub(self.start).saturated_into();
+		let progress =
+			if duration == 0 { Permill::one() } else { Permill::from_rational(elapsed, duration) };
+
+		let project_weight = if self.initial_weight >= self.final_weight {
+			self.initial_weight
+				.saturating_sub(progress * self.initial_weight.saturating_sub(self.final_weight))
+		} else {
+			self.initial_weight
+				.saturating_add(progress * self.final_weight.saturating_sub(self.initial_weight))
+		};
+
+		(project_weight, project_weight.left_from_one())
+	}
+}
+
+/// Pool info for a liquidity bootstrapping pool. There are no LP tokens for these pools, only the
+/// owner can provide liquidity before the sale and withdraw it once the sale ended.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LiquidityBootstrappingPoolInfo<AccountId, AssetId, BlockNumber> {
+	/// Owner of pool
+	pub owner: AccountId,
+	/// `base` is the project token being sold, `quote` the collateral it is bought with
+	pub pair: CurrencyPair<AssetId>,
+	/// Weight schedule of the sale
+	pub sale: Sale<BlockNumber>,
+	/// Behaviour of the pool once the sale ended
+	pub sale_end_behaviour: SaleEndBehaviour,
+	/// Amount of the fee pool charges for the exchange
+	pub fee_config: FeeConfig,
+}
+
+/// Pool info for a concentrated liquidity pool. Liquidity is provided within tick ranges, each
+/// position being represented by a financial NFT of `position_collection_id` instead of fungible
+/// LP tokens.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ConcentratedLiquidityPoolInfo<AccountId, AssetId> {
+	/// Owner of pool
+	pub owner: AccountId,
+	/// `base` and `quote` of the pool, prices are expressed in `quote` per `base`
+	pub pair: CurrencyPair<AssetId>,
+	/// Position bounds must be multiples of this value
+	pub tick_spacing: u32,
+	/// Collection of the financial NFTs representing the positions of the pool
+	pub position_collection_id: AssetId,
+	/// Amount of the fee pool charges for the exchange. Its LP share is distributed to in range
+	/// positions, the owner and protocol shares are accrued to the pool like in other pools.
+	pub fee_config: FeeConfig,
+}
+
 /// Describes route for DEX.
 /// `Direct` gives vector of pool_id to use as router.
 #[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
@@ -452,9 +589,53 @@ pub struct PriceAggregate<PoolId, AssetId, Balance> {
 	pub spot_price: Balance, // prices based on any other stat such as TWAP goes here..
 }
 
+/// Outcome of a swap or buy simulated against the current state of a pool.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SwapSimulation<AssetId, Balance> {
+	/// Amount sent to the pool, fees included.
+	pub amount_in: AssetAmount<AssetId, Balance>,
+	/// Amount received from the pool.
+	pub amount_out: AssetAmount<AssetId, Balance>,
+	/// Fees charged by the pool.
+	pub fee: Fee<AssetId, Balance>,
+	/// How much worse the execution price, fees excluded, is than the spot price of the pool.
+	pub price_impact: Permill,
+}
+
+/// Outcome of a liquidity provision or removal simulated against the current state of a pool.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct LiquiditySimulation<AssetId: Ord, Balance> {
+	/// Amounts deposited to, or withdrawn from, the pool.
+	pub assets: BTreeMap<AssetId, Balance>,
+	/// Amount of LP tokens minted, or burnt.
+	pub lp_amount: Balance,
+	/// Fee charged on single asset and imbalanced deposits. It is left in the pool for its
+	/// liquidity providers, StableSwap pools withhold it in LP tokens. Balanced deposits and
+	/// withdrawals are not charged.
+	pub fee: Option<Fee<AssetId, Balance>>,
+	/// How much worse the LP token price, fees excluded, is than the current one of the pool.
+	pub price_impact: Permill,
+}
+
+/// Route chosen by a router to exchange one asset for another.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct RouteQuote<PoolId, Balance> {
+	/// Pools to swap with, in order.
+	pub route: Vec<PoolId>,
+	/// Amount expected to be received at the end of the route, net of fees and of the price
+	/// impact of each hop.
+	pub amount_out: Balance,
+	/// Whether every pool was considered. Routers bounding their search may miss a better route
+	/// through the pools left out.
+	pub all_pools_searched: bool,
+}
+
 #[cfg(test)]
 mod tests {
-	use crate::dex::{Fee, FeeConfig};
+	use crate::dex::{Fee, FeeConfig, Sale, SaleState};
 	use sp_arithmetic::Permill;
 	use std::ops::Mul;
 
@@ -501,6 +682,50 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn sale_state() {
+		let sale = Sale {
+			start: 100_u64,
+			end: 200,
+			initial_weight: Permill::from_percent(90),
+			final_weight: Permill::from_percent(50),
+		};
+		assert_eq!(sale.state(0), SaleState::NotStarted);
+		assert_eq!(sale.state(99), SaleState::NotStarted);
+		assert_eq!(sale.state(100), SaleState::Ongoing);
+		assert_eq!(sale.state(199), SaleState::Ongoing);
+		assert_eq!(sale.state(200), SaleState::Ended);
+	}
+
+	#[test]
+	fn sale_current_weights() {
+		let sale = Sale {
+			start: 100_u64,
+			end: 200,
+			initial_weight: Permill::from_percent(90),
+			final_weight: Permill::from_percent(50),
+		};
+		assert_eq!(sale.current_weights(0), (Permill::from_percent(90), Permill::from_percent(10)));
+		assert_eq!(
+			sale.current_weights(150),
+			(Permill::from_percent(70), Permill::from_percent(30))
+		);
+		assert_eq!(
+			sale.current_weights(200),
+			(Permill::from_percent(50), Permill::from_percent(50))
+		);
+		assert_eq!(
+			sale.current_weights(1_000),
+			(Permill::from_percent(50), Permill::from_percent(50))
+		);
+
+		let increasing = Sale { initial_weight: Permill::from_percent(20), ..sale };
+		assert_eq!(
+			increasing.current_weights(160),
+			(Permill::from_percent(38), Permill::from_percent(62))
+		);
+	}
 }
 
 #[derive(
@@ -7,11 +7,14 @@ use scale_info::TypeInfo;
 use sp_std::{cmp::Ordering, convert::TryInto};
 
 use sp_runtime::{
-	traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, Saturating, Zero},
+	traits::{
+		AtLeast32BitUnsigned, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One,
+		SaturatedConversion, Saturating, Zero,
+	},
 	ArithmeticError, FixedI128, FixedPointNumber, FixedU128, RuntimeDebug,
 };
 
-use sp_arithmetic::per_things::Percent;
+use sp_arithmetic::per_things::{Percent, Perquintill};
 
 use crate::{
 	defi::{LiftedFixedBalance, Rate, ZeroToOneFixedU128},
@@ -151,6 +154,63 @@ impl InterestRate for InterestRateModel {
 	}
 }
 
+/// Gradual change of the interest rate model of a market to `target`, from block `start` to block
+/// `end`.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+pub struct InterestRateModelRamp<BlockNumber> {
+	pub target: InterestRateModel,
+	pub start: BlockNumber,
+	pub end: BlockNumber,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> InterestRateModelRamp<BlockNumber> {
+	/// Part of the ramp elapsed at block `now`.
+	pub fn progress(&self, now: BlockNumber) -> Perquintill {
+		if now >= self.end {
+			return Perquintill::one()
+		}
+		Perquintill::from_rational(
+			now.saturating_sub(self.start).saturated_into::<u64>(),
+			self.end.saturating_sub(self.start).saturated_into::<u64>(),
+		)
+	}
+
+	/// Interest rate model moving from `from` to the target of the ramp, at block `now`.
+	pub fn ramped<'a>(
+		&'a mut self,
+		from: &'a mut InterestRateModel,
+		now: BlockNumber,
+	) -> RampedInterestRateModel<'a> {
+		RampedInterestRateModel { progress: self.progress(now), from, to: &mut self.target }
+	}
+}
+
+/// Interest rate model whose borrow rate is linearly interpolated between the rates of `from` and
+/// `to`, `progress` of the way to `to`.
+pub struct RampedInterestRateModel<'a> {
+	pub from: &'a mut InterestRateModel,
+	pub to: &'a mut InterestRateModel,
+	pub progress: Perquintill,
+}
+
+impl InterestRate for RampedInterestRateModel<'_> {
+	fn get_borrow_rate(&mut self, utilization: Percent) -> Option<Rate> {
+		// both models are queried, so that stateful models keep track of utilization
+		let from = self.from.get_borrow_rate(utilization)?;
+		let to = self.to.get_borrow_rate(utilization)?;
+		if to >= from {
+			from.checked_add(&Rate::from_inner(
+				self.progress.mul_floor(to.saturating_sub(from).into_inner()),
+			))
+		} else {
+			from.checked_sub(&Rate::from_inner(
+				self.progress.mul_floor(from.saturating_sub(to).into_inner()),
+			))
+		}
+	}
+}
+
 /// The jump interest rate model
 #[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Default, TypeInfo)]
@@ -438,3 +498,22 @@ pub fn increment_borrow_rate(
 		.safe_mul(&FixedU128::saturating_from_integer(delta_time))?
 		.safe_div(&FixedU128::saturating_from_integer(SECONDS_PER_YEAR_NAIVE))
 }
+
+/// Yearly yield of `rate` compounded continuously, that is `e^rate - 1`.
+///
+/// Interest is accrued to the borrow index every block, which is close enough to continuous
+/// compounding.
+pub fn compounded_yearly_yield(rate: Rate) -> Result<Rate, ArithmeticError> {
+	// e^rate - 1 = rate + rate^2/2! + rate^3/3! + ...
+	const MAX_TERMS: u32 = 32;
+	let mut term = rate;
+	let mut result = Rate::zero();
+	for n in 2..=MAX_TERMS + 1 {
+		if term.is_zero() {
+			break
+		}
+		result = result.safe_add(&term)?;
+		term = term.safe_mul(&rate)?.safe_div(&Rate::saturating_from_integer(n))?;
+	}
+	Ok(result)
+}
@@ -10,6 +10,8 @@ use crate::{
 };
 use frame_support::{pallet_prelude::*, sp_std::vec::Vec};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_runtime::{traits::Zero, Percent, Perquintill};
 
 use self::math::*;
@@ -31,26 +33,53 @@ pub type LendAssetAmountOf<T> = <T as DeFiEngine>::Balance;
 
 pub type BorrowAmountOf<T> = <T as DeFiEngine>::Balance;
 
+/// Operations of a market which are paused by its manager.
+#[derive(
+	Encode, Decode, MaxEncodedLen, Default, TypeInfo, RuntimeDebug, Clone, Copy, PartialEq, Eq,
+)]
+pub struct PausedOperations {
+	/// Depositing collateral and lending borrow asset through the market's `vault_deposit`.
+	/// Deposits made to the market's vault directly are not paused.
+	pub deposit: bool,
+	/// Borrowing and taking flash loans of the borrow asset.
+	pub borrow: bool,
+	/// Withdrawing collateral and lent borrow asset through the market's `vault_withdraw`.
+	/// Withdrawals made from the market's vault directly are not paused.
+	pub withdraw: bool,
+	pub liquidate: bool,
+}
+
 #[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
-pub struct UpdateInput<LiquidationStrategyId, BlockNumber> {
+pub struct UpdateInput<BlockNumber, Balance> {
 	/// Collateral factor of market
 	pub collateral_factor: MoreThanOneFixedU128,
 	/// warn borrower when loan's collateral/debt ratio
 	/// given percentage short to be under collateralized
 	pub under_collateralized_warn_percent: Percent,
-	/// liquidation engine id
-	pub liquidators: Vec<LiquidationStrategyId>,
 	/// Count of blocks until throw error PriceIsTooOld
 	pub max_price_age: BlockNumber,
+	/// Maximum part of a borrower's debt which can be repaid in a single liquidation
+	pub close_factor: Percent,
+	/// Bonus on top of the repaid debt value which is seized from the borrower's collateral
+	pub liquidation_incentive: Percent,
+	/// Maximum amount of collateral asset deposited into market, unlimited if `None`
+	pub supply_cap: Option<Balance>,
+	/// Maximum amount of debt, interest included, owed to market, unlimited if `None`
+	pub borrow_cap: Option<Balance>,
+	/// Maximum debt, interest included, a single account may owe to market. Collateral of a market
+	/// with a debt ceiling is isolated, so it can not be part of a portfolio
+	pub debt_ceiling: Option<Balance>,
+	/// Operations which can not be performed on market
+	pub paused: PausedOperations,
 }
 
 /// input to create market extrinsic
 ///
 /// Input to [`Lending::create()`].
 #[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
-pub struct CreateInput<LiquidationStrategyId, AssetId, BlockNumber> {
+pub struct CreateInput<AssetId, BlockNumber, Balance> {
 	/// the part of market which can be changed
-	pub updatable: UpdateInput<LiquidationStrategyId, BlockNumber>,
+	pub updatable: UpdateInput<BlockNumber, Balance>,
 	/// collateral currency and borrow currency
 	/// in case of liquidation, collateral is base and borrow is quote
 	pub currency_pair: CurrencyPair<AssetId>,
@@ -59,9 +88,7 @@ pub struct CreateInput<LiquidationStrategyId, AssetId, BlockNumber> {
 	pub interest_rate_model: InterestRateModel,
 }
 
-impl<LiquidationStrategyId, AssetId: Copy, BlockNumber>
-	CreateInput<LiquidationStrategyId, AssetId, BlockNumber>
-{
+impl<AssetId: Copy, BlockNumber, Balance> CreateInput<AssetId, BlockNumber, Balance> {
 	pub fn borrow_asset(&self) -> AssetId {
 		self.currency_pair.quote
 	}
@@ -75,7 +102,7 @@ impl<LiquidationStrategyId, AssetId: Copy, BlockNumber>
 }
 
 #[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug)]
-pub struct MarketConfig<VaultId, AssetId, AccountId, LiquidationStrategyId, BlockNumber> {
+pub struct MarketConfig<VaultId, AssetId, AccountId, BlockNumber, Balance> {
 	/// The owner of this market.
 	pub manager: AccountId,
 	/// The vault containing the borrow asset.
@@ -87,7 +114,18 @@ pub struct MarketConfig<VaultId, AssetId, AccountId, LiquidationStrategyId, Bloc
 	pub collateral_factor: MoreThanOneFixedU128,
 	pub interest_rate_model: InterestRateModel,
 	pub under_collateralized_warn_percent: Percent,
-	pub liquidators: Vec<LiquidationStrategyId>,
+	/// Maximum part of a borrower's debt which can be repaid in a single liquidation.
+	pub close_factor: Percent,
+	/// Bonus on top of the repaid debt value which is seized from the borrower's collateral.
+	pub liquidation_incentive: Percent,
+	/// Maximum amount of collateral asset deposited into the market.
+	pub supply_cap: Option<Balance>,
+	/// Maximum amount of debt, interest included, owed to the market.
+	pub borrow_cap: Option<Balance>,
+	/// Maximum debt a single account may owe to the market. Markets with a debt ceiling are
+	/// isolated.
+	pub debt_ceiling: Option<Balance>,
+	pub paused: PausedOperations,
 }
 
 /// Different ways that a market can be repaid.
@@ -182,6 +220,31 @@ impl<T> TotalDebtWithInterest<T> {
 	}
 }
 
+/// Collateral deposited and debt owed by an account on a market.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AccountPosition<MarketId, AssetId, Balance> {
+	pub market_id: MarketId,
+	pub collateral_asset: AssetId,
+	/// Amount of collateral asset deposited by the account.
+	pub collateral_amount: Balance,
+	pub borrow_asset: AssetId,
+	/// Principal borrowed by the account plus the interest accrued on it.
+	pub debt_with_interest: Balance,
+}
+
+/// Utilization and yearly interest rates of a market.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MarketRates<Rate> {
+	/// Part of the market's liquidity which is borrowed.
+	pub utilization: Percent,
+	/// Yield of borrowing for a year, with interest compounded.
+	pub borrow_apy: Rate,
+	/// Yield of lending for a year, with interest compounded.
+	pub supply_apy: Rate,
+}
+
 /// Basic lending with no its own wrapper (liquidity) token.
 ///  User will deposit borrow and collateral assets via `Vault`.
 /// `Liquidation` is other trait.
@@ -192,8 +255,6 @@ pub trait Lending: DeFiEngine {
 	type VaultId;
 	type MarketId;
 	type BlockNumber;
-	/// id of dispatch used to liquidate collateral in case of undercollateralized asset
-	type LiquidationStrategyId;
 	type Oracle: OracleTrait;
 	type MaxLiquidationBatchSize;
 
@@ -238,14 +299,14 @@ pub trait Lending: DeFiEngine {
 	/// Returned `MarketId` is mapped one to one with (deposit VaultId, collateral VaultId)
 	fn create_market(
 		manager: Self::AccountId,
-		config: CreateInput<Self::LiquidationStrategyId, Self::MayBeAssetId, Self::BlockNumber>,
+		config: CreateInput<Self::MayBeAssetId, Self::BlockNumber, Self::Balance>,
 		keep_alive: bool,
 	) -> Result<(Self::MarketId, Self::VaultId), DispatchError>;
 
 	fn update_market(
 		manager: Self::AccountId,
 		market_id: Self::MarketId,
-		input: UpdateInput<Self::LiquidationStrategyId, Self::BlockNumber>,
+		input: UpdateInput<Self::BlockNumber, Self::Balance>,
 	) -> Result<(), DispatchError>;
 
 	/// [`AccountId`][Self::AccountId] of the market instance
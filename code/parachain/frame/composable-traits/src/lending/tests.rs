@@ -71,6 +71,53 @@ fn get_supply_rate_works() {
 	);
 }
 
+#[test]
+fn compounded_yearly_yield_works() {
+	assert_eq!(compounded_yearly_yield(Rate::zero()), Ok(Rate::zero()));
+	// e^0.1 - 1 = 0.10517091807...
+	let apy = compounded_yearly_yield(Rate::saturating_from_rational(10, 100)).unwrap();
+	assert!(apy > Rate::saturating_from_rational(105_170, 1_000_000));
+	assert!(apy < Rate::saturating_from_rational(105_171, 1_000_000));
+	// e - 1 = 1.71828182845...
+	let apy = compounded_yearly_yield(Rate::one()).unwrap();
+	assert!(apy > Rate::saturating_from_rational(1_718_281, 1_000_000));
+	assert!(apy < Rate::saturating_from_rational(1_718_282, 1_000_000));
+}
+
+#[test]
+fn ramped_interest_rate_model_interpolates_borrow_rate() {
+	let jump_model = |base_rate| {
+		InterestRateModel::new_jump_model(
+			Rate::saturating_from_rational(base_rate, 100),
+			Rate::saturating_from_rational(10, 100),
+			Rate::saturating_from_rational(32, 100),
+			Percent::from_percent(80),
+		)
+		.unwrap()
+	};
+	let mut from = jump_model(2);
+	let mut ramp = InterestRateModelRamp { target: jump_model(6), start: 10_u64, end: 20 };
+
+	assert_eq!(ramp.progress(5), Perquintill::zero());
+	assert_eq!(ramp.progress(15), Perquintill::from_percent(50));
+	assert_eq!(ramp.progress(25), Perquintill::one());
+	assert_eq!(
+		ramp.ramped(&mut from, 15).get_borrow_rate(Percent::zero()),
+		Some(Rate::saturating_from_rational(4, 100))
+	);
+	assert_eq!(
+		ramp.ramped(&mut from, 20).get_borrow_rate(Percent::zero()),
+		Some(Rate::saturating_from_rational(6, 100))
+	);
+
+	// ramping down
+	let mut ramp = InterestRateModelRamp { target: jump_model(2), start: 10_u64, end: 20 };
+	assert_eq!(
+		ramp.ramped(&mut jump_model(6), 15).get_borrow_rate(Percent::zero()),
+		Some(Rate::saturating_from_rational(4, 100))
+	);
+}
+
 #[test]
 fn curve_model_correctly_calculates_borrow_rate() {
 	let mut model = CurveModel::new(Rate::saturating_from_rational(2, 100)).unwrap();
@@ -3,9 +3,12 @@ use crate::{
 	defi::{CurrencyPair, Ratio},
 };
 use frame_support::{dispatch::DispatchError, pallet_prelude::*};
+use serde::{Deserialize, Serialize};
+use sp_runtime::Percent;
 
 // block timestamped value
 #[derive(Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Price<PriceValue, BlockNumber> {
 	/// value
 	pub price: PriceValue,
@@ -14,6 +17,7 @@ pub struct Price<PriceValue, BlockNumber> {
 
 /// Statistics useful for oracle rewarding and tracking.
 #[derive(Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct RewardTracker<Balance, Timestamp> {
 	/// Period which rewarding resets. Most likely a year.
 	pub period: Timestamp,
@@ -27,6 +31,45 @@ pub struct RewardTracker<Balance, Timestamp> {
 	pub total_reward_weight: Balance,
 }
 
+/// Price submitted by a signer, waiting to be aggregated.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, TypeInfo, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SubmittedPrice<AccountId, PriceValue, BlockNumber> {
+	pub who: AccountId,
+	pub price: PriceValue,
+	pub block: BlockNumber,
+}
+
+/// Aggregation and rewarding configuration of an asset priced by the oracle.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, TypeInfo, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct OracleAssetInfo<AssetId, BlockNumber, Balance> {
+	pub asset_id: AssetId,
+	/// Percent close to the aggregated price an answer must be to be rewarded.
+	pub threshold: Percent,
+	pub min_answers: u32,
+	pub max_answers: u32,
+	pub block_interval: BlockNumber,
+	pub reward_weight: Balance,
+	pub slash: Balance,
+	pub emit_price_changes: bool,
+}
+
+/// Stake of a signer and what it was rewarded and slashed so far.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, TypeInfo, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SignerStats<AccountId, Balance, BlockNumber> {
+	pub signer: AccountId,
+	pub controller: AccountId,
+	pub stake: Balance,
+	/// Stake that would be slashed if all answers waiting to be aggregated were wrong.
+	pub answer_in_transit: Balance,
+	/// Stake declared to be withdrawn and the block it unlocks at.
+	pub declared_withdraw: Option<(Balance, BlockNumber)>,
+	pub total_rewarded: Balance,
+	pub total_slashed: Balance,
+}
+
 /// An object that is able to provide an asset price.
 /// Important: the current price-feed is providing prices in USDT only.
 pub trait Oracle {
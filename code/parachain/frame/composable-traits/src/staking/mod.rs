@@ -303,6 +303,14 @@ pub trait Staking {
 		ratio: Permill,
 	) -> Result<Self::PositionId, DispatchError>;
 
+	/// Merge `merged_position` into `position`, both of the same pool. The merged position is
+	/// removed.
+	fn merge(
+		who: &Self::AccountId,
+		position: &Self::PositionId,
+		merged_position: &Self::PositionId,
+	) -> DispatchResult;
+
 	/// Claim remaining reward earned up to this point in time.
 	///
 	/// Arguments
@@ -1,12 +1,13 @@
 use codec::Codec;
 use frame_support::{
 	pallet_prelude::*,
-	sp_runtime::Perquintill,
+	sp_runtime::{traits::Zero, Perquintill},
 	sp_std::{collections::btree_map::BTreeMap, fmt::Debug},
 };
 use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
 
-use crate::defi::Rate;
+use crate::{defi::Rate, time::Timestamp};
 
 /// An indication for strategies as to how they should be rebalancing. Strategies should evaluate if
 /// it is worth it to deposit or withdraw based on fees.
@@ -60,6 +61,31 @@ where
 	pub strategies: BTreeMap<AccountId, Perquintill>,
 }
 
+/// The amount of underlying assets one LP token of a vault was worth at some block.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq, MaxEncodedLen, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct SharePriceCheckpoint<BlockNumber, Price> {
+	pub block: BlockNumber,
+	/// Unix time in seconds.
+	pub timestamp: Timestamp,
+	pub price: Price,
+}
+
+/// Allocation of the funds of a vault to a strategy and what the strategy made out of them.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StrategyReport<AccountId, Balance, SignedRate> {
+	pub strategy: AccountId,
+	pub allocation: Perquintill,
+	/// The reported balance of the strategy.
+	pub balance: Balance,
+	pub lifetime_withdrawn: Balance,
+	pub lifetime_deposited: Balance,
+	/// Funds returned and still held by the strategy over funds withdrawn, minus one. `None` if
+	/// the strategy never withdrew funds.
+	pub return_rate: Option<SignedRate>,
+}
+
 pub trait Vault {
 	type AccountId: core::cmp::Ord;
 	type AssetId;
@@ -185,6 +211,55 @@ pub trait StrategicVault: Vault {
 	) -> Result<(), DispatchError>;
 }
 
+/// A yield strategy implemented by a pallet, such as providing liquidity to a DEX, supplying a
+/// lending market or staking. Vaults allocating funds to an account managed by a strategy push and
+/// pull funds through it when rebalancing, instead of waiting for the strategy to poll
+/// [`StrategicVault::available_funds`].
+///
+/// Funds are moved between the vault and the strategy account by the vault, the strategy only
+/// deploys and frees them.
+pub trait VaultStrategy<AccountId, AssetId, Balance> {
+	/// Whether `strategy` is an account managed by this implementation.
+	fn manages(strategy: &AccountId) -> bool;
+
+	/// Claims the yield accrued by the funds of `asset_id` deployed by `strategy`. Claimed yield
+	/// stays with the strategy and is part of its [`balance`](VaultStrategy::balance).
+	fn harvest(strategy: &AccountId, asset_id: AssetId) -> Result<Balance, DispatchError>;
+
+	/// Value of the funds of `asset_id` held by `strategy`, deployed or not.
+	fn balance(strategy: &AccountId, asset_id: AssetId) -> Result<Balance, DispatchError>;
+
+	/// Deploys `amount` of `asset_id` transferred by the vault to `strategy`.
+	fn deposit(strategy: &AccountId, asset_id: AssetId, amount: Balance) -> DispatchResult;
+
+	/// Frees `amount` of `asset_id` deployed by `strategy` into its account, for the vault to take
+	/// back.
+	fn withdraw(strategy: &AccountId, asset_id: AssetId, amount: Balance) -> DispatchResult;
+}
+
+/// No strategy managed on-chain, strategies of vaults are left to rebalance themselves.
+impl<AccountId, AssetId, Balance: Zero> VaultStrategy<AccountId, AssetId, Balance> for () {
+	fn manages(_strategy: &AccountId) -> bool {
+		false
+	}
+
+	fn harvest(_strategy: &AccountId, _asset_id: AssetId) -> Result<Balance, DispatchError> {
+		Ok(Balance::zero())
+	}
+
+	fn balance(_strategy: &AccountId, _asset_id: AssetId) -> Result<Balance, DispatchError> {
+		Ok(Balance::zero())
+	}
+
+	fn deposit(_strategy: &AccountId, _asset_id: AssetId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+
+	fn withdraw(_strategy: &AccountId, _asset_id: AssetId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+}
+
 /// A vault which allow the strategy to do periodic report.
 pub trait ReportableStrategicVault: StrategicVault {
 	type Report;
@@ -0,0 +1,83 @@
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::dex::{AssetAmount, RouteQuote};
+use core::{fmt::Display, str::FromStr};
+use dex_router_runtime_api::DexRouterRuntimeApi;
+use jsonrpsee::{
+	core::{Error as RpcError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use sp_std::sync::Arc;
+
+#[rpc(client, server)]
+pub trait DexRouterApi<BlockHash, PoolId, AssetId, Balance>
+where
+	PoolId: FromStr + Display,
+	AssetId: FromStr + Display,
+	Balance: FromStr + Display,
+{
+	#[method(name = "dexRouter_bestRoute")]
+	fn best_route(
+		&self,
+		in_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		out_asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<RouteQuote<SafeRpcWrapper<PoolId>, SafeRpcWrapper<Balance>>>;
+}
+
+pub struct DexRouter<C, Block> {
+	client: Arc<C>,
+	_marker: sp_std::marker::PhantomData<Block>,
+}
+
+impl<C, M> DexRouter<C, M> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, PoolId, AssetId, Balance>
+	DexRouterApiServer<<Block as BlockT>::Hash, PoolId, AssetId, Balance>
+	for DexRouter<C, (Block, PoolId, AssetId, Balance)>
+where
+	Block: BlockT,
+	PoolId: Send + Sync + 'static + Codec + FromStr + Display,
+	AssetId: Send + Sync + 'static + Codec + FromStr + Display,
+	Balance: Send + Sync + 'static + Codec + FromStr + Display,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: DexRouterRuntimeApi<Block, PoolId, AssetId, Balance>,
+{
+	fn best_route(
+		&self,
+		in_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		out_asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<RouteQuote<SafeRpcWrapper<PoolId>, SafeRpcWrapper<Balance>>> {
+		let api = self.client.runtime_api();
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		// calling ../../runtime-api
+		api.best_route(at, in_asset, out_asset_id)
+			.map_err(|e| {
+				RpcError::Call(CallError::Custom(ErrorObject::owned(
+					9876,
+					"Something wrong",
+					Some(format!("{:?}", e)),
+				)))
+			})?
+			.map_err(|e| {
+				RpcError::Call(CallError::Custom(ErrorObject::owned(
+					9877,
+					"No route found",
+					Some(format!("{:?}", e)),
+				)))
+			})
+	}
+}
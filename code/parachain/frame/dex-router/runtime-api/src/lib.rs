@@ -0,0 +1,27 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::dex::{AssetAmount, RouteQuote};
+use sp_runtime::DispatchError;
+
+// DEX Router Runtime API declaration. Implemented for each runtime at
+// `runtime/<runtime-name>/src/lib.rs`.
+sp_api::decl_runtime_apis! {
+	pub trait DexRouterRuntimeApi<PoolId, AssetId, Balance>
+	where
+		PoolId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// Retrieve the route with the greatest expected output when exchanging `in_asset` for
+		/// `out_asset_id`, among the paths through the searched DEX pools and the stored route of
+		/// the pair. The quote tells whether some pools were left out of the search.
+		fn best_route(
+			in_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+			out_asset_id: SafeRpcWrapper<AssetId>,
+		) -> Result<RouteQuote<SafeRpcWrapper<PoolId>, SafeRpcWrapper<Balance>>, DispatchError>;
+	}
+}
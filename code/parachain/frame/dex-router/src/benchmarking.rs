@@ -196,6 +196,52 @@ where
 	(currency_pair, dex_route)
 }
 
+/// Fills the underlying DEX up to `MaxPoolsInRouteSearch` pools, adding pools in parallel to the
+/// ones of [`create_pools_route`] so that every pool is evaluated while searching for the best
+/// route, over as many hops as the route has.
+fn create_parallel_pools<T>()
+where
+	T: pallet_dex_router::Config + pallet_pablo::Config,
+	<T as pallet_pablo::Config>::Balance: From<u128>,
+	<T as pallet_pablo::Config>::AssetId: From<u128>,
+{
+	let unit = 1_000_000_000_000_u128;
+	let owner: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	// pica, ksm, eth, usdc and usdt, with the reserves of the pools between consecutive ones
+	let pairs = [
+		(100_u128, 101_u128, 100 * 1000 * unit, 1000 * unit),
+		(101, 102, 10 * 1000 * unit, 1000 * unit),
+		(102, 103, 1000 * unit, 200 * 1000 * unit),
+		(103, 104, 1000 * unit, 1000 * unit),
+	];
+	let existing_pools = pairs.len() as u32;
+	for i in 0..<T as pallet_dex_router::Config>::MaxPoolsInRouteSearch::get()
+		.saturating_sub(existing_pools)
+	{
+		let (first, second, first_amount, second_amount) = pairs[i as usize % pairs.len()];
+		let (first, second) = (first.into(), second.into());
+		let config = PoolInitConfiguration::DualAssetConstantProduct {
+			owner: owner.clone(),
+			fee: Permill::zero(),
+			assets_weights: dual_asset_pool_weights::<T>(first, Permill::from_percent(50), second),
+		};
+		let lp_token_id = (1004 + i as u128).into();
+		let pool_id = pallet_pablo::Pallet::<T>::do_create_pool(config, Some(lp_token_id)).unwrap();
+		<T as pallet_pablo::Config>::Assets::mint_into(first, &owner, first_amount.into())
+			.expect("mint failed");
+		<T as pallet_pablo::Config>::Assets::mint_into(second, &owner, second_amount.into())
+			.expect("mint failed");
+		<pallet_pablo::Pallet<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(first, first_amount.into()), (second, second_amount.into())]),
+			0_u128.into(),
+			false,
+		)
+		.expect("add_liquidity to parallel pool failed");
+	}
+}
+
 benchmarks! {
 	impl_benchmark_test_suite!(DexRouter, crate::mock::new_test_ext(), crate::mock::Test);
 
@@ -230,6 +276,41 @@ benchmarks! {
 		// exchange 1000 PICA via route
 	} : _(origin, AssetAmount::new(pica_, (1000_u128 * unit).into()), AssetAmount::new(usdt, 0_u128.into()))
 
+	swap_best_route {
+		let unit = 1_000_000_000_000_u128;
+		let pica_amount =  2000_u128 * unit;
+		let (_currency_pair, _dex_route) = create_pools_route::<T>();
+		// worst case, the search goes through `MaxPoolsInRouteSearch` connected pools
+		create_parallel_pools::<T>();
+		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
+		let origin = RawOrigin::Signed(owner.clone());
+		let pica : <T as pallet_pablo::Config>::AssetId = 100_u128.into();
+		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
+		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
+		<T as pallet_pablo::Config>::Assets::mint_into(pica, &owner, pica_amount.into()).expect("Mint pica failed");
+		// exchange 1000 PICA via the best route found among the pools
+	} : _(origin, AssetAmount::new(pica_, (1000_u128 * unit).into()), AssetAmount::new(usdt, 0_u128.into()))
+
+	swap_split {
+		let r in 1 .. <T as pallet_dex_router::Config>::MaxSplitRoutes::get();
+		let unit = 1_000_000_000_000_u128;
+		let pica_amount =  2000_u128 * unit;
+		let (_currency_pair, dex_route) = create_pools_route::<T>();
+		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
+		let origin = RawOrigin::Signed(owner.clone());
+		let pica : <T as pallet_pablo::Config>::AssetId = 100_u128.into();
+		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
+		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
+		<T as pallet_pablo::Config>::Assets::mint_into(pica, &owner, pica_amount.into()).expect("Mint pica failed");
+		// same route r times, the last one taking what is left
+		let share = Permill::from_rational(1, r);
+		let last_share = Permill::one() - Permill::from_parts(share.deconstruct() * (r - 1));
+		let routes = (0..r)
+			.map(|i| (dex_route.clone().try_into().unwrap(), if i + 1 == r { last_share } else { share }))
+			.collect::<Vec<_>>();
+		// exchange 1000 PICA split across r routes
+	} : _(origin, AssetAmount::new(pica_, (1000_u128 * unit).into()), AssetAmount::new(usdt, 0_u128.into()), routes.try_into().unwrap())
+
 	buy {
 		let unit = 1_000_000_000_000_u128;
 		let usdc_amount =  20_u128 * 100 * unit; // 1 pica = 20 usdc based on liquidity added while pool creation
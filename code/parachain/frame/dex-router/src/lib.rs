@@ -31,15 +31,16 @@ pub mod pallet {
 	use crate::pool_id_pair;
 	use composable_support::math::safe::SafeArithmetic;
 	use composable_traits::{
-		defi::CurrencyPair,
-		dex::{Amm, AssetAmount, DexRoute, DexRouter, SwapResult},
+		defi::{CurrencyPair, Rate},
+		dex::{Amm, AssetAmount, DexRoute, DexRouter, RouteQuote, SwapResult},
+		time::DurationSeconds,
 	};
 	use core::fmt::Debug;
 	use frame_support::{pallet_prelude::*, transactional, PalletId};
 	use frame_system::{ensure_signed, pallet_prelude::OriginFor};
 	use sp_arithmetic::Permill;
 	use sp_runtime::{
-		traits::{CheckedAdd, One, Zero},
+		traits::{AtLeast32BitUnsigned, CheckedAdd, One, Zero},
 		DispatchResult,
 	};
 	use sp_std::{
@@ -67,10 +68,18 @@ pub mod pallet {
 			+ Copy
 			+ Zero
 			+ Ord
-			+ SafeArithmetic;
+			+ SafeArithmetic
+			+ AtLeast32BitUnsigned;
 		/// The maximum hops in the route.
 		#[pallet::constant]
 		type MaxHopsInRoute: Get<u32> + MaxEncodedLen + TypeInfo;
+		/// The maximum number of pools, in order of pool id, considered when searching for the best
+		/// route at call time.
+		#[pallet::constant]
+		type MaxPoolsInRouteSearch: Get<u32>;
+		/// The maximum number of routes an order can be split across.
+		#[pallet::constant]
+		type MaxSplitRoutes: Get<u32>;
 		type PoolId: FullCodec
 			+ MaxEncodedLen
 			+ Default
@@ -103,6 +112,20 @@ pub mod pallet {
 	#[pallet::generate_store(trait Store)]
 	pub struct Pallet<T>(_);
 
+	/// Routes of a split swap, each with the share of the order to be swapped through it.
+	pub type SplitRoutesOf<T> = BoundedVec<
+		(BoundedVec<<T as Config>::PoolId, <T as Config>::MaxHopsInRoute>, Permill),
+		<T as Config>::MaxSplitRoutes,
+	>;
+
+	/// Part of a split swap executed through a single route.
+	#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct RouteFill<PoolId, Balance> {
+		pub route: Vec<PoolId>,
+		pub amount_in: Balance,
+		pub amount_out: Balance,
+	}
+
 	#[pallet::storage]
 	pub type DexRoutes<T: Config> = StorageDoubleMap<
 		_,
@@ -130,6 +153,8 @@ pub mod pallet {
 		LoopSuspectedInRouteUpdate,
 		/// Only dual asset pools supported
 		OnlyDualAssetPoolsSupported,
+		/// Shares of a split swap must be non zero and add up to 100%.
+		InvalidSplitShares,
 	}
 
 	#[pallet::event]
@@ -151,6 +176,12 @@ pub mod pallet {
 			old_route: Vec<T::PoolId>,
 			updated_route: Vec<T::PoolId>,
 		},
+		SplitSwapExecuted {
+			who: T::AccountId,
+			in_asset_id: T::AssetId,
+			out_asset_id: T::AssetId,
+			fills: Vec<RouteFill<T::PoolId, T::Balance>>,
+		},
 	}
 
 	#[pallet::call]
@@ -252,9 +283,263 @@ pub mod pallet {
 			<Self as Amm>::remove_liquidity(&who, asset_pair, lp_amount, min_receive)?;
 			Ok(())
 		}
+
+		/// Exchange `in_asset` for at least `min_receive` via the best route found at call time,
+		/// see [`Pallet::best_route`].
+		/// On successful underlying DEX pallets will emit appropriate event.
+		#[pallet::weight(T::WeightInfo::swap_best_route())]
+		pub fn swap_best_route(
+			origin: OriginFor<T>,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let RouteQuote { route, .. } = Self::best_route(in_asset, min_receive.asset_id)?;
+			Self::swap_along_route(&who, &mut route.iter(), in_asset, min_receive, false)?;
+			Ok(())
+		}
+
+		/// Exchange `in_asset` for at least `min_receive` in total, splitting the order across
+		/// `routes` according to their shares, which must add up to 100%. Each route starts with a
+		/// pool of the in asset and ends with a pool of the out asset.
+		/// On successful emits `SplitSwapExecuted` with the amounts swapped through each route.
+		#[pallet::weight(T::WeightInfo::swap_split(routes.len() as u32))]
+		pub fn swap_split(
+			origin: OriginFor<T>,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			routes: SplitRoutesOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let fills = Self::do_swap_split(&who, in_asset, min_receive, routes)?;
+			Self::deposit_event(Event::SplitSwapExecuted {
+				who,
+				in_asset_id: in_asset.asset_id,
+				out_asset_id: min_receive.asset_id,
+				fills,
+			});
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Finds the route with the greatest expected output when exchanging `in_asset` for
+		/// `out_asset_id`.
+		///
+		/// Candidates are all the paths of at most `MaxHopsInRoute` hops through the first
+		/// `MaxPoolsInRouteSearch` dual asset pools of the underlying DEX, and the route stored for
+		/// the pair if any, which is preferred when it yields as much as the best path found. The
+		/// output of each hop is computed for the amount actually reaching its pool, so the price
+		/// impact of every hop is accounted for.
+		///
+		/// Returns the pools in the order they are swapped with, the expected output and whether
+		/// all the pools of the DEX were searched.
+		pub fn best_route(
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			out_asset_id: T::AssetId,
+		) -> Result<RouteQuote<T::PoolId, T::Balance>, DispatchError> {
+			ensure!(in_asset.asset_id != out_asset_id, Error::<T>::NoRouteFound);
+			let (pools, all_pools_searched) = Self::dual_asset_pools();
+			// Best known path and amount for each asset reachable so far, and the ones reached
+			// with the last hop, which are the only ones worth extending.
+			let mut best = BTreeMap::<T::AssetId, (Vec<T::PoolId>, T::Balance)>::new();
+			let mut frontier = BTreeMap::from([(in_asset.asset_id, (Vec::new(), in_asset.amount))]);
+			for _ in 0..T::MaxHopsInRoute::get() {
+				let mut next = BTreeMap::<T::AssetId, (Vec<T::PoolId>, T::Balance)>::new();
+				for (asset_id, (path, amount)) in &frontier {
+					for (pool_id, first_asset, second_asset) in &pools {
+						let hop_asset_id = if first_asset == asset_id {
+							*second_asset
+						} else if second_asset == asset_id {
+							*first_asset
+						} else {
+							continue
+						};
+						if hop_asset_id == in_asset.asset_id || path.contains(pool_id) {
+							continue
+						}
+						let amount_out = match T::Pablo::spot_price(
+							*pool_id,
+							AssetAmount::new(*asset_id, *amount),
+							hop_asset_id,
+							true,
+						) {
+							Ok(swap_result) => swap_result.value.amount,
+							Err(_) => continue,
+						};
+						let improves = |known: Option<&(Vec<T::PoolId>, T::Balance)>| {
+							known.map_or(true, |(_, known_amount)| amount_out > *known_amount)
+						};
+						if improves(best.get(&hop_asset_id)) && improves(next.get(&hop_asset_id)) {
+							let mut route = path.clone();
+							route.push(*pool_id);
+							next.insert(hop_asset_id, (route, amount_out));
+						}
+					}
+				}
+				best.extend(next.clone());
+				// Paths are not extended past the requested asset.
+				next.remove(&out_asset_id);
+				if next.is_empty() {
+					break
+				}
+				frontier = next;
+			}
+
+			let stored = Self::get_route(CurrencyPair::new(out_asset_id, in_asset.asset_id))
+				.and_then(|(mut route, reverse)| {
+					if reverse {
+						route.reverse();
+					}
+					Self::route_output(&route, in_asset).ok().map(|amount_out| (route, amount_out))
+				});
+			[best.remove(&out_asset_id), stored]
+				.into_iter()
+				.flatten()
+				// on equal outputs the last one, the stored route, is kept
+				.max_by_key(|(_, amount_out)| *amount_out)
+				.map(|(route, amount_out)| RouteQuote { route, amount_out, all_pools_searched })
+				.ok_or_else(|| Error::<T>::NoRouteFound.into())
+		}
+
+		/// Dual asset pools of the underlying DEX, with their assets, up to
+		/// `MaxPoolsInRouteSearch` pools, and whether there are no other pools.
+		fn dual_asset_pools() -> (Vec<(T::PoolId, T::AssetId, T::AssetId)>, bool) {
+			let mut pools = Vec::new();
+			let mut pool_id = T::PoolId::zero();
+			// pools are never removed from the underlying DEX, so pool ids are contiguous
+			for _ in 0..T::MaxPoolsInRouteSearch::get() {
+				if !T::Pablo::pool_exists(pool_id) {
+					break
+				}
+				if let Ok(assets) = T::Pablo::assets(pool_id) {
+					let mut assets = assets.into_keys();
+					if let (Some(first_asset), Some(second_asset), None) =
+						(assets.next(), assets.next(), assets.next())
+					{
+						pools.push((pool_id, first_asset, second_asset));
+					}
+				}
+				match pool_id.checked_add(&T::PoolId::one()) {
+					Some(next_pool_id) => pool_id = next_pool_id,
+					None => return (pools, true),
+				}
+			}
+			let all_pools_searched = !T::Pablo::pool_exists(pool_id);
+			(pools, all_pools_searched)
+		}
+
+		/// Expected output of swapping `in_asset` through the pools of `route`, in order.
+		fn route_output(
+			route: &[T::PoolId],
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+		) -> Result<T::Balance, DispatchError> {
+			route
+				.iter()
+				.try_fold(in_asset, |in_asset_itr, pool_id| {
+					let assets = T::Pablo::assets(*pool_id)?;
+					let out_asset_id = assets
+						.keys()
+						.copied()
+						.find(|a| *a != in_asset_itr.asset_id)
+						.ok_or(Error::<T>::NoRouteFound)?;
+					T::Pablo::spot_price(*pool_id, in_asset_itr, out_asset_id, true)
+						.map(|swap_result| swap_result.value)
+				})
+				.map(|out_asset| out_asset.amount)
+		}
+
+		#[transactional]
+		fn do_swap_split(
+			who: &T::AccountId,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			routes: SplitRoutesOf<T>,
+		) -> Result<Vec<RouteFill<T::PoolId, T::Balance>>, DispatchError> {
+			let total_shares = routes.iter().try_fold(0_u32, |total, (_route, share)| {
+				ensure!(!share.is_zero(), Error::<T>::InvalidSplitShares);
+				Ok::<_, DispatchError>(total.saturating_add(share.deconstruct()))
+			})?;
+			ensure!(total_shares == Permill::one().deconstruct(), Error::<T>::InvalidSplitShares);
+
+			let route_count = routes.len();
+			let mut remaining = in_asset.amount;
+			let mut total_out = T::Balance::zero();
+			let mut fills = Vec::with_capacity(route_count);
+			for (index, (route, share)) in routes.into_iter().enumerate() {
+				Self::validate_route(
+					CurrencyPair::new(min_receive.asset_id, in_asset.asset_id),
+					&route,
+				)?;
+				// the last route takes what is left so that rounding leaves no dust behind
+				let amount_in = if index + 1 == route_count {
+					remaining
+				} else {
+					share.mul_floor(in_asset.amount)
+				};
+				remaining = remaining.safe_sub(&amount_in)?;
+				let swap_result = Self::swap_along_route(
+					who,
+					&mut route.iter(),
+					AssetAmount::new(in_asset.asset_id, amount_in),
+					AssetAmount::new(min_receive.asset_id, T::Balance::zero()),
+					false,
+				)?;
+				total_out = total_out.safe_add(&swap_result.value.amount)?;
+				fills.push(RouteFill {
+					route: route.into_inner(),
+					amount_in,
+					amount_out: swap_result.value.amount,
+				});
+			}
+			ensure!(total_out >= min_receive.amount, Error::<T>::CanNotRespectMinAmountRequested);
+			Ok(fills)
+		}
+
+		/// Swap `in_asset` with each pool of `route_iter` in turn, making sure at least
+		/// `min_receive` is obtained at the end.
+		#[transactional]
+		fn swap_along_route(
+			who: &T::AccountId,
+			route_iter: &mut dyn Iterator<Item = &T::PoolId>,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			keep_alive: bool,
+		) -> Result<SwapResult<T::AssetId, T::Balance>, DispatchError> {
+			// Iterate and swap until we obtain the required asset in the `min_receive.asset_id`
+			let mut in_asset_itr = in_asset;
+			let mut swap_result: SwapResult<T::AssetId, T::Balance> = SwapResult {
+				value: in_asset_itr,
+				fee: AssetAmount { asset_id: in_asset_itr.asset_id, amount: T::Balance::zero() },
+			};
+			for pool_id in route_iter {
+				let assets = T::Pablo::assets(*pool_id)?;
+				// We only allow dual asset pools in routes, therefore taking the remaining asset
+				// other than `in_asset_itr.asset_id` gives us the out_asset_id
+				let out_asset_id = assets
+					.keys()
+					.copied()
+					.find(|a| *a != in_asset_itr.asset_id)
+					.ok_or(Error::<T>::NoRouteFound)?;
+				swap_result = T::Pablo::do_swap(
+					who,
+					*pool_id,
+					in_asset_itr,
+					AssetAmount::new(out_asset_id, T::Balance::zero()),
+					keep_alive,
+				)?;
+				in_asset_itr = swap_result.value;
+			}
+			ensure!(
+				swap_result.value.asset_id == min_receive.asset_id &&
+					swap_result.value.amount >= min_receive.amount,
+				Error::<T>::CanNotRespectMinAmountRequested
+			);
+			// TODO (vim): Final fee amount is not correct as the fee need to be incremented with
+			// each swap fee when iterating.
+			Ok(swap_result)
+		}
+
 		fn validate_route(
 			asset_pair: CurrencyPair<T::AssetId>,
 			route: &BoundedVec<T::PoolId, T::MaxHopsInRoute>,
@@ -414,6 +699,20 @@ pub mod pallet {
 			}
 		}
 
+		fn twap_over_window(
+			pool_id: Self::PoolId,
+			base_asset_id: Self::AssetId,
+			quote_asset_id: Self::AssetId,
+			window: DurationSeconds,
+		) -> Result<Rate, DispatchError> {
+			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
+			match route[..] {
+				[pool_id] =>
+					T::Pablo::twap_over_window(pool_id, base_asset_id, quote_asset_id, window),
+				_ => Err(Error::<T>::UnsupportedOperation.into()),
+			}
+		}
+
 		fn simulate_add_liquidity(
 			who: &Self::AccountId,
 			pool_id: Self::PoolId,
@@ -473,37 +772,7 @@ pub mod pallet {
 				backward_iter = route.iter().rev();
 				&mut backward_iter
 			};
-			// Iterate and swap until we obtain the required asset in the `min_receive.asset_id`
-			let mut in_asset_itr = in_asset;
-			let mut swap_result: SwapResult<T::AssetId, T::Balance> = SwapResult {
-				value: in_asset_itr,
-				fee: AssetAmount { asset_id: in_asset_itr.asset_id, amount: T::Balance::zero() },
-			};
-			for pool_id in route_iter {
-				let assets = T::Pablo::assets(*pool_id)?;
-				// We only allow dual asset pools in routes, therefore taking the remaining asset
-				// other than `in_asset_itr.asset_id` gives us the out_asset_id
-				let out_asset_id = assets
-					.keys()
-					.copied()
-					.find(|a| *a != in_asset_itr.asset_id)
-					.ok_or(Error::<T>::NoRouteFound)?;
-				swap_result = T::Pablo::do_swap(
-					who,
-					*pool_id,
-					in_asset_itr,
-					AssetAmount::new(out_asset_id, T::Balance::zero()),
-					keep_alive,
-				)?;
-				in_asset_itr = swap_result.value;
-			}
-			ensure!(
-				swap_result.value.amount >= min_receive.amount,
-				Error::<T>::CanNotRespectMinAmountRequested
-			);
-			// TODO (vim): Final fee amount is not correct as the fee need to be incremented with
-			// each swap fee when iterating.
-			Ok(swap_result)
+			Self::swap_along_route(who, route_iter, in_asset, min_receive, keep_alive)
 		}
 
 		#[transactional]
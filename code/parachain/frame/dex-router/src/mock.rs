@@ -154,6 +154,8 @@ parameter_types! {
 	pub MaxInitialWeight: Permill = Permill::from_percent(95);
 	pub MinFinalWeight: Permill = Permill::from_percent(5);
 	pub const TWAPInterval: Moment = MILLISECS_PER_BLOCK * TWAP_INTERVAL;
+	pub const TWAPObservationInterval: Moment = MILLISECS_PER_BLOCK;
+	pub const MaxTWAPObservations: u32 = 10;
 }
 
 parameter_types! {
@@ -235,10 +237,12 @@ impl pallet_staking_rewards::Config for Test {
 	type TreasuryAccount = TreasuryAccountId;
 	type ExistentialDeposits = ExistentialDeposits;
 	type AssetsTransactor = AssetsTransactor;
+	type MaxAutoCompoundsPerBlock = frame_support::traits::ConstU32<2>;
 }
 
 parameter_types! {
 	pub const LPTED: Balance = 0;
+	pub const MinPositionLiquidity: u128 = 1_000_000;
 }
 
 impl pallet_pablo::Config for Test {
@@ -252,15 +256,23 @@ impl pallet_pablo::Config for Test {
 	type PalletId = TestPalletID;
 	type PoolCreationOrigin = EnsureSigned<Self::AccountId>;
 	type EnableTwapOrigin = EnsureRoot<AccountId>;
+	type ManageFeesOrigin = EnsureRoot<AccountId>;
 	type Time = Timestamp;
 	type TWAPInterval = TWAPInterval;
+	type TWAPObservationInterval = TWAPObservationInterval;
+	type MaxTWAPObservations = MaxTWAPObservations;
 	type WeightInfo = ();
 	type LPTokenExistentialDeposit = LPTED;
+	type FinancialNft = MockFnft;
+	type FinancialNftInstanceId = u64;
+	type MinPositionLiquidity = MinPositionLiquidity;
 }
 
 parameter_types! {
 	#[derive(TypeInfo, codec::MaxEncodedLen, codec::Encode)]
 	pub const MaxHopsCount: u32 = 4;
+	pub const MaxPoolsInRouteSearch: u32 = 16;
+	pub const MaxSplitRoutes: u32 = 4;
 	// cspell:disable-next
 	pub TestDexRouterPalletID: PalletId = PalletId(*b"dex_rout");
 }
@@ -270,6 +282,8 @@ impl dex_router::Config for Test {
 	type AssetId = AssetId;
 	type Balance = Balance;
 	type MaxHopsInRoute = MaxHopsCount;
+	type MaxPoolsInRouteSearch = MaxPoolsInRouteSearch;
+	type MaxSplitRoutes = MaxSplitRoutes;
 	type PoolId = PoolId;
 	type Pablo = Pablo;
 	type PalletId = TestDexRouterPalletID;
@@ -1,18 +1,19 @@
 #![allow(clippy::unwrap_used, clippy::disallowed_methods)]
 
-use crate::{mock::*, Error};
+use crate::{mock::*, Error, RouteFill, SplitRoutesOf};
 use composable_tests_helpers::test::{
 	block::next_block,
 	helper::{acceptable_computation_error, RuntimeTrait},
 };
 use composable_traits::{
 	defi::CurrencyPair,
-	dex::{Amm as AmmTrait, AssetAmount, DexRouter as DexRouterTrait},
+	dex::{Amm as AmmTrait, AssetAmount, DexRouter as DexRouterTrait, RouteQuote},
 };
 use frame_support::{
 	assert_noop, assert_ok,
 	error::BadOrigin,
 	traits::fungibles::{Inspect, Mutate},
+	BoundedVec,
 };
 use pallet_pablo::{Error as PabloError, PoolInitConfiguration};
 use sp_runtime::{PerThing, Permill};
@@ -593,3 +594,313 @@ fn single_pool_route_test() {
 		assert_eq!(8999999999999, bob_usdc_amount);
 	});
 }
+
+fn create_shallow_usdt_eth_pool() -> PoolId {
+	let unit = 1_000_000_000_000_u128;
+	let fee = Permill::zero();
+
+	create_constant_product_amm_pool(
+		AssetAmountPair {
+			base: AssetAmount { asset_id: USDT, amount: 10 * unit },
+			quote: AssetAmount { asset_id: ETH, amount: 10 * unit },
+		},
+		fee,
+	)
+}
+
+// Expected output of swapping `in_asset` with each pool of `route` in turn.
+fn expected_route_output(route: &[PoolId], in_asset: AssetAmount<AssetId, Balance>) -> Balance {
+	route
+		.iter()
+		.fold(in_asset, |in_asset, pool_id| {
+			let out_asset_id = <Pablo as AmmTrait>::assets(*pool_id)
+				.unwrap()
+				.into_keys()
+				.find(|asset_id| *asset_id != in_asset.asset_id)
+				.unwrap();
+			<Pablo as AmmTrait>::spot_price(*pool_id, in_asset, out_asset_id, true)
+				.unwrap()
+				.value
+		})
+		.amount
+}
+
+#[test]
+fn best_route_prefers_the_path_with_the_greatest_output() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let usdt_eth_pool = create_shallow_usdt_eth_pool();
+		let usdt_usdc_pool = create_usdt_usdc_pool();
+		let usdc_eth_pool = create_usdc_eth_pool();
+		let in_asset = AssetAmount::new(USDT, 10 * unit);
+
+		let RouteQuote { route, amount_out, all_pools_searched } =
+			DexRouter::best_route(in_asset, ETH).unwrap();
+
+		assert_eq!(route, vec![usdt_usdc_pool, usdc_eth_pool]);
+		assert!(all_pools_searched);
+		assert_eq!(amount_out, expected_route_output(&route, in_asset));
+		assert!(amount_out > expected_route_output(&[usdt_eth_pool], in_asset));
+
+		assert_ok!(Tokens::mint_into(USDT, &CHARLIE, in_asset.amount));
+		assert_ok!(DexRouter::swap_best_route(
+			RuntimeOrigin::signed(CHARLIE),
+			in_asset,
+			AssetAmount::new(ETH, amount_out),
+		));
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), 0);
+		assert_eq!(Tokens::balance(ETH, &CHARLIE), amount_out);
+	});
+}
+
+#[test]
+fn best_route_keeps_the_stored_route_when_it_yields_as_much() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let usdt_usdc_pool = create_usdt_usdc_pool();
+		let usdc_eth_pool = create_usdc_eth_pool();
+		let usdt_dai_pool = create_usdt_dai_pool();
+		let dai_eth_pool = create_dai_eth_pool();
+		let in_asset = AssetAmount::new(USDT, 10 * unit);
+
+		// both paths go through identical pools
+		let RouteQuote { route: found_route, amount_out, .. } =
+			DexRouter::best_route(in_asset, ETH).unwrap();
+		let other_route = if found_route == vec![usdt_usdc_pool, usdc_eth_pool] {
+			vec![usdt_dai_pool, dai_eth_pool]
+		} else {
+			vec![usdt_usdc_pool, usdc_eth_pool]
+		};
+
+		// stored routes start with the quote asset of the pair
+		assert_ok!(DexRouter::update_route(
+			RuntimeOrigin::root(),
+			CurrencyPair::new(ETH, USDT),
+			Some(other_route.clone().try_into().unwrap())
+		));
+		assert_eq!(
+			DexRouter::best_route(in_asset, ETH),
+			Ok(RouteQuote { route: other_route.clone(), amount_out, all_pools_searched: true })
+		);
+
+		// and are used in reverse for the opposite direction
+		let in_asset = AssetAmount::new(ETH, unit);
+		let mut reversed_route = other_route;
+		reversed_route.reverse();
+		let RouteQuote { amount_out, .. } = DexRouter::best_route(in_asset, USDT).unwrap();
+		assert_eq!(
+			DexRouter::best_route(in_asset, USDT),
+			Ok(RouteQuote { route: reversed_route, amount_out, all_pools_searched: true })
+		);
+	});
+}
+
+#[test]
+fn best_route_reports_pools_left_out_of_the_search() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let usdt_eth_pool = create_shallow_usdt_eth_pool();
+		for _ in 1..MaxPoolsInRouteSearch::get() {
+			create_usdt_usdc_pool();
+		}
+		let in_asset = AssetAmount::new(USDT, unit);
+
+		assert!(DexRouter::best_route(in_asset, ETH).unwrap().all_pools_searched);
+
+		// the path through USDC is not found, as its last pool is not searched
+		create_usdc_eth_pool();
+		assert_eq!(
+			DexRouter::best_route(in_asset, ETH),
+			Ok(RouteQuote {
+				route: vec![usdt_eth_pool],
+				amount_out: expected_route_output(&[usdt_eth_pool], in_asset),
+				all_pools_searched: false,
+			})
+		);
+	});
+}
+
+#[test]
+fn best_route_fails_without_path() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		create_usdt_usdc_pool();
+		create_dai_eth_pool();
+
+		assert_noop!(
+			DexRouter::best_route(AssetAmount::new(USDT, unit), ETH),
+			Error::<Test>::NoRouteFound
+		);
+		assert_noop!(
+			DexRouter::best_route(AssetAmount::new(USDT, unit), USDT),
+			Error::<Test>::NoRouteFound
+		);
+		assert_ok!(Tokens::mint_into(USDT, &CHARLIE, unit));
+		assert_noop!(
+			DexRouter::swap_best_route(
+				RuntimeOrigin::signed(CHARLIE),
+				AssetAmount::new(USDT, unit),
+				AssetAmount::new(USDC, unit),
+			),
+			Error::<Test>::CanNotRespectMinAmountRequested
+		);
+	});
+}
+
+fn split_routes(routes: Vec<(Vec<PoolId>, Permill)>) -> SplitRoutesOf<Test> {
+	routes
+		.into_iter()
+		.map(|(route, share)| (BoundedVec::<PoolId, MaxHopsCount>::try_from(route).unwrap(), share))
+		.collect::<Vec<_>>()
+		.try_into()
+		.unwrap()
+}
+
+#[test]
+fn swap_split_fills_each_route_with_its_share() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let first_pool = create_usdt_usdc_pool();
+		let second_pool = create_usdt_usdc_pool();
+		let in_amount = 101 * unit;
+		let first_in = Permill::from_percent(60).mul_floor(in_amount);
+		let second_in = in_amount - first_in;
+		let first_out = expected_route_output(&[first_pool], AssetAmount::new(USDT, first_in));
+		let second_out = expected_route_output(&[second_pool], AssetAmount::new(USDT, second_in));
+		// splitting the order across parallel pools beats swapping it in a single one
+		assert!(
+			first_out + second_out >
+				expected_route_output(&[first_pool], AssetAmount::new(USDT, in_amount))
+		);
+
+		assert_ok!(Tokens::mint_into(USDT, &CHARLIE, in_amount));
+		Test::assert_extrinsic_event(
+			DexRouter::swap_split(
+				RuntimeOrigin::signed(CHARLIE),
+				AssetAmount::new(USDT, in_amount),
+				AssetAmount::new(USDC, first_out + second_out),
+				split_routes(vec![
+					(vec![first_pool], Permill::from_percent(60)),
+					(vec![second_pool], Permill::from_percent(40)),
+				]),
+			),
+			crate::Event::<Test>::SplitSwapExecuted {
+				who: CHARLIE,
+				in_asset_id: USDT,
+				out_asset_id: USDC,
+				fills: vec![
+					RouteFill {
+						route: vec![first_pool],
+						amount_in: first_in,
+						amount_out: first_out,
+					},
+					RouteFill {
+						route: vec![second_pool],
+						amount_in: second_in,
+						amount_out: second_out,
+					},
+				],
+			},
+		);
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), 0);
+		assert_eq!(Tokens::balance(USDC, &CHARLIE), first_out + second_out);
+	});
+}
+
+#[test]
+fn swap_split_supports_multi_hop_routes() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let usdt_eth_pool = create_shallow_usdt_eth_pool();
+		let usdt_usdc_pool = create_usdt_usdc_pool();
+		let usdc_eth_pool = create_usdc_eth_pool();
+		let in_amount = 10 * unit;
+		let half = in_amount / 2;
+		let direct_out = expected_route_output(&[usdt_eth_pool], AssetAmount::new(USDT, half));
+		let hops_out = expected_route_output(
+			&[usdt_usdc_pool, usdc_eth_pool],
+			AssetAmount::new(USDT, in_amount - half),
+		);
+
+		assert_ok!(Tokens::mint_into(USDT, &CHARLIE, in_amount));
+		assert_ok!(DexRouter::swap_split(
+			RuntimeOrigin::signed(CHARLIE),
+			AssetAmount::new(USDT, in_amount),
+			AssetAmount::new(ETH, 0),
+			split_routes(vec![
+				(vec![usdt_eth_pool], Permill::from_percent(50)),
+				(vec![usdt_usdc_pool, usdc_eth_pool], Permill::from_percent(50)),
+			]),
+		));
+		assert_eq!(Tokens::balance(ETH, &CHARLIE), direct_out + hops_out);
+	});
+}
+
+#[test]
+fn swap_split_validation() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let usdt_usdc_pool = create_usdt_usdc_pool();
+		let usdc_eth_pool = create_usdc_eth_pool();
+		assert_ok!(Tokens::mint_into(USDT, &CHARLIE, unit));
+		let swap_split = |min_receive: Balance, routes: Vec<(Vec<PoolId>, Permill)>| {
+			DexRouter::swap_split(
+				RuntimeOrigin::signed(CHARLIE),
+				AssetAmount::new(USDT, unit),
+				AssetAmount::new(USDC, min_receive),
+				split_routes(routes),
+			)
+		};
+
+		// shares must add up to 100%
+		assert_noop!(swap_split(0, vec![]), Error::<Test>::InvalidSplitShares);
+		assert_noop!(
+			swap_split(0, vec![(vec![usdt_usdc_pool], Permill::from_percent(90))]),
+			Error::<Test>::InvalidSplitShares
+		);
+		assert_noop!(
+			swap_split(
+				0,
+				vec![
+					(vec![usdt_usdc_pool], Permill::one()),
+					(vec![usdt_usdc_pool], Permill::zero())
+				]
+			),
+			Error::<Test>::InvalidSplitShares
+		);
+		// routes must lead from the in asset to the out asset
+		assert_noop!(
+			swap_split(0, vec![(vec![usdc_eth_pool], Permill::one())]),
+			Error::<Test>::UnexpectedNodeFoundWhileValidation
+		);
+		assert_noop!(
+			swap_split(0, vec![(vec![usdt_usdc_pool, usdc_eth_pool], Permill::one())]),
+			Error::<Test>::UnexpectedNodeFoundWhileValidation
+		);
+		// min_receive applies to the order as a whole
+		assert_noop!(
+			swap_split(
+				unit,
+				vec![
+					(vec![usdt_usdc_pool], Permill::from_percent(50)),
+					(vec![usdt_usdc_pool], Permill::from_percent(50))
+				]
+			),
+			Error::<Test>::CanNotRespectMinAmountRequested
+		);
+	});
+}
@@ -12,6 +12,8 @@ pub trait WeightInfo {
   fn buy() -> Weight;
   fn add_liquidity() -> Weight;
   fn remove_liquidity() -> Weight;
+  fn swap_best_route() -> Weight;
+  fn swap_split(r: u32) -> Weight;
 }
 
 // For backwards compatibility and tests
@@ -35,4 +37,12 @@ impl WeightInfo for () {
     fn remove_liquidity() -> Weight {
         Weight::from_ref_time(10_000)
     }
+
+    fn swap_best_route() -> Weight {
+        Weight::from_ref_time(10_000)
+    }
+
+    fn swap_split(_r: u32) -> Weight {
+        Weight::from_ref_time(10_000)
+    }
 }
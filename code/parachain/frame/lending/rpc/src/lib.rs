@@ -1,7 +1,11 @@
 use codec::Codec;
 use composable_support::rpc_helpers::SafeRpcWrapper;
-use composable_traits::defi::Rate;
+use composable_traits::{
+	defi::Rate,
+	lending::{AccountPosition, MarketRates},
+};
 use core::{fmt::Display, str::FromStr};
+use frame_support::{pallet_prelude::MaybeSerializeDeserialize, Parameter};
 use jsonrpsee::{
 	core::{Error as RpcError, RpcResult},
 	proc_macros::rpc,
@@ -10,13 +14,15 @@ use jsonrpsee::{
 use lending_runtime_api::LendingRuntimeApi;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, DispatchError};
 use sp_std::sync::Arc;
 
 #[rpc(client, server)]
-pub trait LendingApi<BlockHash, MarketId>
+pub trait LendingApi<BlockHash, MarketId, AccountId, AssetId, Balance>
 where
 	MarketId: FromStr + Display,
+	AssetId: FromStr + Display,
+	Balance: FromStr + Display,
 {
 	#[method(name = "lending_currentInterestRate")]
 	fn current_interest_rate(
@@ -24,6 +30,59 @@ where
 		market_id: SafeRpcWrapper<MarketId>,
 		at: Option<BlockHash>,
 	) -> RpcResult<SafeRpcWrapper<Rate>>;
+
+	#[method(name = "lending_accountPositions")]
+	fn account_positions(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<
+		Vec<
+			AccountPosition<
+				SafeRpcWrapper<MarketId>,
+				SafeRpcWrapper<AssetId>,
+				SafeRpcWrapper<Balance>,
+			>,
+		>,
+	>;
+
+	#[method(name = "lending_healthFactor")]
+	fn health_factor(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<SafeRpcWrapper<Rate>>>;
+
+	#[method(name = "lending_liquidationPrice")]
+	fn liquidation_price(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<SafeRpcWrapper<Balance>>>;
+
+	#[method(name = "lending_maxBorrowable")]
+	fn max_borrowable(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<SafeRpcWrapper<Balance>>;
+
+	#[method(name = "lending_marketRates")]
+	fn market_rates(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<MarketRates<SafeRpcWrapper<Rate>>>;
+
+	#[method(name = "lending_unhealthyPositions")]
+	fn unhealthy_positions(
+		&self,
+		positions: Vec<(SafeRpcWrapper<MarketId>, AccountId)>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(SafeRpcWrapper<MarketId>, AccountId)>>;
 }
 
 pub struct Lending<C, Block> {
@@ -37,15 +96,35 @@ impl<C, M> Lending<C, M> {
 	}
 }
 
-impl<C, Block, MarketId> LendingApiServer<<Block as BlockT>::Hash, MarketId>
-	for Lending<C, (Block, MarketId)>
+fn runtime_error(e: impl core::fmt::Debug) -> RpcError {
+	RpcError::Call(CallError::Custom(ErrorObject::owned(
+		9876,
+		"Something wrong",
+		Some(format!("{:?}", e)),
+	)))
+}
+
+fn dispatch_error(e: DispatchError) -> RpcError {
+	RpcError::Call(CallError::Custom(ErrorObject::owned(
+		9877,
+		"Query failed",
+		Some(format!("{:?}", e)),
+	)))
+}
+
+impl<C, Block, MarketId, AccountId, AssetId, Balance>
+	LendingApiServer<<Block as BlockT>::Hash, MarketId, AccountId, AssetId, Balance>
+	for Lending<C, (Block, MarketId, AccountId, AssetId, Balance)>
 where
 	Block: BlockT,
 	MarketId: Send + Sync + 'static + Codec + FromStr + Display,
+	AccountId: Send + Sync + Parameter + MaybeSerializeDeserialize + 'static,
+	AssetId: Send + Sync + 'static + Codec + FromStr + Display,
+	Balance: Send + Sync + 'static + Codec + FromStr + Display,
 	C: Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block>,
-	C::Api: LendingRuntimeApi<Block, MarketId>,
+	C::Api: LendingRuntimeApi<Block, MarketId, AccountId, AssetId, Balance>,
 {
 	fn current_interest_rate(
 		&self,
@@ -58,12 +137,96 @@ where
 
 		// calling ../../runtime-api
 		let runtime_api_result = api.current_interest_rate(at, market_id.0);
-		runtime_api_result.map_err(|e| {
-			RpcError::Call(CallError::Custom(ErrorObject::owned(
-				9876,
-				"Something wrong",
-				Some(format!("{:?}", e)),
-			)))
-		})
+		runtime_api_result.map_err(runtime_error)
+	}
+
+	fn account_positions(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<
+		Vec<
+			AccountPosition<
+				SafeRpcWrapper<MarketId>,
+				SafeRpcWrapper<AssetId>,
+				SafeRpcWrapper<Balance>,
+			>,
+		>,
+	> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.account_positions(at, account)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn health_factor(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<SafeRpcWrapper<Rate>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.health_factor(at, market_id.0, account)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn liquidation_price(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<SafeRpcWrapper<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.liquidation_price(at, market_id.0, account)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn max_borrowable(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SafeRpcWrapper<Balance>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.max_borrowable(at, market_id.0, account)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn market_rates(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<MarketRates<SafeRpcWrapper<Rate>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.market_rates(at, market_id.0)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn unhealthy_positions(
+		&self,
+		positions: Vec<(SafeRpcWrapper<MarketId>, AccountId)>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(SafeRpcWrapper<MarketId>, AccountId)>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let positions = positions
+			.into_iter()
+			.map(|(market_id, account)| (market_id.0, account))
+			.collect();
+		Ok(api
+			.unhealthy_positions(at, positions)
+			.map_err(runtime_error)?
+			.into_iter()
+			.map(|(market_id, account)| (SafeRpcWrapper(market_id), account))
+			.collect())
 	}
 }
@@ -2,16 +2,59 @@
 
 use codec::Codec;
 use composable_support::rpc_helpers::SafeRpcWrapper;
-use composable_traits::defi::Rate;
+use composable_traits::{
+	defi::Rate,
+	lending::{AccountPosition, MarketRates},
+};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
 
 // Lending Runtime API declaration. Implemented for each runtime at
 // `runtime/<runtime-name>/src/lib.rs`.
 sp_api::decl_runtime_apis! {
-	pub trait LendingRuntimeApi<MarketId>
+	pub trait LendingRuntimeApi<MarketId, AccountId, AssetId, Balance>
 	where
 		MarketId: Codec,
+		AccountId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
 	{
 		/// Retrieve the current interest rate for the given `market_id`.
 		fn current_interest_rate(market_id: MarketId) -> SafeRpcWrapper<Rate>;
+
+		/// Retrieve collateral and debt with interest of `account` on every market it has a
+		/// position on.
+		fn account_positions(
+			account: AccountId,
+		) -> Result<
+			Vec<AccountPosition<SafeRpcWrapper<MarketId>, SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>,
+			DispatchError,
+		>;
+
+		/// Retrieve the health factor of `account` on `market_id`, which is liquidated once it
+		/// goes below one. `None` if `account` has no debt.
+		fn health_factor(
+			market_id: MarketId,
+			account: AccountId,
+		) -> Result<Option<SafeRpcWrapper<Rate>>, DispatchError>;
+
+		/// Retrieve the price of one unit of the collateral asset of `market_id` at which
+		/// `account` gets liquidated.
+		fn liquidation_price(
+			market_id: MarketId,
+			account: AccountId,
+		) -> Result<Option<SafeRpcWrapper<Balance>>, DispatchError>;
+
+		/// Retrieve the amount of borrow asset `account` can still borrow from `market_id`.
+		fn max_borrowable(
+			market_id: MarketId,
+			account: AccountId,
+		) -> Result<SafeRpcWrapper<Balance>, DispatchError>;
+
+		/// Retrieve the utilization and the yearly borrow and supply yields of `market_id`.
+		fn market_rates(market_id: MarketId) -> Result<MarketRates<SafeRpcWrapper<Rate>>, DispatchError>;
+
+		/// Retrieve the positions among `positions` which should be liquidated.
+		fn unhealthy_positions(positions: Vec<(MarketId, AccountId)>) -> Vec<(MarketId, AccountId)>;
 	}
 }
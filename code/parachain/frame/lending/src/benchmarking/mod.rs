@@ -7,12 +7,12 @@ use super::*;
 use crate::{self as pallet_lending, Pallet as Lending};
 use composable_traits::{
 	defi::{CurrencyPair, DeFiComposableConfig},
-	lending::{CreateInput, Lending as LendingTrait, RepayStrategy},
+	lending::{math::InterestRateModel, CreateInput, Lending as LendingTrait, RepayStrategy},
 	vault::StrategicVault,
 };
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_benchmarking::{benchmarks, whitelisted_caller, BenchmarkError};
 use frame_support::{
-	traits::{fungible, fungibles::Mutate, Get},
+	traits::{fungible, fungibles::Mutate, EnsureOrigin, Get},
 	BoundedVec,
 };
 use frame_system::RawOrigin;
@@ -30,9 +30,9 @@ mod setup;
 fn create_market_from_raw_origin<T: Config>(
 	origin: RawOrigin<<T as frame_system::Config>::AccountId>,
 	input: CreateInput<
-		<T as Config>::LiquidationStrategyId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
 		<T as frame_system::Config>::BlockNumber,
+		<T as DeFiComposableConfig>::Balance,
 	>,
 ) -> MarketId {
 	Lending::<T>::create_market(origin.clone().into(), input, false).unwrap();
@@ -60,9 +60,9 @@ pub struct LendingBenchmarkingSetup<T: Config> {
 	bank: BalanceOf<T>,
 	pair: CurrencyPair<<T as DeFiComposableConfig>::MayBeAssetId>,
 	input: CreateInput<
-		<T as Config>::LiquidationStrategyId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
 		<T as frame_system::Config>::BlockNumber,
+		<T as DeFiComposableConfig>::Balance,
 	>,
 }
 
@@ -198,6 +198,63 @@ benchmarks! {
 		}
 	}: _(origin, market_id, BoundedVec::<_,T::MaxLiquidationBatchSize>::try_from(borrowers).unwrap())
 
+	enter_portfolio {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+
+		Lending::<T>::deposit_collateral(origin.clone().into(), market_id, amount, false).unwrap();
+	}: _(origin, market_id)
+
+	exit_portfolio {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+
+		Lending::<T>::deposit_collateral(origin.clone().into(), market_id, amount, false).unwrap();
+		Lending::<T>::enter_portfolio(origin.clone().into(), market_id).unwrap();
+	}: _(origin, market_id)
+
+	flash_loan {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+
+		<T as Config>::MultiCurrency::mint_into(pair.quote, &Lending::<T>::account_id(&market_id), amount).unwrap();
+		let call: <T as Config>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+	}: _(origin, market_id, amount, Box::new(call))
+
+	ramp_interest_rate_model {
+		let LendingBenchmarkingSetup { origin, input, .. } = lending_benchmarking_setup::<T>();
+		let market_id = create_market_from_raw_origin::<T>(origin, input);
+		let ramp_origin = T::UpdateInterestRateModelOrigin::try_successful_origin()
+			.map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(ramp_origin, market_id, InterestRateModel::default(), 100_u32.into())
+
 	// HOOKS
 
 	now {}: {
@@ -90,16 +90,21 @@ pub(crate) fn create_market_config<T: Config>(
 	borrow_asset: <T as DeFiComposableConfig>::MayBeAssetId,
 	max_price_age: <T as frame_system::Config>::BlockNumber,
 ) -> CreateInput<
-	<T as Config>::LiquidationStrategyId,
 	<T as DeFiComposableConfig>::MayBeAssetId,
 	<T as frame_system::Config>::BlockNumber,
+	<T as DeFiComposableConfig>::Balance,
 > {
 	CreateInput {
 		updatable: UpdateInput {
 			collateral_factor: MoreThanOneFixedU128::saturating_from_rational(200_u128, 100_u128),
 			under_collateralized_warn_percent: Percent::from_percent(10),
-			liquidators: Default::default(),
 			max_price_age,
+			close_factor: Percent::from_percent(50),
+			liquidation_incentive: Percent::from_percent(5),
+			supply_cap: None,
+			borrow_cap: None,
+			debt_ceiling: None,
+			paused: Default::default(),
 		},
 		reserved_factor: Perquintill::from_percent(10),
 		currency_pair: CurrencyPair::new(collateral_asset, borrow_asset),
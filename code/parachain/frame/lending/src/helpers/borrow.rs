@@ -16,7 +16,10 @@ use frame_support::{
 	},
 	weights::WeightToFee,
 };
-use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedPointNumber, Percent};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, DispatchError, FixedPointNumber, Percent,
+};
 use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
@@ -26,8 +29,10 @@ impl<T: Config> Pallet<T> {
 		amount_to_borrow: BorrowAmountOf<Self>,
 	) -> Result<(), DispatchError> {
 		let (_, market) = Self::get_market(market_id)?;
+		ensure!(!market.paused.borrow, Error::<T>::MarketOperationPaused);
 
 		Self::ensure_price_is_recent(&market)?;
+		let borrow_cap = market.borrow_cap;
 
 		let MarketAssets { borrow_asset, debt_asset: debt_asset_id } =
 			Self::get_assets_for_market(market_id)?;
@@ -96,6 +101,14 @@ impl<T: Config> Pallet<T> {
 		} else {
 			// REVIEW
 		}
+		if let Some(borrow_cap) = borrow_cap {
+			let market_debt = Self::total_debt_of_market(market_id)?;
+			if market_debt.saturating_sub(amount_to_borrow) < borrow_cap &&
+				market_debt >= borrow_cap
+			{
+				Self::deposit_event(Event::<T>::BorrowCapReached { market_id: *market_id });
+			}
+		}
 		Ok(())
 	}
 
@@ -107,6 +120,14 @@ impl<T: Config> Pallet<T> {
 	) -> Result<BorrowerData, DispatchError> {
 		let (_, market) = Self::get_market(market_id)?;
 
+		if Self::is_in_portfolio(market_id, account) {
+			return Self::create_portfolio_borrower_data(
+				account,
+				market.under_collateralized_warn_percent,
+				None,
+			)
+		}
+
 		let collateral_balance_value = Self::get_price(
 			market.collateral_asset,
 			Self::collateral_of_account(market_id, account)?,
@@ -149,6 +170,17 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 
+		if let Some(borrow_cap) = market.borrow_cap {
+			let market_debt = Self::total_debt_of_market(market_id)?.safe_add(&amount_to_borrow)?;
+			ensure!(market_debt <= borrow_cap, Error::<T>::BorrowCapExceeded);
+		}
+		if let Some(debt_ceiling) = market.debt_ceiling {
+			let account_debt = Self::total_debt_with_interest(market_id, debt_owner)?
+				.unwrap_or_zero()
+				.safe_add(&amount_to_borrow)?;
+			ensure!(account_debt <= debt_ceiling, Error::<T>::DebtCeilingExceeded);
+		}
+
 		let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
 		let borrow_limit = Self::get_borrow_limit(market_id, debt_owner)?;
 		let borrow_amount_value = Self::get_price(borrow_asset, amount_to_borrow)?;
@@ -182,6 +214,12 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Debt owed to `market_id` by all accounts, interest included.
+	pub(crate) fn total_debt_of_market(market_id: &MarketId) -> Result<T::Balance, DispatchError> {
+		Ok(Self::total_borrowed_from_market_excluding_interest(market_id)?
+			.safe_add(&Self::total_interest(market_id)?)?)
+	}
+
 	pub(crate) fn do_get_markets_for_borrow(borrow: T::VaultId) -> Vec<MarketId> {
 		Markets::<T>::iter()
 			.filter_map(|(index, market)| market.borrow_asset_vault.eq(&borrow).then_some(index))
@@ -205,7 +243,8 @@ impl<T: Config> Pallet<T> {
 			// afterwards.
 			.unwrap_or_else(CollateralLpAmountOf::<Self>::zero);
 
-		if collateral_balance > T::Balance::zero() {
+		// collateral of the other markets of the portfolio counts as well
+		if collateral_balance > T::Balance::zero() || Self::is_in_portfolio(market_id, account) {
 			let borrower = Self::create_borrower_data(market_id, account)?;
 			let balance = borrower
 				.get_borrow_limit()
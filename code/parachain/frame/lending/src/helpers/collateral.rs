@@ -8,7 +8,10 @@ use composable_traits::{
 	lending::{CollateralLpAmountOf, Lending},
 	vault::Vault,
 };
-use frame_support::{pallet_prelude::*, traits::fungibles::Transfer};
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungibles::{Inspect, Transfer},
+};
 use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedPointNumber};
 
 impl<T: Config> Pallet<T> {
@@ -20,8 +23,17 @@ impl<T: Config> Pallet<T> {
 	) -> Result<(), DispatchError> {
 		let amount = amount.value();
 		let (_, market) = Self::get_market(market_id)?;
+		ensure!(!market.paused.deposit, Error::<T>::MarketOperationPaused);
 		let market_account = Self::account_id(market_id);
 
+		// collateral of all accounts is held by the market account
+		let previous_collateral =
+			<T as Config>::MultiCurrency::balance(market.collateral_asset, &market_account);
+		let total_collateral = previous_collateral.safe_add(&amount)?;
+		if let Some(supply_cap) = market.supply_cap {
+			ensure!(total_collateral <= supply_cap, Error::<T>::SupplyCapExceeded);
+		}
+
 		AccountCollateral::<T>::try_mutate(market_id, account, |collateral_balance| {
 			let new_collateral_balance =
 				collateral_balance.unwrap_or_default().safe_add(&amount)?;
@@ -36,6 +48,11 @@ impl<T: Config> Pallet<T> {
 			amount,
 			keep_alive,
 		)?;
+		if let Some(supply_cap) = market.supply_cap {
+			if previous_collateral < supply_cap && total_collateral >= supply_cap {
+				Self::deposit_event(Event::<T>::SupplyCapReached { market_id: *market_id });
+			}
+		}
 		Ok(())
 	}
 
@@ -46,6 +63,7 @@ impl<T: Config> Pallet<T> {
 	) -> Result<(), DispatchError> {
 		let amount = amount.value();
 		let (_, market) = Self::get_market(market_id)?;
+		ensure!(!market.paused.withdraw, Error::<T>::MarketOperationPaused);
 
 		let collateral_balance = AccountCollateral::<T>::try_get(market_id, account)
 			// REVIEW: Perhaps don't default to zero
@@ -54,24 +72,33 @@ impl<T: Config> Pallet<T> {
 
 		ensure!(amount <= collateral_balance, Error::<T>::NotEnoughCollateralToWithdraw);
 
-		let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
-		let borrower_balance_with_interest =
-			Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
-
-		let borrow_balance_value = Self::get_price(borrow_asset, borrower_balance_with_interest)?;
-
-		let collateral_balance_after_withdrawal_value =
-			Self::get_price(market.collateral_asset, collateral_balance.safe_sub(&amount)?)?;
-
-		let borrower_after_withdrawal = BorrowerData::new(
-			collateral_balance_after_withdrawal_value,
-			borrow_balance_value,
-			market
-				.collateral_factor
-				.try_into_validated()
-				.map_err(|_| ArithmeticError::Overflow)?, // TODO: Use a proper error message?
-			market.under_collateralized_warn_percent,
-		);
+		let borrower_after_withdrawal = if Self::is_in_portfolio(market_id, account) {
+			Self::create_portfolio_borrower_data(
+				account,
+				market.under_collateralized_warn_percent,
+				Some((market_id, amount)),
+			)?
+		} else {
+			let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
+			let borrower_balance_with_interest =
+				Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
+
+			let borrow_balance_value =
+				Self::get_price(borrow_asset, borrower_balance_with_interest)?;
+
+			let collateral_balance_after_withdrawal_value =
+				Self::get_price(market.collateral_asset, collateral_balance.safe_sub(&amount)?)?;
+
+			BorrowerData::new(
+				collateral_balance_after_withdrawal_value,
+				borrow_balance_value,
+				market
+					.collateral_factor
+					.try_into_validated()
+					.map_err(|_| ArithmeticError::Overflow)?, // TODO: Use a proper error message?
+				market.under_collateralized_warn_percent,
+			)
+		};
 
 		ensure!(
 			!borrower_after_withdrawal.should_liquidate()?,
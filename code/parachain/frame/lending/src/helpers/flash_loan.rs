@@ -0,0 +1,69 @@
+use crate::{types::MarketId, *};
+use composable_support::math::safe::SafeAdd;
+use composable_traits::{
+	lending::{BorrowAmountOf, Lending},
+	vault::Vault,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungibles::{Inspect, Transfer},
+};
+use sp_runtime::{
+	traits::{Dispatchable, Zero},
+	DispatchError, PerThing,
+};
+
+impl<T: Config> Pallet<T> {
+	/// Lends `amount` of the market's borrow asset to `account`, dispatches `call` on behalf of
+	/// `account` and takes back `amount` plus [`Config::FlashLoanFee`].
+	///
+	/// The fee is transferred to the market's vault, so it is shared among the lenders.
+	/// Returns the fee paid.
+	///
+	/// NOTE: Must be called in transaction!
+	pub(crate) fn do_flash_loan(
+		market_id: &MarketId,
+		account: &T::AccountId,
+		amount: BorrowAmountOf<Self>,
+		call: <T as Config>::RuntimeCall,
+	) -> Result<BorrowAmountOf<Self>, DispatchError> {
+		ensure!(!amount.is_zero(), Error::<T>::CannotFlashLoanZero);
+		let (_, market) = Self::get_market(market_id)?;
+		ensure!(!market.paused.borrow, Error::<T>::MarketOperationPaused);
+		let MarketAssets { borrow_asset, .. } = Self::get_assets_for_market(market_id)?;
+		let market_account = Self::account_id(market_id);
+		let fee = T::FlashLoanFee::get().mul_ceil(amount);
+
+		<T as Config>::MultiCurrency::transfer(
+			borrow_asset,
+			&market_account,
+			account,
+			amount,
+			false,
+		)?;
+
+		call.dispatch(frame_system::RawOrigin::Signed(account.clone()).into())
+			.map_err(|error| error.error)?;
+
+		// loan has to be paid back within the same call, otherwise everything is reverted
+		<T as Config>::MultiCurrency::can_withdraw(borrow_asset, account, amount.safe_add(&fee)?)
+			.into_result()
+			.map_err(|_| Error::<T>::FlashLoanNotRepaid)?;
+		<T as Config>::MultiCurrency::transfer(
+			borrow_asset,
+			account,
+			&market_account,
+			amount,
+			false,
+		)?;
+		<T as Config>::MultiCurrency::transfer(
+			borrow_asset,
+			account,
+			&T::Vault::account_id(&market.borrow_asset_vault),
+			fee,
+			false,
+		)?;
+
+		Ok(fee)
+	}
+}
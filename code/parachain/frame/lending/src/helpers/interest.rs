@@ -4,7 +4,7 @@ use composable_traits::{
 	defi::*,
 	lending::{
 		math::{self, *},
-		BorrowAmountOf, Lending, TotalDebtWithInterest,
+		BorrowAmountOf, Lending, MarketRates, TotalDebtWithInterest,
 	},
 	time::{DurationSeconds, Timestamp, SECONDS_PER_YEAR_NAIVE},
 };
@@ -67,18 +67,48 @@ impl<T: Config> Pallet<T> {
 		let debt_asset_id =
 			DebtTokenForMarket::<T>::get(market_id).ok_or(Error::<T>::MarketDoesNotExist)?;
 
+		let block_number = frame_system::Pallet::<T>::block_number();
+		let mut ramp = InterestRateModelRamps::<T>::get(market_id);
+
 		let accrued_interest = Markets::<T>::try_mutate(market_id, |market_config| {
 			let market_config = market_config.as_mut().ok_or(Error::<T>::MarketDoesNotExist)?;
 
-			Self::accrue_interest_internal::<InterestRateModel>(
-				utilization_ratio,
-				&mut market_config.interest_rate_model,
-				borrow_index,
-				delta_time,
-				total_borrowed_from_market_excluding_interest,
-			)
+			match ramp.as_mut() {
+				Some(ramp) => {
+					let accrued_interest = Self::accrue_interest_internal::<RampedInterestRateModel>(
+						utilization_ratio,
+						&mut ramp.ramped(&mut market_config.interest_rate_model, block_number),
+						borrow_index,
+						delta_time,
+						total_borrowed_from_market_excluding_interest,
+					)?;
+					if block_number >= ramp.end {
+						market_config.interest_rate_model = ramp.target;
+					}
+					Ok(accrued_interest)
+				},
+				None => Self::accrue_interest_internal::<InterestRateModel>(
+					utilization_ratio,
+					&mut market_config.interest_rate_model,
+					borrow_index,
+					delta_time,
+					total_borrowed_from_market_excluding_interest,
+				),
+			}
 		})?;
 
+		if let Some(ramp) = ramp {
+			if block_number >= ramp.end {
+				InterestRateModelRamps::<T>::remove(market_id);
+				Self::deposit_event(Event::<T>::InterestRateModelRampCompleted {
+					market_id: *market_id,
+				});
+			} else {
+				// the target model may be stateful, so it is updated as well
+				InterestRateModelRamps::<T>::insert(market_id, ramp);
+			}
+		}
+
 		// overwrites
 		BorrowIndex::<T>::insert(market_id, accrued_interest.new_borrow_index);
 		<T as Config>::MultiCurrency::mint_into(
@@ -136,6 +166,33 @@ impl<T: Config> Pallet<T> {
 			None => Ok(TotalDebtWithInterest::NoDebt),
 		}
 	}
+
+	/// Utilization of `market_id` along with the yearly yield of borrowing from and lending to
+	/// it at the current borrow rate.
+	pub fn market_rates(market_id: &MarketId) -> Result<MarketRates<Rate>, DispatchError> {
+		let (_, mut market) = Self::get_market(market_id)?;
+		let utilization = Self::calculate_utilization_ratio(
+			Self::total_available_to_be_borrowed(market_id)?,
+			Self::total_borrowed_from_market_excluding_interest(market_id)?,
+		)?;
+		let borrow_rate = match InterestRateModelRamps::<T>::get(market_id).as_mut() {
+			Some(ramp) => ramp
+				.ramped(&mut market.interest_rate_model, frame_system::Pallet::<T>::block_number())
+				.get_borrow_rate(utilization),
+			None => market.interest_rate_model.get_borrow_rate(utilization),
+		}
+		.ok_or(Error::<T>::CannotCalculateBorrowRate)?;
+		// all the interest goes to the lenders
+		let supply_rate =
+			InterestRateModel::get_supply_rate(borrow_rate, utilization.into(), Rate::zero());
+
+		Ok(MarketRates {
+			utilization,
+			borrow_apy: math::compounded_yearly_yield(borrow_rate)?,
+			supply_apy: math::compounded_yearly_yield(supply_rate)?,
+		})
+	}
+
 	/// ```python
 	/// delta_interest_rate = delta_time / period_interest_rate
 	/// debt_delta = debt_principal * delta_interest_rate
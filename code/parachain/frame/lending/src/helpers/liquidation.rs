@@ -1,17 +1,22 @@
 use crate::*;
+use composable_support::math::safe::{SafeAdd, SafeSub};
 use composable_traits::{
-	defi::{CurrencyPair, DeFiComposableConfig, DeFiEngine, Sell},
-	lending::Lending,
-	liquidation::Liquidation,
+	defi::{DeFiComposableConfig, DeFiEngine},
+	lending::{BorrowAmountOf, Lending, TotalDebtWithInterest},
 	oracle::Oracle,
 	vault::Vault,
 };
 use frame_support::{
 	pallet_prelude::*,
 	storage::{with_transaction, TransactionOutcome},
-	traits::fungible::Transfer as NativeTransfer,
+	traits::{
+		fungible::Transfer as NativeTransfer,
+		fungibles::{Inspect, Mutate, MutateHold, Transfer},
+	},
+};
+use sp_runtime::{
+	traits::Saturating, ArithmeticError, DispatchError, FixedPointNumber, FixedU128, PerThing,
 };
-use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
@@ -26,6 +31,27 @@ impl<T: Config> Pallet<T> {
 		Ok(should_liquidate)
 	}
 
+	/// The positions among `positions` which should be liquidated. Positions which can not be
+	/// checked are left out.
+	pub fn unhealthy_positions(
+		positions: Vec<(MarketId, <Self as DeFiEngine>::AccountId)>,
+	) -> Vec<(MarketId, <Self as DeFiEngine>::AccountId)> {
+		positions
+			.into_iter()
+			.filter(|(market_id, account)| {
+				Self::should_liquidate(market_id, account).unwrap_or_else(|error| {
+					log::warn!(
+						"Health check failed, market_id: {:?}, account: {:?}, error: {:?}",
+						market_id,
+						account,
+						error
+					);
+					false
+				})
+			})
+			.collect()
+	}
+
 	pub fn soon_under_collateralized(
 		market_id: &<Self as Lending>::MarketId,
 		account: &<Self as DeFiEngine>::AccountId,
@@ -38,6 +64,11 @@ impl<T: Config> Pallet<T> {
 	/// Initiate liquidation of individual position for particular borrower within mentioned
 	/// market. Returns 'Ok(())' in the case of successful initiation, 'Err(DispatchError)' in
 	/// the opposite case.
+	///
+	/// The liquidator repays at most `close_factor` of the borrower's debt per liquidation and
+	/// receives collateral worth the repaid debt plus `liquidation_incentive`, the rest of the
+	/// position is left intact. If the market is in the borrower's portfolio, the most valuable
+	/// collateral of the portfolio is seized.
 	/// - `liquidator` : Liquidator's account id.
 	/// - `market_pair` : Index and configuration of the market from which tokens were borrowed.
 	/// - `account` : Borrower's account id whose debt are going to be liquidated.
@@ -53,20 +84,111 @@ impl<T: Config> Pallet<T> {
 			DispatchError::Other("Tried liquidate position which is not supposed to be liquidated")
 		);
 
-		let collateral_to_liquidate = Self::collateral_of_account(market_id, account)?;
+		let total_debt = match Self::total_debt_with_interest(market_id, account)? {
+			TotalDebtWithInterest::Amount(amount) => amount,
+			TotalDebtWithInterest::NoDebt => return Err(Error::<T>::BorrowDoesNotExist.into()),
+		};
+		let repaid_amount = market.close_factor.mul_floor(total_debt);
 
-		let source_target_account = Self::account_id(market_id);
+		let repaid_value = Self::get_price(borrow_asset, repaid_amount)?;
+		let seized_value =
+			repaid_value.safe_add(&market.liquidation_incentive.mul_floor(repaid_value))?;
+		let (collateral_market_id, collateral) = if Self::is_in_portfolio(market_id, account) {
+			Self::portfolio_collateral_to_seize(account)?
+		} else {
+			(**market_id, Self::collateral_of_account(market_id, account)?)
+		};
+		let collateral_asset = if collateral_market_id == **market_id {
+			market.collateral_asset
+		} else {
+			Self::get_market(&collateral_market_id)?.1.collateral_asset
+		};
+		let seized_collateral =
+			T::Oracle::get_price_inverse(collateral_asset, seized_value)?.min(collateral);
 
-		let unit_price =
-			T::Oracle::get_ratio(CurrencyPair::new(market.collateral_asset, borrow_asset))?;
+		Self::repay_liquidated_debt(liquidator, market_id, account, total_debt, repaid_amount)?;
 
-		let sell =
-			Sell::new(market.collateral_asset, borrow_asset, collateral_to_liquidate, unit_price);
-		T::Liquidation::liquidate(&source_target_account, sell, market.liquidators.clone())?;
-		if let Some(deposit) = BorrowRent::<T>::get(market_id, account) {
-			let market_account = Self::account_id(market_id);
-			<T as Config>::NativeCurrency::transfer(&market_account, liquidator, deposit, false)?;
+		// collateral is held by the market it was deposited to
+		<T as Config>::MultiCurrency::transfer(
+			collateral_asset,
+			&Self::account_id(&collateral_market_id),
+			liquidator,
+			seized_collateral,
+			false,
+		)?;
+		AccountCollateral::<T>::insert(
+			collateral_market_id,
+			account,
+			collateral.safe_sub(&seized_collateral)?,
+		);
+
+		// the whole debt was covered, so the borrow no longer exists
+		if repaid_amount == total_debt {
+			BorrowTimestamp::<T>::remove(market_id, account);
+			DebtIndex::<T>::remove(market_id, account);
+			if let Some(deposit) = BorrowRent::<T>::get(market_id, account) {
+				<T as Config>::NativeCurrency::transfer(
+					&Self::account_id(market_id),
+					liquidator,
+					deposit,
+					false,
+				)?;
+			}
 		}
+
+		Self::deposit_event(Event::<T>::PositionLiquidated {
+			market_id: **market_id,
+			account: account.clone(),
+			repaid_amount,
+			seized_collateral,
+		});
+		Ok(())
+	}
+
+	/// Repays `amount` of `account`'s debt, interest and principal proportionately, with the
+	/// borrow asset of `liquidator`.
+	fn repay_liquidated_debt(
+		liquidator: &T::AccountId,
+		market_id: &MarketId,
+		account: &T::AccountId,
+		total_debt: BorrowAmountOf<Self>,
+		amount: BorrowAmountOf<Self>,
+	) -> Result<(), DispatchError> {
+		let MarketAssets { borrow_asset, debt_asset } = Self::get_assets_for_market(market_id)?;
+		let market_account = Self::account_id(market_id);
+
+		<T as Config>::MultiCurrency::transfer(
+			borrow_asset,
+			liquidator,
+			&market_account,
+			amount,
+			false,
+		)?;
+
+		let principal = <T as Config>::MultiCurrency::balance(debt_asset, account);
+		let principal_to_write_off = if amount == total_debt {
+			principal
+		} else {
+			// INVARIANT: total_debt is non-zero, as the account has debt to liquidate
+			FixedU128::checked_from_rational(principal, total_debt)
+				.ok_or(ArithmeticError::Overflow)?
+				.checked_mul_int::<u128>(amount.into())
+				.ok_or(ArithmeticError::Overflow)?
+				.into()
+		};
+		let interest_to_write_off = amount.saturating_sub(principal_to_write_off);
+
+		<T as Config>::MultiCurrency::release(debt_asset, account, principal_to_write_off, false)?;
+		<T as Config>::MultiCurrency::burn_from(debt_asset, account, principal_to_write_off)?;
+
+		// see `pay_interest` on why the market's interest balance may be slightly less
+		let market_interest = <T as Config>::MultiCurrency::balance(debt_asset, &market_account);
+		<T as Config>::MultiCurrency::burn_from(
+			debt_asset,
+			&market_account,
+			interest_to_write_off.min(market_interest),
+		)?;
+
 		Ok(())
 	}
 
@@ -83,6 +205,7 @@ impl<T: Config> Pallet<T> {
 		// Vector of borrowers whose positions are involved in the liquidation process.
 		let mut subjected_borrowers: Vec<<Self as DeFiEngine>::AccountId> = Vec::new();
 		let market_pair = Self::get_market(market_id)?;
+		ensure!(!market_pair.1.paused.liquidate, Error::<T>::MarketOperationPaused);
 		let borrow_asset = T::Vault::asset_id(&market_pair.1.borrow_asset_vault)?;
 		for account in borrowers.iter() {
 			// Wrap liquidate position request in a storage transaction.
@@ -102,12 +225,9 @@ impl<T: Config> Pallet<T> {
 				});
 
 			// If storage transaction succeeded,
-			// push borrower to the output vector,
-			// remove debt records from storages.
+			// push borrower to the output vector.
 			if storage_transaction_succeeded.is_ok() {
 				subjected_borrowers.push(account.clone());
-				BorrowTimestamp::<T>::remove(market_id, account);
-				DebtIndex::<T>::remove(market_id, account);
 			}
 		}
 		Ok(subjected_borrowers)
@@ -4,10 +4,13 @@ use crate::{
 	},
 	*,
 };
-use composable_support::validation::Validated;
+use composable_support::validation::{TryIntoValidated, Validated};
 use composable_traits::{
 	currency::CurrencyFactory,
-	lending::{Lending, MarketConfig, UpdateInput},
+	lending::{
+		math::{InterestRateModel, InterestRateModelIsValid, InterestRateModelRamp},
+		Lending, MarketConfig, UpdateInput,
+	},
 	vault::{Deposit, Vault, VaultConfig},
 };
 use frame_support::{pallet_prelude::*, traits::fungibles::Transfer};
@@ -84,7 +87,12 @@ impl<T: Config> Pallet<T> {
 				under_collateralized_warn_percent: config_input
 					.updatable
 					.under_collateralized_warn_percent,
-				liquidators: config_input.updatable.liquidators,
+				close_factor: config_input.updatable.close_factor,
+				liquidation_incentive: config_input.updatable.liquidation_incentive,
+				supply_cap: config_input.updatable.supply_cap,
+				borrow_cap: config_input.updatable.borrow_cap,
+				debt_ceiling: config_input.updatable.debt_ceiling,
+				paused: config_input.updatable.paused,
 			};
 			let debt_token_id = T::CurrencyFactory::reserve_lp_token_id()?;
 
@@ -100,7 +108,7 @@ impl<T: Config> Pallet<T> {
 		manager: T::AccountId,
 		market_id: MarketId,
 		input: Validated<
-			UpdateInput<T::LiquidationStrategyId, <T as frame_system::Config>::BlockNumber>,
+			UpdateInput<<T as frame_system::Config>::BlockNumber, T::Balance>,
 			UpdateInputValid,
 		>,
 	) -> Result<(), DispatchError> {
@@ -113,9 +121,20 @@ impl<T: Config> Pallet<T> {
 					market.collateral_factor >= input.collateral_factor,
 					Error::<T>::CannotIncreaseCollateralFactorOfOpenMarket
 				);
+				// collateral of isolated markets can back only their own debt
+				ensure!(
+					input.debt_ceiling.is_none() ||
+						!MarketPortfolioCount::<T>::contains_key(market_id),
+					Error::<T>::IsolatedMarketCanNotBeInPortfolio
+				);
 				market.collateral_factor = input.collateral_factor;
 				market.under_collateralized_warn_percent = input.under_collateralized_warn_percent;
-				market.liquidators = input.liquidators.clone();
+				market.close_factor = input.close_factor;
+				market.liquidation_incentive = input.liquidation_incentive;
+				market.supply_cap = input.supply_cap;
+				market.borrow_cap = input.borrow_cap;
+				market.debt_ceiling = input.debt_ceiling;
+				market.paused = input.paused;
 				Ok(())
 			} else {
 				Err(Error::<T>::MarketDoesNotExist)
@@ -124,6 +143,28 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Starts ramping the interest rate model of `market_id` to `target`, returns the block at
+	/// which the ramp completes.
+	pub(crate) fn do_ramp_interest_rate_model(
+		market_id: &MarketId,
+		target: InterestRateModel,
+		duration: T::BlockNumber,
+	) -> Result<T::BlockNumber, DispatchError> {
+		Self::get_market(market_id)?;
+		ensure!(
+			!InterestRateModelRamps::<T>::contains_key(market_id),
+			Error::<T>::InterestRateModelRampInProgress
+		);
+		let target = target.try_into_validated::<InterestRateModelIsValid>()?.value();
+		let start = frame_system::Pallet::<T>::block_number();
+		let end = start.saturating_add(duration);
+		InterestRateModelRamps::<T>::insert(
+			market_id,
+			InterestRateModelRamp { target, start, end },
+		);
+		Ok(end)
+	}
+
 	/// Returns pair of market's id and market (as 'MarketConfig') via market's id
 	/// - `market_id` : Market index as a key in 'Markets' storage
 	pub(crate) fn get_market(
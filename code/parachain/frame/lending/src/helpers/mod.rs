@@ -1,9 +1,12 @@
 pub mod borrow;
 pub mod collateral;
+pub mod flash_loan;
 pub mod interest;
 pub mod liquidation;
 pub mod market;
 pub mod offchain_workers;
 pub mod on_init;
+pub mod portfolio;
+pub mod positions;
 pub mod price;
 pub mod repay_borrow;
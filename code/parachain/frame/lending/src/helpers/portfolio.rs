@@ -0,0 +1,157 @@
+use crate::{models::borrower_data::BorrowerData, types::MarketId, *};
+use composable_support::{
+	math::safe::{SafeAdd, SafeDiv, SafeSub},
+	validation::TryIntoValidated,
+};
+use composable_traits::{
+	defi::DeFiComposableConfig,
+	lending::{Lending, TotalDebtWithInterest},
+	vault::Vault,
+};
+use frame_support::pallet_prelude::*;
+use sp_runtime::{
+	traits::{One, Zero},
+	DispatchError, FixedPointNumber, FixedU128, Percent,
+};
+
+impl<T: Config> Pallet<T> {
+	/// Whether `market_id` is one of the markets in `account`'s portfolio.
+	pub fn is_in_portfolio(market_id: &MarketId, account: &T::AccountId) -> bool {
+		AccountPortfolio::<T>::get(account).contains(market_id)
+	}
+
+	pub(crate) fn do_enter_portfolio(
+		market_id: &MarketId,
+		account: &T::AccountId,
+	) -> Result<(), DispatchError> {
+		let (_, market) = Self::get_market(market_id)?;
+		// collateral of isolated markets can back only their own debt
+		ensure!(market.debt_ceiling.is_none(), Error::<T>::IsolatedMarketCanNotBeInPortfolio);
+		AccountPortfolio::<T>::try_mutate(account, |portfolio| -> Result<(), Error<T>> {
+			ensure!(!portfolio.contains(market_id), Error::<T>::MarketAlreadyInPortfolio);
+			portfolio.try_push(*market_id).map_err(|_| Error::<T>::TooManyPortfolioMarkets)
+		})?;
+		MarketPortfolioCount::<T>::mutate(market_id, |count| {
+			*count = Some(count.unwrap_or_default().saturating_add(1))
+		});
+		// debt of the entered market is now covered by the whole portfolio
+		Self::ensure_portfolio_is_healthy(account)
+	}
+
+	pub(crate) fn do_exit_portfolio(
+		market_id: &MarketId,
+		account: &T::AccountId,
+	) -> Result<(), DispatchError> {
+		AccountPortfolio::<T>::try_mutate(account, |portfolio| -> Result<(), Error<T>> {
+			let position = portfolio
+				.iter()
+				.position(|id| id == market_id)
+				.ok_or(Error::<T>::MarketNotInPortfolio)?;
+			portfolio.remove(position);
+			Ok(())
+		})?;
+		MarketPortfolioCount::<T>::mutate_exists(market_id, |count| {
+			*count = count.map(|count| count.saturating_sub(1)).filter(|count| !count.is_zero())
+		});
+		Self::ensure_portfolio_is_healthy(account)?;
+		// the market has to stand on its own collateral from now on
+		if let TotalDebtWithInterest::Amount(_) =
+			Self::total_debt_with_interest(market_id, account)?
+		{
+			ensure!(
+				matches!(Self::should_liquidate(market_id, account), Ok(false)),
+				Error::<T>::WouldGoUnderCollateralized
+			);
+		}
+		Ok(())
+	}
+
+	/// Creates [`BorrowerData`] for the whole portfolio of `account`.
+	///
+	/// Collateral and debt values are summed over all markets of the portfolio. Collateral factor
+	/// is the collateral value weighted average of the markets' collateral factors, so borrow limit
+	/// of the portfolio is the sum of borrow limits of its markets.
+	/// - `collateral_withdrawal` : Market and amount of collateral to be excluded, used to check
+	///   withdrawals.
+	pub(crate) fn create_portfolio_borrower_data(
+		account: &T::AccountId,
+		under_collateralized_warn_percent: Percent,
+		collateral_withdrawal: Option<(&MarketId, T::Balance)>,
+	) -> Result<BorrowerData, DispatchError> {
+		let mut collateral_balance_value = T::Balance::zero();
+		let mut borrow_balance_value = T::Balance::zero();
+		let mut max_borrow_value = FixedU128::zero();
+		for market_id in AccountPortfolio::<T>::get(account).iter() {
+			let (_, market) = Self::get_market(market_id)?;
+
+			let mut collateral = AccountCollateral::<T>::get(market_id, account)
+				.unwrap_or_else(<T as DeFiComposableConfig>::Balance::zero);
+			if let Some((withdrawn_from, amount)) = collateral_withdrawal {
+				if withdrawn_from == market_id {
+					collateral = collateral.safe_sub(&amount)?;
+				}
+			}
+			let collateral_value = Self::get_price(market.collateral_asset, collateral)?;
+			collateral_balance_value = collateral_balance_value.safe_add(&collateral_value)?;
+			max_borrow_value = max_borrow_value.safe_add(
+				&FixedU128::saturating_from_integer(collateral_value.into())
+					.safe_div(&market.collateral_factor)?,
+			)?;
+
+			let debt = Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
+			borrow_balance_value = borrow_balance_value.safe_add(&Self::get_price(
+				T::Vault::asset_id(&market.borrow_asset_vault)?,
+				debt,
+			)?)?;
+		}
+
+		let collateral_factor = if max_borrow_value.is_zero() {
+			FixedU128::one()
+		} else {
+			FixedU128::saturating_from_integer(collateral_balance_value.into())
+				.safe_div(&max_borrow_value)?
+		};
+
+		Ok(BorrowerData::new(
+			collateral_balance_value,
+			borrow_balance_value,
+			collateral_factor
+				.try_into_validated()
+				.map_err(|_| Error::<T>::InvalidCollateralFactor)?,
+			under_collateralized_warn_percent,
+		))
+	}
+
+	/// Picks the market of `account`'s portfolio with the most valuable collateral. Markets
+	/// with liquidations paused or outdated prices are skipped.
+	///
+	/// Returns the market id and the amount of collateral `account` has there.
+	pub(crate) fn portfolio_collateral_to_seize(
+		account: &T::AccountId,
+	) -> Result<(MarketId, T::Balance), DispatchError> {
+		let mut most_valuable: Option<(MarketId, T::Balance, T::Balance)> = None;
+		for market_id in AccountPortfolio::<T>::get(account).iter() {
+			let (_, market) = Self::get_market(market_id)?;
+			if market.paused.liquidate || Self::ensure_price_is_recent(&market).is_err() {
+				continue
+			}
+			let collateral = match AccountCollateral::<T>::get(market_id, account) {
+				Some(collateral) if !collateral.is_zero() => collateral,
+				_ => continue,
+			};
+			let value = Self::get_price(market.collateral_asset, collateral)?;
+			if most_valuable.map_or(true, |(_, _, max_value)| value > max_value) {
+				most_valuable = Some((*market_id, collateral, value));
+			}
+		}
+		most_valuable
+			.map(|(market_id, collateral, _)| (market_id, collateral))
+			.ok_or_else(|| Error::<T>::AccountCollateralAbsent.into())
+	}
+
+	fn ensure_portfolio_is_healthy(account: &T::AccountId) -> Result<(), DispatchError> {
+		let borrower = Self::create_portfolio_borrower_data(account, Percent::zero(), None)?;
+		ensure!(!borrower.should_liquidate()?, Error::<T>::WouldGoUnderCollateralized);
+		Ok(())
+	}
+}
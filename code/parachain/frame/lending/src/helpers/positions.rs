@@ -0,0 +1,119 @@
+use crate::{types::MarketId, *};
+use composable_support::math::safe::{SafeDiv, SafeMul};
+use composable_traits::{
+	currency::LocalAssets,
+	defi::{DeFiComposableConfig, Rate},
+	lending::{AccountPosition, CollateralRatio, Lending},
+	oracle::Oracle,
+	vault::Vault,
+};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, DispatchError, FixedPointNumber, FixedU128,
+};
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+	/// Collateral and debt with interest of `account` on every market it has collateral or debt
+	/// on.
+	pub fn account_positions(
+		account: &T::AccountId,
+	) -> Result<Vec<AccountPosition<MarketId, T::MayBeAssetId, T::Balance>>, DispatchError> {
+		let mut positions = Vec::new();
+		for (market_id, market) in Markets::<T>::iter() {
+			let collateral_amount = AccountCollateral::<T>::get(market_id, account)
+				.unwrap_or_else(<T as DeFiComposableConfig>::Balance::zero);
+			let debt_with_interest =
+				Self::total_debt_with_interest(&market_id, account)?.unwrap_or_zero();
+			if collateral_amount.is_zero() && debt_with_interest.is_zero() {
+				continue
+			}
+			positions.push(AccountPosition {
+				market_id,
+				collateral_asset: market.collateral_asset,
+				collateral_amount,
+				borrow_asset: T::Vault::asset_id(&market.borrow_asset_vault)?,
+				debt_with_interest,
+			});
+		}
+		Ok(positions)
+	}
+
+	/// Ratio of `account`'s collateral to debt on `market_id`, relative to the collateral factor of
+	/// the market, or of the portfolio if the market is in `account`'s portfolio.
+	///
+	/// The position is liquidated once it goes below one. Returns `None` if `account` has no debt.
+	pub fn health_factor(
+		market_id: &MarketId,
+		account: &T::AccountId,
+	) -> Result<Option<Rate>, DispatchError> {
+		let borrower = Self::create_borrower_data(market_id, account)?;
+		match borrower.current_collateral_ratio()? {
+			CollateralRatio::Ratio(ratio) => Ok(Some(ratio.safe_div(&borrower.collateral_factor)?)),
+			CollateralRatio::NoBorrowValue => Ok(None),
+		}
+	}
+
+	/// Price of one unit of `market_id`'s collateral asset at which `account` gets liquidated,
+	/// all other prices being unchanged.
+	///
+	/// Returns `None` if `account` has no debt or no collateral on `market_id`, or if the
+	/// collateral of the other markets of its portfolio covers the debt on its own.
+	pub fn liquidation_price(
+		market_id: &MarketId,
+		account: &T::AccountId,
+	) -> Result<Option<T::Balance>, DispatchError> {
+		let (_, market) = Self::get_market(market_id)?;
+		let collateral = AccountCollateral::<T>::get(market_id, account)
+			.unwrap_or_else(<T as DeFiComposableConfig>::Balance::zero);
+		let borrower = Self::create_borrower_data(market_id, account)?;
+		if collateral.is_zero() || borrower.borrow_balance_total_value.is_zero() {
+			return Ok(None)
+		}
+
+		let collateral_value = FixedU128::saturating_from_integer(
+			Self::get_price(market.collateral_asset, collateral)?.into(),
+		);
+		// borrow limit provided by collateral of the other markets of the portfolio
+		let other_borrow_limit = borrower
+			.collateral_balance_total_value
+			.safe_div(&borrower.collateral_factor)?
+			.saturating_sub(collateral_value.safe_div(&market.collateral_factor)?);
+		// the position is liquidated once the borrow limit of all collateral falls below the debt
+		let liquidation_value = borrower
+			.borrow_balance_total_value
+			.saturating_sub(other_borrow_limit)
+			.safe_mul(&market.collateral_factor)?;
+		if liquidation_value.is_zero() {
+			return Ok(None)
+		}
+
+		let unit = <<T::Oracle as Oracle>::LocalAssets as LocalAssets<T::MayBeAssetId>>::unit::<
+			T::Balance,
+		>(market.collateral_asset)?;
+		let unit_price = Self::get_price(market.collateral_asset, unit)?;
+		let liquidation_price = liquidation_value
+			.safe_div(&collateral_value)?
+			.checked_mul_int(unit_price)
+			.ok_or(ArithmeticError::Overflow)?;
+		Ok(Some(liquidation_price))
+	}
+
+	/// Amount of borrow asset `account` can borrow from `market_id`, limited by its collateral and
+	/// by the liquidity of the market.
+	pub fn max_borrowable(
+		market_id: &MarketId,
+		account: &T::AccountId,
+	) -> Result<T::Balance, DispatchError> {
+		let (_, market) = Self::get_market(market_id)?;
+		let borrow_limit = Self::get_borrow_limit(market_id, account)?;
+		if borrow_limit.is_zero() {
+			return Ok(borrow_limit)
+		}
+		let borrowable = <T::Oracle as Oracle>::get_price_inverse(
+			T::Vault::asset_id(&market.borrow_asset_vault)?,
+			borrow_limit,
+		)?;
+		Ok(borrowable.min(Self::total_available_to_be_borrowed(market_id)?))
+	}
+}
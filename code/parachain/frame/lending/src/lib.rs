@@ -71,10 +71,10 @@ pub mod pallet {
 		currency::CurrencyFactory,
 		defi::{DeFiComposableConfig, *},
 		lending::{
+			math::{InterestRateModel, InterestRateModelRamp},
 			BorrowAmountOf, CollateralLpAmountOf, CreateInput, LendAssetAmountOf, Lending,
 			MarketConfig, RepayStrategy, TotalDebtWithInterest, UpdateInput,
 		},
-		liquidation::Liquidation,
 		oracle::Oracle,
 		time::Timestamp,
 		vault::{StrategicVault, Vault},
@@ -84,6 +84,7 @@ pub mod pallet {
 	use codec::Codec;
 	use composable_support::validation::TryIntoValidated;
 	use frame_support::{
+		dispatch::{GetDispatchInfo, PostDispatchInfo},
 		pallet_prelude::*,
 		traits::{
 			fungible::{Inspect as NativeInspect, Transfer as NativeTransfer},
@@ -100,10 +101,10 @@ pub mod pallet {
 	};
 	use sp_core::crypto::KeyTypeId;
 	use sp_runtime::{
-		traits::{AccountIdConversion, Get},
-		DispatchError, KeyTypeId as CryptoKeyTypeId, Percent,
+		traits::{AccountIdConversion, Dispatchable, Get},
+		DispatchError, KeyTypeId as CryptoKeyTypeId, Percent, Permill,
 	};
-	use sp_std::{fmt::Debug, vec::Vec};
+	use sp_std::{boxed::Box, fmt::Debug, vec::Vec};
 
 	// ----------------------------------------------------------------------------------------------------
 	//                                   @Declaration Of The Pallet Type
@@ -171,13 +172,6 @@ pub mod pallet {
 				AssetId = <Self as DeFiComposableConfig>::MayBeAssetId,
 			>;
 
-		type Liquidation: Liquidation<
-			MayBeAssetId = Self::MayBeAssetId,
-			Balance = Self::Balance,
-			AccountId = Self::AccountId,
-			LiquidationStrategyId = Self::LiquidationStrategyId,
-		>;
-
 		type UnixTime: UnixTime;
 
 		/// The maximum amount of markets that can be open at once.
@@ -187,9 +181,6 @@ pub mod pallet {
 
 		type WeightInfo: WeightInfo;
 
-		/// Id of proxy to liquidate
-		type LiquidationStrategyId: Parameter + Default + PartialEq + Clone + Debug + TypeInfo;
-
 		/// Minimal price of borrow asset in Oracle price required to create.
 		/// Examples, 100 USDC.
 		/// Creators puts that amount and it is staked under Vault account.
@@ -227,9 +218,29 @@ pub mod pallet {
 		/// The maximum size of batch for liquidation.
 		type MaxLiquidationBatchSize: Get<u32>;
 
+		/// The maximum amount of markets in a single account's portfolio.
+		#[pallet::constant]
+		type MaxPortfolioMarkets: Get<u32>;
+
+		/// The call dispatched on behalf of flash loan takers, e.g. a swap or a CosmWasm contract
+		/// execution.
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo
+			+ From<frame_system::Call<Self>>;
+
+		/// Part of the flash loan amount which is charged as a fee and credited to the lenders.
+		#[pallet::constant]
+		type FlashLoanFee: Get<Permill>;
+
 		/// Convert a weight value into a deductible fee based on the currency type.
 		type WeightToFee: WeightToFeePolynomial<Balance = Self::Balance>
 			+ WeightToFee<Balance = Self::Balance>;
+
+		/// The origin which may ramp the interest rate model of a market.
+		type UpdateInterestRateModelOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 	}
 
 	// ----------------------------------------------------------------------------------------------------
@@ -241,15 +252,17 @@ pub mod pallet {
 		<T as Config>::VaultId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
 		<T as frame_system::Config>::AccountId,
-		<T as Config>::LiquidationStrategyId,
 		<T as frame_system::Config>::BlockNumber,
+		<T as DeFiComposableConfig>::Balance,
 	>;
 	/// A convenience wrapper around [`CreateInput`].
 	pub type CreateInputOf<T> = CreateInput<
-		<T as Config>::LiquidationStrategyId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
 		<T as frame_system::Config>::BlockNumber,
+		<T as DeFiComposableConfig>::Balance,
 	>;
+	pub(crate) type InterestRateModelRampOf<T> =
+		InterestRateModelRamp<<T as frame_system::Config>::BlockNumber>;
 
 	// ----------------------------------------------------------------------------------------------------
 	//                                      @Pallet Constants
@@ -351,6 +364,33 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Markets whose collateral and debt count toward a single health factor of the account.
+	///
+	/// Account -> [MarketId]
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // Empty portfolio is the default, so ValueQuery is ok here.
+	pub type AccountPortfolio<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<MarketId, T::MaxPortfolioMarkets>,
+		ValueQuery,
+	>;
+
+	/// Number of portfolios each market is in, markets in none are absent.
+	///
+	/// MarketId -> u32
+	#[pallet::storage]
+	pub type MarketPortfolioCount<T: Config> =
+		StorageMap<_, Twox64Concat, MarketId, u32, OptionQuery>;
+
+	/// Ramps of interest rate models in progress.
+	///
+	/// MarketId -> InterestRateModelRamp
+	#[pallet::storage]
+	pub type InterestRateModelRamps<T: Config> =
+		StorageMap<_, Twox64Concat, MarketId, InterestRateModelRampOf<T>, OptionQuery>;
+
 	/// The timestamp of the previous block or defaults to timestamp at genesis.
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)] // LastBlockTimestamp is set on genesis (see below) so it will always be set.
@@ -372,7 +412,7 @@ pub mod pallet {
 		},
 		MarketUpdated {
 			market_id: MarketId,
-			input: UpdateInput<T::LiquidationStrategyId, <T as frame_system::Config>::BlockNumber>,
+			input: UpdateInput<<T as frame_system::Config>::BlockNumber, T::Balance>,
 		},
 		/// Event emitted when asset is deposited by lender.
 		AssetDeposited { sender: T::AccountId, market_id: MarketId, amount: T::Balance },
@@ -393,8 +433,39 @@ pub mod pallet {
 		},
 		/// Event emitted when a liquidation is initiated for a loan.
 		LiquidationInitiated { market_id: MarketId, borrowers: Vec<T::AccountId> },
+		/// Event emitted when a liquidator repays part of a borrower's debt and seizes collateral.
+		PositionLiquidated {
+			market_id: MarketId,
+			account: T::AccountId,
+			repaid_amount: T::Balance,
+			seized_collateral: T::Balance,
+		},
 		/// Event emitted to warn that loan may go under collateralize soon.
 		MayGoUnderCollateralizedSoon { market_id: MarketId, account: T::AccountId },
+		/// Event emitted when market is added to the account's portfolio.
+		PortfolioMarketEntered { account: T::AccountId, market_id: MarketId },
+		/// Event emitted when market is removed from the account's portfolio.
+		PortfolioMarketExited { account: T::AccountId, market_id: MarketId },
+		/// Event emitted when total collateral deposited into market reaches its supply cap,
+		/// once per crossing.
+		SupplyCapReached { market_id: MarketId },
+		/// Event emitted when total debt owed to market reaches its borrow cap, once per crossing.
+		BorrowCapReached { market_id: MarketId },
+		/// Event emitted when flash loan is taken and repaid.
+		FlashLoaned {
+			account: T::AccountId,
+			market_id: MarketId,
+			amount: T::Balance,
+			fee: T::Balance,
+		},
+		/// Event emitted when the interest rate model of market starts ramping to `target`.
+		InterestRateModelRampScheduled {
+			market_id: MarketId,
+			target: InterestRateModel,
+			end: T::BlockNumber,
+		},
+		/// Event emitted when the ramped interest rate model replaces the model of market.
+		InterestRateModelRampCompleted { market_id: MarketId },
 	}
 
 	// ----------------------------------------------------------------------------------------------------
@@ -453,6 +524,29 @@ pub mod pallet {
 		// If Vault is unbalanced we can not borrow from it, since
 		// we do not know how many asset one needs to balance the value.
 		CannotBorrowFromMarketWithUnbalancedVault,
+		/// Market is already a part of the account's portfolio.
+		MarketAlreadyInPortfolio,
+		/// Market is not a part of the account's portfolio.
+		MarketNotInPortfolio,
+		/// Account's portfolio can not contain more markets.
+		TooManyPortfolioMarkets,
+		/// Flash loan of zero amount was requested.
+		CannotFlashLoanZero,
+		/// Flash loan and its fee were not paid back by the end of the call.
+		FlashLoanNotRepaid,
+		/// The operation is paused by the market manager.
+		MarketOperationPaused,
+		/// Deposit would exceed the supply cap of the market.
+		SupplyCapExceeded,
+		/// Borrow would exceed the borrow cap of the market.
+		BorrowCapExceeded,
+		/// Borrow would exceed the debt ceiling of the account in the isolated market.
+		DebtCeilingExceeded,
+		/// Isolated market, one with a debt ceiling, can not be added to a portfolio, nor can a
+		/// market in portfolios get a debt ceiling.
+		IsolatedMarketCanNotBeInPortfolio,
+		/// The interest rate model of the market is already being ramped.
+		InterestRateModelRampInProgress,
 	}
 
 	// ----------------------------------------------------------------------------------------------------
@@ -517,7 +611,6 @@ pub mod pallet {
 		type VaultId = T::VaultId;
 		type MarketId = MarketId;
 		type BlockNumber = T::BlockNumber;
-		type LiquidationStrategyId = <T as Config>::LiquidationStrategyId;
 		type Oracle = T::Oracle;
 		type MaxLiquidationBatchSize = T::MaxLiquidationBatchSize;
 
@@ -543,7 +636,7 @@ pub mod pallet {
 		fn update_market(
 			manager: Self::AccountId,
 			market_id: Self::MarketId,
-			input: UpdateInput<Self::LiquidationStrategyId, Self::BlockNumber>,
+			input: UpdateInput<Self::BlockNumber, Self::Balance>,
 		) -> Result<(), DispatchError> {
 			Self::do_update_market(manager, market_id, input.clone().try_into_validated()?)?;
 			Self::deposit_event(Event::<T>::MarketUpdated { market_id, input });
@@ -560,6 +653,7 @@ pub mod pallet {
 			amount: LendAssetAmountOf<Self>,
 		) -> Result<(), DispatchError> {
 			let (_, market) = Self::get_market(market_id)?;
+			ensure!(!market.paused.deposit, Error::<T>::MarketOperationPaused);
 			T::VaultLender::deposit(&market.borrow_asset_vault, account, amount)?;
 			Self::deposit_event(Event::<T>::AssetDeposited {
 				sender: account.clone(),
@@ -575,6 +669,7 @@ pub mod pallet {
 			amount: LendAssetAmountOf<Self>,
 		) -> Result<(), DispatchError> {
 			let (_, market) = Self::get_market(market_id)?;
+			ensure!(!market.paused.withdraw, Error::<T>::MarketOperationPaused);
 			T::VaultLender::withdraw(&market.borrow_asset_vault, account, amount)?;
 			Self::deposit_event(Event::<T>::AssetWithdrawn {
 				sender: account.clone(),
@@ -776,7 +871,7 @@ pub mod pallet {
 		pub fn update_market(
 			origin: OriginFor<T>,
 			market_id: MarketId,
-			input: UpdateInput<T::LiquidationStrategyId, <T as frame_system::Config>::BlockNumber>,
+			input: UpdateInput<<T as frame_system::Config>::BlockNumber, T::Balance>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			<Self as Lending>::update_market(who, market_id, input)?;
@@ -904,5 +999,91 @@ pub mod pallet {
 			<Self as Lending>::liquidate(&sender, &market_id, borrowers)?;
 			Ok(().into())
 		}
+
+		/// Add market to the sender's portfolio. Collateral and debt of all markets in the
+		/// portfolio count toward a single health factor.
+		/// - `origin` : Sender of this extrinsic.
+		/// - `market_id` : Market index to be added to the portfolio.
+		#[pallet::weight(<T as Config>::WeightInfo::enter_portfolio())]
+		#[transactional]
+		pub fn enter_portfolio(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::do_enter_portfolio(&market_id, &sender)?;
+			Self::deposit_event(Event::<T>::PortfolioMarketEntered { account: sender, market_id });
+			Ok(().into())
+		}
+
+		/// Remove market from the sender's portfolio. Fails if either the portfolio or the
+		/// removed market would go under collateralized.
+		/// - `origin` : Sender of this extrinsic.
+		/// - `market_id` : Market index to be removed from the portfolio.
+		#[pallet::weight(<T as Config>::WeightInfo::exit_portfolio())]
+		#[transactional]
+		pub fn exit_portfolio(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::do_exit_portfolio(&market_id, &sender)?;
+			Self::deposit_event(Event::<T>::PortfolioMarketExited { account: sender, market_id });
+			Ok(().into())
+		}
+
+		/// Lend borrow asset of the market without collateral, for the duration of `call`.
+		/// `amount` plus [`Config::FlashLoanFee`] must be paid back by the end of `call`,
+		/// otherwise the whole extrinsic is reverted. The fee is credited to the market's vault.
+		/// - `origin` : Sender of this extrinsic. (Also the receiver of the loan.)
+		/// - `market_id` : Market index from which borrow asset is lent.
+		/// - `amount` : Amount of borrow asset to lend.
+		/// - `call` : Call dispatched with the sender's origin while the loan is taken.
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			<T as Config>::WeightInfo::flash_loan().saturating_add(dispatch_info.weight)
+		})]
+		#[transactional]
+		pub fn flash_loan(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+			amount: T::Balance,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let fee = Self::do_flash_loan(&market_id, &sender, amount, *call)?;
+			Self::deposit_event(Event::<T>::FlashLoaned {
+				account: sender,
+				market_id,
+				amount,
+				fee,
+			});
+			Ok(().into())
+		}
+
+		/// Gradually change the interest rate model of the market to `target`. Over the next
+		/// `duration` blocks the borrow rate moves linearly from the rate of the current model to
+		/// the rate of `target`, then `target` replaces the current model.
+		/// - `origin` : [`Config::UpdateInterestRateModelOrigin`].
+		/// - `market_id` : Market index whose interest rate model is ramped.
+		/// - `target` : Interest rate model of the market once the ramp completes.
+		/// - `duration` : Number of blocks the ramp lasts.
+		#[pallet::weight(<T as Config>::WeightInfo::ramp_interest_rate_model())]
+		#[transactional]
+		pub fn ramp_interest_rate_model(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+			target: InterestRateModel,
+			duration: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateInterestRateModelOrigin::ensure_origin(origin)?;
+			let end = Self::do_ramp_interest_rate_model(&market_id, target, duration)?;
+			Self::deposit_event(Event::<T>::InterestRateModelRampScheduled {
+				market_id,
+				target,
+				end,
+			});
+			Ok(().into())
+		}
 	}
 }
@@ -3,14 +3,16 @@ pub use self::currency::*;
 use crate::{self as pallet_lending, *};
 use composable_traits::{
 	currency::{Exponent, LocalAssets},
-	defi::DeFiComposableConfig,
+	defi::{DeFiComposableConfig, Rate},
+	dex::{Amm, AssetAmount, SwapResult},
 	governance::{GovernanceRegistry, SignedRawOrigin},
 	oracle::Price,
+	time::DurationSeconds,
 };
 
 use frame_support::{
 	ord_parameter_types, parameter_types,
-	traits::{ConstU32, Everything, GenesisBuild},
+	traits::{ConstU32, Everything},
 	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
 	PalletId,
 };
@@ -27,9 +29,9 @@ use sp_runtime::{
 	traits::{
 		BlakeTwo256, ConvertInto, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify,
 	},
-	DispatchError, Perbill,
+	DispatchError, Perbill, Permill,
 };
-use xcm::latest::SendXcm;
+use sp_std::collections::btree_map::BTreeMap;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
 type Block = frame_system::mocking::MockBlock<Runtime>;
@@ -39,14 +41,6 @@ pub type BlockNumber = u64;
 pub type VaultId = u64;
 pub type Moment = u64;
 
-pub type LiquidationStrategyId = u32;
-pub type OrderId = u32;
-
-parameter_types! {
-	// cspell:disable-next
-	pub const LiquidationsPalletId : PalletId = PalletId(*b"liqd_tns");
-}
-
 pub const MINIMUM_BALANCE: Balance = 1_000_000;
 
 pub static ALICE: Lazy<AccountId> = Lazy::new(|| {
@@ -77,9 +71,7 @@ frame_support::construct_runtime!(
 		Vault: pallet_vault::{Pallet, Call, Storage, Event<T>},
 		Tokens: orml_tokens::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Assets: pallet_assets::{Pallet, Call, Storage},
-		Liquidations: pallet_liquidations::{Pallet, Call, Event<T>},
 		Lending: pallet_lending::{Pallet, Call, Config, Storage, Event<T>},
-		DutchAuction: pallet_dutch_auction::{Pallet, Call, Storage, Event<T>},
 		Oracle: pallet_oracle::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -162,6 +154,11 @@ parameter_types! {
 	pub const MinimumWithdrawal: Balance = 0;
 	pub const VaultPalletId: PalletId = PalletId(*b"cubic___");
 	pub const TombstoneDuration: u64 = 42;
+	pub const RebalanceInterval: u64 = 1;
+	pub const CheckpointInterval: u64 = 1;
+	pub const MaxCheckpoints: u32 = 16;
+	pub const MaxQueuedWithdrawals: u32 = 16;
+	pub const MinimumQueuedWithdrawal: Balance = 0;
 }
 
 impl pallet_vault::Config for Runtime {
@@ -182,6 +179,13 @@ impl pallet_vault::Config for Runtime {
 	type VaultId = VaultId;
 	type TombstoneDuration = TombstoneDuration;
 	type WeightInfo = ();
+	type Strategies = ();
+	type RebalanceInterval = RebalanceInterval;
+	type UnixTime = Timestamp;
+	type CheckpointInterval = CheckpointInterval;
+	type MaxCheckpoints = MaxCheckpoints;
+	type MaxQueuedWithdrawals = MaxQueuedWithdrawals;
+	type MinimumQueuedWithdrawal = MinimumQueuedWithdrawal;
 }
 
 parameter_type_with_key! {
@@ -274,6 +278,109 @@ impl LocalAssets<CurrencyId> for Decimals {
 	}
 }
 
+/// DEX without any pool, lending tests only use prices submitted to the oracle.
+pub struct NoDex;
+
+impl Amm for NoDex {
+	type AssetId = CurrencyId;
+	type Balance = Balance;
+	type AccountId = AccountId;
+	type PoolId = u128;
+
+	fn pool_exists(_pool_id: u128) -> bool {
+		false
+	}
+
+	fn assets(_pool_id: u128) -> Result<BTreeMap<CurrencyId, Permill>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn lp_token(_pool_id: u128) -> Result<CurrencyId, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn redeemable_assets_for_lp_tokens(
+		_pool_id: u128,
+		_lp_amount: Balance,
+	) -> Result<BTreeMap<CurrencyId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn simulate_add_liquidity(
+		_who: &AccountId,
+		_pool_id: u128,
+		_amounts: BTreeMap<CurrencyId, Balance>,
+	) -> Result<Balance, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn simulate_remove_liquidity(
+		_who: &AccountId,
+		_pool_id: u128,
+		_lp_amount: Balance,
+		_min_amounts: BTreeMap<CurrencyId, Balance>,
+	) -> Result<BTreeMap<CurrencyId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn spot_price(
+		_pool_id: u128,
+		_base_asset: AssetAmount<CurrencyId, Balance>,
+		_quote_asset_id: CurrencyId,
+		_calculate_with_fees: bool,
+	) -> Result<SwapResult<CurrencyId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn twap_over_window(
+		_pool_id: u128,
+		_base_asset_id: CurrencyId,
+		_quote_asset_id: CurrencyId,
+		_window: DurationSeconds,
+	) -> Result<Rate, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn add_liquidity(
+		_who: &AccountId,
+		_pool_id: u128,
+		_assets: BTreeMap<CurrencyId, Balance>,
+		_min_mint_amount: Balance,
+		_keep_alive: bool,
+	) -> Result<Balance, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn remove_liquidity(
+		_who: &AccountId,
+		_pool_id: u128,
+		_lp_amount: Balance,
+		_min_receive: BTreeMap<CurrencyId, Balance>,
+	) -> Result<BTreeMap<CurrencyId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn do_buy(
+		_who: &AccountId,
+		_pool_id: u128,
+		_in_asset_id: CurrencyId,
+		_out_asset: AssetAmount<CurrencyId, Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<CurrencyId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn do_swap(
+		_who: &AccountId,
+		_pool_id: u128,
+		_in_asset: AssetAmount<CurrencyId, Balance>,
+		_min_receive: AssetAmount<CurrencyId, Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<CurrencyId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+}
+
 impl pallet_oracle::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Assets;
@@ -300,6 +407,9 @@ impl pallet_oracle::Config for Runtime {
 	type Moment = Moment;
 	type Time = Timestamp;
 	type PalletId = OraclePalletId;
+	type PoolId = u128;
+	type Dex = NoDex;
+	type MaxPriceSourceHops = MinU32;
 }
 
 impl DeFiComposableConfig for Runtime {
@@ -307,83 +417,6 @@ impl DeFiComposableConfig for Runtime {
 	type Balance = Balance;
 }
 
-parameter_types! {
-	// cspell:disable-next
-	pub DutchAuctionPalletId: PalletId = PalletId(*b"dutchauc");
-}
-
-// later will reuse mocks from that crate
-pub struct DutchAuctionsMocks;
-
-impl WeightToFeePolynomial for DutchAuctionsMocks {
-	type Balance = Balance;
-
-	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
-		let one = WeightToFeeCoefficient {
-			degree: 1,
-			coeff_frac: Perbill::zero(),
-			coeff_integer: WEIGHT_TO_FEE.with(|v| *v.borrow()),
-			negative: false,
-		};
-		smallvec![one]
-	}
-}
-
-pub struct XcmFake;
-impl Into<Result<cumulus_pallet_xcm::Origin, XcmFake>> for XcmFake {
-	fn into(self) -> Result<cumulus_pallet_xcm::Origin, XcmFake> {
-		unimplemented!("please test via local-integration-tests")
-	}
-}
-impl From<RuntimeOrigin> for XcmFake {
-	fn from(_: RuntimeOrigin) -> Self {
-		unimplemented!("please test via local-integration-tests")
-	}
-}
-impl SendXcm for XcmFake {
-	type Ticket = ();
-
-	fn validate(
-		_destination: &mut Option<xcm::v3::MultiLocation>,
-		_message: &mut Option<xcm::v3::Xcm<()>>,
-	) -> xcm::v3::SendResult<Self::Ticket> {
-		unimplemented!("please test via local-integration-tests")
-	}
-
-	fn deliver(
-		_ticket: Self::Ticket,
-	) -> core::result::Result<xcm::v3::XcmHash, xcm::v3::SendError> {
-		unimplemented!("please test via local-integration-tests")
-	}
-}
-
-impl pallet_dutch_auction::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type UnixTime = Timestamp;
-	type OrderId = OrderId;
-	type MultiCurrency = Assets;
-	type WeightInfo = pallet_dutch_auction::weights::SubstrateWeight<Self>;
-	type PositionExistentialDeposit = MinimumDeposit;
-	type PalletId = DutchAuctionPalletId;
-	type NativeCurrency = Balances;
-	type XcmOrigin = XcmFake;
-	type AdminOrigin = EnsureRoot<Self::AccountId>;
-	type XcmSender = XcmFake;
-}
-
-impl pallet_liquidations::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type UnixTime = Timestamp;
-	type DutchAuction = DutchAuction;
-	type LiquidationStrategyId = LiquidationStrategyId;
-	type OrderId = OrderId;
-	type PalletId = LiquidationsPalletId;
-	type WeightInfo = pallet_liquidations::weights::SubstrateWeight<Self>;
-	type CanModifyStrategies = EnsureRoot<Self::AccountId>;
-	type XcmSender = XcmFake;
-	type MaxLiquidationStrategiesAmount = ConstU32<3>;
-}
-
 pub type Extrinsic = TestXt<RuntimeCall, ()>;
 pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
 
@@ -420,6 +453,8 @@ parameter_types! {
 	pub LendingPalletId: PalletId = PalletId(*b"liqiudat");
 	pub OracleMarketCreationStake: Balance = NORMALIZED::ONE;
 	pub const MaxLiquidationBatchSize: u32 = 5;
+	pub const MaxPortfolioMarkets: u32 = 4;
+	pub const FlashLoanFee: Permill = Permill::from_parts(900);
 }
 
 parameter_types! {
@@ -448,17 +483,19 @@ impl pallet_lending::Config for Runtime {
 	type NativeCurrency = Balances;
 	type MultiCurrency = Tokens;
 	type CurrencyFactory = LpTokenFactory;
-	type Liquidation = Liquidations;
 	type UnixTime = Timestamp;
 	type MaxMarketCount = MaxLendingCount;
 	type AuthorityId = crypto::TestAuthId;
 	type WeightInfo = ();
-	type LiquidationStrategyId = LiquidationStrategyId;
 	type PalletId = LendingPalletId;
 	type OracleMarketCreationStake = OracleMarketCreationStake;
 	type MaxLiquidationBatchSize = MaxLiquidationBatchSize;
+	type MaxPortfolioMarkets = MaxPortfolioMarkets;
+	type RuntimeCall = RuntimeCall;
+	type FlashLoanFee = FlashLoanFee;
 
 	type WeightToFee = WeightToFee;
+	type UpdateInterestRateModelOrigin = EnsureRoot<AccountId>;
 }
 
 /// Convenience function to set the price of an asset in [`pallet_oracle::Prices`].
@@ -480,11 +517,6 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	pallet_lending::GenesisConfig {}
 		.assimilate_storage::<Runtime>(&mut storage)
 		.unwrap();
-	GenesisBuild::<Runtime>::assimilate_storage(
-		&pallet_liquidations::GenesisConfig {},
-		&mut storage,
-	)
-	.unwrap();
 
 	let mut ext = sp_io::TestExternalities::new(storage);
 	ext.execute_with(|| {
@@ -10,7 +10,7 @@ use composable_traits::{
 };
 use frame_support::{
 	ord_parameter_types, parameter_types,
-	traits::{ConstU32, Everything, GenesisBuild, OnRuntimeUpgrade},
+	traits::{ConstU32, Everything, OnRuntimeUpgrade},
 	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
 	PalletId,
 };
@@ -25,9 +25,8 @@ use sp_runtime::{
 		BlakeTwo256, ConvertInto, Extrinsic as ExtrinsicT, Header as HeaderTrait, IdentifyAccount,
 		IdentityLookup,
 	},
-	DispatchError, Perbill,
+	DispatchError, Perbill, Permill,
 };
-use xcm::latest::SendXcm;
 
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
@@ -36,7 +35,7 @@ use sp_runtime::{
 	transaction_validity::TransactionValidityError,
 };
 
-use super::authority_id_wrapper::*;
+use super::{authority_id_wrapper::*, general::NoDex};
 use sp_runtime::testing::{Block, Digest, Header as HeaderType, TestSignature, TestXt, H256};
 
 pub struct CustomOnRuntimeUpgrade;
@@ -62,18 +61,11 @@ pub type Amount = i128;
 pub type VaultId = u64;
 pub type Moment = u64;
 pub type Signature = TestSignature;
-pub type LiquidationStrategyId = u32;
-pub type OrderId = u32;
 pub type AuthorityId = UintAuthorityIdWrapper;
 pub type AccountId = <AuthorityId as IdentifyAccount>::AccountId;
 pub type Public = AuthorityId;
 pub type Header = HeaderType;
 
-parameter_types! {
-	// cspell:disable-next
-	pub const LiquidationsPalletId: PalletId = PalletId(*b"liqd_tns");
-}
-
 pub static ALICE: Lazy<AccountId> = Lazy::new(|| 0);
 pub static BOB: Lazy<AccountId> = Lazy::new(|| 1);
 pub static CHARLIE: Lazy<AccountId> = Lazy::new(|| 2);
@@ -94,9 +86,7 @@ frame_support::construct_runtime!(
 		Vault: pallet_vault::{Pallet, Call, Storage, Event<T>},
 		Tokens: orml_tokens::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Assets: pallet_assets::{Pallet, Call, Storage},
-		Liquidations: pallet_liquidations::{Pallet, Call, Event<T>},
 		Lending: pallet_lending::{Pallet, Call, Config, Storage, Event<T>},
-		DutchAuction: pallet_dutch_auction::{Pallet, Call, Storage, Event<T>},
 		Oracle: pallet_oracle::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -179,6 +169,11 @@ parameter_types! {
 	pub const MinimumWithdrawal: Balance = 0;
 	pub const VaultPalletId: PalletId = PalletId(*b"cubic___");
   pub const TombstoneDuration: u64 = 42;
+	pub const RebalanceInterval: u64 = 1;
+	pub const CheckpointInterval: u64 = 1;
+	pub const MaxCheckpoints: u32 = 16;
+	pub const MaxQueuedWithdrawals: u32 = 16;
+	pub const MinimumQueuedWithdrawal: Balance = 0;
 }
 
 impl pallet_vault::Config for Runtime {
@@ -199,6 +194,13 @@ impl pallet_vault::Config for Runtime {
 	type VaultId = VaultId;
 	type TombstoneDuration = TombstoneDuration;
 	type WeightInfo = ();
+	type Strategies = ();
+	type RebalanceInterval = RebalanceInterval;
+	type UnixTime = Timestamp;
+	type CheckpointInterval = CheckpointInterval;
+	type MaxCheckpoints = MaxCheckpoints;
+	type MaxQueuedWithdrawals = MaxQueuedWithdrawals;
+	type MinimumQueuedWithdrawal = MinimumQueuedWithdrawal;
 }
 
 parameter_type_with_key! {
@@ -313,6 +315,9 @@ impl pallet_oracle::Config for Runtime {
 	type Moment = Moment;
 	type Time = Timestamp;
 	type PalletId = OraclePalletId;
+	type PoolId = u128;
+	type Dex = NoDex;
+	type MaxPriceSourceHops = MinU32;
 }
 
 impl DeFiComposableConfig for Runtime {
@@ -320,82 +325,6 @@ impl DeFiComposableConfig for Runtime {
 	type Balance = Balance;
 }
 
-parameter_types! {
-	pub DutchAuctionPalletId: PalletId = PalletId(*b"dutchauc");
-}
-
-// later will reuse mocks from that crate
-pub struct DutchAuctionsMocks;
-
-impl WeightToFeePolynomial for DutchAuctionsMocks {
-	type Balance = Balance;
-
-	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
-		let one = WeightToFeeCoefficient {
-			degree: 1,
-			coeff_frac: Perbill::zero(),
-			coeff_integer: WEIGHT_TO_FEE.with(|v| *v.borrow()),
-			negative: false,
-		};
-		smallvec![one]
-	}
-}
-
-pub struct XcmFake;
-impl Into<Result<cumulus_pallet_xcm::Origin, XcmFake>> for XcmFake {
-	fn into(self) -> Result<cumulus_pallet_xcm::Origin, XcmFake> {
-		unimplemented!("please test via local-integration-tests")
-	}
-}
-impl From<RuntimeOrigin> for XcmFake {
-	fn from(_: RuntimeOrigin) -> Self {
-		unimplemented!("please test via local-integration-tests")
-	}
-}
-impl SendXcm for XcmFake {
-	type Ticket = ();
-
-	fn validate(
-		_destination: &mut Option<xcm::v3::MultiLocation>,
-		_message: &mut Option<xcm::v3::Xcm<()>>,
-	) -> xcm::v3::SendResult<Self::Ticket> {
-		unimplemented!("please test via local-integration-tests")
-	}
-
-	fn deliver(
-		_ticket: Self::Ticket,
-	) -> core::result::Result<xcm::v3::XcmHash, xcm::v3::SendError> {
-		unimplemented!("please test via local-integration-tests")
-	}
-}
-
-impl pallet_dutch_auction::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type UnixTime = Timestamp;
-	type OrderId = OrderId;
-	type MultiCurrency = Assets;
-	type WeightInfo = pallet_dutch_auction::weights::SubstrateWeight<Self>;
-	type PositionExistentialDeposit = MinimumDeposit;
-	type PalletId = DutchAuctionPalletId;
-	type NativeCurrency = Balances;
-	type XcmOrigin = XcmFake;
-	type AdminOrigin = EnsureRoot<Self::AccountId>;
-	type XcmSender = XcmFake;
-}
-
-impl pallet_liquidations::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type UnixTime = Timestamp;
-	type DutchAuction = DutchAuction;
-	type LiquidationStrategyId = LiquidationStrategyId;
-	type OrderId = OrderId;
-	type PalletId = LiquidationsPalletId;
-	type WeightInfo = pallet_liquidations::weights::SubstrateWeight<Self>;
-	type CanModifyStrategies = EnsureRoot<Self::AccountId>;
-	type XcmSender = XcmFake;
-	type MaxLiquidationStrategiesAmount = ConstU32<3>;
-}
-
 pub type Extrinsic = TestExtrinsic;
 
 impl frame_system::offchain::SigningTypes for Runtime {
@@ -430,6 +359,8 @@ parameter_types! {
 	pub LendingPalletId: PalletId = PalletId(*b"liquidat");
 	pub OracleMarketCreationStake: Balance = NORMALIZED::ONE;
 	pub const MaxLiquidationBatchSize: u32 = 5;
+	pub const MaxPortfolioMarkets: u32 = 4;
+	pub const FlashLoanFee: Permill = Permill::from_parts(900);
 }
 
 parameter_types! {
@@ -458,16 +389,18 @@ impl pallet_lending::Config for Runtime {
 	type NativeCurrency = Balances;
 	type MultiCurrency = Tokens;
 	type CurrencyFactory = LpTokenFactory;
-	type Liquidation = Liquidations;
 	type UnixTime = Timestamp;
 	type MaxMarketCount = MaxLendingCount;
 	type AuthorityId = AuthorityId;
 	type WeightInfo = ();
-	type LiquidationStrategyId = LiquidationStrategyId;
 	type PalletId = LendingPalletId;
 	type OracleMarketCreationStake = OracleMarketCreationStake;
 	type MaxLiquidationBatchSize = MaxLiquidationBatchSize;
+	type MaxPortfolioMarkets = MaxPortfolioMarkets;
+	type RuntimeCall = RuntimeCall;
+	type FlashLoanFee = FlashLoanFee;
 	type WeightToFee = WeightToFee;
+	type UpdateInterestRateModelOrigin = EnsureRoot<AccountId>;
 }
 
 /// Convenience function to set the price of an asset in [`pallet_oracle::Prices`].
@@ -489,11 +422,6 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	pallet_lending::GenesisConfig {}
 		.assimilate_storage::<Runtime>(&mut storage)
 		.unwrap();
-	GenesisBuild::<Runtime>::assimilate_storage(
-		&pallet_liquidations::GenesisConfig {},
-		&mut storage,
-	)
-	.unwrap();
 
 	let mut ext = sp_io::TestExternalities::new(storage);
 	ext.execute_with(|| {
@@ -0,0 +1,83 @@
+use super::prelude::*;
+use crate::{tests::process_and_progress_blocks, MarketId};
+use composable_traits::vault::Vault as VaultTrait;
+use sp_runtime::{PerThing, Permill};
+
+/// Creates a simple market and funds it's account from the vault.
+fn create_market_with_liquidity() -> (MarketId, VaultId) {
+	let (market_id, vault_id) = create_simple_market();
+	let vault_value = USDT::units(1_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	(market_id, vault_id)
+}
+
+fn remark() -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+}
+
+#[test]
+fn flash_loan_is_repaid_with_fee_credited_to_vault() {
+	new_test_ext().execute_with(|| {
+		let (market_id, vault_id) = create_market_with_liquidity();
+		let market_account = Lending::account_id(&market_id);
+		let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+		let market_balance = Tokens::balance(USDT::ID, &market_account);
+		let vault_balance = Tokens::balance(USDT::ID, &vault_account);
+
+		let amount = USDT::units(1_000);
+		let fee = Permill::from_parts(900).mul_ceil(amount);
+		assert_ok!(Tokens::mint_into(USDT::ID, &BOB, USDT::units(1)));
+
+		assert_extrinsic_event::<Runtime>(
+			Lending::flash_loan(RuntimeOrigin::signed(*BOB), market_id, amount, remark()),
+			RuntimeEvent::Lending(crate::Event::FlashLoaned {
+				account: *BOB,
+				market_id,
+				amount,
+				fee,
+			}),
+		);
+		assert_eq!(Tokens::balance(USDT::ID, &BOB), USDT::units(1) - fee);
+		assert_eq!(Tokens::balance(USDT::ID, &market_account), market_balance);
+		assert_eq!(Tokens::balance(USDT::ID, &vault_account), vault_balance + fee);
+	})
+}
+
+#[test]
+fn flash_loan_must_be_repaid_within_the_call() {
+	new_test_ext().execute_with(|| {
+		let (market_id, _) = create_market_with_liquidity();
+
+		assert_noop!(
+			Lending::flash_loan(RuntimeOrigin::signed(*BOB), market_id, 0, remark()),
+			Error::<Runtime>::CannotFlashLoanZero
+		);
+		// BOB has nothing to pay the fee with
+		assert_noop!(
+			Lending::flash_loan(
+				RuntimeOrigin::signed(*BOB),
+				market_id,
+				USDT::units(1_000),
+				remark()
+			),
+			Error::<Runtime>::FlashLoanNotRepaid
+		);
+		// the inner call fails
+		let nested_call = Box::new(RuntimeCall::Lending(crate::Call::flash_loan {
+			market_id,
+			amount: 0,
+			call: remark(),
+		}));
+		assert_noop!(
+			Lending::flash_loan(
+				RuntimeOrigin::signed(*BOB),
+				market_id,
+				USDT::units(1_000),
+				nested_call
+			),
+			Error::<Runtime>::CannotFlashLoanZero
+		);
+	})
+}
@@ -2,7 +2,11 @@ use super::prelude::*;
 use crate::{
 	helpers::interest::current_interest_rate, tests::new_jump_model, types::AccruedInterest,
 };
-use composable_traits::{defi::Rate, lending::math::InterestRate, time::SECONDS_PER_YEAR_NAIVE};
+use composable_traits::{
+	defi::Rate,
+	lending::math::{self, InterestRate},
+	time::SECONDS_PER_YEAR_NAIVE,
+};
 use sp_arithmetic::assert_eq_error_rate;
 
 #[test]
@@ -20,6 +24,66 @@ fn current_interest_rate_test() {
 	})
 }
 
+#[test]
+fn market_rates_of_market_without_borrows() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let ((market_id, _), _) = create_simple_vaulted_market(BTC::instance(), *ALICE);
+
+		let rates = Lending::market_rates(&market_id).unwrap();
+		assert_eq!(rates.utilization, Percent::zero());
+		assert_eq!(rates.supply_apy, Rate::zero());
+		// e^0.02 - 1
+		assert_eq_error_rate!(
+			rates.borrow_apy,
+			Rate::saturating_from_rational(20_201_340_u128, 1_000_000_000_u128),
+			Rate::saturating_from_rational(1_u128, 1_000_000_000_u128)
+		);
+	})
+}
+
+#[test]
+fn interest_rate_model_is_ramped_to_target() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let ((market_id, _), _) = create_simple_vaulted_market(BTC::instance(), *ALICE);
+		let target = InterestRateModel::new_jump_model(
+			Rate::saturating_from_rational(6, 100),
+			Rate::saturating_from_rational(10, 100),
+			Rate::saturating_from_rational(32, 100),
+			Percent::from_percent(80),
+		)
+		.unwrap();
+
+		assert_noop!(
+			Lending::ramp_interest_rate_model(RuntimeOrigin::signed(*ALICE), market_id, target, 10),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Lending::ramp_interest_rate_model(RuntimeOrigin::root(), market_id, target, 10));
+		System::assert_last_event(RuntimeEvent::Lending(
+			crate::Event::InterestRateModelRampScheduled { market_id, target, end: 11 },
+		));
+		assert_noop!(
+			Lending::ramp_interest_rate_model(RuntimeOrigin::root(), market_id, target, 10),
+			Error::<Runtime>::InterestRateModelRampInProgress
+		);
+
+		// halfway through the ramp, the rate is halfway between 2% and 6%
+		crate::tests::process_and_progress_blocks::<Lending, Runtime>(5);
+		assert_eq!(
+			Lending::market_rates(&market_id).unwrap().borrow_apy,
+			math::compounded_yearly_yield(Rate::saturating_from_rational(4, 100)).unwrap()
+		);
+
+		crate::tests::process_and_progress_blocks::<Lending, Runtime>(5);
+		System::assert_has_event(RuntimeEvent::Lending(
+			crate::Event::InterestRateModelRampCompleted { market_id },
+		));
+		assert!(!crate::InterestRateModelRamps::<Runtime>::contains_key(market_id));
+		assert_eq!(crate::Markets::<Runtime>::get(market_id).unwrap().interest_rate_model, target);
+	})
+}
+
 #[test]
 fn apr_for_zero() {
 	let (_, ref mut interest_rate_model) = new_jump_model();
@@ -1,8 +1,10 @@
 use super::prelude::*;
 use crate::tests::{
-	borrow, create_market_for_liquidation_test, mint_and_deposit_collateral,
-	process_and_progress_blocks,
+	borrow, create_market_for_liquidation_test, default_close_factor,
+	default_liquidation_incentive, mint_and_deposit_collateral, process_and_progress_blocks,
 };
+use composable_traits::{lending::TotalDebtWithInterest, oracle};
+use sp_runtime::PerThing;
 
 #[test]
 fn test_liquidate_multiple() {
@@ -29,6 +31,8 @@ fn test_liquidate_multiple() {
 		// Emulate situation when collateral price has fallen down
 		// from 50_000 USDT to 38_000 USDT.
 		set_price(BTC::ID, NORMALIZED::units(38_000));
+		// the liquidator repays half of each debt
+		assert_ok!(Tokens::mint_into(USDT::ID, &manager, USDT::units(10_000)));
 		let borrowers_vec = vec![first_borrower, second_borrower, third_borrower];
 		let borrowers = TestBoundedVec::try_from(borrowers_vec.clone()).unwrap();
 		assert_extrinsic_event::<Runtime>(
@@ -42,12 +46,18 @@ fn test_liquidate_multiple() {
 				borrowers: borrowers_vec,
 			}),
 		);
-		// Check if cleanup was done correctly
+		// Only close factor of each debt was repaid, the rest of the positions is left intact
 		borrowers.iter().for_each(|borrower| {
-			assert!(!crate::DebtIndex::<Runtime>::contains_key(market_id, borrower))
-		});
-		borrowers.iter().for_each(|borrower| {
-			assert!(!crate::BorrowTimestamp::<Runtime>::contains_key(market_id, borrower))
+			assert_eq!(
+				Lending::total_debt_with_interest(&market_id, borrower),
+				Ok(TotalDebtWithInterest::Amount(USDT::units(10_000)))
+			);
+			assert!(crate::DebtIndex::<Runtime>::contains_key(market_id, borrower));
+			assert!(crate::BorrowTimestamp::<Runtime>::contains_key(market_id, borrower));
+			assert!(
+				crate::AccountCollateral::<Runtime>::get(market_id, borrower).unwrap() <
+					BTC::units(1)
+			);
 		});
 	})
 }
@@ -124,11 +134,19 @@ fn test_liquidation_storage_transaction_rollback() {
 				borrowers: vec![normal_borrower],
 			}),
 		);
-		// Check if cleanup was done correctly
-		assert!(crate::DebtIndex::<Runtime>::contains_key(market_id, borrower_with_a_twist));
-		assert!(crate::BorrowTimestamp::<Runtime>::contains_key(market_id, borrower_with_a_twist));
-		assert!(!crate::DebtIndex::<Runtime>::contains_key(market_id, normal_borrower));
-		assert!(!crate::BorrowTimestamp::<Runtime>::contains_key(market_id, normal_borrower));
+		// Check that only the normal borrower's position was liquidated
+		assert_eq!(
+			Lending::total_debt_with_interest(&market_id, &borrower_with_a_twist),
+			Ok(TotalDebtWithInterest::Amount(USDT::units(20_000)))
+		);
+		assert_eq!(
+			Lending::total_debt_with_interest(&market_id, &normal_borrower),
+			Ok(TotalDebtWithInterest::Amount(USDT::units(10_000)))
+		);
+		assert!(
+			crate::AccountCollateral::<Runtime>::get(market_id, normal_borrower).unwrap() <
+				BTC::units(1)
+		);
 	})
 }
 
@@ -194,12 +212,62 @@ fn liquidation() {
 				borrowers: vec![*ALICE],
 			}),
 		);
-		// Check if cleanup was done correctly
-		assert!(!crate::DebtIndex::<Runtime>::contains_key(market_id, *ALICE));
-		assert!(!crate::BorrowTimestamp::<Runtime>::contains_key(market_id, *ALICE));
+		// The position is liquidated partially
+		assert!(crate::DebtIndex::<Runtime>::contains_key(market_id, *ALICE));
+		assert!(crate::BorrowTimestamp::<Runtime>::contains_key(market_id, *ALICE));
+		assert!(crate::AccountCollateral::<Runtime>::get(market_id, *ALICE).unwrap() < collateral);
 	});
 }
 
+#[test]
+fn liquidation_repays_close_factor_of_debt_and_seizes_collateral_with_incentive() {
+	new_test_ext().execute_with(|| {
+		let manager = *ALICE;
+		let lender = *CHARLIE;
+		let borrower = *BOB;
+		let (market_id, vault_id) = create_market_for_liquidation_test::<Runtime>(manager);
+		let vault_value = USDT::units(100_000_000);
+		assert_ok!(Tokens::mint_into(USDT::ID, &lender, vault_value));
+		assert_ok!(Vault::deposit(RuntimeOrigin::signed(lender), vault_id, vault_value));
+		process_and_progress_blocks::<Lending, Runtime>(1);
+		mint_and_deposit_collateral::<Runtime>(borrower, BTC::units(1), market_id, BTC::ID);
+		borrow::<Runtime>(borrower, market_id, USDT::units(20_000));
+		set_price(BTC::ID, NORMALIZED::units(38_000));
+
+		let repaid_amount = default_close_factor().mul_floor(USDT::units(20_000));
+		assert_ok!(Tokens::mint_into(USDT::ID, &manager, repaid_amount));
+		let repaid_value = get_price(USDT::ID, repaid_amount);
+		let seized_collateral = <Oracle as oracle::Oracle>::get_price_inverse(
+			BTC::ID,
+			repaid_value + default_liquidation_incentive().mul_floor(repaid_value),
+		)
+		.unwrap();
+
+		assert_ok!(Lending::liquidate(
+			RuntimeOrigin::signed(manager),
+			market_id,
+			TestBoundedVec::try_from(vec![borrower]).unwrap(),
+		));
+		System::assert_has_event(RuntimeEvent::Lending(crate::Event::PositionLiquidated {
+			market_id,
+			account: borrower,
+			repaid_amount,
+			seized_collateral,
+		}));
+		assert_eq!(
+			crate::AccountCollateral::<Runtime>::get(market_id, borrower),
+			Some(BTC::units(1) - seized_collateral)
+		);
+		assert_eq!(
+			Lending::total_debt_with_interest(&market_id, &borrower),
+			Ok(TotalDebtWithInterest::Amount(USDT::units(20_000) - repaid_amount))
+		);
+		// the liquidator paid the repaid debt and received the seized collateral
+		assert_eq!(Tokens::balance(USDT::ID, &manager), 0);
+		assert_eq!(Tokens::balance(BTC::ID, &manager), seized_collateral);
+	})
+}
+
 #[test]
 fn test_warn_soon_under_collateralized() {
 	new_test_ext().execute_with(|| {
@@ -304,8 +372,13 @@ fn market_owner_cannot_retroactively_liquidate() {
 		let updatable = UpdateInput {
 			collateral_factor,
 			under_collateralized_warn_percent: Percent::from_float(1.1),
-			liquidators: vec![],
 			max_price_age: DEFAULT_MAX_PRICE_AGE,
+			close_factor: default_close_factor(),
+			liquidation_incentive: default_liquidation_incentive(),
+			supply_cap: None,
+			borrow_cap: None,
+			debt_ceiling: None,
+			paused: Default::default(),
 		};
 		// ALICE is the creator of the market.
 		assert_noop!(
@@ -21,8 +21,13 @@ fn can_update_market() {
 		let update_input = UpdateInput {
 			collateral_factor: market.collateral_factor,
 			under_collateralized_warn_percent: market.under_collateralized_warn_percent,
-			liquidators: market.liquidators.clone(),
 			max_price_age: market.max_price_age,
+			close_factor: market.close_factor,
+			liquidation_incentive: market.liquidation_incentive,
+			supply_cap: market.supply_cap,
+			borrow_cap: market.borrow_cap,
+			debt_ceiling: market.debt_ceiling,
+			paused: market.paused,
 		};
 		let updated = Lending::update_market(origin, market_id, update_input.clone());
 		// check if the market was successfully updated
@@ -36,13 +41,35 @@ fn can_update_market() {
 		let update_input = UpdateInput {
 			collateral_factor: FixedU128::from_float(0.5),
 			under_collateralized_warn_percent: market.under_collateralized_warn_percent,
-			liquidators: market.liquidators,
 			max_price_age: market.max_price_age,
+			close_factor: market.close_factor,
+			liquidation_incentive: market.liquidation_incentive,
+			supply_cap: market.supply_cap,
+			borrow_cap: market.borrow_cap,
+			debt_ceiling: market.debt_ceiling,
+			paused: market.paused,
 		};
 		assert_err!(
 			update_input.try_into_validated::<UpdateInputValid>(),
 			"Collateral factor must be more than one."
 		);
+
+		// validation on input fails as the seized collateral would be worth twice the repaid debt
+		let update_input = UpdateInput {
+			collateral_factor: market.collateral_factor,
+			under_collateralized_warn_percent: market.under_collateralized_warn_percent,
+			max_price_age: market.max_price_age,
+			close_factor: market.close_factor,
+			liquidation_incentive: Percent::from_percent(100),
+			supply_cap: market.supply_cap,
+			borrow_cap: market.borrow_cap,
+			debt_ceiling: market.debt_ceiling,
+			paused: market.paused,
+		};
+		assert_err!(
+			update_input.try_into_validated::<UpdateInputValid>(),
+			"Liquidation incentive must be less than 100%."
+		);
 	})
 }
 
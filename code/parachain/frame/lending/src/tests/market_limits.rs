@@ -0,0 +1,197 @@
+use super::prelude::*;
+use crate::{
+	tests::{borrow, create_market_for_liquidation_test, process_and_progress_blocks},
+	MarketId,
+};
+use composable_traits::lending::PausedOperations;
+use frame_support::dispatch::DispatchResultWithPostInfo;
+
+/// Creates a market with USDT as borrow asset and BTC (`50_000`) as collateral, funds its vault
+/// and lets BOB deposit `1` BTC.
+fn create_market_with_collateral() -> MarketId {
+	let (market_id, vault_id) = create_market_for_liquidation_test::<Runtime>(*ALICE);
+	let vault_value = USDT::units(100_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), market_id, BTC::ID);
+	market_id
+}
+
+/// Updates the market with its current configuration, modified by `f`.
+fn update_market(market_id: MarketId, f: impl FnOnce(&mut UpdateInput<BlockNumber, Balance>)) {
+	assert_ok!(try_update_market(market_id, f));
+}
+
+fn try_update_market(
+	market_id: MarketId,
+	f: impl FnOnce(&mut UpdateInput<BlockNumber, Balance>),
+) -> DispatchResultWithPostInfo {
+	let market = crate::Markets::<Runtime>::get(market_id).unwrap();
+	let mut input = UpdateInput {
+		collateral_factor: market.collateral_factor,
+		under_collateralized_warn_percent: market.under_collateralized_warn_percent,
+		max_price_age: market.max_price_age,
+		close_factor: market.close_factor,
+		liquidation_incentive: market.liquidation_incentive,
+		supply_cap: market.supply_cap,
+		borrow_cap: market.borrow_cap,
+		debt_ceiling: market.debt_ceiling,
+		paused: market.paused,
+	};
+	f(&mut input);
+	Lending::update_market(RuntimeOrigin::signed(*ALICE), market_id, input)
+}
+
+#[test]
+fn deposit_can_not_exceed_supply_cap() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_collateral();
+		update_market(market_id, |input| input.supply_cap = Some(BTC::units(2)));
+		assert_ok!(Tokens::mint_into(BTC::ID, &BOB, BTC::units(2)));
+
+		assert_noop!(
+			Lending::deposit_collateral(
+				RuntimeOrigin::signed(*BOB),
+				market_id,
+				BTC::units(2),
+				false
+			),
+			Error::<Runtime>::SupplyCapExceeded
+		);
+		assert_ok!(Lending::deposit_collateral(
+			RuntimeOrigin::signed(*BOB),
+			market_id,
+			BTC::units(1),
+			false
+		));
+		System::assert_has_event(RuntimeEvent::Lending(crate::Event::SupplyCapReached {
+			market_id,
+		}));
+	})
+}
+
+#[test]
+fn supply_cap_reached_is_emitted_when_the_cap_is_crossed() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_collateral();
+		update_market(market_id, |input| input.supply_cap = Some(BTC::units(3)));
+		let supply_cap_reached =
+			RuntimeEvent::Lending(crate::Event::SupplyCapReached { market_id });
+
+		mint_and_deposit_collateral::<Runtime>(*CHARLIE, BTC::units(1), market_id, BTC::ID);
+		assert!(!System::events().iter().any(|record| record.event == supply_cap_reached));
+
+		mint_and_deposit_collateral::<Runtime>(*CHARLIE, BTC::units(1), market_id, BTC::ID);
+		System::assert_has_event(supply_cap_reached);
+	})
+}
+
+#[test]
+fn borrow_can_not_exceed_borrow_cap() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_collateral();
+		update_market(market_id, |input| input.borrow_cap = Some(USDT::units(10_000)));
+
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*BOB), market_id, USDT::units(10_001)),
+			Error::<Runtime>::BorrowCapExceeded
+		);
+		borrow::<Runtime>(*BOB, market_id, USDT::units(10_000));
+		System::assert_has_event(RuntimeEvent::Lending(crate::Event::BorrowCapReached {
+			market_id,
+		}));
+	})
+}
+
+#[test]
+fn isolated_market_limits_debt_of_account() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_collateral();
+		update_market(market_id, |input| input.debt_ceiling = Some(USDT::units(5_000)));
+
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*BOB), market_id, USDT::units(6_000)),
+			Error::<Runtime>::DebtCeilingExceeded
+		);
+		borrow::<Runtime>(*BOB, market_id, USDT::units(5_000));
+		assert_noop!(
+			Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), market_id),
+			Error::<Runtime>::IsolatedMarketCanNotBeInPortfolio
+		);
+	})
+}
+
+#[test]
+fn market_in_portfolios_can_not_be_isolated() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_collateral();
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), market_id));
+
+		assert_noop!(
+			try_update_market(market_id, |input| input.debt_ceiling = Some(USDT::units(5_000))),
+			Error::<Runtime>::IsolatedMarketCanNotBeInPortfolio
+		);
+		assert_ok!(Lending::exit_portfolio(RuntimeOrigin::signed(*BOB), market_id));
+		update_market(market_id, |input| input.debt_ceiling = Some(USDT::units(5_000)));
+	})
+}
+
+#[test]
+fn paused_operations_can_not_be_performed() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_collateral();
+		update_market(market_id, |input| {
+			input.paused =
+				PausedOperations { deposit: true, borrow: true, withdraw: true, liquidate: true }
+		});
+		assert_ok!(Tokens::mint_into(BTC::ID, &BOB, BTC::units(1)));
+		assert_ok!(Tokens::mint_into(USDT::ID, &BOB, USDT::units(1)));
+
+		assert_noop!(
+			Lending::deposit_collateral(
+				RuntimeOrigin::signed(*BOB),
+				market_id,
+				BTC::units(1),
+				false
+			),
+			Error::<Runtime>::MarketOperationPaused
+		);
+		assert_noop!(
+			Lending::vault_deposit(RuntimeOrigin::signed(*BOB), market_id, USDT::units(1)),
+			Error::<Runtime>::MarketOperationPaused
+		);
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*BOB), market_id, USDT::units(1)),
+			Error::<Runtime>::MarketOperationPaused
+		);
+		assert_noop!(
+			Lending::flash_loan(
+				RuntimeOrigin::signed(*BOB),
+				market_id,
+				USDT::units(1),
+				Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+			),
+			Error::<Runtime>::MarketOperationPaused
+		);
+		assert_noop!(
+			Lending::withdraw_collateral(RuntimeOrigin::signed(*BOB), market_id, BTC::units(1)),
+			Error::<Runtime>::MarketOperationPaused
+		);
+		assert_noop!(
+			Lending::liquidate(
+				RuntimeOrigin::signed(*ALICE),
+				market_id,
+				TestBoundedVec::try_from(vec![*BOB]).unwrap()
+			),
+			Error::<Runtime>::MarketOperationPaused
+		);
+
+		update_market(market_id, |input| input.paused = PausedOperations::default());
+		assert_ok!(Lending::withdraw_collateral(
+			RuntimeOrigin::signed(*BOB),
+			market_id,
+			BTC::units(1)
+		));
+	})
+}
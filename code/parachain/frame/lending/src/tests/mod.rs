@@ -21,10 +21,14 @@ use pallet_timestamp::Config as PalletTimestampConfig;
 use sp_runtime::traits::One;
 
 pub mod borrow;
+pub mod flash_loan;
 pub mod interest;
 pub mod liquidation;
 pub mod market;
+pub mod market_limits;
 pub mod offchain;
+pub mod portfolio;
+pub mod positions;
 pub mod prelude;
 pub mod repay;
 pub mod vault;
@@ -53,13 +57,18 @@ impl ConfigBound for Runtime {}
 /// Creates a "default" [`CreateInput`], with the specified [`CurrencyPair`].
 fn default_create_input<AssetId, BlockNumber: sp_runtime::traits::Bounded>(
 	currency_pair: CurrencyPair<AssetId>,
-) -> CreateInput<u32, AssetId, BlockNumber> {
+) -> CreateInput<AssetId, BlockNumber, Balance> {
 	CreateInput {
 		updatable: UpdateInput {
 			collateral_factor: default_collateral_factor(),
 			under_collateralized_warn_percent: default_under_collateralized_warn_percent(),
-			liquidators: vec![],
 			max_price_age: BlockNumber::max_value(),
+			close_factor: default_close_factor(),
+			liquidation_incentive: default_liquidation_incentive(),
+			supply_cap: None,
+			borrow_cap: None,
+			debt_ceiling: None,
+			paused: Default::default(),
 		},
 		interest_rate_model: InterestRateModel::default(),
 		reserved_factor: DEFAULT_MARKET_VAULT_RESERVE,
@@ -72,6 +81,16 @@ pub fn default_under_collateralized_warn_percent() -> Percent {
 	Percent::from_float(0.10)
 }
 
+/// Returns a "default" value (`50%`) for the close factor.
+pub fn default_close_factor() -> Percent {
+	Percent::from_percent(50)
+}
+
+/// Returns a "default" value (`5%`) for the liquidation incentive.
+pub fn default_liquidation_incentive() -> Percent {
+	Percent::from_percent(5)
+}
+
 /// Creates a "default" [`MoreThanOneFixedU128`], equal to [`DEFAULT_COLLATERAL_FACTOR`].
 pub fn default_collateral_factor() -> sp_runtime::FixedU128 {
 	MoreThanOneFixedU128::saturating_from_integer(DEFAULT_COLLATERAL_FACTOR)
@@ -118,7 +137,6 @@ pub fn create_simple_vault(
 /// Values used:
 ///
 /// - `interest_rate_model`: [`Default`] implementation of [`InterestRateModel`]
-/// - `liquidators`: empty [`Vec`]
 /// - `under_collateralized_warn_percent`: [`default_under_collateralized_warn_percent()`]
 ///
 /// # Panics
@@ -154,8 +172,13 @@ where
 		updatable: UpdateInput {
 			collateral_factor,
 			under_collateralized_warn_percent: default_under_collateralized_warn_percent(),
-			liquidators: vec![],
 			max_price_age: DEFAULT_MAX_PRICE_AGE,
+			close_factor: default_close_factor(),
+			liquidation_incentive: default_liquidation_incentive(),
+			supply_cap: None,
+			borrow_cap: None,
+			debt_ceiling: None,
+			paused: Default::default(),
 		},
 		interest_rate_model: InterestRateModel::default(),
 		reserved_factor,
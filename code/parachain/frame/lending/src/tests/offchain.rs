@@ -27,12 +27,13 @@ fn test_liquidation_offchain_worker() {
 	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
 	ext.register_extension(TransactionPoolExt::new(pool));
 	ext.execute_with(|| {
+		// ALICE signs the liquidations of the off-chain worker
 		let manager = *ALICE;
 		let lender = *CHARLIE;
-		// ALICE is also borrower who's borrow going to be liquidated
-		let risky_borrower = *ALICE;
-		// BOB is reliable borrower who's borrow should not be liquidated
-		let reliable_borrower = *BOB;
+		// BOB is risky borrower who's borrow going to be liquidated
+		let risky_borrower = *BOB;
+		// CHARLIE is reliable borrower who's borrow should not be liquidated
+		let reliable_borrower = *CHARLIE;
 		// Create a market with BTC as collateral asset and USDT as borrow asset.
 		// Initial collateral asset price is 50_000 USDT. Market's collateral factor equals two.
 		// It means that borrow supposed to be undercollateralized when
@@ -64,6 +65,8 @@ fn test_liquidation_offchain_worker() {
 		// Now the risky borrow is undercollateralized since market's collateral factor equals two.
 		// Therefore, one BTC can cover only 19_000 of 20_0000 borrowed USDT.
 		set_price(BTC::ID, NORMALIZED::units(38_000));
+		// The liquidator repays the debt with its own borrow asset
+		assert_ok!(Tokens::mint_into(USDT::ID, &manager, USDT::units(20_000)));
 		// Header for the fake block to execute off-chain worker
 		let header =
 			Header::new(2, H256::default(), H256::default(), [69u8; 32].into(), Digest::default());
@@ -88,9 +91,6 @@ fn test_liquidation_offchain_worker() {
 		let event =
 			crate::Event::LiquidationInitiated { market_id, borrowers: vec![risky_borrower] };
 		System::assert_has_event(RuntimeEvent::Lending(event));
-		// Check event from Liquidations pallet
-		let event = pallet_liquidations::Event::PositionWasSentToLiquidation {};
-		System::assert_has_event(RuntimeEvent::Liquidations(event));
 		// Check that events for the reliable borrow were not emitted
 		// Check event from Lending pallet
 		let event = crate::Event::<Runtime>::LiquidationInitiated {
@@ -98,9 +98,16 @@ fn test_liquidation_offchain_worker() {
 			borrowers: vec![reliable_borrower],
 		};
 		assert_no_event::<Runtime>(RuntimeEvent::Lending(event));
-		// Check that Liquidations pallet emitted only one event
-		let event =
-			RuntimeEvent::Liquidations(pallet_liquidations::Event::PositionWasSentToLiquidation {});
-		assert!(System::events().iter().filter(|record| record.event == event).count() == 1);
+		// Check that only the risky borrow was liquidated
+		assert_eq!(
+			System::events()
+				.iter()
+				.filter(|record| matches!(
+					record.event,
+					RuntimeEvent::Lending(crate::Event::PositionLiquidated { .. })
+				))
+				.count(),
+			1
+		);
 	});
 }
@@ -0,0 +1,158 @@
+use super::prelude::*;
+use crate::{
+	tests::{
+		borrow, create_market_for_liquidation_test, mint_and_deposit_collateral,
+		process_and_progress_blocks,
+	},
+	MarketId,
+};
+
+/// Creates two markets with USDT as borrow asset, with BTC (`50_000`) and PICA (`10`) as
+/// collateral respectively, and funds the vault of the first one.
+fn create_portfolio_markets() -> (MarketId, MarketId) {
+	let manager = *ALICE;
+	let (btc_market, vault_id) = create_market_for_liquidation_test::<Runtime>(manager);
+	let (pica_market, _) = create_market::<Runtime, 10>(
+		USDT::instance(),
+		PICA::instance(),
+		manager,
+		DEFAULT_MARKET_VAULT_RESERVE,
+		MoreThanOneFixedU128::saturating_from_integer(DEFAULT_COLLATERAL_FACTOR),
+	);
+	let vault_value = USDT::units(100_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	(btc_market, pica_market)
+}
+
+#[test]
+fn portfolio_borrow_limit_counts_collateral_of_all_markets() {
+	new_test_ext().execute_with(|| {
+		let (btc_market, pica_market) = create_portfolio_markets();
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), btc_market, BTC::ID);
+		mint_and_deposit_collateral::<Runtime>(*BOB, PICA::units(1_000), pica_market, PICA::ID);
+
+		assert_eq!(Lending::get_borrow_limit(&btc_market, &BOB), Ok(USDT::units(25_000)));
+
+		assert_extrinsic_event::<Runtime>(
+			Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), btc_market),
+			RuntimeEvent::Lending(crate::Event::PortfolioMarketEntered {
+				account: *BOB,
+				market_id: btc_market,
+			}),
+		);
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), pica_market));
+		assert_noop!(
+			Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), pica_market),
+			Error::<Runtime>::MarketAlreadyInPortfolio
+		);
+
+		assert_eq!(Lending::get_borrow_limit(&btc_market, &BOB), Ok(USDT::units(30_000)));
+	})
+}
+
+#[test]
+fn portfolio_collateral_can_not_be_removed_while_backing_debt() {
+	new_test_ext().execute_with(|| {
+		let (btc_market, pica_market) = create_portfolio_markets();
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), btc_market, BTC::ID);
+		mint_and_deposit_collateral::<Runtime>(*BOB, PICA::units(1_000), pica_market, PICA::ID);
+
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*BOB), btc_market, USDT::units(28_000)),
+			Error::<Runtime>::NotEnoughCollateralToBorrow
+		);
+
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), btc_market));
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), pica_market));
+		borrow::<Runtime>(*BOB, btc_market, USDT::units(28_000));
+
+		assert_noop!(
+			Lending::withdraw_collateral(
+				RuntimeOrigin::signed(*BOB),
+				pica_market,
+				PICA::units(1_000)
+			),
+			Error::<Runtime>::WouldGoUnderCollateralized
+		);
+		assert_noop!(
+			Lending::exit_portfolio(RuntimeOrigin::signed(*BOB), pica_market),
+			Error::<Runtime>::WouldGoUnderCollateralized
+		);
+		assert_noop!(
+			Lending::exit_portfolio(RuntimeOrigin::signed(*BOB), btc_market),
+			Error::<Runtime>::WouldGoUnderCollateralized
+		);
+
+		// part of the collateral which is not needed can still be withdrawn
+		assert_ok!(Lending::withdraw_collateral(
+			RuntimeOrigin::signed(*BOB),
+			pica_market,
+			PICA::units(100)
+		));
+	})
+}
+
+#[test]
+fn portfolio_liquidation_seizes_most_valuable_collateral() {
+	new_test_ext().execute_with(|| {
+		let (btc_market, pica_market) = create_portfolio_markets();
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), btc_market, BTC::ID);
+		mint_and_deposit_collateral::<Runtime>(*BOB, PICA::units(10_000), pica_market, PICA::ID);
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), btc_market));
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), pica_market));
+		borrow::<Runtime>(*BOB, btc_market, USDT::units(70_000));
+		assert_eq!(Lending::should_liquidate(&btc_market, &BOB), Ok(false));
+
+		// the portfolio does not cover the debt anymore
+		set_price(PICA::ID, NORMALIZED::units(8));
+		assert_eq!(Lending::should_liquidate(&btc_market, &BOB), Ok(true));
+
+		assert_extrinsic_event::<Runtime>(
+			Lending::liquidate(
+				RuntimeOrigin::signed(*ALICE),
+				btc_market,
+				TestBoundedVec::try_from(vec![*BOB]).unwrap(),
+			),
+			RuntimeEvent::Lending(crate::Event::LiquidationInitiated {
+				market_id: btc_market,
+				borrowers: vec![*BOB],
+			}),
+		);
+		assert_eq!(crate::AccountCollateral::<Runtime>::get(btc_market, *BOB), Some(BTC::units(1)));
+		assert!(
+			crate::AccountCollateral::<Runtime>::get(pica_market, *BOB).unwrap() <
+				PICA::units(10_000)
+		);
+	})
+}
+
+#[test]
+fn portfolio_liquidation_does_not_seize_collateral_of_paused_markets() {
+	new_test_ext().execute_with(|| {
+		let (btc_market, pica_market) = create_portfolio_markets();
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), btc_market, BTC::ID);
+		mint_and_deposit_collateral::<Runtime>(*BOB, PICA::units(10_000), pica_market, PICA::ID);
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), btc_market));
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), pica_market));
+		borrow::<Runtime>(*BOB, btc_market, USDT::units(70_000));
+		set_price(PICA::ID, NORMALIZED::units(8));
+		crate::Markets::<Runtime>::mutate(pica_market, |market| {
+			market.as_mut().unwrap().paused.liquidate = true
+		});
+
+		assert_ok!(Lending::liquidate(
+			RuntimeOrigin::signed(*ALICE),
+			btc_market,
+			TestBoundedVec::try_from(vec![*BOB]).unwrap(),
+		));
+		assert!(
+			crate::AccountCollateral::<Runtime>::get(btc_market, *BOB).unwrap() < BTC::units(1)
+		);
+		assert_eq!(
+			crate::AccountCollateral::<Runtime>::get(pica_market, *BOB),
+			Some(PICA::units(10_000))
+		);
+	})
+}
@@ -0,0 +1,65 @@
+use super::prelude::*;
+use crate::{
+	tests::{borrow, create_market_for_liquidation_test, process_and_progress_blocks},
+	MarketId,
+};
+use composable_traits::{defi::Rate, lending::AccountPosition};
+use sp_runtime::traits::One;
+
+/// Creates a market with USDT as borrow asset and BTC (`50_000`) as collateral, funds its vault
+/// and lets BOB borrow `20_000` USDT against `1` BTC.
+fn create_market_with_borrow() -> MarketId {
+	let (market_id, vault_id) = create_market_for_liquidation_test::<Runtime>(*ALICE);
+	let vault_value = USDT::units(100_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), market_id, BTC::ID);
+	assert_eq!(Lending::health_factor(&market_id, &BOB), Ok(None));
+	assert_eq!(Lending::liquidation_price(&market_id, &BOB), Ok(None));
+	borrow::<Runtime>(*BOB, market_id, USDT::units(20_000));
+	market_id
+}
+
+#[test]
+fn account_positions_lists_collateral_and_debt() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_borrow();
+
+		assert_eq!(
+			Lending::account_positions(&BOB),
+			Ok(vec![AccountPosition {
+				market_id,
+				collateral_asset: BTC::ID,
+				collateral_amount: BTC::units(1),
+				borrow_asset: USDT::ID,
+				debt_with_interest: USDT::units(20_000),
+			}])
+		);
+		assert_eq!(Lending::account_positions(&CHARLIE), Ok(vec![]));
+		assert_eq!(Lending::max_borrowable(&market_id, &BOB), Ok(USDT::units(5_000)));
+	})
+}
+
+#[test]
+fn health_factor_reaches_one_at_liquidation_price() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_market_with_borrow();
+
+		// 50_000 / 20_000 / 2
+		assert_eq!(
+			Lending::health_factor(&market_id, &BOB),
+			Ok(Some(Rate::saturating_from_rational(125, 100)))
+		);
+		let liquidation_price = NORMALIZED::units(40_000);
+		assert_eq!(Lending::liquidation_price(&market_id, &BOB), Ok(Some(liquidation_price)));
+
+		set_price(BTC::ID, liquidation_price);
+		assert_eq!(Lending::health_factor(&market_id, &BOB), Ok(Some(Rate::one())));
+		assert_eq!(Lending::should_liquidate(&market_id, &BOB), Ok(false));
+		assert_eq!(Lending::max_borrowable(&market_id, &BOB), Ok(0));
+
+		set_price(BTC::ID, liquidation_price - NORMALIZED::units(1));
+		assert_eq!(Lending::should_liquidate(&market_id, &BOB), Ok(true));
+	})
+}
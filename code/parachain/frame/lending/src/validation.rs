@@ -6,21 +6,32 @@ use composable_traits::{
 };
 use frame_support::pallet_prelude::*;
 use scale_info::TypeInfo;
-use sp_runtime::traits::{One, Zero};
+use sp_runtime::{
+	traits::{One, Zero},
+	Percent,
+};
 
 #[derive(Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, Default)]
 pub struct UpdateInputValid;
 
-impl<LiquidationStrategyId, BlockNumber>
-	Validate<UpdateInput<LiquidationStrategyId, BlockNumber>, UpdateInputValid> for UpdateInputValid
+impl<BlockNumber, Balance> Validate<UpdateInput<BlockNumber, Balance>, UpdateInputValid>
+	for UpdateInputValid
 {
 	fn validate(
-		update_input: UpdateInput<LiquidationStrategyId, BlockNumber>,
-	) -> Result<UpdateInput<LiquidationStrategyId, BlockNumber>, &'static str> {
+		update_input: UpdateInput<BlockNumber, Balance>,
+	) -> Result<UpdateInput<BlockNumber, Balance>, &'static str> {
 		if update_input.collateral_factor < MoreThanOneFixedU128::one() {
 			return Err("Collateral factor must be more than one.")
 		}
 
+		if update_input.close_factor.is_zero() {
+			return Err("Close factor must be more than zero.")
+		}
+
+		if update_input.liquidation_incentive >= Percent::from_percent(100) {
+			return Err("Liquidation incentive must be less than 100%.")
+		}
+
 		Ok(update_input)
 	}
 }
@@ -30,13 +41,12 @@ pub struct MarketModelValid;
 #[derive(Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, Default)]
 pub struct CurrencyPairIsNotSame;
 
-impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
-	Validate<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, MarketModelValid>
-	for MarketModelValid
+impl<Asset: Eq, BlockNumber, Balance>
+	Validate<CreateInput<Asset, BlockNumber, Balance>, MarketModelValid> for MarketModelValid
 {
 	fn validate(
-		create_input: CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-	) -> Result<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, &'static str> {
+		create_input: CreateInput<Asset, BlockNumber, Balance>,
+	) -> Result<CreateInput<Asset, BlockNumber, Balance>, &'static str> {
 		let updatable = create_input.updatable.try_into_validated::<UpdateInputValid>()?.value();
 		let interest_rate_model = create_input
 			.interest_rate_model
@@ -47,13 +57,13 @@ impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
 	}
 }
 
-impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
-	Validate<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, CurrencyPairIsNotSame>
+impl<Asset: Eq, BlockNumber, Balance>
+	Validate<CreateInput<Asset, BlockNumber, Balance>, CurrencyPairIsNotSame>
 	for CurrencyPairIsNotSame
 {
 	fn validate(
-		create_input: CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-	) -> Result<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, &'static str> {
+		create_input: CreateInput<Asset, BlockNumber, Balance>,
+	) -> Result<CreateInput<Asset, BlockNumber, Balance>, &'static str> {
 		if create_input.currency_pair.base == create_input.currency_pair.quote {
 			Err("Base and quote currencies supposed to be different in currency pair")
 		} else {
@@ -65,15 +75,13 @@ impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
 #[derive(RuntimeDebug, PartialEq, Eq, TypeInfo, Default, Clone, Copy)]
 pub struct AssetIsSupportedByOracle<Oracle: OracleTrait>(PhantomData<Oracle>);
 
-impl<LiquidationStrategyId, Asset: Copy, BlockNumber, Oracle: OracleTrait<AssetId = Asset>>
-	Validate<
-		CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-		AssetIsSupportedByOracle<Oracle>,
-	> for AssetIsSupportedByOracle<Oracle>
+impl<Asset: Copy, BlockNumber, Balance, Oracle: OracleTrait<AssetId = Asset>>
+	Validate<CreateInput<Asset, BlockNumber, Balance>, AssetIsSupportedByOracle<Oracle>>
+	for AssetIsSupportedByOracle<Oracle>
 {
 	fn validate(
-		create_input: CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-	) -> Result<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, &'static str> {
+		create_input: CreateInput<Asset, BlockNumber, Balance>,
+	) -> Result<CreateInput<Asset, BlockNumber, Balance>, &'static str> {
 		ensure!(
 			Oracle::is_supported(create_input.borrow_asset())?,
 			"Borrow asset is not supported by oracle"
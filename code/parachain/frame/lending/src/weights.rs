@@ -25,6 +25,10 @@ pub trait WeightInfo {
 	fn handle_withdrawable() -> Weight;
 	fn handle_depositable() -> Weight;
 	fn handle_must_liquidate() -> Weight;
+	fn enter_portfolio() -> Weight;
+	fn exit_portfolio() -> Weight;
+	fn flash_loan() -> Weight;
+	fn ramp_interest_rate_model() -> Weight;
 }
 
 impl WeightInfo for () {
@@ -99,4 +103,24 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	fn enter_portfolio() -> Weight {
+		Weight::from_ref_time(98_512_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn exit_portfolio() -> Weight {
+		Weight::from_ref_time(124_377_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(17_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn flash_loan() -> Weight {
+		Weight::from_ref_time(118_415_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn ramp_interest_rate_model() -> Weight {
+		Weight::from_ref_time(32_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
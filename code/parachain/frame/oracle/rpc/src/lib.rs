@@ -0,0 +1,194 @@
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::oracle::{
+	OracleAssetInfo, Price, RewardTracker, SignerStats, SubmittedPrice,
+};
+use core::{fmt::Display, str::FromStr};
+use frame_support::{pallet_prelude::MaybeSerializeDeserialize, Parameter};
+use jsonrpsee::{
+	core::{Error as RpcError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use oracle_runtime_api::OracleRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{traits::Block as BlockT, DispatchError};
+use sp_std::sync::Arc;
+
+#[rpc(client, server)]
+pub trait OracleApi<BlockHash, AssetId, AccountId, BlockNumber, Balance, Moment>
+where
+	AssetId: FromStr + Display,
+	Balance: FromStr + Display,
+{
+	#[method(name = "oracle_getPrice")]
+	fn get_price(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		amount: SafeRpcWrapper<Balance>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Price<SafeRpcWrapper<Balance>, BlockNumber>>;
+
+	#[method(name = "oracle_getTwap")]
+	fn get_twap(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		amount: SafeRpcWrapper<Balance>,
+		at: Option<BlockHash>,
+	) -> RpcResult<SafeRpcWrapper<Balance>>;
+
+	#[method(name = "oracle_priceHistory")]
+	fn price_history(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<Price<SafeRpcWrapper<Balance>, BlockNumber>>>;
+
+	#[method(name = "oracle_prePrices")]
+	fn pre_prices(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<SubmittedPrice<AccountId, SafeRpcWrapper<Balance>, BlockNumber>>>;
+
+	#[method(name = "oracle_assetsInfo")]
+	fn assets_info(
+		&self,
+		at: Option<BlockHash>,
+	) -> RpcResult<
+		Vec<OracleAssetInfo<SafeRpcWrapper<AssetId>, BlockNumber, SafeRpcWrapper<Balance>>>,
+	>;
+
+	#[method(name = "oracle_signerStats")]
+	fn signer_stats(
+		&self,
+		signer: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<SignerStats<AccountId, SafeRpcWrapper<Balance>, BlockNumber>>>;
+
+	#[method(name = "oracle_rewardTracker")]
+	fn reward_tracker(
+		&self,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<RewardTracker<SafeRpcWrapper<Balance>, Moment>>>;
+}
+
+pub struct Oracle<C, Block> {
+	client: Arc<C>,
+	_marker: sp_std::marker::PhantomData<Block>,
+}
+
+impl<C, M> Oracle<C, M> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(e: impl core::fmt::Debug) -> RpcError {
+	RpcError::Call(CallError::Custom(ErrorObject::owned(
+		9876,
+		"Something wrong",
+		Some(format!("{:?}", e)),
+	)))
+}
+
+fn dispatch_error(e: DispatchError) -> RpcError {
+	RpcError::Call(CallError::Custom(ErrorObject::owned(
+		9877,
+		"Query failed",
+		Some(format!("{:?}", e)),
+	)))
+}
+
+impl<C, Block, AssetId, AccountId, BlockNumber, Balance, Moment>
+	OracleApiServer<<Block as BlockT>::Hash, AssetId, AccountId, BlockNumber, Balance, Moment>
+	for Oracle<C, (Block, AssetId, AccountId, BlockNumber, Balance, Moment)>
+where
+	Block: BlockT,
+	AssetId: Send + Sync + 'static + Codec + FromStr + Display,
+	AccountId: Send + Sync + Parameter + MaybeSerializeDeserialize + 'static,
+	BlockNumber: Send + Sync + Parameter + MaybeSerializeDeserialize + 'static,
+	Balance: Send + Sync + 'static + Codec + FromStr + Display,
+	Moment: Send + Sync + Parameter + MaybeSerializeDeserialize + 'static,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: OracleRuntimeApi<Block, AssetId, AccountId, BlockNumber, Balance, Moment>,
+{
+	fn get_price(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		amount: SafeRpcWrapper<Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Price<SafeRpcWrapper<Balance>, BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.get_price(at, asset_id.0, amount.0)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn get_twap(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		amount: SafeRpcWrapper<Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SafeRpcWrapper<Balance>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.get_twap(at, asset_id.0, amount.0)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn price_history(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<Price<SafeRpcWrapper<Balance>, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.price_history(at, asset_id.0).map_err(runtime_error)
+	}
+
+	fn pre_prices(
+		&self,
+		asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<SubmittedPrice<AccountId, SafeRpcWrapper<Balance>, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.pre_prices(at, asset_id.0).map_err(runtime_error)
+	}
+
+	fn assets_info(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<
+		Vec<OracleAssetInfo<SafeRpcWrapper<AssetId>, BlockNumber, SafeRpcWrapper<Balance>>>,
+	> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.assets_info(at).map_err(runtime_error)
+	}
+
+	fn signer_stats(
+		&self,
+		signer: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<SignerStats<AccountId, SafeRpcWrapper<Balance>, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.signer_stats(at, signer).map_err(runtime_error)
+	}
+
+	fn reward_tracker(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<RewardTracker<SafeRpcWrapper<Balance>, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.reward_tracker(at).map_err(runtime_error)
+	}
+}
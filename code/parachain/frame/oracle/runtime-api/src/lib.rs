@@ -0,0 +1,48 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::oracle::{
+	OracleAssetInfo, Price, RewardTracker, SignerStats, SubmittedPrice,
+};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+// Oracle Runtime API declaration. Implemented for each runtime at
+// `runtime/<runtime-name>/src/lib.rs`.
+sp_api::decl_runtime_apis! {
+	pub trait OracleRuntimeApi<AssetId, AccountId, BlockNumber, Balance, Moment>
+	where
+		AssetId: Codec,
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+		Moment: Codec,
+	{
+		/// Retrieve the price of `amount` of `asset_id` and the block it was last updated at.
+		fn get_price(
+			asset_id: AssetId,
+			amount: Balance,
+		) -> Result<Price<SafeRpcWrapper<Balance>, BlockNumber>, DispatchError>;
+
+		/// Retrieve the time weighted average price of `amount` of `asset_id`.
+		fn get_twap(asset_id: AssetId, amount: Balance) -> Result<SafeRpcWrapper<Balance>, DispatchError>;
+
+		/// Retrieve the past prices of `asset_id`, oldest first.
+		fn price_history(asset_id: AssetId) -> Vec<Price<SafeRpcWrapper<Balance>, BlockNumber>>;
+
+		/// Retrieve the prices submitted for `asset_id` which are not aggregated yet.
+		fn pre_prices(asset_id: AssetId) -> Vec<SubmittedPrice<AccountId, SafeRpcWrapper<Balance>, BlockNumber>>;
+
+		/// Retrieve the configuration of every asset the oracle provides prices for.
+		fn assets_info() -> Vec<OracleAssetInfo<SafeRpcWrapper<AssetId>, BlockNumber, SafeRpcWrapper<Balance>>>;
+
+		/// Retrieve the stake, rewards and slashes of `signer`. `None` if it is not registered.
+		fn signer_stats(signer: AccountId) -> Option<SignerStats<AccountId, SafeRpcWrapper<Balance>, BlockNumber>>;
+
+		/// Retrieve the current rewarding period of the oracle, `None` if rewarding never started.
+		fn reward_tracker() -> Option<RewardTracker<SafeRpcWrapper<Balance>, Moment>>;
+	}
+}
@@ -3,6 +3,7 @@ use super::*;
 use crate::validation::ValidBlockInterval;
 #[allow(unused)]
 use crate::Pallet as Oracle;
+use codec::{Decode, Encode};
 use composable_support::validation::Validated;
 use composable_traits::oracle::Price;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
@@ -12,7 +13,10 @@ use frame_support::{
 	BoundedVec,
 };
 use frame_system::{EventRecord, RawOrigin};
-use sp_runtime::{DispatchResult, Percent};
+use sp_runtime::{
+	traits::{IdentifyAccount, TrailingZeroInput},
+	DispatchResult, Percent,
+};
 
 use sp_std::{prelude::*, vec};
 
@@ -38,6 +42,12 @@ macro_rules! whitelist {
 }
 
 benchmarks! {
+	where_clause {
+		where
+			T::Public: From<sp_core::sr25519::Public>,
+			T::Signature: From<sp_core::sr25519::Signature>,
+	}
+
 	add_asset_and_info {
 		let asset_id = 1;
 		let threshold = Validated::new(Percent::from_percent(80)).unwrap();
@@ -161,6 +171,76 @@ benchmarks! {
 		assert_last_event::<T>(Event::PriceSubmitted(caller, asset_id, price.into()).into())
 	}
 
+	set_price_source {
+		let p in 0 .. T::MaxPriceSourceHops::get();
+		let asset_id: T::AssetId = 1.into();
+		let pool_id = T::PoolId::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+		let path = (0..p).map(|i| PriceSourceHop {
+			pool_id,
+			base_asset_id: (1 + i as u128).into(),
+			quote_asset_id: (2 + i as u128).into(),
+		})
+		.collect::<Vec<_>>();
+		let path = BoundedVec::try_from(path).unwrap();
+	}: _(RawOrigin::Root, asset_id, PriceSourcePolicy::SignersOnly, path, 60)
+	verify {
+		assert_last_event::<T>(Event::PriceSourceSet(asset_id, PriceSourcePolicy::SignersOnly).into());
+	}
+
+	set_update_trigger {
+		let asset_id: T::AssetId = 1.into();
+		let asset_info = AssetInfo {
+			threshold: Percent::from_percent(80),
+			min_answers: 1,
+			max_answers: T::MaxAnswerBound::get(),
+			block_interval: T::StalePrice::get() + 1u32.into(),
+			reward_weight: T::Currency::minimum_balance(),
+			slash: T::Currency::minimum_balance(),
+			emit_price_changes: false,
+		};
+		AssetsInfo::<T>::insert(asset_id, asset_info);
+		let trigger = UpdateTrigger {
+			deviation: Percent::from_percent(1),
+			heartbeat: T::StalePrice::get() + 100u32.into(),
+		};
+	}: _(RawOrigin::Root, asset_id, Some(trigger))
+	verify {
+		assert_last_event::<T>(Event::UpdateTriggerSet(asset_id, Some(trigger)).into());
+	}
+
+	submit_report {
+		let p in 1 .. T::MaxAnswerBound::get();
+		let asset_id: T::AssetId = 1.into();
+		let asset_info = AssetInfo {
+			threshold: Percent::from_percent(80),
+			min_answers: 1,
+			max_answers: p,
+			block_interval: T::StalePrice::get() + 1u32.into(),
+			reward_weight: T::Currency::minimum_balance(),
+			slash: T::Currency::minimum_balance(),
+			emit_price_changes: false,
+		};
+		AssetsInfo::<T>::insert(asset_id, asset_info.clone());
+		frame_system::Pallet::<T>::set_block_number(asset_info.block_interval + 1u32.into());
+		let round = Oracle::<T>::prices(asset_id).block;
+		let price: T::PriceValue = 100u128.into();
+		let observations = (0..p).map(|_| {
+			let public = sp_io::crypto::sr25519_generate(CRYPTO_KEY_TYPE, None);
+			let signer = T::Public::from(public).into_account();
+			SignerToController::<T>::insert(&signer, signer.clone());
+			OracleStake::<T>::insert(&signer, T::MinStake::get() + asset_info.slash);
+			let payload = ObservationPayload { asset_id, price, round, public: T::Public::from(public) };
+			let signature = sp_io::crypto::sr25519_sign(CRYPTO_KEY_TYPE, &public, &payload.encode()).unwrap();
+			Observation { public: public.into(), price, signature: signature.into() }
+		})
+		.collect::<Vec<_>>();
+		let transmitter = observations[0].public.clone().into_account();
+		let observations = BoundedVec::try_from(observations).unwrap();
+	}: _(RawOrigin::Signed(transmitter.clone()), asset_id, observations)
+	verify {
+		assert_last_event::<T>(Event::ReportSubmitted(transmitter, asset_id, price).into());
+	}
+
 	update_pre_prices {
 		let p in 1 .. T::MaxAnswerBound::get();
 		let who: T::AccountId = whitelisted_caller();
@@ -45,8 +45,10 @@ pub mod pallet {
 	};
 	use composable_traits::{
 		currency::{BalanceLike, LocalAssets},
-		oracle::{Oracle, Price, RewardTracker},
-		time::MS_PER_YEAR_NAIVE,
+		defi::Rate,
+		dex::Amm,
+		oracle::{Oracle, OracleAssetInfo, Price, RewardTracker, SignerStats, SubmittedPrice},
+		time::{DurationSeconds, MS_PER_YEAR_NAIVE},
 	};
 	use frame_support::{
 		dispatch::{DispatchClass, DispatchResult, DispatchResultWithPostInfo, Pays},
@@ -72,7 +74,8 @@ pub mod pallet {
 		offchain::{http, Duration},
 		traits::{
 			AccountIdConversion, AtLeast32Bit, AtLeast32BitUnsigned, CheckedAdd, CheckedDiv,
-			CheckedMul, CheckedSub, Saturating, UniqueSaturatedInto as _, Zero,
+			CheckedMul, CheckedSub, IdentifyAccount, One, Saturating, UniqueSaturatedInto as _,
+			Zero,
 		},
 		AccountId32, ArithmeticError, FixedPointNumber, FixedU128, KeyTypeId as CryptoKeyTypeId,
 		PerThing, Percent, RuntimeDebug,
@@ -85,6 +88,8 @@ pub mod pallet {
 	pub const KEY_ID: [u8; 4] = *b"orac";
 	pub const KEY_TYPE: KeyTypeId = KeyTypeId(KEY_ID);
 	pub const CRYPTO_KEY_TYPE: CryptoKeyTypeId = CryptoKeyTypeId(KEY_ID);
+	/// Prices are normalized to 12 decimals.
+	const PRICE_UNIT: u128 = 1_000_000_000_000;
 
 	pub mod crypto {
 		use super::KEY_TYPE;
@@ -193,6 +198,16 @@ pub mod pallet {
 
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
+
+		/// Type of the pools prices can be sourced from.
+		type PoolId: FullCodec + MaxEncodedLen + TypeInfo + Copy + Eq + Debug;
+
+		/// DEX whose TWAP is used as a price source for assets with a configured pool path.
+		type Dex: Amm<AssetId = Self::AssetId, PoolId = Self::PoolId>;
+
+		/// Max number of pools a price source path goes through.
+		#[pallet::constant]
+		type MaxPriceSourceHops: Get<u32>;
 	}
 
 	#[derive(Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo, Clone)]
@@ -225,7 +240,70 @@ pub mod pallet {
 		pub emit_price_changes: bool,
 	}
 
+	/// Price observed by a signer, signed off-chain and submitted in a report.
+	#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct Observation<Public, PriceValue, Signature> {
+		pub public: Public,
+		/// The price of an asset, normalized to 12 decimals.
+		pub price: PriceValue,
+		/// Signature of the `ObservationPayload` of the price.
+		pub signature: Signature,
+	}
+
+	/// Opens a new round when a submitted price deviates from the current one, or once the
+	/// heartbeat elapsed, instead of every `block_interval`.
+	#[derive(
+		Encode, Decode, MaxEncodedLen, Clone, Copy, Default, Debug, PartialEq, Eq, TypeInfo,
+	)]
+	pub struct UpdateTrigger<BlockNumber> {
+		/// Deviation from the current price a submitted price must exceed to open a round.
+		pub deviation: Percent,
+		/// Blocks after which a round opens even if the price did not deviate.
+		pub heartbeat: BlockNumber,
+	}
+
+	/// Which sources the price of an asset is taken from.
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, Debug, PartialEq, Eq, TypeInfo)]
+	pub enum PriceSourcePolicy {
+		/// Prices aggregated from staked signers only.
+		SignersOnly,
+		/// DEX TWAP along the configured path only.
+		DexOnly,
+		/// Median of the signers and DEX prices, or whichever one is available.
+		Median,
+		/// Signers price, rejected if it deviates from the DEX price by more than the given
+		/// percent.
+		SignersWithDexGuard(Percent),
+	}
+
+	/// A pool the price is routed through, from `base_asset_id` to `quote_asset_id`.
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct PriceSourceHop<AssetId, PoolId> {
+		pub pool_id: PoolId,
+		pub base_asset_id: AssetId,
+		pub quote_asset_id: AssetId,
+	}
+
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct PriceSource<Path> {
+		pub policy: PriceSourcePolicy,
+		/// Pools the asset is routed through, ending in the asset prices are quoted in.
+		pub path: Path,
+		/// Window, in seconds, the TWAP of each pool is taken over.
+		pub twap_window: DurationSeconds,
+	}
+
 	type BalanceOf<T> = <T as Config>::Balance;
+	pub type ObservationOf<T> = Observation<
+		<T as SigningTypes>::Public,
+		<T as Config>::PriceValue,
+		<T as SigningTypes>::Signature,
+	>;
+	pub type PriceSourcePathOf<T> = BoundedVec<
+		PriceSourceHop<<T as Config>::AssetId, <T as Config>::PoolId>,
+		<T as Config>::MaxPriceSourceHops,
+	>;
+	pub type PriceSourceOf<T> = PriceSource<PriceSourcePathOf<T>>;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
@@ -331,6 +409,32 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn price_source)]
+	/// Where the price of an asset is sourced from, signers only when not set
+	pub type PriceSources<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, PriceSourceOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn update_trigger)]
+	/// Deviation and heartbeat opening rounds of an asset, its block interval when not set
+	pub type UpdateTriggers<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, UpdateTrigger<T::BlockNumber>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn total_rewarded)]
+	#[allow(clippy::disallowed_types)] // a signer never rewarded has been rewarded 0
+	/// Mapping of signing key to the total amount of rewards paid for its answers
+	pub type TotalRewarded<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn total_slashed)]
+	#[allow(clippy::disallowed_types)] // a signer never slashed has been slashed 0
+	/// Mapping of signing key to the total amount slashed from its stake
+	pub type TotalSlashed<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -359,6 +463,12 @@ pub mod pallet {
 		PriceChanged(T::AssetId, T::PriceValue),
 		/// Signer removed
 		SignerRemoved(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Price source of an asset changed. \[asset_id, policy\]
+		PriceSourceSet(T::AssetId, PriceSourcePolicy),
+		/// Report aggregated by signers submitted. \[transmitter, asset_id, price\]
+		ReportSubmitted(T::AccountId, T::AssetId, T::PriceValue),
+		/// Update trigger of an asset changed. \[asset_id, trigger\]
+		UpdateTriggerSet(T::AssetId, Option<UpdateTrigger<T::BlockNumber>>),
 	}
 
 	#[pallet::error]
@@ -424,6 +534,16 @@ pub mod pallet {
 		NoRewardTrackerSet,
 		/// Annual rewarding cost too high
 		AnnualRewardLessThanAlreadyRewarded,
+		/// Price source path is empty, broken or goes through an unknown pool
+		InvalidPriceSourcePath,
+		/// TWAP window of a price source must be positive
+		InvalidTwapWindow,
+		/// Signers price deviates from the DEX price more than allowed
+		PriceDeviatesFromDex,
+		/// Report has less observations than the min answers of the asset
+		NotEnoughAnswers,
+		/// Observation signature does not match its signer
+		InvalidSignature,
 	}
 
 	#[pallet::hooks]
@@ -452,11 +572,8 @@ pub mod pallet {
 			asset_id: Self::AssetId,
 			amount: Self::Balance,
 		) -> Result<Price<Self::Balance, Self::Timestamp>, DispatchError> {
-			let Price { price, block } =
-				Prices::<T>::try_get(asset_id).map_err(|_| Error::<T>::PriceNotFound)?;
-			// dbg!(&price);
+			let Price { price, block } = Self::sourced_price(asset_id)?;
 			let price = Self::quote(asset_id, price, amount)?;
-			// dbg!(&price);
 			Ok(Price { price, block })
 		}
 
@@ -732,7 +849,10 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let author_stake = OracleStake::<T>::get(&who).unwrap_or_else(Zero::zero);
-			ensure!(Self::is_requested(&asset_id), Error::<T>::PriceNotRequested);
+			ensure!(
+				Self::is_requested(&asset_id) || Self::deviates(asset_id, price),
+				Error::<T>::PriceNotRequested
+			);
 			ensure!(
 				author_stake >=
 					T::MinStake::get().saturating_add(
@@ -794,6 +914,167 @@ pub mod pallet {
 			Self::deposit_event(Event::SignerRemoved(who, signer, stake));
 			Ok(().into())
 		}
+
+		/// Permissioned call to set where the price of an asset is sourced from
+		///
+		/// - `asset_id`: Id for the asset
+		/// - `policy`: how signers and DEX prices are combined
+		/// - `path`: pools to route the asset through, ending in the asset prices are quoted in
+		/// - `twap_window`: seconds the TWAP of each pool is taken over
+		///
+		/// Emits `PriceSourceSet` event when successful.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::set_price_source(path.len() as u32))]
+		pub fn set_price_source(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			policy: PriceSourcePolicy,
+			path: PriceSourcePathOf<T>,
+			twap_window: DurationSeconds,
+		) -> DispatchResultWithPostInfo {
+			T::AddOracle::ensure_origin(origin)?;
+			if policy != PriceSourcePolicy::SignersOnly {
+				ensure!(!twap_window.is_zero(), Error::<T>::InvalidTwapWindow);
+				let mut asset = asset_id;
+				ensure!(!path.is_empty(), Error::<T>::InvalidPriceSourcePath);
+				for hop in path.iter() {
+					ensure!(
+						hop.base_asset_id == asset && T::Dex::pool_exists(hop.pool_id),
+						Error::<T>::InvalidPriceSourcePath
+					);
+					asset = hop.quote_asset_id;
+				}
+			}
+			PriceSources::<T>::insert(asset_id, PriceSource { policy, path, twap_window });
+			Self::deposit_event(Event::PriceSourceSet(asset_id, policy));
+			Ok(().into())
+		}
+
+		/// Permissioned call to open rounds of an asset on price deviation and heartbeat
+		///
+		/// - `asset_id`: Id for the asset
+		/// - `trigger`: deviation and heartbeat opening rounds, `None` to go back to the block
+		///   interval of the asset
+		///
+		/// Emits `UpdateTriggerSet` event when successful.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::set_update_trigger())]
+		pub fn set_update_trigger(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			trigger: Option<UpdateTrigger<T::BlockNumber>>,
+		) -> DispatchResultWithPostInfo {
+			T::AddOracle::ensure_origin(origin)?;
+			ensure!(AssetsInfo::<T>::contains_key(asset_id), Error::<T>::InvalidAssetId);
+			if let Some(trigger) = trigger {
+				ensure!(trigger.heartbeat > T::StalePrice::get(), Error::<T>::BlockIntervalLength);
+			}
+			UpdateTriggers::<T>::set(asset_id, trigger);
+			Self::deposit_event(Event::UpdateTriggerSet(asset_id, trigger));
+			Ok(().into())
+		}
+
+		/// Call for a signer to submit the observations of other signers aggregated off-chain,
+		/// the price is updated at once. Gas is returned if extrinsic is successful.
+		///
+		/// This is an operational transaction.
+		///
+		/// - `asset_id`: id for the asset
+		/// - `observations`: prices signed by distinct signers for the current round, at least min
+		///   answers of the asset
+		///
+		/// Emits `ReportSubmitted` event when successful.
+		#[pallet::call_index(10)]
+		#[pallet::weight((T::WeightInfo::submit_report(observations.len() as u32), DispatchClass::Operational))]
+		pub fn submit_report(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			observations: BoundedVec<ObservationOf<T>, T::MaxAnswerBound>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(SignerToController::<T>::contains_key(&who), Error::<T>::UnsetSigner);
+			let asset_info = Self::asset_info(asset_id).ok_or(Error::<T>::InvalidAssetId)?;
+			ensure!(
+				observations.len() as u32 >= asset_info.min_answers,
+				Error::<T>::NotEnoughAnswers
+			);
+			ensure!(
+				observations.len() as u32 <= asset_info.max_answers,
+				Error::<T>::ExceedMaxAnswers
+			);
+
+			let block = frame_system::Pallet::<T>::block_number();
+			let round = Self::prices(asset_id).block;
+			let mut pre_prices: Vec<PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>> =
+				Vec::with_capacity(observations.len());
+			for Observation { public, price, signature } in observations {
+				let payload = ObservationPayload { asset_id, price, round, public: public.clone() };
+				ensure!(
+					SignedPayload::<T>::verify::<T::AuthorityId>(&payload, signature),
+					Error::<T>::InvalidSignature
+				);
+				let signer = public.into_account();
+				ensure!(SignerToController::<T>::contains_key(&signer), Error::<T>::UnsetSigner);
+				ensure!(
+					pre_prices.iter().all(|pre_price| pre_price.who != signer),
+					Error::<T>::AlreadySubmitted
+				);
+				let stake = OracleStake::<T>::get(&signer).unwrap_or_else(Zero::zero);
+				ensure!(
+					stake >=
+						T::MinStake::get().saturating_add(
+							Self::answer_in_transit(&signer).unwrap_or_else(Zero::zero)
+						) && stake >= asset_info.slash,
+					Error::<T>::NotEnoughStake
+				);
+				pre_prices.push(PrePrice { price, block, who: signer });
+			}
+			let median = Self::get_median_price(&pre_prices).ok_or(Error::<T>::NotEnoughAnswers)?;
+			ensure!(
+				Self::calculate_price(&pre_prices, &asset_info).is_some(),
+				Error::<T>::NotEnoughAnswers
+			);
+			ensure!(
+				Self::is_requested(&asset_id) || Self::deviates(asset_id, median),
+				Error::<T>::PriceNotRequested
+			);
+
+			// answers submitted one by one are superseded by the report
+			for pre_price in PrePrices::<T>::take(asset_id) {
+				Self::remove_price_in_transit(&pre_price.who, &asset_info);
+			}
+			// put in transit as if submitted one by one, payout takes them out
+			for pre_price in &pre_prices {
+				AnswerInTransit::<T>::mutate(&pre_price.who, |transit| {
+					*transit =
+						Some(transit.unwrap_or_else(Zero::zero).saturating_add(asset_info.slash));
+				});
+			}
+			// the in-transit amounts are rolled back with the call if no price comes out of it
+			let price = Self::update_price(asset_id, asset_info, block, pre_prices)?
+				.ok_or(Error::<T>::NotEnoughAnswers)?;
+
+			Self::deposit_event(Event::ReportSubmitted(who, asset_id, price));
+			Ok(Pays::No.into())
+		}
+	}
+
+	/// Payload signed by a signer observing the price of an asset. `round` is the block the
+	/// current price was updated at, so observations can not be replayed in later rounds.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct ObservationPayload<Public, AssetId, PriceValue, BlockNumber> {
+		pub asset_id: AssetId,
+		pub price: PriceValue,
+		pub round: BlockNumber,
+		pub public: Public,
+	}
+
+	impl<T: Config> SignedPayload<T>
+		for ObservationPayload<T::Public, T::AssetId, T::PriceValue, T::BlockNumber>
+	{
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
 	}
 
 	/// Payload used by this example crate to hold price
@@ -858,10 +1139,14 @@ pub mod pallet {
 						BalanceStatus::Free,
 					);
 					match result {
-						Ok(remaining_val) =>
+						Ok(remaining_val) => {
 							if remaining_val > BalanceOf::<T>::zero() {
 								log::warn!("Only slashed {:?}", slash_amount - remaining_val);
-							},
+							}
+							TotalSlashed::<T>::mutate(&answer.who, |slashed| {
+								*slashed = slashed.saturating_add(slash_amount - remaining_val)
+							});
+						},
 						Err(e) => {
 							log::warn!("Failed to slash {:?} due to {:?}", answer.who, e);
 						},
@@ -878,6 +1163,10 @@ pub mod pallet {
 				}
 				Self::remove_price_in_transit(&answer.who, asset_info)
 			}
+			if !Self::is_round_rewarded(asset_id, price) {
+				// rewards keep accumulating for the next round
+				return Ok(())
+			}
 			if let Some(mut reward_tracker) = Self::get_reward_tracker_if_enabled() {
 				// accumulated amount of reward to distribute for the asset
 				let reward_amount_per_asset: T::Balance =
@@ -920,6 +1209,19 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Rounds opened by deviation are rewarded only when the aggregated price confirms the
+		/// deviation, or when the heartbeat elapsed meanwhile. Must be called before `Prices` is
+		/// updated with `price`.
+		fn is_round_rewarded(asset_id: T::AssetId, price: T::PriceValue) -> bool {
+			match Self::update_trigger(asset_id) {
+				Some(trigger) =>
+					Self::prices(asset_id).block + trigger.heartbeat <
+						frame_system::Pallet::<T>::block_number() ||
+						Self::deviates(asset_id, price),
+				None => true,
+			}
+		}
+
 		fn get_reward_tracker_if_enabled(
 		) -> Option<RewardTracker<<T as Config>::Balance, <T as Config>::Moment>> {
 			RewardTrackerStore::<T>::get().and_then(|r| {
@@ -938,6 +1240,9 @@ pub mod pallet {
 			reward_amount: BalanceOf<T>,
 		) -> DispatchResult {
 			T::Currency::transfer(&Self::account_id(), &controller, reward_amount, KeepAlive)?;
+			TotalRewarded::<T>::mutate(&who, |rewarded| {
+				*rewarded = rewarded.saturating_add(reward_amount)
+			});
 			Self::deposit_event(Event::OracleRewarded(who, asset_id, reward_amount));
 			Ok(())
 		}
@@ -1041,13 +1346,16 @@ pub mod pallet {
 			Ok((prev_pre_prices_len - pre_prices.len(), pre_prices))
 		}
 
+		/// Aggregates `pre_prices` into the price of `asset_id` and pays out the signers.
+		///
+		/// Returns the new price, or `None` if there were not enough answers to update it.
 		#[transactional]
 		pub fn update_price(
 			asset_id: T::AssetId,
 			asset_info: AssetInfo<Percent, T::BlockNumber, BalanceOf<T>>,
 			block: T::BlockNumber,
 			pre_prices: Vec<PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>>,
-		) -> DispatchResult {
+		) -> Result<Option<T::PriceValue>, DispatchError> {
 			// There can convert pre_prices.len() to u32 safely
 			// because pre_prices.len() limited by u32
 			// (type of AssetsInfo::<T>::get(asset_id).max_answers).
@@ -1058,6 +1366,8 @@ pub mod pallet {
 						_ => Zero::zero(),
 					};
 
+					Self::handle_payout(&pre_prices, price, asset_id, &asset_info)?;
+
 					Prices::<T>::insert(asset_id, Price { price, block });
 					PriceHistory::<T>::try_mutate(asset_id, |prices| -> DispatchResult {
 						if prices.len() as u32 >= T::MaxHistory::get() {
@@ -1072,15 +1382,14 @@ pub mod pallet {
 					})?;
 					PrePrices::<T>::remove(asset_id);
 
-					Self::handle_payout(&pre_prices, price, asset_id, &asset_info)?;
-
 					// Emit `PriceChanged` event when prices have changed, if required.
 					if price != last_price && asset_info.emit_price_changes {
 						Self::deposit_event(Event::PriceChanged(asset_id, price));
 					}
+					return Ok(Some(price))
 				}
 			}
-			Ok(())
+			Ok(None)
 		}
 
 		#[allow(clippy::type_complexity)]
@@ -1172,7 +1481,8 @@ pub mod pallet {
 
 		pub fn check_requests() {
 			for (i, asset_info) in AssetsInfo::<T>::iter() {
-				if Self::is_requested(&i) {
+				// prices of assets with an update trigger are fetched to check their deviation
+				if Self::is_requested(&i) || UpdateTriggers::<T>::contains_key(i) {
 					let _ = Self::fetch_price_and_send_signed(&i, asset_info);
 				}
 			}
@@ -1181,14 +1491,28 @@ pub mod pallet {
 		pub fn is_requested(price_id: &T::AssetId) -> bool {
 			let last_update = Self::prices(price_id);
 			let current_block = frame_system::Pallet::<T>::block_number();
-			let asset_info = Self::asset_info(price_id);
-			if asset_info.is_none() {
-				false
-			} else {
-				last_update.block + asset_info.unwrap_or_default().block_interval < current_block
+			let Some(asset_info) = Self::asset_info(price_id) else { return false };
+			match Self::update_trigger(price_id) {
+				// a round opened by a deviating price stays open until it is aggregated
+				Some(trigger) =>
+					!PrePrices::<T>::get(price_id).is_empty() ||
+						last_update.block + trigger.heartbeat < current_block,
+				None => last_update.block + asset_info.block_interval < current_block,
 			}
 		}
 
+		/// Whether `price` deviates enough from the current price of an asset with an update
+		/// trigger to open a new round.
+		pub fn deviates(asset_id: T::AssetId, price: T::PriceValue) -> bool {
+			let Some(trigger) = Self::update_trigger(asset_id) else { return false };
+			let current = Self::prices(asset_id).price;
+			if current.is_zero() {
+				return true
+			}
+			let deviation = price.max(current) - price.min(current);
+			Percent::from_rational(deviation, current) > trigger.deviation
+		}
+
 		pub fn remove_price_in_transit(
 			who: &T::AccountId,
 			asset_info: &AssetInfo<Percent, T::BlockNumber, BalanceOf<T>>,
@@ -1256,6 +1580,70 @@ pub mod pallet {
 			Ok(twap.into())
 		}
 
+		/// Price of one unit of the asset, taken from the sources configured for it.
+		pub fn sourced_price(
+			asset_id: T::AssetId,
+		) -> Result<Price<T::PriceValue, T::BlockNumber>, DispatchError> {
+			let signers_price = || -> Result<_, DispatchError> {
+				Prices::<T>::try_get(asset_id).map_err(|_| Error::<T>::PriceNotFound.into())
+			};
+			let Some(source) = PriceSources::<T>::get(asset_id) else { return signers_price() };
+			match source.policy {
+				PriceSourcePolicy::SignersOnly => signers_price(),
+				PriceSourcePolicy::DexOnly => Self::dex_price(asset_id, &source),
+				PriceSourcePolicy::Median =>
+					match (signers_price(), Self::dex_price(asset_id, &source)) {
+						(Ok(signers), Ok(dex)) => Ok(Price {
+							price: signers.price.saturating_add(dex.price) / 2_u32.into(),
+							block: signers.block,
+						}),
+						(Ok(price), Err(_)) | (Err(_), Ok(price)) => Ok(price),
+						(Err(error), Err(_)) => Err(error),
+					},
+				PriceSourcePolicy::SignersWithDexGuard(max_deviation) => {
+					let signers = signers_price()?;
+					let dex = Self::dex_price(asset_id, &source)?;
+					let deviation = signers.price.max(dex.price) - signers.price.min(dex.price);
+					ensure!(
+						Percent::from_rational(deviation, dex.price) <= max_deviation,
+						Error::<T>::PriceDeviatesFromDex
+					);
+					Ok(signers)
+				},
+			}
+		}
+
+		/// Price of one unit of the asset from the TWAP of each pool along the source path.
+		///
+		/// The TWAP is taken when the price is read, so the price is stamped with the current
+		/// block. When mixed with the signers price, the block of the signers price is kept.
+		pub fn dex_price(
+			asset_id: T::AssetId,
+			source: &PriceSourceOf<T>,
+		) -> Result<Price<T::PriceValue, T::BlockNumber>, DispatchError> {
+			let quote_asset_id =
+				source.path.last().ok_or(Error::<T>::PriceNotFound)?.quote_asset_id;
+			let rate = source.path.iter().try_fold(Rate::one(), |rate, hop| {
+				let hop_rate = T::Dex::twap_over_window(
+					hop.pool_id,
+					hop.base_asset_id,
+					hop.quote_asset_id,
+					source.twap_window,
+				)?;
+				rate.checked_mul(&hop_rate)
+					.ok_or(DispatchError::from(ArithmeticError::Overflow))
+			})?;
+			// the rate is between the smallest units of the assets, so one unit of the asset is
+			// worth `rate * unit` of the smallest units of the quote asset, normalized by its unit
+			let unit: u128 = T::LocalAssets::unit(asset_id)?;
+			let quote_unit: u128 = T::LocalAssets::unit(quote_asset_id)?;
+			let price = rate
+				.checked_mul_int(safe_multiply_by_rational(unit, PRICE_UNIT, quote_unit)?)
+				.ok_or(ArithmeticError::Overflow)?;
+			ensure!(!price.is_zero(), Error::<T>::PriceNotFound);
+			Ok(Price { price: price.into(), block: frame_system::Pallet::<T>::block_number() })
+		}
+
 		fn quote(
 			asset_id: T::AssetId,
 			price: T::PriceValue,
@@ -1303,6 +1691,11 @@ pub mod pallet {
 			let price = Self::fetch_price(price_id).map_err(|_| "Failed to fetch price")?;
 			log::info!("price {:#?}", price);
 
+			if !Self::is_requested(price_id) && !Self::deviates(*price_id, price.into()) {
+				log::info!("Price did not deviate");
+				return Err("Price did not deviate")
+			}
+
 			// Using `send_signed_transaction` associated type we create and submit a transaction
 			// representing the call, we've just created.
 			// Submit signed will return a vector of results for all accounts that were found in the
@@ -1399,5 +1792,48 @@ pub mod pallet {
 		pub fn account_id() -> T::AccountId {
 			T::PalletId::get().into_account_truncating()
 		}
+
+		/// Prices submitted for an asset which are not aggregated yet.
+		pub fn submitted_prices(
+			asset_id: T::AssetId,
+		) -> Vec<SubmittedPrice<T::AccountId, T::PriceValue, T::BlockNumber>> {
+			Self::pre_prices(asset_id)
+				.into_iter()
+				.map(|PrePrice { price, block, who }| SubmittedPrice { who, price, block })
+				.collect()
+		}
+
+		/// Configuration of every asset the oracle provides prices for.
+		pub fn assets_info() -> Vec<OracleAssetInfo<T::AssetId, T::BlockNumber, BalanceOf<T>>> {
+			AssetsInfo::<T>::iter()
+				.map(|(asset_id, info)| OracleAssetInfo {
+					asset_id,
+					threshold: info.threshold,
+					min_answers: info.min_answers,
+					max_answers: info.max_answers,
+					block_interval: info.block_interval,
+					reward_weight: info.reward_weight,
+					slash: info.slash,
+					emit_price_changes: info.emit_price_changes,
+				})
+				.collect()
+		}
+
+		/// Stake, rewards and slashes of a signer, `None` if it is not registered.
+		pub fn signer_stats(
+			signer: T::AccountId,
+		) -> Option<SignerStats<T::AccountId, BalanceOf<T>, T::BlockNumber>> {
+			let controller = Self::signer_to_controller(&signer)?;
+			Some(SignerStats {
+				controller,
+				stake: Self::oracle_stake(&signer).unwrap_or_else(Zero::zero),
+				answer_in_transit: Self::answer_in_transit(&signer).unwrap_or_else(Zero::zero),
+				declared_withdraw: Self::declared_withdraws(&signer)
+					.map(|withdraw| (withdraw.stake, withdraw.unlock_block)),
+				total_rewarded: Self::total_rewarded(&signer),
+				total_slashed: Self::total_slashed(&signer),
+				signer,
+			})
+		}
 	}
 }
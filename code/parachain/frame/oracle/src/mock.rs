@@ -1,6 +1,11 @@
 use crate as pallet_oracle;
 use crate::*;
 
+use composable_traits::{
+	defi::Rate,
+	dex::{Amm, AssetAmount, SwapResult},
+	time::DurationSeconds,
+};
 use frame_support::{
 	ord_parameter_types,
 	pallet_prelude::ConstU32,
@@ -11,10 +16,13 @@ use frame_support::{
 use frame_system as system;
 use frame_system::EnsureSignedBy;
 use sp_core::{sr25519, sr25519::Signature, H256};
+use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{
 	testing::{Header, TestXt},
 	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+	DispatchError, Permill,
 };
+use std::{collections::BTreeMap, sync::Arc};
 use system::{EnsureRoot, EnsureSigned};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -149,6 +157,123 @@ where
 
 pub type AssetId = u128;
 pub type PriceValue = u128;
+pub type PoolId = u128;
+
+parameter_types! {
+	/// TWAP of the mocked DEX pools, keyed by pool, base and quote asset.
+	pub static DexTwaps: BTreeMap<(PoolId, AssetId, AssetId), Rate> = BTreeMap::new();
+	pub const MaxPriceSourceHops: u32 = 3;
+}
+
+pub fn set_dex_twap(pool_id: PoolId, base_asset_id: AssetId, quote_asset_id: AssetId, twap: Rate) {
+	DexTwaps::mutate(|twaps| twaps.insert((pool_id, base_asset_id, quote_asset_id), twap));
+}
+
+/// DEX serving the TWAP set with `set_dex_twap`, the only part of `Amm` the oracle uses.
+pub struct MockDex;
+
+impl Amm for MockDex {
+	type AssetId = AssetId;
+	type Balance = Balance;
+	type AccountId = AccountId;
+	type PoolId = PoolId;
+
+	fn pool_exists(pool_id: PoolId) -> bool {
+		DexTwaps::get().keys().any(|(pool, _, _)| *pool == pool_id)
+	}
+
+	fn assets(_pool_id: PoolId) -> Result<BTreeMap<AssetId, Permill>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn lp_token(_pool_id: PoolId) -> Result<AssetId, DispatchError> {
+		unimplemented!()
+	}
+
+	fn redeemable_assets_for_lp_tokens(
+		_pool_id: PoolId,
+		_lp_amount: Balance,
+	) -> Result<BTreeMap<AssetId, Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn simulate_add_liquidity(
+		_who: &AccountId,
+		_pool_id: PoolId,
+		_amounts: BTreeMap<AssetId, Balance>,
+	) -> Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn simulate_remove_liquidity(
+		_who: &AccountId,
+		_pool_id: PoolId,
+		_lp_amount: Balance,
+		_min_amounts: BTreeMap<AssetId, Balance>,
+	) -> Result<BTreeMap<AssetId, Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn spot_price(
+		_pool_id: PoolId,
+		_base_asset: AssetAmount<AssetId, Balance>,
+		_quote_asset_id: AssetId,
+		_calculate_with_fees: bool,
+	) -> Result<SwapResult<AssetId, Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn twap_over_window(
+		pool_id: PoolId,
+		base_asset_id: AssetId,
+		quote_asset_id: AssetId,
+		_window: DurationSeconds,
+	) -> Result<Rate, DispatchError> {
+		DexTwaps::get()
+			.get(&(pool_id, base_asset_id, quote_asset_id))
+			.copied()
+			.ok_or(DispatchError::Other("no twap"))
+	}
+
+	fn add_liquidity(
+		_who: &AccountId,
+		_pool_id: PoolId,
+		_assets: BTreeMap<AssetId, Balance>,
+		_min_mint_amount: Balance,
+		_keep_alive: bool,
+	) -> Result<Balance, DispatchError> {
+		unimplemented!()
+	}
+
+	fn remove_liquidity(
+		_who: &AccountId,
+		_pool_id: PoolId,
+		_lp_amount: Balance,
+		_min_receive: BTreeMap<AssetId, Balance>,
+	) -> Result<BTreeMap<AssetId, Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn do_buy(
+		_who: &AccountId,
+		_pool_id: PoolId,
+		_in_asset_id: AssetId,
+		_out_asset: AssetAmount<AssetId, Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<AssetId, Balance>, DispatchError> {
+		unimplemented!()
+	}
+
+	fn do_swap(
+		_who: &AccountId,
+		_pool_id: PoolId,
+		_in_asset: AssetAmount<AssetId, Balance>,
+		_min_receive: AssetAmount<AssetId, Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<AssetId, Balance>, DispatchError> {
+		unimplemented!()
+	}
+}
 
 parameter_types! {
 	pub const TreasuryAccountId : AccountId= sr25519::Public([10u8; 32]);
@@ -189,6 +314,9 @@ impl pallet_oracle::Config for Test {
 	type PalletId = OraclePalletId;
 	type MsPerBlock = MsPerBlock;
 	type Balance = Balance;
+	type PoolId = PoolId;
+	type Dex = MockDex;
+	type MaxPriceSourceHops = MaxPriceSourceHops;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -205,7 +333,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		],
 	};
 	genesis.assimilate_storage(&mut t).unwrap();
-	t.into()
+	let mut ext: sp_io::TestExternalities = t.into();
+	// signers of reports sign their observations with keys of the keystore in benchmarks
+	ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
+	ext
 }
 
 pub const fn get_account_1() -> AccountId {
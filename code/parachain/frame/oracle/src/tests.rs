@@ -2,13 +2,14 @@ use crate::{
 	mock::{AccountId, Extrinsic, RuntimeCall, RuntimeEvent, *},
 	AssetInfo, Error, PrePrice, Withdraw, *,
 };
-use codec::Decode;
+use codec::{Decode, Encode};
 use composable_traits::{
 	defi::CurrencyPair,
-	oracle::{self, Price},
+	oracle::{self, OracleAssetInfo, Price, SignerStats, SubmittedPrice},
 };
 use frame_support::{
 	assert_noop, assert_ok,
+	dispatch::DispatchResultWithPostInfo,
 	traits::{Currency as _, Hooks},
 	BoundedVec,
 };
@@ -33,7 +34,7 @@ use proptest::prelude::*;
 
 use composable_tests_helpers::test::{block::process_and_progress_blocks, helper::RuntimeTrait};
 use composable_traits::{oracle::RewardTracker, time::MS_PER_YEAR_NAIVE};
-use sp_core::H256;
+use sp_core::{sr25519, Pair as _, H256};
 
 const UNIT: Balance = 1_000_000_000_000;
 
@@ -1362,6 +1363,274 @@ fn is_requested() {
 	});
 }
 
+#[test]
+fn set_update_trigger() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let trigger = UpdateTrigger { deviation: Percent::from_percent(10), heartbeat: 20 };
+		assert_noop!(
+			Oracle::set_update_trigger(RuntimeOrigin::root(), 0, Some(trigger)),
+			Error::<Test>::InvalidAssetId
+		);
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::root(),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(1).unwrap(),
+			Validated::new(3).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		assert_noop!(
+			Oracle::set_update_trigger(RuntimeOrigin::signed(get_account_1()), 0, Some(trigger)),
+			BadOrigin
+		);
+		assert_noop!(
+			Oracle::set_update_trigger(
+				RuntimeOrigin::root(),
+				0,
+				Some(UpdateTrigger { heartbeat: StalePrice::get(), ..trigger })
+			),
+			Error::<Test>::BlockIntervalLength
+		);
+
+		assert_ok!(Oracle::set_update_trigger(RuntimeOrigin::root(), 0, Some(trigger)));
+		assert_eq!(Oracle::update_trigger(0), Some(trigger));
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::UpdateTriggerSet(
+			0,
+			Some(trigger),
+		)));
+
+		assert_ok!(Oracle::set_update_trigger(RuntimeOrigin::root(), 0, None));
+		assert_eq!(Oracle::update_trigger(0), None);
+	});
+}
+
+#[test]
+fn update_trigger_opens_round_on_deviation_or_heartbeat() {
+	new_test_ext().execute_with(|| {
+		let account_1 = get_account_1();
+		let account_2 = get_root_account();
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::signed(account_2),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(2).unwrap(),
+			Validated::new(3).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		assert_ok!(Oracle::set_update_trigger(
+			RuntimeOrigin::root(),
+			0,
+			Some(UpdateTrigger { deviation: Percent::from_percent(10), heartbeat: 20 })
+		));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::root(), account_2, account_1));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::root(), account_1, account_2));
+		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_1), 50));
+		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_2), 50));
+		Prices::<Test>::insert(0, Price { price: 100, block: 6 });
+
+		// block interval elapsed, but not the heartbeat
+		System::set_block_number(12);
+		assert!(!Oracle::is_requested(&0));
+		assert_noop!(
+			Oracle::submit_price(RuntimeOrigin::signed(account_1), 109_u128, 0_u128),
+			Error::<Test>::PriceNotRequested
+		);
+
+		// a deviating price opens the round for everyone
+		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_1), 111_u128, 0_u128));
+		assert!(Oracle::is_requested(&0));
+		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_2), 111_u128, 0_u128));
+		Oracle::on_initialize(13);
+		assert_eq!(Oracle::prices(0), Price { price: 111, block: 13 });
+		assert!(!Oracle::is_requested(&0));
+
+		System::set_block_number(33);
+		assert!(!Oracle::is_requested(&0));
+		System::set_block_number(34);
+		assert!(Oracle::is_requested(&0));
+	});
+}
+
+#[test]
+fn update_trigger_rewards_rounds_confirming_deviation() {
+	new_test_ext().execute_with(|| {
+		let account_1 = get_account_1();
+		let account_2 = get_root_account();
+		let mut reward_tracker = RewardTracker::default();
+		reward_tracker.start = 1;
+		reward_tracker.current_block_reward = 100;
+		RewardTrackerStore::<Test>::set(Some(reward_tracker));
+		Balances::make_free_balance_be(&Oracle::account_id(), 100);
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::signed(account_2),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(1).unwrap(),
+			Validated::new(3).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			18,
+			0,
+			false,
+		));
+		assert_ok!(Oracle::set_update_trigger(
+			RuntimeOrigin::root(),
+			0,
+			Some(UpdateTrigger { deviation: Percent::from_percent(10), heartbeat: 20 })
+		));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::root(), account_2, account_1));
+		System::set_block_number(10);
+		Prices::<Test>::insert(0, Price { price: 100, block: 6 });
+		AccumulatedRewardsPerAsset::<Test>::insert(0, 18);
+		let answer = PrePrice { price: 105, block: 10, who: account_1 };
+
+		// the aggregated price did not confirm the deviation
+		assert_ok!(Oracle::handle_payout(&[answer], 105, 0, &Oracle::asset_info(0).unwrap()));
+		assert_eq!(Oracle::accumulated_rewards(0), Some(18));
+		assert_eq!(Balances::free_balance(Oracle::account_id()), 100);
+
+		assert_ok!(Oracle::handle_payout(&[answer], 111, 0, &Oracle::asset_info(0).unwrap()));
+		assert_eq!(Oracle::accumulated_rewards(0), Some(0));
+		assert_eq!(Balances::free_balance(Oracle::account_id()), 82);
+
+		// heartbeat rounds are always rewarded
+		AccumulatedRewardsPerAsset::<Test>::insert(0, 18);
+		System::set_block_number(27);
+		assert_ok!(Oracle::handle_payout(&[answer], 100, 0, &Oracle::asset_info(0).unwrap()));
+		assert_eq!(Oracle::accumulated_rewards(0), Some(0));
+	});
+}
+
+fn observation(
+	pair: &sr25519::Pair,
+	asset_id: AssetId,
+	price: PriceValue,
+	round: BlockNumber,
+) -> ObservationOf<Test> {
+	let payload = ObservationPayload { asset_id, price, round, public: pair.public() };
+	Observation { public: pair.public(), price, signature: pair.sign(&payload.encode()) }
+}
+
+fn report_signers() -> [sr25519::Pair; 3] {
+	let signers = ["//Alice", "//Bob", "//Charlie"]
+		.map(|suri| sr25519::Pair::from_string(suri, None).unwrap());
+	for (controller, signer) in
+		[get_account_1(), get_account_3(), get_account_4()].iter().zip(&signers)
+	{
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::root(), *controller, signer.public()));
+	}
+	assert_ok!(Oracle::add_asset_and_info(
+		RuntimeOrigin::root(),
+		0,
+		Validated::new(Percent::from_percent(80)).unwrap(),
+		Validated::new(2).unwrap(),
+		Validated::new(3).unwrap(),
+		Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+		5,
+		1,
+		false,
+	));
+	signers
+}
+
+#[test]
+fn submit_report_aggregates_signed_observations() {
+	new_test_ext().execute_with(|| {
+		let [alice, bob, charlie] = report_signers();
+		System::set_block_number(6);
+		let observations: BoundedVec<_, _> = vec![
+			observation(&alice, 0, 100, 0),
+			observation(&bob, 0, 102, 0),
+			observation(&charlie, 0, 104, 0),
+		]
+		.try_into()
+		.unwrap();
+
+		assert_ok!(Oracle::submit_report(
+			RuntimeOrigin::signed(alice.public()),
+			0,
+			observations.clone()
+		));
+		assert_eq!(Oracle::prices(0), Price { price: 102, block: 6 });
+		assert_eq!(Oracle::price_history(0).into_inner(), vec![Price { price: 102, block: 6 }]);
+		assert!(Oracle::pre_prices(0).is_empty());
+		for signer in [&alice, &bob, &charlie] {
+			assert_eq!(Oracle::answer_in_transit(signer.public()), Some(0));
+		}
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::ReportSubmitted(
+			alice.public(),
+			0,
+			102,
+		)));
+
+		// observations are only valid for the round they were signed for
+		System::set_block_number(12);
+		assert_noop!(
+			Oracle::submit_report(RuntimeOrigin::signed(alice.public()), 0, observations),
+			Error::<Test>::InvalidSignature
+		);
+	});
+}
+
+#[test]
+fn submit_report_rejects_invalid_observations() {
+	new_test_ext().execute_with(|| {
+		let [alice, bob, _] = report_signers();
+		let dave = sr25519::Pair::from_string("//Dave", None).unwrap();
+		System::set_block_number(6);
+		let submit = |who: AccountId, observations: Vec<ObservationOf<Test>>| {
+			Oracle::submit_report(RuntimeOrigin::signed(who), 0, observations.try_into().unwrap())
+		};
+
+		assert_noop!(
+			submit(
+				get_account_5(),
+				vec![observation(&alice, 0, 100, 0), observation(&bob, 0, 100, 0)]
+			),
+			Error::<Test>::UnsetSigner
+		);
+		assert_noop!(
+			submit(alice.public(), vec![observation(&alice, 0, 100, 0)]),
+			Error::<Test>::NotEnoughAnswers
+		);
+		assert_noop!(
+			submit(
+				alice.public(),
+				vec![observation(&alice, 0, 100, 0), observation(&alice, 0, 100, 0)]
+			),
+			Error::<Test>::AlreadySubmitted
+		);
+		assert_noop!(
+			submit(
+				alice.public(),
+				vec![observation(&alice, 0, 100, 0), observation(&dave, 0, 100, 0)]
+			),
+			Error::<Test>::UnsetSigner
+		);
+		let mut forged = observation(&bob, 0, 100, 0);
+		forged.price = 200;
+		assert_noop!(
+			submit(alice.public(), vec![observation(&alice, 0, 100, 0), forged]),
+			Error::<Test>::InvalidSignature
+		);
+
+		System::set_block_number(5);
+		assert_noop!(
+			submit(
+				alice.public(),
+				vec![observation(&alice, 0, 100, 0), observation(&bob, 0, 100, 0)]
+			),
+			Error::<Test>::PriceNotRequested
+		);
+	});
+}
+
 #[test]
 fn test_payout_slash() {
 	new_test_ext().execute_with(|| {
@@ -1494,6 +1763,91 @@ fn test_payout_slash() {
 	});
 }
 
+#[test]
+fn signer_stats_track_rewards_and_slashes() {
+	new_test_ext().execute_with(|| {
+		let root_account = get_root_account();
+		let controller_1 = get_account_3();
+		let signer_1 = get_account_1();
+		let controller_2 = get_account_5();
+		let signer_2 = get_account_4();
+		RewardTrackerStore::<Test>::set(Some(RewardTracker {
+			start: 1,
+			current_block_reward: 100,
+			..Default::default()
+		}));
+		Balances::make_free_balance_be(&Oracle::account_id(), 100);
+
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::signed(root_account),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(1).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			18,
+			5,
+			false,
+		));
+		assert_eq!(
+			Oracle::assets_info(),
+			vec![OracleAssetInfo {
+				asset_id: 0,
+				threshold: Percent::from_percent(80),
+				min_answers: 1,
+				max_answers: 5,
+				block_interval: 5,
+				reward_weight: 18,
+				slash: 5,
+				emit_price_changes: false,
+			}]
+		);
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(root_account), controller_1, signer_1));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(root_account), controller_2, signer_2));
+		assert_eq!(Oracle::signer_stats(controller_1), None);
+
+		add_price_storage(100, 0, signer_1, 0);
+		add_price_storage(400, 0, signer_2, 0);
+		assert_eq!(
+			Oracle::submitted_prices(0),
+			vec![
+				SubmittedPrice { who: signer_1, price: 100, block: 0 },
+				SubmittedPrice { who: signer_2, price: 400, block: 0 },
+			]
+		);
+
+		AccumulatedRewardsPerAsset::<Test>::insert(0, 18);
+		assert_ok!(Oracle::handle_payout(
+			&Oracle::pre_prices(0),
+			100,
+			0,
+			&Oracle::asset_info(0).unwrap(),
+		));
+
+		assert_eq!(
+			Oracle::signer_stats(signer_1),
+			Some(SignerStats {
+				signer: signer_1,
+				controller: controller_1,
+				stake: 1,
+				answer_in_transit: 0,
+				declared_withdraw: None,
+				total_rewarded: 18,
+				total_slashed: 0,
+			})
+		);
+		// only the reserved stake could be slashed
+		assert_eq!(Oracle::total_slashed(signer_2), 1);
+		assert_eq!(Oracle::total_rewarded(signer_2), 0);
+
+		assert_ok!(Oracle::remove_stake(RuntimeOrigin::signed(controller_1)));
+		assert_eq!(
+			Oracle::signer_stats(signer_1).and_then(|stats| stats.declared_withdraw),
+			Some((1, StakeLock::get()))
+		);
+	});
+}
+
 #[test]
 fn test_reset_reward_tracker_if_expired() {
 	new_test_ext().execute_with(|| {
@@ -1786,6 +2140,9 @@ fn update_price() {
 			false, // do not emit PriceChange event
 		));
 
+		// Nothing is updated under min answers.
+		assert_eq!(Oracle::update_price(4, Oracle::asset_info(4).unwrap(), 2, vec![]), Ok(None));
+
 		// Update price for KSM.
 		do_price_update(4, 2);
 
@@ -1950,6 +2307,160 @@ fn ratio_base_is_way_less_smaller() {
 	})
 }
 
+fn set_price_source(
+	asset_id: AssetId,
+	policy: PriceSourcePolicy,
+	path: Vec<PriceSourceHop<AssetId, PoolId>>,
+) -> DispatchResultWithPostInfo {
+	Oracle::set_price_source(
+		RuntimeOrigin::root(),
+		asset_id,
+		policy,
+		BoundedVec::try_from(path).unwrap(),
+		60,
+	)
+}
+
+fn hop(
+	pool_id: PoolId,
+	base_asset_id: AssetId,
+	quote_asset_id: AssetId,
+) -> PriceSourceHop<AssetId, PoolId> {
+	PriceSourceHop { pool_id, base_asset_id, quote_asset_id }
+}
+
+#[test]
+fn set_price_source_validates_path() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_dex_twap(1, BTC::ID, 2, FixedU128::saturating_from_integer(2));
+		set_dex_twap(2, 2, 3, FixedU128::saturating_from_integer(3));
+
+		assert_noop!(
+			Oracle::set_price_source(
+				RuntimeOrigin::signed(get_account_1()),
+				BTC::ID,
+				PriceSourcePolicy::DexOnly,
+				BoundedVec::try_from(vec![hop(1, BTC::ID, 2)]).unwrap(),
+				60,
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			set_price_source(BTC::ID, PriceSourcePolicy::DexOnly, vec![]),
+			Error::<Test>::InvalidPriceSourcePath
+		);
+		assert_noop!(
+			set_price_source(BTC::ID, PriceSourcePolicy::Median, vec![hop(2, 2, 3)]),
+			Error::<Test>::InvalidPriceSourcePath
+		);
+		assert_noop!(
+			set_price_source(
+				BTC::ID,
+				PriceSourcePolicy::Median,
+				vec![hop(1, BTC::ID, 2), hop(3, 2, 3)]
+			),
+			Error::<Test>::InvalidPriceSourcePath
+		);
+		assert_noop!(
+			Oracle::set_price_source(
+				RuntimeOrigin::root(),
+				BTC::ID,
+				PriceSourcePolicy::DexOnly,
+				BoundedVec::try_from(vec![hop(1, BTC::ID, 2)]).unwrap(),
+				0,
+			),
+			Error::<Test>::InvalidTwapWindow
+		);
+
+		assert_ok!(set_price_source(
+			BTC::ID,
+			PriceSourcePolicy::DexOnly,
+			vec![hop(1, BTC::ID, 2), hop(2, 2, 3)]
+		));
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::PriceSourceSet(
+			BTC::ID,
+			PriceSourcePolicy::DexOnly,
+		)));
+		// signers only prices need no path
+		assert_ok!(set_price_source(PICA::ID, PriceSourcePolicy::SignersOnly, vec![]));
+	});
+}
+
+#[test]
+fn dex_only_price_is_product_of_twaps_along_path() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_dex_twap(1, BTC::ID, 2, FixedU128::saturating_from_integer(2));
+		set_dex_twap(2, 2, 3, FixedU128::saturating_from_rational(3, 2));
+
+		assert_noop!(
+			<Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE),
+			Error::<Test>::PriceNotFound
+		);
+		assert_ok!(set_price_source(
+			BTC::ID,
+			PriceSourcePolicy::DexOnly,
+			vec![hop(1, BTC::ID, 2), hop(2, 2, 3)]
+		));
+
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::units(2)).unwrap();
+		assert_eq!(price, Price { price: 6 * UNIT, block: 1 });
+
+		// signers prices are ignored
+		Prices::<Test>::insert(BTC::ID, Price { price: UNIT, block: 1 });
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE).unwrap();
+		assert_eq!(price.price, 3 * UNIT);
+	});
+}
+
+#[test]
+fn median_price_falls_back_to_available_source() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_dex_twap(1, BTC::ID, 2, FixedU128::saturating_from_integer(4));
+		assert_ok!(set_price_source(BTC::ID, PriceSourcePolicy::Median, vec![hop(1, BTC::ID, 2)]));
+
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE).unwrap();
+		assert_eq!(price.price, 4 * UNIT);
+
+		Prices::<Test>::insert(BTC::ID, Price { price: 2 * UNIT, block: 1 });
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE).unwrap();
+		assert_eq!(price.price, 3 * UNIT);
+
+		DexTwaps::set(Default::default());
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE).unwrap();
+		assert_eq!(price.price, 2 * UNIT);
+	});
+}
+
+#[test]
+fn signers_price_is_guarded_by_dex_price() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		set_dex_twap(1, BTC::ID, 2, FixedU128::saturating_from_integer(100));
+		assert_ok!(set_price_source(
+			BTC::ID,
+			PriceSourcePolicy::SignersWithDexGuard(Percent::from_percent(5)),
+			vec![hop(1, BTC::ID, 2)]
+		));
+		assert_noop!(
+			<Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE),
+			Error::<Test>::PriceNotFound
+		);
+
+		Prices::<Test>::insert(BTC::ID, Price { price: 104 * UNIT, block: 1 });
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE).unwrap();
+		assert_eq!(price.price, 104 * UNIT);
+
+		Prices::<Test>::insert(BTC::ID, Price { price: 94 * UNIT, block: 1 });
+		assert_noop!(
+			<Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::ONE),
+			Error::<Test>::PriceDeviatesFromDex
+		);
+	});
+}
+
 #[test]
 fn get_twap() {
 	new_test_ext().execute_with(|| {
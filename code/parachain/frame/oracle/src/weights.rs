@@ -17,6 +17,9 @@ pub trait WeightInfo {
 	fn submit_price(p: u32) -> Weight;
 	fn update_pre_prices(p: u32) -> Weight;
 	fn update_price(p: u32) -> Weight;
+	fn set_price_source(p: u32) -> Weight;
+	fn set_update_trigger() -> Weight;
+	fn submit_report(p: u32) -> Weight;
 }
 
 /// Weights for pallet_oracle using the Substrate node and recommended hardware.
@@ -78,6 +81,26 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
+	fn set_price_source(p: u32) -> Weight {
+		Weight::from_ref_time(30_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_200_000_u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(p as u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn set_update_trigger() -> Weight {
+		Weight::from_ref_time(24_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn submit_report(p: u32) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			// Standard Error: 120_000
+			.saturating_add(Weight::from_ref_time(75_000_000_u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(p as u64)))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p as u64)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -140,4 +163,24 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
+	fn set_price_source(p: u32) -> Weight {
+		Weight::from_ref_time(30_000_000_u64)
+			.saturating_add(Weight::from_ref_time(1_200_000_u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(p as u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_update_trigger() -> Weight {
+		Weight::from_ref_time(24_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn submit_report(p: u32) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			// Standard Error: 120_000
+			.saturating_add(Weight::from_ref_time(75_000_000_u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(p as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p as u64)))
+	}
 }
@@ -1,7 +1,13 @@
 use codec::Codec;
 use composable_support::rpc_helpers::SafeRpcWrapper;
-use composable_traits::dex::PriceAggregate;
-use core::{fmt::Display, str::FromStr};
+use composable_traits::{
+	defi::Rate,
+	dex::{AssetAmount, LiquiditySimulation, PriceAggregate, SwapSimulation},
+};
+use core::{
+	fmt::{Debug, Display},
+	str::FromStr,
+};
 use jsonrpsee::{
 	core::{Error as RpcError, RpcResult},
 	proc_macros::rpc,
@@ -10,7 +16,7 @@ use jsonrpsee::{
 use pablo_runtime_api::PabloRuntimeApi;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, DispatchError};
 use sp_std::{cmp::Ord, collections::btree_map::BTreeMap, sync::Arc};
 
 #[rpc(client, server)]
@@ -47,7 +53,7 @@ where
 		pool_id: SafeRpcWrapper<PoolId>,
 		amounts: BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
 		at: Option<BlockHash>,
-	) -> RpcResult<SafeRpcWrapper<Balance>>;
+	) -> RpcResult<LiquiditySimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>;
 
 	#[method(name = "pablo_simulateRemoveLiquidity")]
 	fn simulate_remove_liquidity(
@@ -57,7 +63,35 @@ where
 		lp_amount: SafeRpcWrapper<Balance>,
 		min_expected_amounts: BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
 		at: Option<BlockHash>,
-	) -> RpcResult<BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>;
+	) -> RpcResult<LiquiditySimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>;
+
+	#[method(name = "pablo_simulateSwap")]
+	fn simulate_swap(
+		&self,
+		pool_id: SafeRpcWrapper<PoolId>,
+		in_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		out_asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<SwapSimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>;
+
+	#[method(name = "pablo_simulateBuy")]
+	fn simulate_buy(
+		&self,
+		pool_id: SafeRpcWrapper<PoolId>,
+		in_asset_id: SafeRpcWrapper<AssetId>,
+		out_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		at: Option<BlockHash>,
+	) -> RpcResult<SwapSimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>;
+
+	#[method(name = "pablo_twap")]
+	fn twap(
+		&self,
+		pool_id: SafeRpcWrapper<PoolId>,
+		base_asset_id: SafeRpcWrapper<AssetId>,
+		quote_asset_id: SafeRpcWrapper<AssetId>,
+		window_seconds: SafeRpcWrapper<u64>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<SafeRpcWrapper<Rate>>>;
 }
 
 pub struct Pablo<C, Block> {
@@ -137,20 +171,14 @@ where
 		pool_id: SafeRpcWrapper<PoolId>,
 		amounts: BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
 		at: Option<<Block as BlockT>::Hash>,
-	) -> RpcResult<SafeRpcWrapper<Balance>> {
+	) -> RpcResult<LiquiditySimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>> {
 		let api = self.client.runtime_api();
 
 		let at = at.unwrap_or_else(|| self.client.info().best_hash);
 
 		// calling ../../runtime-api
 		let runtime_api_result = api.simulate_add_liquidity(at, who, pool_id, amounts);
-		runtime_api_result.map_err(|e| {
-			RpcError::Call(CallError::Custom(ErrorObject::owned(
-				9876,
-				"Something wrong",
-				Some(format!("{:?}", e)),
-			)))
-		})
+		flatten_simulation_result(runtime_api_result)
 	}
 
 	fn simulate_remove_liquidity(
@@ -160,7 +188,7 @@ where
 		lp_amount: SafeRpcWrapper<Balance>,
 		min_expected_amounts: BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
 		at: Option<<Block as BlockT>::Hash>,
-	) -> RpcResult<BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>> {
+	) -> RpcResult<LiquiditySimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>> {
 		let api = self.client.runtime_api();
 
 		let at = at.unwrap_or_else(|| self.client.info().best_hash);
@@ -168,6 +196,56 @@ where
 		// calling ../../runtime-api
 		let runtime_api_result =
 			api.simulate_remove_liquidity(at, who, pool_id, lp_amount, min_expected_amounts);
+		flatten_simulation_result(runtime_api_result)
+	}
+
+	fn simulate_swap(
+		&self,
+		pool_id: SafeRpcWrapper<PoolId>,
+		in_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		out_asset_id: SafeRpcWrapper<AssetId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SwapSimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>> {
+		let api = self.client.runtime_api();
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		// calling ../../runtime-api
+		let runtime_api_result = api.simulate_swap(at, pool_id, in_asset, out_asset_id);
+		flatten_simulation_result(runtime_api_result)
+	}
+
+	fn simulate_buy(
+		&self,
+		pool_id: SafeRpcWrapper<PoolId>,
+		in_asset_id: SafeRpcWrapper<AssetId>,
+		out_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SwapSimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>> {
+		let api = self.client.runtime_api();
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		// calling ../../runtime-api
+		let runtime_api_result = api.simulate_buy(at, pool_id, in_asset_id, out_asset);
+		flatten_simulation_result(runtime_api_result)
+	}
+
+	fn twap(
+		&self,
+		pool_id: SafeRpcWrapper<PoolId>,
+		base_asset_id: SafeRpcWrapper<AssetId>,
+		quote_asset_id: SafeRpcWrapper<AssetId>,
+		window_seconds: SafeRpcWrapper<u64>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<SafeRpcWrapper<Rate>>> {
+		let api = self.client.runtime_api();
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		// calling ../../runtime-api
+		let runtime_api_result =
+			api.twap(at, pool_id, base_asset_id, quote_asset_id, window_seconds);
 		runtime_api_result.map_err(|e| {
 			RpcError::Call(CallError::Custom(ErrorObject::owned(
 				9876,
@@ -177,3 +255,24 @@ where
 		})
 	}
 }
+
+/// Reports both the failures to call the runtime API and the failed simulations as RPC errors.
+fn flatten_simulation_result<T, E: Debug>(
+	runtime_api_result: Result<Result<T, DispatchError>, E>,
+) -> RpcResult<T> {
+	runtime_api_result
+		.map_err(|e| {
+			RpcError::Call(CallError::Custom(ErrorObject::owned(
+				9876,
+				"Something wrong",
+				Some(format!("{:?}", e)),
+			)))
+		})?
+		.map_err(|e| {
+			RpcError::Call(CallError::Custom(ErrorObject::owned(
+				9877,
+				"Simulation failed",
+				Some(format!("{:?}", e)),
+			)))
+		})
+}
@@ -4,12 +4,17 @@
 
 use codec::Codec;
 use composable_support::rpc_helpers::SafeRpcWrapper;
-use composable_traits::dex::PriceAggregate;
+use composable_traits::{
+	defi::Rate,
+	dex::{AssetAmount, LiquiditySimulation, PriceAggregate, SwapSimulation},
+};
+use sp_runtime::DispatchError;
 use sp_std::collections::btree_map::BTreeMap;
 
 // Pablo Runtime API declaration. Implemented for each runtime at
 // `runtime/<runtime-name>/src/lib.rs`.
 sp_api::decl_runtime_apis! {
+	#[api_version(2)]
 	pub trait PabloRuntimeApi<AccountId, PoolId, AssetId, Balance>
 	where
 		PoolId: Codec,
@@ -30,17 +35,46 @@ sp_api::decl_runtime_apis! {
 			asset_id: AssetId,
 		) -> Option<SafeRpcWrapper<Balance>>;
 
+		/// Simulate adding `amounts` of liquidity to the given pool as `who`, returning the LP
+		/// tokens minted, the fees charged and the price impact of the deposit.
 		fn simulate_add_liquidity(
 			who: SafeRpcWrapper<AccountId>,
 			pool_id: SafeRpcWrapper<PoolId>,
 			amounts: BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
-		) -> SafeRpcWrapper<Balance>;
+		) -> Result<LiquiditySimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>, DispatchError>;
 
+		/// Simulate removing `lp_amount` of liquidity from the given pool as `who`, returning the
+		/// amounts withdrawn and the price impact of the withdrawal.
 		fn simulate_remove_liquidity(
 			who: SafeRpcWrapper<AccountId>,
 			pool_id: SafeRpcWrapper<PoolId>,
 			lp_amount: SafeRpcWrapper<Balance>,
 			min_expected_amounts: BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
-		) -> BTreeMap<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>;
+		) -> Result<LiquiditySimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>, DispatchError>;
+
+		/// Simulate swapping `in_asset` for `out_asset_id` in the given pool, returning the amounts
+		/// exchanged, the fees charged and the price impact of the swap.
+		fn simulate_swap(
+			pool_id: SafeRpcWrapper<PoolId>,
+			in_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+			out_asset_id: SafeRpcWrapper<AssetId>,
+		) -> Result<SwapSimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>, DispatchError>;
+
+		/// Simulate buying `out_asset` with `in_asset_id` from the given pool, returning the amounts
+		/// exchanged, the fees charged and the price impact of the buy.
+		fn simulate_buy(
+			pool_id: SafeRpcWrapper<PoolId>,
+			in_asset_id: SafeRpcWrapper<AssetId>,
+			out_asset: AssetAmount<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>,
+		) -> Result<SwapSimulation<SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>, DispatchError>;
+
+		/// Retrieve the time weighted average price of the given pool for the given
+		/// `base_asset_id` and `quote_asset_id` pair over the last `window_seconds`.
+		fn twap(
+			pool_id: SafeRpcWrapper<PoolId>,
+			base_asset_id: SafeRpcWrapper<AssetId>,
+			quote_asset_id: SafeRpcWrapper<AssetId>,
+			window_seconds: SafeRpcWrapper<u64>,
+		) -> Option<SafeRpcWrapper<Rate>>;
 	}
 }
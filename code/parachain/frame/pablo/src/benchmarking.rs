@@ -1,8 +1,14 @@
 use super::*;
-use crate::{Pallet as Pablo, PoolConfiguration::DualAssetConstantProduct};
+use crate::{
+	Pallet as Pablo,
+	PoolConfiguration::{
+		ConcentratedLiquidity, DualAssetConstantProduct, LiquidityBootstrapping,
+		MultiAssetConstantProduct, StableSwap,
+	},
+};
 use composable_traits::{
 	defi::CurrencyPair,
-	dex::{Amm, AssetAmount},
+	dex::{Amm, AssetAmount, FeeConfig},
 };
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::{
@@ -35,10 +41,29 @@ fn create_amm_pool<T: Config>(
 	Pablo::<T>::do_create_pool(swap_pool_init, Some(lp_token_id)).expect("impossible; qed;")
 }
 
+fn create_concentrated_liquidity_pool<T: Config>(
+	owner: T::AccountId,
+	pair: CurrencyPair<T::AssetId>,
+	position_collection_id: T::AssetId,
+) -> T::PoolId {
+	let pool_init = PoolInitConfiguration::ConcentratedLiquidity {
+		owner,
+		pair,
+		tick_spacing: 10,
+		initial_tick: 0,
+		fee: Permill::from_percent(1),
+	};
+	Pablo::<T>::do_create_pool(pool_init, Some(position_collection_id)).expect("impossible; qed;")
+}
+
 fn get_lp_token<T: Config>(pool_id: T::PoolId) -> T::AssetId {
 	let pool_info = Pablo::<T>::get_pool(pool_id).expect("impossible; qed;");
 	match pool_info {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		MultiAssetConstantProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => unreachable!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => unreachable!("concentrated liquidity pools have no lp token"),
 	}
 }
 
@@ -93,6 +118,31 @@ benchmarks! {
 		let lp_amount = T::Assets::balance(get_lp_token::<T>(pool_id), &owner);
 	  }: _(RawOrigin::Signed(owner), pool_id, lp_amount, BTreeMap::from([(usdc, 0.into()), (usdt, 0.into())]))
 
+	remove_liquidity_single_asset {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_amm_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		// Add the liquidity
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0.into(),
+			false
+		));
+		// redeem a tenth of the LP tokens for USDC only
+		let lp_amount = T::Assets::balance(get_lp_token::<T>(pool_id), &owner) / 10_u128.into();
+	  }: _(RawOrigin::Signed(owner), pool_id, lp_amount, AssetAmount::new(usdc, 0.into()))
+
 	buy {
 		let usdc = 100.into();
 		let usdt = 101.into();
@@ -146,6 +196,131 @@ benchmarks! {
 		// swap 1000 USDC
 	 }: _(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false)
 
+	create_position {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let position_collection_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), position_collection_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+	  }: _(RawOrigin::Signed(owner), pool_id, -1000, 1000, BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]), 0, false)
+
+	remove_position_liquidity {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let position_collection_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), position_collection_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		let (fnft_collection_id, fnft_instance_id) = Pablo::<T>::do_create_position(
+			&owner,
+			pool_id,
+			-1000,
+			1000,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0,
+			false,
+		).expect("impossible; qed;");
+		let liquidity = Positions::<T>::get(fnft_collection_id, fnft_instance_id).expect("impossible; qed;").liquidity;
+		// closing the position is the most expensive case
+	  }: _(RawOrigin::Signed(owner), fnft_collection_id, fnft_instance_id, liquidity, BTreeMap::new())
+
+	collect_position_fees {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let position_collection_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), position_collection_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		let (fnft_collection_id, fnft_instance_id) = Pablo::<T>::do_create_position(
+			&owner,
+			pool_id,
+			-1000,
+			1000,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0,
+			false,
+		).expect("impossible; qed;");
+		// accrue some fees
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		assert_ok!(<Pablo<T> as Amm>::do_swap(
+			&user,
+			pool_id,
+			AssetAmount::new(usdt, (1000_u128 * unit).into()),
+			AssetAmount::new(usdc, 0.into()),
+			false
+		));
+	  }: _(RawOrigin::Signed(owner), fnft_collection_id, fnft_instance_id)
+
+	update_fee_config {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_amm_pool::<T>(owner, CurrencyPair::new(usdc, usdt), lp_token_id);
+		let fee_config = FeeConfig::default_from(Permill::from_percent(2));
+	  }: _(RawOrigin::Root, pool_id, fee_config)
+
+	claim_fees {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_amm_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		// Add the liquidity
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0.into(),
+			false
+		));
+		// accrue protocol fees in both assets
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		let swap_result = <Pablo<T> as Amm>::do_swap(
+			&user,
+			pool_id,
+			AssetAmount::new(usdt, (1000_u128 * unit).into()),
+			AssetAmount::new(usdc, 0.into()),
+			false
+		).expect("impossible; qed;");
+		assert_ok!(<Pablo<T> as Amm>::do_swap(
+			&user,
+			pool_id,
+			swap_result.value,
+			AssetAmount::new(usdt, 0.into()),
+			false
+		));
+		let beneficiary = account("beneficiary", 0, 0);
+	  }: _(RawOrigin::Root, pool_id, beneficiary)
+
 	do_create_pool {
 		let usdc = 100.into();
 		let usdt = 101.into();
@@ -0,0 +1,725 @@
+use crate::{
+	create_lpt_asset,
+	types::{ConcentratedLiquidityState, FeeGrowth, Position, TickInfo},
+	ConcentratedLiquidityStates, Config, Error, LPTNonce, Pallet, PoolConfiguration, PoolCount,
+	Pools, Positions, TickBitmap, Ticks,
+};
+use composable_maths::dex::concentrated_liquidity::{
+	amounts_for_liquidity, compute_swap_step, liquidity_for_amounts, sqrt_price_at_tick,
+	tick_at_sqrt_price, MAX_TICK, MIN_TICK,
+};
+use composable_support::{
+	abstractions::utils::increment::Increment,
+	math::safe::{SafeAdd, SafeSub},
+};
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{AssetAmount, ConcentratedLiquidityPoolInfo, Fee, FeeConfig},
+	fnft::FinancialNft,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		fungibles::Transfer,
+		tokens::nonfungibles::{Create, Inspect as NonFungiblesInspect, Mutate},
+	},
+};
+use sp_arithmetic::Rounding;
+use sp_runtime::{
+	traits::{CheckedDiv, CheckedMul, Convert, One, Zero},
+	ArithmeticError, FixedPointNumber, FixedU128, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// Number of ticks, in multiples of the tick spacing, covered by a word of the tick bitmap.
+const TICKS_PER_WORD: i32 = 128;
+
+pub(crate) type ConcentratedLiquidityPoolInfoOf<T> =
+	ConcentratedLiquidityPoolInfo<<T as frame_system::Config>::AccountId, <T as Config>::AssetId>;
+pub(crate) type PositionOf<T> = Position<<T as Config>::PoolId>;
+
+/// Amounts swapped against a concentrated liquidity pool.
+#[derive(Default)]
+struct SwapOutcome {
+	/// Amount sent to the pool, fees included.
+	amount_in: u128,
+	amount_out: u128,
+	fee: u128,
+	/// Ticks crossed by the swap, with their updated fee growth outside.
+	crossed_ticks: Vec<(i32, FeeGrowth)>,
+}
+
+fn to_liquidity_delta(liquidity: u128) -> Result<i128, ArithmeticError> {
+	i128::try_from(liquidity).map_err(|_| ArithmeticError::Overflow)
+}
+
+fn apply_liquidity_delta(liquidity: u128, delta: i128) -> Result<u128, ArithmeticError> {
+	if delta >= 0 {
+		liquidity.checked_add(delta.unsigned_abs()).ok_or(ArithmeticError::Overflow)
+	} else {
+		liquidity.checked_sub(delta.unsigned_abs()).ok_or(ArithmeticError::Underflow)
+	}
+}
+
+/// Word of the tick bitmap containing `compressed_tick`, and the bit of the tick in that word.
+fn bitmap_position(compressed_tick: i32) -> (i32, u32) {
+	(
+		compressed_tick.div_euclid(TICKS_PER_WORD),
+		compressed_tick.rem_euclid(TICKS_PER_WORD).unsigned_abs(),
+	)
+}
+
+// Uniswap V3 style pool, liquidity is provided within price ranges and each position is represented
+// by a financial NFT.
+pub(crate) struct ConcentratedLiquidity<T>(PhantomData<T>);
+
+impl<T: Config> ConcentratedLiquidity<T> {
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		pair: CurrencyPair<T::AssetId>,
+		tick_spacing: u32,
+		initial_tick: i32,
+		fee_config: FeeConfig,
+		position_collection_id: Option<T::AssetId>,
+	) -> Result<(T::PoolId, T::AssetId), DispatchError> {
+		ensure!(pair.base != pair.quote, Error::<T>::InvalidPair);
+		ensure!(
+			tick_spacing > 0 && tick_spacing <= MAX_TICK.unsigned_abs(),
+			Error::<T>::InvalidTickSpacing
+		);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+		let sqrt_price = sqrt_price_at_tick(initial_tick).map_err(|_| Error::<T>::InvalidTick)?;
+
+		let position_collection_id = match position_collection_id {
+			Some(position_collection_id) => position_collection_id,
+			None => create_lpt_asset::<T>(
+				LPTNonce::<T>::increment().expect("Does not exceed u64::MAX"),
+			)?,
+		};
+
+		let pool_id = PoolCount::<T>::try_mutate(|pool_count| -> Result<_, DispatchError> {
+			let pool_id = *pool_count;
+			Pools::<T>::insert(
+				pool_id,
+				PoolConfiguration::ConcentratedLiquidity(ConcentratedLiquidityPoolInfo {
+					owner: who.clone(),
+					pair,
+					tick_spacing,
+					position_collection_id,
+					fee_config,
+				}),
+			);
+			ConcentratedLiquidityStates::<T>::insert(
+				pool_id,
+				ConcentratedLiquidityState { sqrt_price, tick: initial_tick, ..Default::default() },
+			);
+			*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+			Ok(pool_id)
+		})?;
+
+		// the pool account administers the collection, positions are minted to their owners
+		let pool_account = Pallet::<T>::account_id(&pool_id);
+		T::FinancialNft::create_collection(&position_collection_id, &pool_account, &pool_account)?;
+
+		Ok((pool_id, position_collection_id))
+	}
+
+	pub(crate) fn get_state(
+		pool_id: T::PoolId,
+	) -> Result<ConcentratedLiquidityState, DispatchError> {
+		ConcentratedLiquidityStates::<T>::get(pool_id)
+			.ok_or_else(|| Error::<T>::PoolNotFound.into())
+	}
+
+	/// Current price of the pool, in quote asset per base asset.
+	pub(crate) fn price(pool_id: T::PoolId) -> Result<FixedU128, DispatchError> {
+		let sqrt_price = Self::get_state(pool_id)?.sqrt_price;
+		Ok(sqrt_price.checked_mul(&sqrt_price).ok_or(ArithmeticError::Overflow)?)
+	}
+
+	/// Price of the quote asset, in base asset.
+	pub(crate) fn inverse_price(pool_id: T::PoolId) -> Result<FixedU128, DispatchError> {
+		Ok(FixedU128::one()
+			.checked_div(&Self::price(pool_id)?)
+			.ok_or(ArithmeticError::DivisionByZero)?)
+	}
+
+	fn ensure_valid_range(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		lower_tick: i32,
+		upper_tick: i32,
+	) -> Result<(), DispatchError> {
+		let tick_spacing = i64::from(pool.tick_spacing);
+		ensure!(
+			lower_tick < upper_tick &&
+				lower_tick >= MIN_TICK &&
+				upper_tick <= MAX_TICK &&
+				i64::from(lower_tick) % tick_spacing == 0 &&
+				i64::from(upper_tick) % tick_spacing == 0,
+			Error::<T>::InvalidTick
+		);
+		Ok(())
+	}
+
+	fn tick_spacing(pool: &ConcentratedLiquidityPoolInfoOf<T>) -> Result<i32, DispatchError> {
+		i32::try_from(pool.tick_spacing).map_err(|_| Error::<T>::InvalidTickSpacing.into())
+	}
+
+	/// Marks `tick` as initialized in the tick bitmap if it wasn't, as uninitialized otherwise.
+	fn flip_tick(pool_id: T::PoolId, tick: i32, tick_spacing: i32) {
+		let (word, bit) = bitmap_position(tick.div_euclid(tick_spacing));
+		TickBitmap::<T>::mutate_exists(pool_id, word, |bits| {
+			let flipped = bits.unwrap_or_default() ^ (1_u128 << bit);
+			*bits = if flipped.is_zero() { None } else { Some(flipped) };
+		});
+	}
+
+	/// Next initialized tick at or below `tick` if `lte`, above it otherwise, looking only at the
+	/// word of the tick bitmap where the search starts. If there is none, the bound of the word in
+	/// the direction of the search is returned along with `false`, so that swaps read the bitmap
+	/// one word at a time.
+	fn next_initialized_tick_within_word(
+		pool_id: T::PoolId,
+		tick: i32,
+		tick_spacing: i32,
+		lte: bool,
+	) -> (i32, bool) {
+		let compressed = tick.div_euclid(tick_spacing);
+		if lte {
+			let (word, bit) = bitmap_position(compressed);
+			// ticks of the word at or below `compressed`
+			let masked = TickBitmap::<T>::get(pool_id, word).unwrap_or_default() &
+				(u128::MAX >> (u128::BITS - 1 - bit));
+			let offset = if masked.is_zero() {
+				bit
+			} else {
+				bit - (u128::BITS - 1 - masked.leading_zeros())
+			};
+			((compressed - offset as i32) * tick_spacing, !masked.is_zero())
+		} else {
+			let (word, bit) = bitmap_position(compressed + 1);
+			// ticks of the word above `compressed`
+			let masked =
+				TickBitmap::<T>::get(pool_id, word).unwrap_or_default() & (u128::MAX << bit);
+			let offset =
+				if masked.is_zero() { u128::BITS - 1 - bit } else { masked.trailing_zeros() - bit };
+			((compressed + 1 + offset as i32) * tick_spacing, !masked.is_zero())
+		}
+	}
+
+	fn ensure_position_owner(
+		who: &T::AccountId,
+		fnft_collection_id: &T::AssetId,
+		fnft_instance_id: &T::FinancialNftInstanceId,
+	) -> Result<(), DispatchError> {
+		let owner = T::FinancialNft::owner(fnft_collection_id, fnft_instance_id)
+			.ok_or(Error::<T>::PositionNotFound)?;
+		ensure!(*who == owner, Error::<T>::NotPositionOwner);
+		Ok(())
+	}
+
+	fn get_position_pool(
+		position: &PositionOf<T>,
+	) -> Result<ConcentratedLiquidityPoolInfoOf<T>, DispatchError> {
+		match Pallet::<T>::get_pool(position.pool_id)? {
+			PoolConfiguration::ConcentratedLiquidity(info) => Ok(info),
+			_ => Err(Error::<T>::IncorrectPoolConfig.into()),
+		}
+	}
+
+	/// Fee growth between `lower_tick` and `upper_tick`.
+	fn fee_growth_inside(
+		pool_id: T::PoolId,
+		state: &ConcentratedLiquidityState,
+		lower_tick: i32,
+		upper_tick: i32,
+	) -> FeeGrowth {
+		let outside = |tick| {
+			Ticks::<T>::get(pool_id, tick)
+				.map(|info| info.fee_growth_outside)
+				.unwrap_or_default()
+		};
+		let below = if state.tick >= lower_tick {
+			outside(lower_tick)
+		} else {
+			state.fee_growth_global.wrapping_sub(outside(lower_tick))
+		};
+		let above = if state.tick < upper_tick {
+			outside(upper_tick)
+		} else {
+			state.fee_growth_global.wrapping_sub(outside(upper_tick))
+		};
+		state.fee_growth_global.wrapping_sub(below).wrapping_sub(above)
+	}
+
+	fn update_tick(
+		pool_id: T::PoolId,
+		tick_spacing: i32,
+		state: &ConcentratedLiquidityState,
+		tick: i32,
+		liquidity_delta: i128,
+		upper: bool,
+	) -> Result<(), DispatchError> {
+		let mut info = Ticks::<T>::get(pool_id, tick).unwrap_or_else(|| TickInfo {
+			// by convention, all of the fee growth before the initialization of a tick happened
+			// below it
+			fee_growth_outside: if tick <= state.tick {
+				state.fee_growth_global
+			} else {
+				FeeGrowth::default()
+			},
+			..Default::default()
+		});
+		let liquidity_gross = apply_liquidity_delta(info.liquidity_gross, liquidity_delta)?;
+		if info.liquidity_gross.is_zero() != liquidity_gross.is_zero() {
+			Self::flip_tick(pool_id, tick, tick_spacing);
+		}
+		info.liquidity_gross = liquidity_gross;
+		info.liquidity_net = if upper {
+			info.liquidity_net.checked_sub(liquidity_delta)
+		} else {
+			info.liquidity_net.checked_add(liquidity_delta)
+		}
+		.ok_or(ArithmeticError::Overflow)?;
+
+		if info.liquidity_gross.is_zero() {
+			Ticks::<T>::remove(pool_id, tick);
+		} else {
+			Ticks::<T>::insert(pool_id, tick, info);
+		}
+		Ok(())
+	}
+
+	/// Accrues the fees earned by `position` and changes its liquidity by `liquidity_delta`. The
+	/// ticks of the position are updated in storage, the pool state is only updated in `state`.
+	fn modify_position(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		state: &mut ConcentratedLiquidityState,
+		position: &mut PositionOf<T>,
+		liquidity_delta: i128,
+	) -> Result<(), DispatchError> {
+		let (pool_id, lower_tick, upper_tick) =
+			(position.pool_id, position.lower_tick, position.upper_tick);
+		let tick_spacing = Self::tick_spacing(pool)?;
+		// ticks must be initialized before computing the fee growth of a new position, and must
+		// only be cleared afterwards when closing one
+		if liquidity_delta > 0 {
+			Self::update_tick(pool_id, tick_spacing, state, lower_tick, liquidity_delta, false)?;
+			Self::update_tick(pool_id, tick_spacing, state, upper_tick, liquidity_delta, true)?;
+		}
+
+		let fee_growth_inside = Self::fee_growth_inside(pool_id, state, lower_tick, upper_tick);
+		let fee_growth = fee_growth_inside.wrapping_sub(position.fee_growth_inside_last);
+		position.fees_owed_base = position.fees_owed_base.safe_add(
+			&fee_growth
+				.base
+				.checked_mul_int(position.liquidity)
+				.ok_or(ArithmeticError::Overflow)?,
+		)?;
+		position.fees_owed_quote = position.fees_owed_quote.safe_add(
+			&fee_growth
+				.quote
+				.checked_mul_int(position.liquidity)
+				.ok_or(ArithmeticError::Overflow)?,
+		)?;
+		position.fee_growth_inside_last = fee_growth_inside;
+
+		if liquidity_delta < 0 {
+			Self::update_tick(pool_id, tick_spacing, state, lower_tick, liquidity_delta, false)?;
+			Self::update_tick(pool_id, tick_spacing, state, upper_tick, liquidity_delta, true)?;
+		}
+
+		position.liquidity = apply_liquidity_delta(position.liquidity, liquidity_delta)?;
+		if lower_tick <= state.tick && state.tick < upper_tick {
+			state.liquidity = apply_liquidity_delta(state.liquidity, liquidity_delta)?;
+		}
+		Ok(())
+	}
+
+	fn position_amounts(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		base_amount: u128,
+		quote_amount: u128,
+	) -> BTreeMap<T::AssetId, T::Balance> {
+		[(pool.pair.base, base_amount), (pool.pair.quote, quote_amount)]
+			.into_iter()
+			.filter(|(_, amount)| !amount.is_zero())
+			.map(|(asset_id, amount)| (asset_id, T::Convert::convert(amount)))
+			.collect()
+	}
+
+	/// Opens a position between `lower_tick` and `upper_tick` with as much liquidity as
+	/// `max_amounts` allows, minting the financial NFT representing it to `who`.
+	#[allow(clippy::too_many_arguments)]
+	pub(crate) fn create_position(
+		who: &T::AccountId,
+		pool_id: T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		lower_tick: i32,
+		upper_tick: i32,
+		max_amounts: BTreeMap<T::AssetId, T::Balance>,
+		min_liquidity: u128,
+		keep_alive: bool,
+	) -> Result<(T::FinancialNftInstanceId, u128, BTreeMap<T::AssetId, T::Balance>), DispatchError>
+	{
+		Self::ensure_valid_range(pool, lower_tick, upper_tick)?;
+		ensure!(
+			max_amounts.keys().all(|asset_id| pool.pair.contains(*asset_id)),
+			Error::<T>::AssetNotFound
+		);
+		let max_amount =
+			|asset_id| max_amounts.get(&asset_id).map_or(0, |amount| T::Convert::convert(*amount));
+
+		let mut state = Self::get_state(pool_id)?;
+		let sqrt_price_lower = sqrt_price_at_tick(lower_tick)?;
+		let sqrt_price_upper = sqrt_price_at_tick(upper_tick)?;
+		let liquidity = liquidity_for_amounts(
+			state.sqrt_price,
+			sqrt_price_lower,
+			sqrt_price_upper,
+			max_amount(pool.pair.base),
+			max_amount(pool.pair.quote),
+		)?;
+		ensure!(!liquidity.is_zero(), Error::<T>::InvalidAmount);
+		ensure!(liquidity >= T::MinPositionLiquidity::get(), Error::<T>::PositionLiquidityTooLow);
+		ensure!(liquidity >= min_liquidity, Error::<T>::CannotRespectMinimumRequested);
+
+		let mut position =
+			PositionOf::<T> { pool_id, lower_tick, upper_tick, ..Default::default() };
+		Self::modify_position(pool, &mut state, &mut position, to_liquidity_delta(liquidity)?)?;
+
+		let (base_amount, quote_amount) = amounts_for_liquidity(
+			state.sqrt_price,
+			sqrt_price_lower,
+			sqrt_price_upper,
+			liquidity,
+			Rounding::Up,
+		)?;
+		let deposited = Self::position_amounts(pool, base_amount, quote_amount);
+		let pool_account = Pallet::<T>::account_id(&pool_id);
+		for (asset_id, amount) in &deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		let fnft_instance_id = T::FinancialNft::get_next_nft_id(&pool.position_collection_id)?;
+		T::FinancialNft::mint_into(&pool.position_collection_id, &fnft_instance_id, who)?;
+		Positions::<T>::insert(pool.position_collection_id, fnft_instance_id, position);
+		ConcentratedLiquidityStates::<T>::insert(pool_id, state);
+
+		Ok((fnft_instance_id, liquidity, deposited))
+	}
+
+	/// Removes `liquidity` from a position. Once all of its liquidity is removed, the accrued fees
+	/// are paid out as well and the position is closed.
+	pub(crate) fn remove_position_liquidity(
+		who: &T::AccountId,
+		fnft_collection_id: T::AssetId,
+		fnft_instance_id: T::FinancialNftInstanceId,
+		liquidity: u128,
+		mut min_receive: BTreeMap<T::AssetId, T::Balance>,
+	) -> Result<(T::PoolId, BTreeMap<T::AssetId, T::Balance>), DispatchError> {
+		Self::ensure_position_owner(who, &fnft_collection_id, &fnft_instance_id)?;
+		let mut position = Positions::<T>::get(fnft_collection_id, fnft_instance_id)
+			.ok_or(Error::<T>::PositionNotFound)?;
+		ensure!(!liquidity.is_zero() && liquidity <= position.liquidity, Error::<T>::InvalidAmount);
+		// partially emptied positions must keep the minimum liquidity
+		ensure!(
+			liquidity == position.liquidity ||
+				position.liquidity - liquidity >= T::MinPositionLiquidity::get(),
+			Error::<T>::PositionLiquidityTooLow
+		);
+		let pool_id = position.pool_id;
+		let pool = Self::get_position_pool(&position)?;
+		let mut state = Self::get_state(pool_id)?;
+
+		Self::modify_position(&pool, &mut state, &mut position, -to_liquidity_delta(liquidity)?)?;
+
+		let (mut base_amount, mut quote_amount) = amounts_for_liquidity(
+			state.sqrt_price,
+			sqrt_price_at_tick(position.lower_tick)?,
+			sqrt_price_at_tick(position.upper_tick)?,
+			liquidity,
+			Rounding::Down,
+		)?;
+		for (asset_id, amount) in [(pool.pair.base, base_amount), (pool.pair.quote, quote_amount)] {
+			if let Some(min_amount) = min_receive.remove(&asset_id) {
+				ensure!(
+					T::Convert::convert(amount) >= min_amount,
+					Error::<T>::CannotRespectMinimumRequested
+				);
+			}
+		}
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		if position.liquidity.is_zero() {
+			base_amount = base_amount.safe_add(&position.fees_owed_base)?;
+			quote_amount = quote_amount.safe_add(&position.fees_owed_quote)?;
+			Positions::<T>::remove(fnft_collection_id, fnft_instance_id);
+			T::FinancialNft::burn(&fnft_collection_id, &fnft_instance_id, Some(who))?;
+		} else {
+			Positions::<T>::insert(fnft_collection_id, fnft_instance_id, position);
+		}
+		ConcentratedLiquidityStates::<T>::insert(pool_id, state);
+
+		let withdrawn = Self::position_amounts(&pool, base_amount, quote_amount);
+		let pool_account = Pallet::<T>::account_id(&pool_id);
+		for (asset_id, amount) in &withdrawn {
+			T::Assets::transfer(
+				*asset_id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		Ok((pool_id, withdrawn))
+	}
+
+	/// Pays out the fees accrued by a position.
+	pub(crate) fn collect_fees(
+		who: &T::AccountId,
+		fnft_collection_id: T::AssetId,
+		fnft_instance_id: T::FinancialNftInstanceId,
+	) -> Result<(T::PoolId, BTreeMap<T::AssetId, T::Balance>), DispatchError> {
+		Self::ensure_position_owner(who, &fnft_collection_id, &fnft_instance_id)?;
+		let mut position = Positions::<T>::get(fnft_collection_id, fnft_instance_id)
+			.ok_or(Error::<T>::PositionNotFound)?;
+		let pool_id = position.pool_id;
+		let pool = Self::get_position_pool(&position)?;
+		// the pool state is left untouched when the liquidity doesn't change
+		let mut state = Self::get_state(pool_id)?;
+		Self::modify_position(&pool, &mut state, &mut position, 0)?;
+
+		let fees = Self::position_amounts(&pool, position.fees_owed_base, position.fees_owed_quote);
+		position.fees_owed_base = Zero::zero();
+		position.fees_owed_quote = Zero::zero();
+		Positions::<T>::insert(fnft_collection_id, fnft_instance_id, position);
+
+		let pool_account = Pallet::<T>::account_id(&pool_id);
+		for (asset_id, amount) in &fees {
+			T::Assets::transfer(*asset_id, &pool_account, who, *amount, false)?;
+		}
+
+		Ok((pool_id, fees))
+	}
+
+	/// Swaps against the liquidity of the pool, crossing ticks as the price moves. `amount` is the
+	/// amount sent to the pool if `exact_in`, the amount received from it otherwise. Crossed ticks
+	/// are returned in the outcome rather than updated in storage.
+	#[allow(clippy::too_many_arguments)]
+	fn swap(
+		pool_id: T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		state: &mut ConcentratedLiquidityState,
+		in_asset_id: T::AssetId,
+		out_asset_id: T::AssetId,
+		amount: T::Balance,
+		exact_in: bool,
+		apply_fees: bool,
+	) -> Result<SwapOutcome, DispatchError> {
+		// selling the base asset moves the price down
+		let base_for_quote = if in_asset_id == pool.pair.base && out_asset_id == pool.pair.quote {
+			true
+		} else if in_asset_id == pool.pair.quote && out_asset_id == pool.pair.base {
+			false
+		} else {
+			return Err(Error::<T>::AssetNotFound.into())
+		};
+		ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+		let fee_rate = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let sqrt_price_limit =
+			sqrt_price_at_tick(if base_for_quote { MIN_TICK } else { MAX_TICK })?;
+		let tick_spacing = Self::tick_spacing(pool)?;
+
+		let mut amount_remaining: u128 = T::Convert::convert(amount);
+		let mut outcome = SwapOutcome::default();
+		while !amount_remaining.is_zero() {
+			ensure!(state.sqrt_price != sqrt_price_limit, Error::<T>::NotEnoughLiquidity);
+
+			// the swap stops at each initialized tick, and at each word of the tick bitmap
+			let (next_tick, initialized) = Self::next_initialized_tick_within_word(
+				pool_id,
+				state.tick,
+				tick_spacing,
+				base_for_quote,
+			);
+			let next_tick = next_tick.clamp(MIN_TICK, MAX_TICK);
+			let sqrt_price_target = sqrt_price_at_tick(next_tick)?;
+			let step = compute_swap_step(
+				state.sqrt_price,
+				sqrt_price_target,
+				state.liquidity,
+				amount_remaining,
+				fee_rate,
+				exact_in,
+			)?;
+
+			amount_remaining = amount_remaining.safe_sub(&if exact_in {
+				step.amount_in.safe_add(&step.fee_amount)?
+			} else {
+				step.amount_out
+			})?;
+			outcome.amount_in =
+				outcome.amount_in.safe_add(&step.amount_in)?.safe_add(&step.fee_amount)?;
+			outcome.amount_out = outcome.amount_out.safe_add(&step.amount_out)?;
+			outcome.fee = outcome.fee.safe_add(&step.fee_amount)?;
+
+			if !state.liquidity.is_zero() && !step.fee_amount.is_zero() {
+				let lp_fee = pool.fee_config.calculate_fees(in_asset_id, step.fee_amount).lp_fee;
+				let growth = FixedU128::checked_from_rational(lp_fee, state.liquidity)
+					.ok_or(ArithmeticError::Overflow)?;
+				state.fee_growth_global = state.fee_growth_global.wrapping_add(if base_for_quote {
+					FeeGrowth { base: growth, quote: FixedU128::zero() }
+				} else {
+					FeeGrowth { base: FixedU128::zero(), quote: growth }
+				});
+			}
+
+			state.sqrt_price = step.sqrt_price_next;
+			if step.sqrt_price_next == sqrt_price_target {
+				let crossed_tick =
+					if initialized { Ticks::<T>::get(pool_id, next_tick) } else { None };
+				if let Some(info) = crossed_tick {
+					outcome.crossed_ticks.push((
+						next_tick,
+						state.fee_growth_global.wrapping_sub(info.fee_growth_outside),
+					));
+					let liquidity_net = if base_for_quote {
+						info.liquidity_net.checked_neg().ok_or(ArithmeticError::Overflow)?
+					} else {
+						info.liquidity_net
+					};
+					state.liquidity = apply_liquidity_delta(state.liquidity, liquidity_net)?;
+				}
+				state.tick = if base_for_quote { next_tick.saturating_sub(1) } else { next_tick };
+			} else {
+				// the price only stops between two ticks once the whole amount has been swapped
+				state.tick = tick_at_sqrt_price(state.sqrt_price)?;
+				break
+			}
+		}
+
+		Ok(outcome)
+	}
+
+	/// Stores the pool state after a swap, along with the ticks it crossed.
+	fn commit_swap(
+		pool_id: T::PoolId,
+		state: ConcentratedLiquidityState,
+		crossed_ticks: &[(i32, FeeGrowth)],
+	) {
+		for (tick, fee_growth_outside) in crossed_ticks {
+			Ticks::<T>::mutate_exists(pool_id, tick, |info| {
+				if let Some(info) = info {
+					info.fee_growth_outside = *fee_growth_outside;
+				}
+			});
+		}
+		ConcentratedLiquidityStates::<T>::insert(pool_id, state);
+	}
+
+	fn swap_result(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		in_asset_id: T::AssetId,
+		out_asset_id: T::AssetId,
+		outcome: SwapOutcome,
+	) -> (
+		AssetAmount<T::AssetId, T::Balance>,
+		AssetAmount<T::AssetId, T::Balance>,
+		Fee<T::AssetId, T::Balance>,
+	) {
+		(
+			AssetAmount::new(out_asset_id, T::Convert::convert(outcome.amount_out)),
+			AssetAmount::new(in_asset_id, T::Convert::convert(outcome.amount_in)),
+			pool.fee_config.calculate_fees(in_asset_id, T::Convert::convert(outcome.fee)),
+		)
+	}
+
+	/// Simulates a swap of `in_asset`, without updating the pool.
+	pub(crate) fn get_exchange_value(
+		pool_id: T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let mut state = Self::get_state(pool_id)?;
+		let outcome = Self::swap(
+			pool_id,
+			pool,
+			&mut state,
+			in_asset.asset_id,
+			out_asset_id,
+			in_asset.amount,
+			true,
+			apply_fees,
+		)?;
+		Ok(Self::swap_result(pool, in_asset.asset_id, out_asset_id, outcome))
+	}
+
+	/// Swaps `in_asset`, updating the state of the pool. Assets are not transferred.
+	pub(crate) fn do_swap(
+		pool_id: T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let mut state = Self::get_state(pool_id)?;
+		let outcome = Self::swap(
+			pool_id,
+			pool,
+			&mut state,
+			in_asset.asset_id,
+			out_asset_id,
+			in_asset.amount,
+			true,
+			true,
+		)?;
+		Self::commit_swap(pool_id, state, &outcome.crossed_ticks);
+		Ok(Self::swap_result(pool, in_asset.asset_id, out_asset_id, outcome))
+	}
+
+	/// Buys `out_asset`, updating the state of the pool. Assets are not transferred.
+	pub(crate) fn do_buy(
+		pool_id: T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let mut state = Self::get_state(pool_id)?;
+		let outcome = Self::swap(
+			pool_id,
+			pool,
+			&mut state,
+			in_asset_id,
+			out_asset.asset_id,
+			out_asset.amount,
+			false,
+			true,
+		)?;
+		Self::commit_swap(pool_id, state, &outcome.crossed_ticks);
+		Ok(Self::swap_result(pool, in_asset_id, out_asset.asset_id, outcome))
+	}
+}
@@ -1,5 +1,6 @@
 use crate::{
-	create_lpt_asset, AssetIdOf, Config, Error, LPTNonce, PoolConfiguration, PoolCount, Pools,
+	create_lpt_asset, AssetIdOf, Config, Error, LPTNonce, Pallet, PoolConfiguration, PoolCount,
+	Pools,
 };
 use composable_maths::dex::{
 	constant_product::{
@@ -101,7 +102,10 @@ impl<T: Config> DualAssetConstantProduct<T> {
 			.map(|(asset_id, weight)| {
 				(
 					*asset_id,
-					(*weight, T::Convert::convert(T::Assets::balance(*asset_id, pool_account))),
+					(
+						*weight,
+						T::Convert::convert(Pallet::<T>::pool_balance(*asset_id, pool_account)),
+					),
 				)
 			})
 			// TODO(benluelo): This function should return an iterator, rather than eagerly
@@ -116,8 +120,38 @@ impl<T: Config> DualAssetConstantProduct<T> {
 		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
 		min_mint_amount: T::Balance,
 		keep_alive: bool,
-	) -> Result<(T::Balance, BTreeMap<T::AssetId, T::Balance>), DispatchError> {
-		let mut pool_assets = Self::get_pool_balances(&pool, &pool_account);
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		let (amount_of_lp_token_to_mint, actual_amounts_deposited, fee) =
+			Self::compute_add_liquidity(&pool, &pool_account, assets)?;
+
+		ensure!(
+			amount_of_lp_token_to_mint >= min_mint_amount,
+			Error::<T>::CannotRespectMinimumRequested
+		);
+
+		for (asset_id, amount) in &actual_amounts_deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		T::Assets::mint_into(pool.lp_token, who, amount_of_lp_token_to_mint)?;
+
+		Ok((amount_of_lp_token_to_mint, actual_amounts_deposited, fee))
+	}
+
+	/// Computes the LP tokens minted for depositing `assets` into the pool, the amounts actually
+	/// deposited and the fee charged, without transferring anything.
+	pub(crate) fn compute_add_liquidity(
+		pool: &BasicPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		let mut pool_assets = Self::get_pool_balances(pool, pool_account);
 
 		let assets_with_balances = assets.try_mapped(|asset_amount| {
 			if asset_amount.amount.is_zero() {
@@ -137,70 +171,41 @@ impl<T: Config> DualAssetConstantProduct<T> {
 
 		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
 
-		let (amount_of_lp_token_to_mint, actual_amounts_deposited) = if let [single] =
-			assets_with_balances.as_slice()
-		{
-			if lp_total_issuance.is_zero() {
-				return Err(Error::<T>::InitialDepositMustContainAllAssets.into())
-			}
-
-			let single_deposit = compute_deposit_lp(
-				lp_total_issuance,
-				single.deposit_amount,
-				single.existing_balance,
-				single.asset_weight,
-				pool.fee_config.fee_rate,
-			)?;
-
-			T::Assets::transfer(
-				single.asset_id,
-				who,
-				&pool_account,
-				T::Convert::convert(single.deposit_amount),
-				keep_alive,
-			)?;
-
-			(
-				single_deposit.value,
-				assets_with_balances
-					.into_iter()
-					.map(|adi| (adi.asset_id, T::Convert::convert(adi.deposit_amount)))
-					.collect(),
-			)
-		} else {
-			// ensure that `assets` contains all of the assets in the pool at this point
-			// a bit convoluted, but it works
-			ensure!(pool_assets.is_empty(), Error::<T>::UnsupportedOperation);
-
-			if lp_total_issuance.is_zero() {
-				let lp_to_mint = compute_first_deposit_lp(
-					assets_with_balances
-						.iter()
-						.map(|adi| (adi.asset_id, adi.deposit_amount, adi.asset_weight)),
-					Permill::zero(),
-				)?
-				.value;
-
-				for deposit in &assets_with_balances {
-					T::Assets::transfer(
-						deposit.asset_id,
-						who,
-						&pool_account,
-						T::Convert::convert(deposit.deposit_amount),
-						keep_alive,
-					)?;
+		let (amount_of_lp_token_to_mint, deposits, fee): (_, Vec<AssetDepositInfo<T::AssetId>>, _) =
+			if let [single] = assets_with_balances.as_slice() {
+				if lp_total_issuance.is_zero() {
+					return Err(Error::<T>::InitialDepositMustContainAllAssets.into())
 				}
 
-				(
-					lp_to_mint,
-					assets_with_balances
-						.into_iter()
-						.map(|adi| (adi.asset_id, T::Convert::convert(adi.deposit_amount)))
-						.collect(),
-				)
+				let single_deposit = compute_deposit_lp(
+					lp_total_issuance,
+					single.deposit_amount,
+					single.existing_balance,
+					single.asset_weight,
+					pool.fee_config.fee_rate,
+				)?;
+				let fee = Pallet::<T>::deposit_fee(single.asset_id, single_deposit.fee);
+
+				(single_deposit.value, assets_with_balances.into(), fee)
 			} else {
-				let normalized_deposits =
-					match normalize_asset_deposit_infos_to_min_ratio(assets_with_balances.into()) {
+				// ensure that `assets` contains all of the assets in the pool at this point
+				// a bit convoluted, but it works
+				ensure!(pool_assets.is_empty(), Error::<T>::UnsupportedOperation);
+
+				if lp_total_issuance.is_zero() {
+					let lp_to_mint = compute_first_deposit_lp(
+						assets_with_balances
+							.iter()
+							.map(|adi| (adi.asset_id, adi.deposit_amount, adi.asset_weight)),
+						Permill::zero(),
+					)?
+					.value;
+
+					(lp_to_mint, assets_with_balances.into(), None)
+				} else {
+					let normalized_deposits = match normalize_asset_deposit_infos_to_min_ratio(
+						assets_with_balances.into(),
+					) {
 						Ok(normalized_assets) => normalized_assets,
 						Err(AssetDepositNormalizationError::ArithmeticOverflow) =>
 							return Err(DispatchError::Arithmetic(ArithmeticError::Overflow)),
@@ -209,52 +214,32 @@ impl<T: Config> DualAssetConstantProduct<T> {
 						),
 					};
 
-				// since the asset deposits were normalized, the lp_to_mint will be the same for all
-				// asset deposits
-				let asset_to_calculate_with =
-					normalized_deposits.first().expect("2 assets in the vec; qed;");
-
-				// pass 1 as weight since adding liquidity for all assets with normalized deposits
-				// see docs on compute_deposit_lp_ for more information
-				let lp_to_mint = compute_deposit_lp(
-					lp_total_issuance,
-					asset_to_calculate_with.deposit_amount,
-					asset_to_calculate_with.existing_balance,
-					Permill::one(),
-					Zero::zero(),
-				)?
-				.value;
-
-				for normalized_deposit in &normalized_deposits {
-					T::Assets::transfer(
-						normalized_deposit.asset_id,
-						who,
-						&pool_account,
-						T::Convert::convert(normalized_deposit.deposit_amount),
-						keep_alive,
-					)?;
+					// since the asset deposits were normalized, the lp_to_mint will be the same for
+					// all asset deposits
+					let asset_to_calculate_with =
+						normalized_deposits.first().expect("2 assets in the vec; qed;");
+
+					// pass 1 as weight since adding liquidity for all assets with normalized
+					// deposits see docs on compute_deposit_lp_ for more information
+					let lp_to_mint = compute_deposit_lp(
+						lp_total_issuance,
+						asset_to_calculate_with.deposit_amount,
+						asset_to_calculate_with.existing_balance,
+						Permill::one(),
+						Zero::zero(),
+					)?
+					.value;
+
+					(lp_to_mint, normalized_deposits, None)
 				}
+			};
 
-				(
-					lp_to_mint,
-					normalized_deposits
-						.into_iter()
-						.map(|adi| (adi.asset_id, T::Convert::convert(adi.deposit_amount)))
-						.collect(),
-				)
-			}
-		};
-
-		let amount_of_lp_token_to_mint = T::Convert::convert(amount_of_lp_token_to_mint);
-
-		ensure!(
-			amount_of_lp_token_to_mint >= min_mint_amount,
-			Error::<T>::CannotRespectMinimumRequested
-		);
-
-		T::Assets::mint_into(pool.lp_token, who, amount_of_lp_token_to_mint)?;
+		let actual_amounts_deposited = deposits
+			.into_iter()
+			.map(|adi| (adi.asset_id, T::Convert::convert(adi.deposit_amount)))
+			.collect::<BTreeMap<_, T::Balance>>();
 
-		Ok((amount_of_lp_token_to_mint, actual_amounts_deposited))
+		Ok((T::Convert::convert(amount_of_lp_token_to_mint), actual_amounts_deposited, fee))
 	}
 
 	pub(crate) fn remove_liquidity(
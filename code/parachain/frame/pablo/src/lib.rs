@@ -44,7 +44,11 @@ pub mod weights;
 #[cfg(any(feature = "runtime-benchmarks", test))]
 mod benchmarking;
 
+mod concentrated_liquidity;
 mod dual_asset_constant_product;
+mod liquidity_bootstrapping;
+mod multi_asset_constant_product;
+mod stable_swap;
 mod twap;
 mod types;
 
@@ -53,20 +57,31 @@ pub use crate::weights::WeightInfo;
 #[frame_support::pallet]
 pub mod pallet {
 	use crate::{
+		concentrated_liquidity::{ConcentratedLiquidity, PositionOf},
 		dual_asset_constant_product::DualAssetConstantProduct,
-		twap::{update_price_cumulative_state, update_twap_state},
-		types::{PriceCumulative, TimeWeightedAveragePrice},
+		liquidity_bootstrapping::LiquidityBootstrapping,
+		multi_asset_constant_product::{MaxMultiAssetPoolAssets, MultiAssetConstantProduct},
+		stable_swap::StableSwap,
+		twap::{
+			record_observation, twap_over_window, update_price_cumulative_state, update_twap_state,
+		},
+		types::{ConcentratedLiquidityState, PriceCumulative, TickInfo, TimeWeightedAveragePrice},
 		WeightInfo,
 	};
 	use codec::FullCodec;
 	use composable_support::{
 		collections::vec::bounded::{bi_bounded_vec::BiBoundedVecOutOfBounds, BiBoundedVec},
-		math::safe::{SafeArithmetic, SafeSub},
+		math::safe::{safe_multiply_by_rational, SafeAdd, SafeArithmetic, SafeMul, SafeSub},
 	};
 	use composable_traits::{
 		assets::CreateAsset,
 		defi::{CurrencyPair, Rate},
-		dex::{Amm, BasicPoolInfo, Fee, PriceAggregate},
+		dex::{
+			Amm, BasicPoolInfo, ConcentratedLiquidityPoolInfo, Fee, LiquidityBootstrappingPoolInfo,
+			LiquiditySimulation, PriceAggregate, Sale, SaleEndBehaviour, SaleState,
+			StableSwapPoolInfo, SwapSimulation,
+		},
+		fnft::FinancialNft,
 	};
 	use core::fmt::Debug;
 	use frame_support::{
@@ -74,6 +89,7 @@ pub mod pallet {
 		storage::with_transaction,
 		traits::{
 			fungibles::{Inspect, Mutate, Transfer},
+			tokens::nonfungibles::{Create as NonFungiblesCreate, Mutate as NonFungiblesMutate},
 			Time,
 		},
 		transactional, BoundedBTreeMap, PalletId, RuntimeDebug,
@@ -92,44 +108,127 @@ pub mod pallet {
 		assets::AssetInfo,
 		currency::BalanceLike,
 		dex::{AssetAmount, FeeConfig, SwapResult},
+		time::DurationSeconds,
 	};
 	use frame_system::{ensure_signed, pallet_prelude::OriginFor};
 	use sp_runtime::{
-		traits::{AccountIdConversion, Convert, One, Zero},
-		ArithmeticError, FixedPointNumber, Permill, TransactionOutcome,
+		traits::{AccountIdConversion, Convert, One, Saturating, Zero},
+		ArithmeticError, FixedPointNumber, Permill, SaturatedConversion, TransactionOutcome,
 	};
 	use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 	#[derive(
 		RuntimeDebug, Encode, Decode, MaxEncodedLen, CloneNoBound, PartialEq, Eq, TypeInfo,
 	)]
-	pub enum PoolInitConfiguration<AccountId: Clone, AssetId: Clone> {
+	pub enum PoolInitConfiguration<AccountId: Clone, AssetId: Clone, BlockNumber: Clone> {
 		DualAssetConstantProduct {
 			owner: AccountId,
 			assets_weights: Vec<(AssetId, Permill)>,
 			/// trading fee
 			fee: Permill,
 		},
+		StableSwap {
+			owner: AccountId,
+			assets: Vec<AssetId>,
+			/// amplification coefficient of the StableSwap invariant
+			amplification_coefficient: u16,
+			/// trading fee
+			fee: Permill,
+		},
+		MultiAssetConstantProduct {
+			owner: AccountId,
+			assets_weights: Vec<(AssetId, Permill)>,
+			/// trading fee
+			fee: Permill,
+		},
+		LiquidityBootstrapping {
+			owner: AccountId,
+			/// `base` is the project token being sold, `quote` the collateral
+			pair: CurrencyPair<AssetId>,
+			/// weight schedule of the sale
+			sale: Sale<BlockNumber>,
+			/// behaviour of the pool once the sale ended
+			sale_end_behaviour: SaleEndBehaviour,
+			/// trading fee
+			fee: Permill,
+		},
+		ConcentratedLiquidity {
+			owner: AccountId,
+			/// prices are expressed in `quote` per `base`
+			pair: CurrencyPair<AssetId>,
+			/// position bounds must be multiples of this value
+			tick_spacing: u32,
+			/// tick of the initial price of the pool
+			initial_tick: i32,
+			/// trading fee
+			fee: Permill,
+		},
 	}
 
 	#[derive(
 		RuntimeDebug, Encode, Decode, MaxEncodedLen, CloneNoBound, PartialEqNoBound, Eq, TypeInfo,
 	)]
-	pub enum PoolConfiguration<AccountId: Clone + PartialEq + Debug, AssetId: Clone + Ord + Debug> {
+	pub enum PoolConfiguration<
+		AccountId: Clone + PartialEq + Debug,
+		AssetId: Clone + Ord + Debug,
+		BlockNumber: Clone + PartialEq + Debug,
+	> {
 		DualAssetConstantProduct(BasicPoolInfo<AccountId, AssetId, ConstU32<2>>),
+		StableSwap(StableSwapPoolInfo<AccountId, AssetId, ConstU32<2>>),
+		MultiAssetConstantProduct(BasicPoolInfo<AccountId, AssetId, MaxMultiAssetPoolAssets>),
+		LiquidityBootstrapping(LiquidityBootstrappingPoolInfo<AccountId, AssetId, BlockNumber>),
+		ConcentratedLiquidity(ConcentratedLiquidityPoolInfo<AccountId, AssetId>),
+	}
+
+	impl<
+			AccountId: Clone + PartialEq + Debug,
+			AssetId: Clone + Ord + Debug,
+			BlockNumber: Clone + PartialEq + Debug,
+		> PoolConfiguration<AccountId, AssetId, BlockNumber>
+	{
+		/// Owner of the pool, receiving its owner fees.
+		pub fn owner(&self) -> &AccountId {
+			match self {
+				PoolConfiguration::DualAssetConstantProduct(info) => &info.owner,
+				PoolConfiguration::StableSwap(info) => &info.pool_info.owner,
+				PoolConfiguration::MultiAssetConstantProduct(info) => &info.owner,
+				PoolConfiguration::LiquidityBootstrapping(info) => &info.owner,
+				PoolConfiguration::ConcentratedLiquidity(info) => &info.owner,
+			}
+		}
+
+		/// Fee configuration of the pool, whatever its type.
+		pub fn fee_config_mut(&mut self) -> &mut FeeConfig {
+			match self {
+				PoolConfiguration::DualAssetConstantProduct(info) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo { pool_info: info, .. }) =>
+					&mut info.fee_config,
+				PoolConfiguration::MultiAssetConstantProduct(info) => &mut info.fee_config,
+				PoolConfiguration::LiquidityBootstrapping(info) => &mut info.fee_config,
+				PoolConfiguration::ConcentratedLiquidity(info) => &mut info.fee_config,
+			}
+		}
 	}
 
 	pub(crate) type AssetIdOf<T> = <T as Config>::AssetId;
 	pub(crate) type BalanceOf<T> = <T as Config>::Balance;
 	pub(crate) type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-	type PoolConfigurationOf<T> =
-		PoolConfiguration<<T as frame_system::Config>::AccountId, <T as Config>::AssetId>;
-	pub(crate) type PoolInitConfigurationOf<T> =
-		PoolInitConfiguration<<T as frame_system::Config>::AccountId, <T as Config>::AssetId>;
+	type PoolConfigurationOf<T> = PoolConfiguration<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::AssetId,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+	pub(crate) type PoolInitConfigurationOf<T> = PoolInitConfiguration<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::AssetId,
+		<T as frame_system::Config>::BlockNumber,
+	>;
 	pub(crate) type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
 	pub(crate) type TWAPStateOf<T> = TimeWeightedAveragePrice<MomentOf<T>, <T as Config>::Balance>;
 	pub(crate) type PriceCumulativeStateOf<T> =
 		PriceCumulative<MomentOf<T>, <T as Config>::Balance>;
+	pub(crate) type TWAPObservationsOf<T> =
+		BoundedVec<PriceCumulativeStateOf<T>, <T as Config>::MaxTWAPObservations>;
 
 	// TODO (vim): Introduce a  new event for "buy" operation as swap is different.
 	#[pallet::event]
@@ -146,6 +245,18 @@ pub mod pallet {
 			/// LP token ID
 			lp_token_id: T::AssetId,
 		},
+		/// Liquidity bootstrapping pool with specified id `T::PoolId` was created successfully by
+		/// `T::AccountId`.
+		LiquidityBootstrappingPoolCreated {
+			/// Id of newly created pool.
+			pool_id: T::PoolId,
+			/// Owner of the pool.
+			owner: T::AccountId,
+			/// Project and collateral assets of the pool.
+			pair: CurrencyPair<T::AssetId>,
+			/// Weight schedule of the sale.
+			sale: Sale<T::BlockNumber>,
+		},
 		/// Liquidity added into the pool `T::PoolId`.
 		LiquidityAdded {
 			/// Account id who added liquidity.
@@ -157,7 +268,8 @@ pub mod pallet {
 			/// Amount of minted lp.
 			minted_lp: T::Balance,
 		},
-		/// Liquidity removed from pool `T::PoolId` by `T::AccountId` in balanced way.
+		/// Liquidity removed from pool `T::PoolId` by `T::AccountId`, either in a balanced way or
+		/// for a single asset.
 		LiquidityRemoved {
 			/// Account id who removed liquidity.
 			who: T::AccountId,
@@ -166,6 +278,81 @@ pub mod pallet {
 			/// Amount(s) of asset(s) removed from the pool.
 			asset_amounts: BTreeMap<T::AssetId, T::Balance>,
 		},
+		/// Position of a concentrated liquidity pool created, represented by the financial NFT
+		/// `fnft_instance_id` of `fnft_collection_id`.
+		PositionCreated {
+			/// Account id who created the position.
+			who: T::AccountId,
+			/// Pool id of the position.
+			pool_id: T::PoolId,
+			/// Collection of the position financial NFT.
+			fnft_collection_id: T::AssetId,
+			/// Instance of the position financial NFT.
+			fnft_instance_id: T::FinancialNftInstanceId,
+			/// Lower bound of the price range of the position.
+			lower_tick: i32,
+			/// Upper bound of the price range of the position.
+			upper_tick: i32,
+			/// Liquidity of the position.
+			liquidity: u128,
+			/// Amounts of assets deposited.
+			asset_amounts: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Liquidity removed from a position of a concentrated liquidity pool.
+		PositionLiquidityRemoved {
+			/// Account id who removed liquidity.
+			who: T::AccountId,
+			/// Pool id of the position.
+			pool_id: T::PoolId,
+			/// Collection of the position financial NFT.
+			fnft_collection_id: T::AssetId,
+			/// Instance of the position financial NFT.
+			fnft_instance_id: T::FinancialNftInstanceId,
+			/// Liquidity removed from the position.
+			liquidity: u128,
+			/// Amounts of assets withdrawn, including the accrued fees if the position was closed.
+			asset_amounts: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Fees accrued by a position of a concentrated liquidity pool collected.
+		PositionFeesCollected {
+			/// Account id who collected the fees.
+			who: T::AccountId,
+			/// Pool id of the position.
+			pool_id: T::PoolId,
+			/// Collection of the position financial NFT.
+			fnft_collection_id: T::AssetId,
+			/// Instance of the position financial NFT.
+			fnft_instance_id: T::FinancialNftInstanceId,
+			/// Amounts of fees collected.
+			fees: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Fee configuration of a pool updated.
+		FeeConfigUpdated {
+			/// Pool id of the updated pool.
+			pool_id: T::PoolId,
+			/// New fee configuration of the pool.
+			fee_config: FeeConfig,
+		},
+		/// Owner fees accrued by a pool claimed by its owner.
+		OwnerFeesClaimed {
+			/// Pool id of the pool.
+			pool_id: T::PoolId,
+			/// Owner of the pool.
+			owner: T::AccountId,
+			/// Account receiving the fees.
+			beneficiary: T::AccountId,
+			/// Amounts of fees claimed.
+			fees: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Protocol fees accrued by a pool claimed.
+		ProtocolFeesClaimed {
+			/// Pool id of the pool.
+			pool_id: T::PoolId,
+			/// Account receiving the fees.
+			beneficiary: T::AccountId,
+			/// Amounts of fees claimed.
+			fees: BTreeMap<T::AssetId, T::Balance>,
+		},
 		/// Token exchange happened.
 		Swapped {
 			/// Pool id on which exchange done.
@@ -231,6 +418,24 @@ pub mod pallet {
 		/// Cannot buy an asset with itself.
 		CannotBuyAssetWithItself,
 		IncorrectPoolConfig,
+		/// Tick out of bounds, or not a multiple of the tick spacing of the pool.
+		InvalidTick,
+		InvalidTickSpacing,
+		/// The liquidity of a position must be at least `MinPositionLiquidity`, unless it is
+		/// closed.
+		PositionLiquidityTooLow,
+		PositionNotFound,
+		/// Only the owner of the position financial NFT can interact with the position.
+		NotPositionOwner,
+		/// The pool has not accrued any fees to claim.
+		NoFeesToClaim,
+		/// TWAP is not enabled for the pool.
+		TwapNotEnabled,
+		/// The TWAP window must be greater than zero.
+		InvalidTwapWindow,
+		/// The recorded TWAP observations of the pool don't go back as far as the requested
+		/// window.
+		TwapWindowNotCovered,
 	}
 
 	#[pallet::config]
@@ -290,16 +495,49 @@ pub mod pallet {
 		/// Required origin to enable TWAP on pool.
 		type EnableTwapOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
-		/// Time provider.
+		/// Required origin to update the fee configuration of a pool and to claim its protocol
+		/// fees.
+		type ManageFeesOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Time provider. Its moments are assumed to be milliseconds, like the ones of
+		/// `pallet_timestamp`, as TWAP windows are given in seconds.
 		type Time: Time;
 
 		/// The interval between TWAP computations.
 		#[pallet::constant]
 		type TWAPInterval: Get<MomentOf<Self>>;
 
+		/// The minimum interval between two recorded TWAP observations of a pool, in milliseconds.
+		#[pallet::constant]
+		type TWAPObservationInterval: Get<MomentOf<Self>>;
+
+		/// The maximum number of TWAP observations kept per pool, the oldest ones being
+		/// overwritten first.
+		#[pallet::constant]
+		type MaxTWAPObservations: Get<u32>;
+
 		type WeightInfo: WeightInfo;
 
 		type LPTokenExistentialDeposit: Get<Self::Balance>;
+
+		/// Financial NFTs representing the positions of concentrated liquidity pools.
+		type FinancialNft: NonFungiblesMutate<AccountIdOf<Self>>
+			+ NonFungiblesCreate<
+				AccountIdOf<Self>,
+				CollectionId = Self::AssetId,
+				ItemId = Self::FinancialNftInstanceId,
+			> + FinancialNft<
+				AccountIdOf<Self>,
+				CollectionId = Self::AssetId,
+				ItemId = Self::FinancialNftInstanceId,
+			>;
+
+		type FinancialNftInstanceId: Parameter + Member + Copy + Ord + MaxEncodedLen;
+
+		/// Minimum liquidity of a position of a concentrated liquidity pool, so that pools can't
+		/// be filled with dust positions that swaps have to cross.
+		#[pallet::constant]
+		type MinPositionLiquidity: Get<u128>;
 	}
 
 	#[pallet::pallet]
@@ -332,10 +570,60 @@ pub mod pallet {
 	pub type PriceCumulativeState<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::PoolId, PriceCumulativeStateOf<T>, OptionQuery>;
 
+	/// Ring buffer of the price cumulative observations of the TWAP enabled pools, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn twap_observations)]
+	pub type TWAPObservations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, TWAPObservationsOf<T>, OptionQuery>;
+
+	/// Swap state of the concentrated liquidity pools.
+	#[pallet::storage]
+	pub type ConcentratedLiquidityStates<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, ConcentratedLiquidityState>;
+
+	/// Ticks of the concentrated liquidity pools used as bounds by at least one position.
+	#[pallet::storage]
+	pub type Ticks<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Twox64Concat, i32, TickInfo>;
+
+	/// Bitmap of the initialized ticks of the concentrated liquidity pools. Ticks are divided by
+	/// the tick spacing of their pool and grouped by words of 128 bits, empty words are not
+	/// stored.
+	#[pallet::storage]
+	pub type TickBitmap<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Twox64Concat, i32, u128>;
+
+	/// Positions of the concentrated liquidity pools, by financial NFT collection and instance.
+	#[pallet::storage]
+	pub type Positions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::FinancialNftInstanceId,
+		PositionOf<T>,
+	>;
+
+	/// Owner fees accrued by a pool and not claimed yet, by asset. They are held by the pool
+	/// account but are not part of its reserves.
+	#[pallet::storage]
+	pub type OwnerFees<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Blake2_128Concat, T::AssetId, T::Balance>;
+
+	/// Protocol fees accrued by a pool and not claimed yet, by asset. They are held by the pool
+	/// account but are not part of its reserves.
+	#[pallet::storage]
+	pub type ProtocolFees<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Blake2_128Concat, T::AssetId, T::Balance>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)] // Allow for `ValueQuery` because of nonce
 	pub type LPTNonce<T: Config> = StorageValue<_, u64, ValueQuery, Nonce<OneInit, SafeIncrement>>;
 
+	/// Simulated trades and liquidity operations measure their price impact against the same
+	/// operation this many times smaller.
+	const PRICE_IMPACT_REFERENCE_DIVISOR: u128 = 1_000;
+
 	pub(crate) enum PriceRatio {
 		Swapped,
 		NotSwapped,
@@ -479,14 +767,138 @@ pub mod pallet {
 					quote_twap: rate_quote,
 				},
 			);
-			PriceCumulativeState::<T>::insert(
+			let price_cumulative = PriceCumulative {
+				timestamp: current_timestamp,
+				base_price_cumulative,
+				quote_price_cumulative,
+			};
+			record_observation::<T>(pool_id, &price_cumulative);
+			PriceCumulativeState::<T>::insert(pool_id, price_cumulative);
+			Ok(())
+		}
+
+		/// Remove liquidity from the given weighted pool, receiving a single asset of the pool.
+		///
+		/// `min_receive` is the asset to receive and the minimum amount of it to be received.
+		///
+		/// Emits `LiquidityRemoved` event when successful.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::remove_liquidity_single_asset())]
+		pub fn remove_liquidity_single_asset(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			lp_amount: T::Balance,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_remove_liquidity_single_asset(&who, pool_id, lp_amount, min_receive)?;
+			Ok(())
+		}
+
+		/// Create a position in the given concentrated liquidity pool, providing liquidity between
+		/// `lower_tick` and `upper_tick`. As much liquidity as `max_amounts` allows is provided,
+		/// the position is represented by a financial NFT minted to the caller.
+		///
+		/// Emits `PositionCreated` event when successful.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::create_position())]
+		#[allow(clippy::too_many_arguments)]
+		pub fn create_position(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			lower_tick: i32,
+			upper_tick: i32,
+			max_amounts: BTreeMap<T::AssetId, T::Balance>,
+			min_liquidity: u128,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_create_position(
+				&who,
 				pool_id,
-				PriceCumulative {
-					timestamp: current_timestamp,
-					base_price_cumulative,
-					quote_price_cumulative,
+				lower_tick,
+				upper_tick,
+				max_amounts,
+				min_liquidity,
+				keep_alive,
+			)?;
+			Ok(())
+		}
+
+		/// Remove `liquidity` from the position represented by the given financial NFT. Removing
+		/// all of the liquidity closes the position, paying out the fees it accrued and burning
+		/// the financial NFT.
+		///
+		/// Emits `PositionLiquidityRemoved` event when successful.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::remove_position_liquidity())]
+		pub fn remove_position_liquidity(
+			origin: OriginFor<T>,
+			fnft_collection_id: T::AssetId,
+			fnft_instance_id: T::FinancialNftInstanceId,
+			liquidity: u128,
+			min_receive: BTreeMap<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_remove_position_liquidity(
+				&who,
+				fnft_collection_id,
+				fnft_instance_id,
+				liquidity,
+				min_receive,
+			)?;
+			Ok(())
+		}
+
+		/// Collect the fees accrued by the position represented by the given financial NFT.
+		///
+		/// Emits `PositionFeesCollected` event when successful.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::collect_position_fees())]
+		pub fn collect_position_fees(
+			origin: OriginFor<T>,
+			fnft_collection_id: T::AssetId,
+			fnft_instance_id: T::FinancialNftInstanceId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_collect_position_fees(&who, fnft_collection_id, fnft_instance_id)?;
+			Ok(())
+		}
+
+		/// Update the fee configuration of a pool. Only applies to the trades happening after the
+		/// update, the fees accrued so far are left untouched.
+		///
+		/// Emits `FeeConfigUpdated` event when successful.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::update_fee_config())]
+		pub fn update_fee_config(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			fee_config: FeeConfig,
+		) -> DispatchResult {
+			T::ManageFeesOrigin::ensure_origin(origin)?;
+			Self::do_update_fee_config(pool_id, fee_config)
+		}
+
+		/// Claim the fees accrued by a pool, sending them to `beneficiary`. The protocol fees are
+		/// claimed when called by `ManageFeesOrigin`, the owner fees when called by the owner of
+		/// the pool.
+		///
+		/// Emits `ProtocolFeesClaimed` or `OwnerFeesClaimed` event when successful.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::claim_fees())]
+		pub fn claim_fees(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			beneficiary: T::AccountId,
+		) -> DispatchResult {
+			match T::ManageFeesOrigin::try_origin(origin) {
+				Ok(_) => Self::do_claim_protocol_fees(pool_id, &beneficiary)?,
+				Err(origin) => {
+					let who = ensure_signed(origin)?;
+					Self::do_claim_owner_fees(&who, pool_id, &beneficiary)?
 				},
-			);
+			};
 			Ok(())
 		}
 	}
@@ -519,6 +931,9 @@ pub mod pallet {
 				);
 				if result.is_ok() {
 					weight = weight.saturating_add(Weight::from_ref_time(1));
+					if let Some(price_cumulative) = PriceCumulativeState::<T>::get(pool_id) {
+						record_observation::<T>(pool_id, &price_cumulative);
+					}
 					if let Some(updated_twap) = TWAPState::<T>::get(pool_id) {
 						#[allow(deprecated)]
 						if let Ok(assets) = Self::pool_ordered_pair(pool_id) {
@@ -559,13 +974,102 @@ pub mod pallet {
 						assets_weights.clone(),
 						lp_token_id,
 					)?;
-					(owner, pool_id, assets_weights, lp_token)
+					(owner, pool_id, assets_weights.into_inner(), lp_token)
+				},
+				PoolInitConfiguration::MultiAssetConstantProduct { owner, fee, assets_weights } => {
+					let assets_weights: BTreeMap<T::AssetId, Permill> =
+						assets_weights.into_iter().collect();
+					let assets_weights: BoundedBTreeMap<
+						T::AssetId,
+						Permill,
+						MaxMultiAssetPoolAssets,
+					> = assets_weights.try_into().map_err(|_| Error::<T>::IncorrectPoolConfig)?;
+					let (pool_id, lp_token) = MultiAssetConstantProduct::<T>::do_create_pool(
+						&owner,
+						FeeConfig::default_from(fee),
+						assets_weights.clone(),
+						lp_token_id,
+					)?;
+					(owner, pool_id, assets_weights.into_inner(), lp_token)
+				},
+				PoolInitConfiguration::StableSwap {
+					owner,
+					assets,
+					amplification_coefficient,
+					fee,
+				} => {
+					// StableSwap pools don't have weights, each asset gets an equal share
+					let assets_weights: BTreeMap<T::AssetId, Permill> = assets
+						.iter()
+						.map(|asset_id| {
+							(*asset_id, Permill::from_rational(1_u32, assets.len() as u32))
+						})
+						.collect();
+					let assets_weights: BoundedBTreeMap<T::AssetId, Permill, ConstU32<2>> =
+						assets_weights.try_into().map_err(|_| Error::<T>::IncorrectPoolConfig)?;
+					let (pool_id, lp_token) = StableSwap::<T>::do_create_pool(
+						&owner,
+						FeeConfig::default_from(fee),
+						assets_weights.clone(),
+						amplification_coefficient,
+						lp_token_id,
+					)?;
+					(owner, pool_id, assets_weights.into_inner(), lp_token)
+				},
+				PoolInitConfiguration::LiquidityBootstrapping {
+					owner,
+					pair,
+					sale,
+					sale_end_behaviour,
+					fee,
+				} => {
+					// there are no LP tokens for liquidity bootstrapping pools
+					ensure!(lp_token_id.is_none(), Error::<T>::NoLpTokenForLbp);
+					let pool_id = LiquidityBootstrapping::<T>::do_create_pool(
+						&owner,
+						pair,
+						sale,
+						sale_end_behaviour,
+						FeeConfig::default_from(fee),
+					)?;
+					Self::deposit_event(Event::<T>::LiquidityBootstrappingPoolCreated {
+						pool_id,
+						owner,
+						pair,
+						sale,
+					});
+					return Ok(pool_id)
+				},
+				PoolInitConfiguration::ConcentratedLiquidity {
+					owner,
+					pair,
+					tick_spacing,
+					initial_tick,
+					fee,
+				} => {
+					// `lp_token_id` is used as the collection of the position financial NFTs
+					let (pool_id, position_collection_id) =
+						ConcentratedLiquidity::<T>::do_create_pool(
+							&owner,
+							pair,
+							tick_spacing,
+							initial_tick,
+							FeeConfig::default_from(fee),
+							lp_token_id,
+						)?;
+					let half = Permill::from_percent(50);
+					(
+						owner,
+						pool_id,
+						BTreeMap::from([(pair.base, half), (pair.quote, half)]),
+						position_collection_id,
+					)
 				},
 			};
 			Self::deposit_event(Event::<T>::PoolCreated {
 				owner,
 				pool_id,
-				asset_weights: assets_weights.into_inner(),
+				asset_weights: assets_weights,
 				lp_token_id: lp_token,
 			});
 			Ok(pool_id)
@@ -592,8 +1096,28 @@ pub mod pallet {
 				PriceRatio::NotSwapped => pair,
 				PriceRatio::Swapped => pair.swap(),
 			};
-			let pool_base_asset_under_management = T::Assets::balance(pair.base, &pool_account);
-			let pool_quote_asset_under_management = T::Assets::balance(pair.quote, &pool_account);
+			// the price of these pools isn't the plain ratio of their balances
+			match Self::get_pool(pool_id)? {
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return match price_ratio {
+						PriceRatio::NotSwapped =>
+							ConcentratedLiquidity::<T>::inverse_price(pool_id),
+						PriceRatio::Swapped => ConcentratedLiquidity::<T>::price(pool_id),
+					},
+				PoolConfiguration::StableSwap(info) =>
+					return StableSwap::<T>::spot_price(&info, &pool_account, pair.base, pair.quote),
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					return LiquidityBootstrapping::<T>::spot_price(
+						&info,
+						&pool_account,
+						pair.base,
+						pair.quote,
+					),
+				PoolConfiguration::DualAssetConstantProduct(_) |
+				PoolConfiguration::MultiAssetConstantProduct(_) => {},
+			}
+			let pool_base_asset_under_management = Self::pool_balance(pair.base, &pool_account);
+			let pool_quote_asset_under_management = Self::pool_balance(pair.quote, &pool_account);
 
 			ensure!(
 				pool_base_asset_under_management > Zero::zero(),
@@ -611,7 +1135,164 @@ pub mod pallet {
 			.ok_or(ArithmeticError::Overflow)?)
 		}
 
+		/// Computes the outcome of swapping `in_asset` for `out_asset_id` in the given pool,
+		/// without updating its state.
+		pub(crate) fn get_exchange_value(
+			pool_id: T::PoolId,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			out_asset_id: T::AssetId,
+			apply_fees: bool,
+		) -> Result<
+			(
+				AssetAmount<T::AssetId, T::Balance>,
+				AssetAmount<T::AssetId, T::Balance>,
+				Fee<T::AssetId, T::Balance>,
+			),
+			DispatchError,
+		> {
+			let pool = Self::get_pool(pool_id)?;
+			let pool_account = Self::account_id(&pool_id);
+			Ok(match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						in_asset,
+						out_asset_id,
+						apply_fees,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::get_exchange_value(
+					&info,
+					&pool_account,
+					in_asset,
+					out_asset_id,
+					apply_fees,
+				)?,
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						in_asset,
+						out_asset_id,
+						apply_fees,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						in_asset,
+						out_asset_id,
+						apply_fees,
+					)?,
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					ConcentratedLiquidity::<T>::get_exchange_value(
+						pool_id,
+						&info,
+						in_asset,
+						out_asset_id,
+						apply_fees,
+					)?,
+			})
+		}
+
+		/// Computes the amount of `in_asset_id` to send to the given pool to buy `out_asset`. The
+		/// state of concentrated liquidity pools is updated, assets are not transferred.
+		fn compute_buy(
+			pool_id: T::PoolId,
+			in_asset_id: T::AssetId,
+			out_asset: AssetAmount<T::AssetId, T::Balance>,
+		) -> Result<
+			(
+				AssetAmount<T::AssetId, T::Balance>,
+				AssetAmount<T::AssetId, T::Balance>,
+				Fee<T::AssetId, T::Balance>,
+			),
+			DispatchError,
+		> {
+			let pool = Self::get_pool(pool_id)?;
+			let pool_account = Self::account_id(&pool_id);
+			Ok(match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::do_buy(
+						&info,
+						&pool_account,
+						out_asset,
+						in_asset_id,
+						true,
+					)?,
+				PoolConfiguration::StableSwap(info) =>
+					StableSwap::<T>::do_buy(&info, &pool_account, out_asset, in_asset_id, true)?,
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::do_buy(
+						&info,
+						&pool_account,
+						out_asset,
+						in_asset_id,
+						true,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) => {
+					LiquidityBootstrapping::<T>::ensure_trading_allowed(&info)?;
+					LiquidityBootstrapping::<T>::do_buy(
+						&info,
+						&pool_account,
+						out_asset,
+						in_asset_id,
+						true,
+					)?
+				},
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					ConcentratedLiquidity::<T>::do_buy(pool_id, &info, out_asset, in_asset_id)?,
+			})
+		}
+
+		/// Completes a simulated swap or buy with its price impact, measured against a trade
+		/// `PRICE_IMPACT_REFERENCE_DIVISOR` times smaller.
+		fn swap_simulation(
+			pool_id: T::PoolId,
+			amount_in: AssetAmount<T::AssetId, T::Balance>,
+			amount_out: AssetAmount<T::AssetId, T::Balance>,
+			fee: Fee<T::AssetId, T::Balance>,
+		) -> Result<SwapSimulation<T::AssetId, T::Balance>, DispatchError> {
+			ensure!(
+				Self::pool_balance(amount_out.asset_id, &Self::account_id(&pool_id)) >
+					amount_out.amount,
+				Error::<T>::NotEnoughLiquidity
+			);
+			let (in_without_fee, out_without_fee) = if fee.asset_id == amount_in.asset_id {
+				(amount_in.amount.safe_sub(&fee.fee)?, amount_out.amount)
+			} else {
+				(amount_in.amount, amount_out.amount.safe_add(&fee.fee)?)
+			};
+			let (in_without_fee, out_without_fee): (u128, u128) =
+				(in_without_fee.into(), out_without_fee.into());
+			let reference_in = in_without_fee / PRICE_IMPACT_REFERENCE_DIVISOR;
+			let price_impact = if reference_in.is_zero() {
+				Permill::zero()
+			} else {
+				let (reference_out, _, _) = Self::get_exchange_value(
+					pool_id,
+					AssetAmount::new(amount_in.asset_id, T::Convert::convert(reference_in)),
+					amount_out.asset_id,
+					false,
+				)?;
+				let reference_out: u128 = reference_out.amount.into();
+				if reference_out.is_zero() {
+					Permill::zero()
+				} else {
+					// value of the amount out in the asset in, at the reference price
+					let out_value =
+						safe_multiply_by_rational(out_without_fee, reference_in, reference_out)?;
+					Permill::one().saturating_sub(Permill::from_rational(out_value, in_without_fee))
+				}
+			};
+			Ok(SwapSimulation { amount_in, amount_out, fee, price_impact })
+		}
+
 		fn update_twap(pool_id: T::PoolId) -> Result<(), DispatchError> {
+			// TWAP can only be enabled for pools with exactly two assets
+			if !PriceCumulativeState::<T>::contains_key(pool_id) {
+				return Ok(())
+			}
 			#[allow(deprecated)]
 			let currency_pair = Self::pool_ordered_pair(pool_id)?; // update price cumulatives
 			let (base_price_cumulative, quote_price_cumulative) =
@@ -621,6 +1302,9 @@ pub mod pallet {
 						update_price_cumulative_state::<T>(pool_id, prev_price_cumulative)
 					},
 				)?;
+			if let Some(price_cumulative) = PriceCumulativeState::<T>::get(pool_id) {
+				record_observation::<T>(pool_id, &price_cumulative);
+			}
 			if base_price_cumulative != T::Balance::zero() &&
 				quote_price_cumulative != T::Balance::zero()
 			{
@@ -651,55 +1335,481 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[transactional]
-		fn disburse_fees(
-			who: &T::AccountId,
-			_: &T::PoolId,
-			owner: &T::AccountId,
-			fees: &Fee<T::AssetId, T::Balance>,
-		) -> Result<(), DispatchError> {
+		/// Accrues the owner and protocol parts of `fees` charged by a pool. They stay in the pool
+		/// account until they are claimed.
+		fn accrue_fees(pool_id: T::PoolId, fees: &Fee<T::AssetId, T::Balance>) -> DispatchResult {
+			let accrue = |accrued: &mut Option<T::Balance>, fee: T::Balance| -> DispatchResult {
+				*accrued = Some(
+					accrued
+						.unwrap_or_else(Zero::zero)
+						.checked_add(&fee)
+						.ok_or(ArithmeticError::Overflow)?,
+				);
+				Ok(())
+			};
 			if !fees.owner_fee.is_zero() {
-				T::Assets::transfer(fees.asset_id, who, owner, fees.owner_fee, false)?;
+				OwnerFees::<T>::try_mutate(pool_id, fees.asset_id, |accrued| {
+					accrue(accrued, fees.owner_fee)
+				})?;
+			}
+			if !fees.protocol_fee.is_zero() {
+				ProtocolFees::<T>::try_mutate(pool_id, fees.asset_id, |accrued| {
+					accrue(accrued, fees.protocol_fee)
+				})?;
 			}
-			// TODO: Enable fee disbursal for release 3
-			// if !fees.protocol_fee.is_zero() {
-			// 	T::ProtocolStaking::transfer_reward(
-			// 		who,
-			// 		&T::PbloAssetId::get(),
-			// 		fees.asset_id,
-			// 		fees.protocol_fee,
-			// 		false,
-			// 	)?;
-			// }
 			Ok(())
 		}
 
-		#[deprecated(
-			note = "This is a temporary function for refactoring/migration purposes. Use `Amm::assets` instead."
-		)]
-		#[allow(deprecated)]
-		fn pool_ordered_pair(
-			pool_id: T::PoolId,
-		) -> Result<CurrencyPair<T::AssetId>, DispatchError> {
-			let pool = Self::get_pool(pool_id)?;
-			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let assets = info.assets_weights.keys().copied().collect::<Vec<_>>();
-					ensure!(assets.len() == 2, Error::<T>::PairMismatch);
-					let base_asset = assets.get(0).ok_or(Error::<T>::PairMismatch)?;
-					let quote_asset = assets.get(1).ok_or(Error::<T>::PairMismatch)?;
-					Ok(CurrencyPair::new(*base_asset, *quote_asset))
-				},
+		/// Fee charged on a deposit. It is left in the pool, entirely for the liquidity providers.
+		pub(crate) fn deposit_fee(
+			asset_id: T::AssetId,
+			fee: u128,
+		) -> Option<Fee<T::AssetId, T::Balance>> {
+			(!fee.is_zero()).then(|| {
+				let fee = T::Convert::convert(fee);
+				Fee { fee, lp_fee: fee, ..Fee::zero(asset_id) }
+			})
+		}
+
+		/// Balance of `asset_id` held by a pool account, excluding the owner and protocol fees the
+		/// pool accrued. This is the balance available to the liquidity providers of the pool.
+		pub(crate) fn pool_balance(
+			asset_id: T::AssetId,
+			pool_account: &T::AccountId,
+		) -> T::Balance {
+			let balance = T::Assets::balance(asset_id, pool_account);
+			match PalletId::try_from_sub_account::<T::PoolId>(pool_account) {
+				Some((pallet_id, pool_id)) if pallet_id == T::PalletId::get() => balance
+					.saturating_sub(
+						OwnerFees::<T>::get(pool_id, asset_id).unwrap_or_else(Zero::zero),
+					)
+					.saturating_sub(
+						ProtocolFees::<T>::get(pool_id, asset_id).unwrap_or_else(Zero::zero),
+					),
+				_ => balance,
 			}
 		}
-	}
 
-	impl<T: Config> FlatFeeConverter for Pallet<T> {
-		type AssetId = T::AssetId;
-		type Balance = T::Balance;
+		/// Update the fee configuration of a pool. The owner and protocol fee rates may not add up
+		/// to more than 100%.
+		pub fn do_update_fee_config(pool_id: T::PoolId, fee_config: FeeConfig) -> DispatchResult {
+			ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+			// decoded rates are not capped at 100%
+			let one = Permill::one().deconstruct();
+			let (owner_fee_rate, protocol_fee_rate) = (
+				fee_config.owner_fee_rate.deconstruct(),
+				fee_config.protocol_fee_rate.deconstruct(),
+			);
+			ensure!(
+				owner_fee_rate <= one &&
+					protocol_fee_rate <= one &&
+					owner_fee_rate.saturating_add(protocol_fee_rate) <= one,
+				Error::<T>::InvalidFees
+			);
+			Pools::<T>::try_mutate(pool_id, |pool| -> DispatchResult {
+				let pool = pool.as_mut().ok_or(Error::<T>::PoolNotFound)?;
+				*pool.fee_config_mut() = fee_config;
+				Ok(())
+			})?;
 
-		fn get_flat_fee(
-			asset_id: Self::AssetId,
+			Self::deposit_event(Event::<T>::FeeConfigUpdated { pool_id, fee_config });
+			Ok(())
+		}
+
+		/// Claim the owner fees accrued by a pool, sending them to `beneficiary`.
+		#[transactional]
+		pub fn do_claim_owner_fees(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			beneficiary: &T::AccountId,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			let pool = Self::get_pool(pool_id)?;
+			ensure!(pool.owner() == who, Error::<T>::MustBeOwner);
+
+			let fees = OwnerFees::<T>::drain_prefix(pool_id).collect::<BTreeMap<_, _>>();
+			Self::pay_out_fees(pool_id, &fees, beneficiary)?;
+
+			Self::deposit_event(Event::<T>::OwnerFeesClaimed {
+				pool_id,
+				owner: who.clone(),
+				beneficiary: beneficiary.clone(),
+				fees: fees.clone(),
+			});
+			Ok(fees)
+		}
+
+		/// Claim the protocol fees accrued by a pool, sending them to `beneficiary`.
+		#[transactional]
+		pub fn do_claim_protocol_fees(
+			pool_id: T::PoolId,
+			beneficiary: &T::AccountId,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			Self::get_pool(pool_id)?;
+
+			let fees = ProtocolFees::<T>::drain_prefix(pool_id).collect::<BTreeMap<_, _>>();
+			Self::pay_out_fees(pool_id, &fees, beneficiary)?;
+
+			Self::deposit_event(Event::<T>::ProtocolFeesClaimed {
+				pool_id,
+				beneficiary: beneficiary.clone(),
+				fees: fees.clone(),
+			});
+			Ok(fees)
+		}
+
+		fn pay_out_fees(
+			pool_id: T::PoolId,
+			fees: &BTreeMap<T::AssetId, T::Balance>,
+			beneficiary: &T::AccountId,
+		) -> DispatchResult {
+			ensure!(!fees.is_empty(), Error::<T>::NoFeesToClaim);
+			let pool_account = Self::account_id(&pool_id);
+			for (asset_id, amount) in fees {
+				T::Assets::transfer(
+					*asset_id,
+					&pool_account,
+					beneficiary,
+					*amount,
+					false, // pool account doesn't need to be kept alive
+				)?;
+			}
+			Ok(())
+		}
+
+		/// Adds liquidity to a pool, returning the LP tokens minted, the amounts deposited and the
+		/// fee charged on the deposit.
+		#[transactional]
+		pub(crate) fn do_add_liquidity(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			assets: BTreeMap<T::AssetId, T::Balance>,
+			min_mint_amount: T::Balance,
+			keep_alive: bool,
+		) -> Result<
+			(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+			DispatchError,
+		> {
+			let pool = Self::get_pool(pool_id)?;
+			let pool_account = Self::account_id(&pool_id);
+			let (minted_lp, actual_deposited_amounts, fee) = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::add_liquidity(
+						who,
+						info,
+						pool_account,
+						Self::bounded_deposits(assets)?,
+						min_mint_amount,
+						keep_alive,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::add_liquidity(
+					who,
+					info,
+					pool_account,
+					Self::bounded_deposits(assets)?,
+					min_mint_amount,
+					keep_alive,
+				)?,
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::add_liquidity(
+						who,
+						info,
+						pool_account,
+						Self::bounded_deposits(assets)?,
+						min_mint_amount,
+						keep_alive,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::add_liquidity(
+						who,
+						info,
+						pool_account,
+						Self::bounded_deposits(assets)?,
+						min_mint_amount,
+						keep_alive,
+					)?,
+				// liquidity is provided through positions
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::UnsupportedOperation.into()),
+			};
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::LiquidityAdded {
+				who: who.clone(),
+				pool_id,
+				asset_amounts: actual_deposited_amounts.clone(),
+				minted_lp,
+			});
+			Ok((minted_lp, actual_deposited_amounts, fee))
+		}
+
+		/// Computes the outcome of adding liquidity to a pool like `do_add_liquidity`, without
+		/// transferring or minting anything.
+		pub(crate) fn compute_add_liquidity(
+			pool_id: T::PoolId,
+			assets: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<
+			(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+			DispatchError,
+		> {
+			let pool = Self::get_pool(pool_id)?;
+			let pool_account = Self::account_id(&pool_id);
+			match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::compute_add_liquidity(
+						&info,
+						&pool_account,
+						Self::bounded_deposits(assets)?,
+					),
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::compute_add_liquidity(
+					&info,
+					&pool_account,
+					Self::bounded_deposits(assets)?,
+				),
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::compute_add_liquidity(
+						&info,
+						&pool_account,
+						Self::bounded_deposits(assets)?,
+					),
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::compute_add_liquidity(
+						&info,
+						Self::bounded_deposits(assets)?,
+					),
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					Err(Error::<T>::UnsupportedOperation.into()),
+			}
+		}
+
+		/// Computes the assets redeemed by removing liquidity from a pool like `remove_liquidity`,
+		/// without transferring or burning anything.
+		pub(crate) fn compute_remove_liquidity(
+			pool_id: T::PoolId,
+			lp_amount: T::Balance,
+			mut min_receive: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			let redeemed_assets = match Self::get_pool(pool_id)? {
+				// the whole pool is withdrawn once the sale ended
+				PoolConfiguration::LiquidityBootstrapping(info) => {
+					ensure!(
+						LiquidityBootstrapping::<T>::sale_state(&info) == SaleState::Ended,
+						Error::<T>::InvalidSaleState
+					);
+					let pool_account = Self::account_id(&pool_id);
+					[info.pair.base, info.pair.quote]
+						.into_iter()
+						.map(|id| (id, Self::pool_balance(id, &pool_account)))
+						.collect::<BTreeMap<_, _>>()
+				},
+				_ => <Self as Amm>::redeemable_assets_for_lp_tokens(pool_id, lp_amount)?,
+			};
+
+			for (id, amount) in &redeemed_assets {
+				if let Some(min_amount) = min_receive.remove(id) {
+					ensure!(*amount >= min_amount, Error::<T>::CannotRespectMinimumRequested);
+				}
+			}
+			ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+			Ok(redeemed_assets)
+		}
+
+		/// Remove liquidity from a weighted pool in exchange for a single asset of the pool.
+		#[transactional]
+		pub fn do_remove_liquidity_single_asset(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			lp_amount: T::Balance,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+		) -> Result<AssetAmount<T::AssetId, T::Balance>, DispatchError> {
+			let pool = Self::get_pool(pool_id)?;
+			let pool_account = Self::account_id(&pool_id);
+			let (redeemed, fee) = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::remove_liquidity_single_asset(
+						who,
+						info,
+						pool_account,
+						lp_amount,
+						min_receive,
+					)?,
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::remove_liquidity_single_asset(
+						who,
+						info,
+						pool_account,
+						lp_amount,
+						min_receive,
+					)?,
+				PoolConfiguration::StableSwap(_) |
+				PoolConfiguration::LiquidityBootstrapping(_) |
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::UnsupportedOperation.into()),
+			};
+			// the redeemed asset is partly swapped for the others, it is charged like a swap
+			Self::accrue_fees(pool_id, &fee)?;
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::LiquidityRemoved {
+				pool_id,
+				who: who.clone(),
+				asset_amounts: BTreeMap::from([(redeemed.asset_id, redeemed.amount)]),
+			});
+
+			Ok(redeemed)
+		}
+
+		/// Create a position in a concentrated liquidity pool, returning the id of the financial
+		/// NFT representing it.
+		#[transactional]
+		#[allow(clippy::too_many_arguments)]
+		pub fn do_create_position(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			lower_tick: i32,
+			upper_tick: i32,
+			max_amounts: BTreeMap<T::AssetId, T::Balance>,
+			min_liquidity: u128,
+			keep_alive: bool,
+		) -> Result<(T::AssetId, T::FinancialNftInstanceId), DispatchError> {
+			let pool = match Self::get_pool(pool_id)? {
+				PoolConfiguration::ConcentratedLiquidity(info) => info,
+				_ => return Err(Error::<T>::UnsupportedOperation.into()),
+			};
+			let (fnft_instance_id, liquidity, asset_amounts) =
+				ConcentratedLiquidity::<T>::create_position(
+					who,
+					pool_id,
+					&pool,
+					lower_tick,
+					upper_tick,
+					max_amounts,
+					min_liquidity,
+					keep_alive,
+				)?;
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::PositionCreated {
+				who: who.clone(),
+				pool_id,
+				fnft_collection_id: pool.position_collection_id,
+				fnft_instance_id,
+				lower_tick,
+				upper_tick,
+				liquidity,
+				asset_amounts,
+			});
+
+			Ok((pool.position_collection_id, fnft_instance_id))
+		}
+
+		/// Remove liquidity from a position of a concentrated liquidity pool.
+		#[transactional]
+		pub fn do_remove_position_liquidity(
+			who: &T::AccountId,
+			fnft_collection_id: T::AssetId,
+			fnft_instance_id: T::FinancialNftInstanceId,
+			liquidity: u128,
+			min_receive: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			let (pool_id, asset_amounts) = ConcentratedLiquidity::<T>::remove_position_liquidity(
+				who,
+				fnft_collection_id,
+				fnft_instance_id,
+				liquidity,
+				min_receive,
+			)?;
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::PositionLiquidityRemoved {
+				who: who.clone(),
+				pool_id,
+				fnft_collection_id,
+				fnft_instance_id,
+				liquidity,
+				asset_amounts: asset_amounts.clone(),
+			});
+
+			Ok(asset_amounts)
+		}
+
+		/// Collect the fees accrued by a position of a concentrated liquidity pool.
+		#[transactional]
+		pub fn do_collect_position_fees(
+			who: &T::AccountId,
+			fnft_collection_id: T::AssetId,
+			fnft_instance_id: T::FinancialNftInstanceId,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			let (pool_id, fees) = ConcentratedLiquidity::<T>::collect_fees(
+				who,
+				fnft_collection_id,
+				fnft_instance_id,
+			)?;
+
+			Self::deposit_event(Event::<T>::PositionFeesCollected {
+				who: who.clone(),
+				pool_id,
+				fnft_collection_id,
+				fnft_instance_id,
+				fees: fees.clone(),
+			});
+
+			Ok(fees)
+		}
+
+		fn bounded_deposits<const U: usize>(
+			assets: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, U>, Error<T>> {
+			BiBoundedVec::from_vec(
+				assets
+					.into_iter()
+					.map(|(asset_id, amount)| AssetAmount { asset_id, amount })
+					.collect(),
+			)
+			.map_err(|err| match err {
+				BiBoundedVecOutOfBounds::LowerBoundError { .. } =>
+					Error::<T>::MustDepositMinimumOneAsset,
+				BiBoundedVecOutOfBounds::UpperBoundError { .. } => Error::<T>::UnsupportedOperation,
+			})
+		}
+
+		fn bounded_min_receive<S: Get<u32>>(
+			min_receive: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<BoundedBTreeMap<T::AssetId, T::Balance, S>, Error<T>> {
+			min_receive.try_into().map_err(|_| Error::<T>::UnsupportedOperation)
+		}
+
+		#[deprecated(
+			note = "This is a temporary function for refactoring/migration purposes. Use `Amm::assets` instead."
+		)]
+		#[allow(deprecated)]
+		fn pool_ordered_pair(
+			pool_id: T::PoolId,
+		) -> Result<CurrencyPair<T::AssetId>, DispatchError> {
+			let pool = Self::get_pool(pool_id)?;
+			match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo { pool_info: info, .. }) => {
+					let assets = info.assets_weights.keys().copied().collect::<Vec<_>>();
+					ensure!(assets.len() == 2, Error::<T>::PairMismatch);
+					let base_asset = assets.get(0).ok_or(Error::<T>::PairMismatch)?;
+					let quote_asset = assets.get(1).ok_or(Error::<T>::PairMismatch)?;
+					Ok(CurrencyPair::new(*base_asset, *quote_asset))
+				},
+				PoolConfiguration::MultiAssetConstantProduct(_) =>
+					Err(Error::<T>::PairMismatch.into()),
+				PoolConfiguration::LiquidityBootstrapping(info) => Ok(info.pair),
+				PoolConfiguration::ConcentratedLiquidity(info) => Ok(info.pair),
+			}
+		}
+	}
+
+	impl<T: Config> FlatFeeConverter for Pallet<T> {
+		type AssetId = T::AssetId;
+		type Balance = T::Balance;
+
+		fn get_flat_fee(
+			asset_id: Self::AssetId,
 			fee_asset_id: Self::AssetId,
 			fee_asset_amount: Self::Balance,
 		) -> Option<u128> {
@@ -716,6 +1826,11 @@ pub mod pallet {
 						lp_token: _,
 						fee_config: _,
 						assets_weights,
+					}) |
+					PoolConfiguration::StableSwap(StableSwapPoolInfo {
+						pool_info:
+							BasicPoolInfo { owner: _, lp_token: _, fee_config: _, assets_weights },
+						amplification_coefficient: _,
 					}) => {
 						if assets_weights.get(&fee_asset_id).is_some() &&
 							assets_weights.get(&asset_id).is_some()
@@ -723,6 +1838,25 @@ pub mod pallet {
 							conversion_pool_id = Some(pool_id);
 						}
 					},
+					PoolConfiguration::MultiAssetConstantProduct(BasicPoolInfo {
+						assets_weights,
+						..
+					}) =>
+						if assets_weights.get(&fee_asset_id).is_some() &&
+							assets_weights.get(&asset_id).is_some()
+						{
+							conversion_pool_id = Some(pool_id);
+						},
+					// prices of liquidity bootstrapping pools move with the sale, they are not
+					// suitable for fee conversion
+					PoolConfiguration::LiquidityBootstrapping(_) => {},
+					PoolConfiguration::ConcentratedLiquidity(ConcentratedLiquidityPoolInfo {
+						pair,
+						..
+					}) =>
+						if pair.contains(fee_asset_id) && pair.contains(asset_id) {
+							conversion_pool_id = Some(pool_id);
+						},
 				};
 			}
 			if let Some(pool_id) = conversion_pool_id {
@@ -753,8 +1887,17 @@ pub mod pallet {
 		) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError> {
 			let pool = Self::get_pool(pool_id)?;
 			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) =>
+				PoolConfiguration::DualAssetConstantProduct(info) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo { pool_info: info, .. }) =>
 					Ok(info.assets_weights.into_inner()),
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					Ok(info.assets_weights.into_inner()),
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					Ok(LiquidityBootstrapping::<T>::current_weights(&info)),
+				PoolConfiguration::ConcentratedLiquidity(info) => {
+					let half = Permill::from_percent(50);
+					Ok(BTreeMap::from([(info.pair.base, half), (info.pair.quote, half)]))
+				},
 			}
 		}
 
@@ -762,25 +1905,22 @@ pub mod pallet {
 			let pool = Self::get_pool(pool_id)?;
 			match pool {
 				PoolConfiguration::DualAssetConstantProduct(info) => Ok(info.lp_token),
+				PoolConfiguration::StableSwap(info) => Ok(info.pool_info.lp_token),
+				PoolConfiguration::MultiAssetConstantProduct(info) => Ok(info.lp_token),
+				PoolConfiguration::LiquidityBootstrapping(_) =>
+					Err(Error::<T>::NoLpTokenForLbp.into()),
+				// positions are represented by financial NFTs
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					Err(Error::<T>::UnsupportedOperation.into()),
 			}
 		}
 
 		fn simulate_add_liquidity(
-			who: &Self::AccountId,
+			_who: &Self::AccountId,
 			pool_id: Self::PoolId,
 			amounts: BTreeMap<Self::AssetId, Self::Balance>,
 		) -> Result<Self::Balance, DispatchError> {
-			with_transaction(|| {
-				// since we're simulating this, we want to immediately roll it back no matter what
-				// the outcome is
-				TransactionOutcome::Rollback(<Self as Amm>::add_liquidity(
-					who,
-					pool_id,
-					amounts,
-					Zero::zero(),
-					false,
-				))
-			})
+			Self::compute_add_liquidity(pool_id, amounts).map(|(minted_lp, _, _)| minted_lp)
 		}
 
 		fn redeemable_assets_for_lp_tokens(
@@ -789,47 +1929,52 @@ pub mod pallet {
 		) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
-			#[allow(deprecated)]
-			match pool {
+			let (lp_token, assets_weights) = match pool {
 				PoolConfiguration::DualAssetConstantProduct(BasicPoolInfo {
 					lp_token,
 					assets_weights,
 					..
-				}) => {
-					let assets = assets_weights
-						.into_iter()
-						.map(|(id, w)| {
-							compute_redeemed_for_lp(
-								T::Convert::convert(T::Assets::total_issuance(lp_token)),
-								T::Convert::convert(lp_amount),
-								T::Convert::convert(T::Assets::balance(id, &pool_account)),
-								w,
-							)
-							.map(|res| (id, T::Convert::convert(res)))
-						})
-						.collect::<Result<BTreeMap<_, _>, _>>()?;
+				}) => (lp_token, assets_weights.into_inner()),
+				PoolConfiguration::MultiAssetConstantProduct(BasicPoolInfo {
+					lp_token,
+					assets_weights,
+					..
+				}) => (lp_token, assets_weights.into_inner()),
+				PoolConfiguration::StableSwap(StableSwapPoolInfo {
+					pool_info: BasicPoolInfo { lp_token, assets_weights, .. },
+					..
+				}) => (lp_token, assets_weights.into_inner()),
+				PoolConfiguration::LiquidityBootstrapping(_) =>
+					return Err(Error::<T>::NoLpTokenForLbp.into()),
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::UnsupportedOperation.into()),
+			};
 
-					Ok(assets)
-				},
-			}
+			// withdrawals are always balanced, whatever the weights of the pool, see
+			// `remove_liquidity`
+			let assets = assets_weights
+				.into_iter()
+				.map(|(id, _)| {
+					compute_redeemed_for_lp(
+						T::Convert::convert(T::Assets::total_issuance(lp_token)),
+						T::Convert::convert(lp_amount),
+						T::Convert::convert(Self::pool_balance(id, &pool_account)),
+						Permill::one(),
+					)
+					.map(|res| (id, T::Convert::convert(res)))
+				})
+				.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+			Ok(assets)
 		}
 
 		fn simulate_remove_liquidity(
-			who: &Self::AccountId,
+			_who: &Self::AccountId,
 			pool_id: Self::PoolId,
 			lp_amount: Self::Balance,
 			min_amounts: BTreeMap<Self::AssetId, Self::Balance>,
 		) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
-			with_transaction(|| {
-				// since we're simulating this, we want to immediately roll it back no matter what
-				// the outcome is
-				TransactionOutcome::Rollback(<Self as Amm>::remove_liquidity(
-					who,
-					pool_id,
-					lp_amount,
-					min_amounts,
-				))
-			})
+			Self::compute_remove_liquidity(pool_id, lp_amount, min_amounts)
 		}
 
 		fn spot_price(
@@ -838,29 +1983,36 @@ pub mod pallet {
 			quote_asset_id: Self::AssetId,
 			calculate_with_fees: bool,
 		) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
-			let pool = Self::get_pool(pool_id)?;
-			let pool_account = Self::account_id(&pool_id);
-			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let (amount_out, amount_in, fee) =
-						DualAssetConstantProduct::<T>::get_exchange_value(
-							&info,
-							&pool_account,
-							base_asset,
-							quote_asset_id,
-							calculate_with_fees,
-						)?;
+			let (amount_out, amount_in, fee) =
+				Self::get_exchange_value(pool_id, base_asset, quote_asset_id, calculate_with_fees)?;
 
-					Ok(SwapResult {
-						value: amount_out,
-						// fee = initial_amount - post_fee_amount
-						fee: AssetAmount::new(amount_in.asset_id, fee.fee),
-					})
-				},
-			}
+			Ok(SwapResult {
+				value: amount_out,
+				// fee = initial_amount - post_fee_amount
+				fee: AssetAmount::new(amount_in.asset_id, fee.fee),
+			})
+		}
+
+		fn twap_over_window(
+			pool_id: Self::PoolId,
+			base_asset_id: Self::AssetId,
+			quote_asset_id: Self::AssetId,
+			window: DurationSeconds,
+		) -> Result<Rate, DispatchError> {
+			#[allow(deprecated)]
+			let pair = Self::pool_ordered_pair(pool_id)?;
+			let price_ratio = if (pair.base, pair.quote) == (base_asset_id, quote_asset_id) {
+				PriceRatio::NotSwapped
+			} else if (pair.quote, pair.base) == (base_asset_id, quote_asset_id) {
+				PriceRatio::Swapped
+			} else {
+				return Err(Error::<T>::AssetNotFound.into())
+			};
+			// `T::Time` is expected to measure milliseconds
+			let window = window.saturating_mul(1_000).saturated_into::<MomentOf<T>>();
+			twap_over_window::<T>(pool_id, price_ratio, window)
 		}
 
-		#[transactional]
 		fn add_liquidity(
 			who: &Self::AccountId,
 			pool_id: Self::PoolId,
@@ -868,40 +2020,8 @@ pub mod pallet {
 			min_mint_amount: Self::Balance,
 			keep_alive: bool,
 		) -> Result<Self::Balance, DispatchError> {
-			let pool = Self::get_pool(pool_id)?;
-			let pool_account = Self::account_id(&pool_id);
-			let (minted_lp, actual_deposited_amounts) = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) =>
-					DualAssetConstantProduct::<T>::add_liquidity(
-						who,
-						info,
-						pool_account,
-						BiBoundedVec::from_vec(
-							assets
-								.clone()
-								.into_iter()
-								.map(|(asset_id, amount)| AssetAmount { asset_id, amount })
-								.collect(),
-						)
-						.map_err(|err| match err {
-							BiBoundedVecOutOfBounds::LowerBoundError { .. } =>
-								Error::<T>::MustDepositMinimumOneAsset,
-							BiBoundedVecOutOfBounds::UpperBoundError { .. } =>
-								Error::<T>::UnsupportedOperation,
-						})?,
-						min_mint_amount,
-						keep_alive,
-					)?,
-			};
-
-			Self::update_twap(pool_id)?;
-			Self::deposit_event(Event::<T>::LiquidityAdded {
-				who: who.clone(),
-				pool_id,
-				asset_amounts: actual_deposited_amounts,
-				minted_lp,
-			});
-			Ok(minted_lp)
+			Self::do_add_liquidity(who, pool_id, assets, min_mint_amount, keep_alive)
+				.map(|(minted_lp, _, _)| minted_lp)
 		}
 
 		#[transactional]
@@ -914,26 +2034,48 @@ pub mod pallet {
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
 			let res = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let res = DualAssetConstantProduct::<T>::remove_liquidity(
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::remove_liquidity(
 						who,
 						info,
 						pool_account,
 						lp_amount,
-						min_receive.try_into().map_err(|_| Error::<T>::UnsupportedOperation)?,
-					)?;
-
-					Self::update_twap(pool_id)?;
-					Self::deposit_event(Event::<T>::LiquidityRemoved {
-						pool_id,
-						who: who.clone(),
-						asset_amounts: res.clone(),
-					});
-
-					res
-				},
+						Self::bounded_min_receive(min_receive)?,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::remove_liquidity(
+					who,
+					info,
+					pool_account,
+					lp_amount,
+					Self::bounded_min_receive(min_receive)?,
+				)?,
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::remove_liquidity(
+						who,
+						info,
+						pool_account,
+						lp_amount,
+						Self::bounded_min_receive(min_receive)?,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::remove_liquidity(
+						who,
+						info,
+						pool_account,
+						lp_amount,
+						Self::bounded_min_receive(min_receive)?,
+					)?,
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::UnsupportedOperation.into()),
 			};
 
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::LiquidityRemoved {
+				pool_id,
+				who: who.clone(),
+				asset_amounts: res.clone(),
+			});
+
 			Ok(res)
 		}
 
@@ -949,46 +2091,70 @@ pub mod pallet {
 
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
-			let (amount_out, amount_in, fee, _owner) = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let (amount_out, amount_in, fee) =
-						DualAssetConstantProduct::<T>::get_exchange_value(
-							&info,
-							&pool_account,
-							in_asset,
-							min_receive.asset_id,
-							true,
-						)?;
-
-					ensure!(
-						amount_out.amount >= min_receive.amount,
-						Error::<T>::CannotRespectMinimumRequested
-					);
-					ensure!(
-						T::Assets::balance(amount_out.asset_id, &pool_account) > amount_out.amount,
-						Error::<T>::NotEnoughLiquidity
-					);
-
-					// Transfer the in asset amount to the pool
-					T::Assets::transfer(
-						amount_in.asset_id,
-						who,
+			let (amount_out, amount_in, fee) = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::get_exchange_value(
+						&info,
 						&pool_account,
-						amount_in.amount,
-						keep_alive,
-					)?;
-					// Transfer swapped value to user
-					T::Assets::transfer(
-						amount_out.asset_id,
+						in_asset,
+						min_receive.asset_id,
+						true,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::get_exchange_value(
+					&info,
+					&pool_account,
+					in_asset,
+					min_receive.asset_id,
+					true,
+				)?,
+				PoolConfiguration::MultiAssetConstantProduct(info) =>
+					MultiAssetConstantProduct::<T>::get_exchange_value(
+						&info,
 						&pool_account,
-						who,
-						amount_out.amount,
-						false,
-					)?;
-
-					(amount_out, amount_in, fee, info.owner)
+						in_asset,
+						min_receive.asset_id,
+						true,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) => {
+					LiquidityBootstrapping::<T>::ensure_trading_allowed(&info)?;
+					LiquidityBootstrapping::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						in_asset,
+						min_receive.asset_id,
+						true,
+					)?
 				},
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					ConcentratedLiquidity::<T>::do_swap(
+						pool_id,
+						&info,
+						in_asset,
+						min_receive.asset_id,
+					)?,
 			};
+
+			ensure!(
+				amount_out.amount >= min_receive.amount,
+				Error::<T>::CannotRespectMinimumRequested
+			);
+			ensure!(
+				Self::pool_balance(amount_out.asset_id, &pool_account) > amount_out.amount,
+				Error::<T>::NotEnoughLiquidity
+			);
+
+			// Transfer the in asset amount to the pool
+			T::Assets::transfer(
+				amount_in.asset_id,
+				who,
+				&pool_account,
+				amount_in.amount,
+				keep_alive,
+			)?;
+			// Transfer swapped value to user
+			T::Assets::transfer(amount_out.asset_id, &pool_account, who, amount_out.amount, false)?;
+			Self::accrue_fees(pool_id, &fee)?;
+
 			Self::update_twap(pool_id)?;
 			Self::deposit_event(Event::<T>::Swapped {
 				pool_id,
@@ -1017,36 +2183,20 @@ pub mod pallet {
 		) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
 			ensure!(in_asset_id != out_asset.asset_id, Error::<T>::CannotBuyAssetWithItself);
 
-			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
-			let (amount_sent, _owner, fees) = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					// NOTE: lp_fees includes owner_fees.
-					let (amount_out, amount_sent, fees) = DualAssetConstantProduct::<T>::do_buy(
-						&info,
-						&pool_account,
-						out_asset,
-						in_asset_id,
-						true,
-					)?;
-
-					T::Assets::transfer(
-						amount_sent.asset_id,
-						who,
-						&pool_account,
-						amount_sent.amount,
-						keep_alive,
-					)?;
-					T::Assets::transfer(
-						amount_out.asset_id,
-						&pool_account,
-						who,
-						amount_out.amount,
-						false,
-					)?;
-					(amount_sent, info.owner, fees)
-				},
-			};
+			// NOTE: lp_fees includes owner_fees.
+			let (amount_out, amount_sent, fees) =
+				Self::compute_buy(pool_id, in_asset_id, out_asset)?;
+
+			T::Assets::transfer(
+				amount_sent.asset_id,
+				who,
+				&pool_account,
+				amount_sent.amount,
+				keep_alive,
+			)?;
+			T::Assets::transfer(amount_out.asset_id, &pool_account, who, amount_out.amount, false)?;
+			Self::accrue_fees(pool_id, &fees)?;
 			Self::update_twap(pool_id)?;
 			// TODO (vim): Emit a Buy event: Release 3
 			Self::deposit_event(Event::<T>::Swapped {
@@ -1101,4 +2251,120 @@ pub mod pallet {
 			spot_price: spot_price.value.amount,
 		})
 	}
+
+	/// Simulate swapping `in_asset` for `out_asset_id` in the given pool, without dispatching the
+	/// swap.
+	pub fn simulate_swap<T: Config>(
+		pool_id: T::PoolId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+	) -> Result<SwapSimulation<T::AssetId, T::Balance>, DispatchError> {
+		ensure!(in_asset.asset_id != out_asset_id, Error::<T>::CannotSwapSameAsset);
+		if let PoolConfiguration::LiquidityBootstrapping(info) = Pallet::<T>::get_pool(pool_id)? {
+			LiquidityBootstrapping::<T>::ensure_trading_allowed(&info)?;
+		}
+		let (amount_out, amount_in, fee) =
+			Pallet::<T>::get_exchange_value(pool_id, in_asset, out_asset_id, true)?;
+		Pallet::<T>::swap_simulation(pool_id, amount_in, amount_out, fee)
+	}
+
+	/// Simulate buying `out_asset` with `in_asset_id` from the given pool, without dispatching the
+	/// buy.
+	pub fn simulate_buy<T: Config>(
+		pool_id: T::PoolId,
+		in_asset_id: T::AssetId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+	) -> Result<SwapSimulation<T::AssetId, T::Balance>, DispatchError> {
+		ensure!(in_asset_id != out_asset.asset_id, Error::<T>::CannotBuyAssetWithItself);
+		// computing a buy updates the state of concentrated liquidity pools
+		let (amount_out, amount_in, fee) = with_transaction(|| {
+			TransactionOutcome::Rollback(Pallet::<T>::compute_buy(pool_id, in_asset_id, out_asset))
+		})?;
+		Pallet::<T>::swap_simulation(pool_id, amount_in, amount_out, fee)
+	}
+
+	/// How much worse `amount` is than `reference_amount` scaled up by
+	/// `PRICE_IMPACT_REFERENCE_DIVISOR`.
+	fn liquidity_price_impact<T: Config>(
+		amount: T::Balance,
+		reference_amount: T::Balance,
+	) -> Result<Permill, DispatchError> {
+		let reference_amount: u128 = reference_amount.into();
+		let reference_amount = reference_amount.safe_mul(&PRICE_IMPACT_REFERENCE_DIVISOR)?;
+		Ok(if reference_amount.is_zero() {
+			Permill::zero()
+		} else {
+			Permill::one().saturating_sub(Permill::from_rational(amount.into(), reference_amount))
+		})
+	}
+
+	/// Simulate adding `amounts` of liquidity to the given pool, computed from the pool math
+	/// without dispatching the deposit, so `who` doesn't need to hold the assets. Its price impact
+	/// is measured against the same deposit `PRICE_IMPACT_REFERENCE_DIVISOR` times smaller, which
+	/// is charged the same fee rate.
+	pub fn simulate_add_liquidity<T: Config>(
+		_who: &T::AccountId,
+		pool_id: T::PoolId,
+		amounts: BTreeMap<T::AssetId, T::Balance>,
+	) -> Result<LiquiditySimulation<T::AssetId, T::Balance>, DispatchError> {
+		let deposit = |amounts| Pallet::<T>::compute_add_liquidity(pool_id, amounts);
+		let (lp_amount, assets, fee) = deposit(amounts)?;
+
+		let reference_amounts = assets
+			.iter()
+			.map(|(asset_id, amount)| {
+				let amount: u128 = (*amount).into();
+				(*asset_id, T::Convert::convert(amount / PRICE_IMPACT_REFERENCE_DIVISOR))
+			})
+			.collect::<BTreeMap<_, _>>();
+		let price_impact = if reference_amounts.values().any(Zero::is_zero) {
+			Permill::zero()
+		} else {
+			let (reference_lp_amount, _, _) = deposit(reference_amounts)?;
+			liquidity_price_impact::<T>(lp_amount, reference_lp_amount)?
+		};
+
+		Ok(LiquiditySimulation { assets, lp_amount, fee, price_impact })
+	}
+
+	/// Simulate removing `lp_amount` of liquidity from the given pool, computed from the pool
+	/// math without dispatching the withdrawal, so `who` doesn't need to hold the LP tokens. Its
+	/// price impact is the worst of the withdrawn assets, measured against the same withdrawal
+	/// `PRICE_IMPACT_REFERENCE_DIVISOR` times smaller.
+	pub fn simulate_remove_liquidity<T: Config>(
+		_who: &T::AccountId,
+		pool_id: T::PoolId,
+		lp_amount: T::Balance,
+		min_amounts: BTreeMap<T::AssetId, T::Balance>,
+	) -> Result<LiquiditySimulation<T::AssetId, T::Balance>, DispatchError> {
+		let withdraw = |lp_amount, min_amounts| {
+			Pallet::<T>::compute_remove_liquidity(pool_id, lp_amount, min_amounts)
+		};
+		let reference_min_amounts = min_amounts
+			.keys()
+			.map(|asset_id| (*asset_id, Zero::zero()))
+			.collect::<BTreeMap<_, _>>();
+		let assets = withdraw(lp_amount, min_amounts)?;
+
+		let reference_lp_amount: u128 = lp_amount.into();
+		let reference_lp_amount = reference_lp_amount / PRICE_IMPACT_REFERENCE_DIVISOR;
+		let price_impact = if reference_lp_amount.is_zero() {
+			Permill::zero()
+		} else {
+			let reference_assets =
+				withdraw(T::Convert::convert(reference_lp_amount), reference_min_amounts)?;
+			assets
+				.iter()
+				.map(|(asset_id, amount)| {
+					liquidity_price_impact::<T>(
+						*amount,
+						reference_assets.get(asset_id).copied().unwrap_or_else(Zero::zero),
+					)
+				})
+				.try_fold(Permill::zero(), |worst, impact| impact.map(|impact| worst.max(impact)))?
+		};
+
+		// balanced withdrawals are not charged
+		Ok(LiquiditySimulation { assets, lp_amount, fee: None, price_impact })
+	}
 }
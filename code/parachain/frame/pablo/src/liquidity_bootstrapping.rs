@@ -0,0 +1,270 @@
+use crate::{Config, Error, Pallet, PoolConfiguration, PoolCount, Pools};
+use composable_maths::dex::constant_product::{compute_in_given_out, compute_out_given_in};
+use composable_support::{collections::vec::bounded::BiBoundedVec, math::safe::SafeAdd};
+use composable_traits::{
+	defi::{CurrencyPair, Rate},
+	dex::{
+		AssetAmount, Fee, FeeConfig, LiquidityBootstrappingPoolInfo, Sale, SaleEndBehaviour,
+		SaleState,
+	},
+};
+use frame_support::{pallet_prelude::*, traits::fungibles::Transfer};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	ArithmeticError, BoundedBTreeMap, FixedPointNumber, Permill,
+};
+use sp_std::collections::btree_map::BTreeMap;
+
+pub(crate) type LiquidityBootstrappingPoolInfoOf<T> = LiquidityBootstrappingPoolInfo<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::AssetId,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
+// Balancer style Liquidity Bootstrapping Pool, a two asset weighted pool where the weights move
+// linearly over the duration of the sale.
+pub(crate) struct LiquidityBootstrapping<T>(PhantomData<T>);
+
+impl<T: Config> LiquidityBootstrapping<T> {
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		pair: CurrencyPair<T::AssetId>,
+		sale: Sale<T::BlockNumber>,
+		sale_end_behaviour: SaleEndBehaviour,
+		fee_config: FeeConfig,
+	) -> Result<T::PoolId, DispatchError> {
+		ensure!(pair.base != pair.quote, Error::<T>::InvalidPair);
+		ensure!(
+			sale.start < sale.end && sale.start >= frame_system::Pallet::<T>::block_number(),
+			Error::<T>::InvalidSaleState
+		);
+		ensure!(
+			!sale.initial_weight.is_zero() &&
+				!sale.final_weight.is_zero() &&
+				sale.initial_weight < Permill::one() &&
+				sale.final_weight < Permill::one(),
+			Error::<T>::WeightsMustBeNonZero
+		);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+
+		let pool_id = PoolCount::<T>::try_mutate(|pool_count| -> Result<_, DispatchError> {
+			let pool_id = *pool_count;
+			Pools::<T>::insert(
+				pool_id,
+				PoolConfiguration::LiquidityBootstrapping(LiquidityBootstrappingPoolInfo {
+					owner: who.clone(),
+					pair,
+					sale,
+					sale_end_behaviour,
+					fee_config,
+				}),
+			);
+			*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+			Ok(pool_id)
+		})?;
+
+		Ok(pool_id)
+	}
+
+	pub(crate) fn sale_state(pool: &LiquidityBootstrappingPoolInfoOf<T>) -> SaleState {
+		pool.sale.state(frame_system::Pallet::<T>::block_number())
+	}
+
+	/// Weights of the pool assets at the current block.
+	pub(crate) fn current_weights(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+	) -> BTreeMap<T::AssetId, Permill> {
+		let (base_weight, quote_weight) =
+			pool.sale.current_weights(frame_system::Pallet::<T>::block_number());
+		BTreeMap::from([(pool.pair.base, base_weight), (pool.pair.quote, quote_weight)])
+	}
+
+	/// Trading is only possible during the sale, or after it if the pool is configured to continue.
+	pub(crate) fn ensure_trading_allowed(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+	) -> Result<(), DispatchError> {
+		match (Self::sale_state(pool), pool.sale_end_behaviour) {
+			(SaleState::Ongoing, _) | (SaleState::Ended, SaleEndBehaviour::Continue) => Ok(()),
+			_ => Err(Error::<T>::InvalidSaleState.into()),
+		}
+	}
+
+	fn get_pool_balances(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+	) -> BTreeMap<T::AssetId, (Permill, u128)> {
+		Self::current_weights(pool)
+			.into_iter()
+			.map(|(asset_id, weight)| {
+				(
+					asset_id,
+					(
+						weight,
+						T::Convert::convert(Pallet::<T>::pool_balance(asset_id, pool_account)),
+					),
+				)
+			})
+			.collect()
+	}
+
+	/// Only the owner can provide liquidity, and only before the sale starts. No LP tokens are
+	/// minted.
+	pub(crate) fn add_liquidity(
+		who: &T::AccountId,
+		pool: LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
+		min_mint_amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		ensure!(*who == pool.owner, Error::<T>::MustBeOwner);
+		let (minted, deposited, fee) = Self::compute_add_liquidity(&pool, assets)?;
+		ensure!(min_mint_amount.is_zero(), Error::<T>::CannotRespectMinimumRequested);
+
+		for (asset_id, amount) in &deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		Ok((minted, deposited, fee))
+	}
+
+	/// Checks that `assets` can be deposited into the pool, without transferring anything.
+	pub(crate) fn compute_add_liquidity(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		ensure!(Self::sale_state(pool) == SaleState::NotStarted, Error::<T>::InvalidSaleState);
+
+		let mut deposited = BTreeMap::new();
+		for AssetAmount { asset_id, amount } in assets {
+			ensure!(pool.pair.contains(asset_id), Error::<T>::AssetNotFound);
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+			deposited.insert(asset_id, amount);
+		}
+
+		Ok((Zero::zero(), deposited, None))
+	}
+
+	/// Withdraws all of the pool's liquidity to the owner once the sale ended. `lp_amount` is
+	/// ignored as there are no LP tokens for these pools.
+	pub(crate) fn remove_liquidity(
+		who: &T::AccountId,
+		pool: LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		_lp_amount: T::Balance,
+		mut min_receive: BoundedBTreeMap<T::AssetId, T::Balance, ConstU32<2>>,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		ensure!(*who == pool.owner, Error::<T>::MustBeOwner);
+		ensure!(Self::sale_state(&pool) == SaleState::Ended, Error::<T>::InvalidSaleState);
+
+		let redeemed_assets = [pool.pair.base, pool.pair.quote]
+			.into_iter()
+			.map(|id| {
+				let amount = Pallet::<T>::pool_balance(id, &pool_account);
+				if let Some(min_amount) = min_receive.remove(&id) {
+					ensure!(amount >= min_amount, Error::<T>::CannotRespectMinimumRequested);
+				}
+				Ok::<_, DispatchError>((id, amount))
+			})
+			.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (id, amount) in &redeemed_assets {
+			T::Assets::transfer(
+				*id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		Ok(redeemed_assets)
+	}
+
+	/// Price of `quote_asset` in terms of `base_asset`, the ratio of the balances weighted by the
+	/// weights at the current block.
+	pub(crate) fn spot_price(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		base_asset: T::AssetId,
+		quote_asset: T::AssetId,
+	) -> Result<Rate, DispatchError> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let (w_base, b_base) = pool_assets.get(&base_asset).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_quote, b_quote) = pool_assets.get(&quote_asset).ok_or(Error::<T>::AssetNotFound)?;
+		ensure!(!b_base.is_zero() && !b_quote.is_zero(), Error::<T>::NotEnoughLiquidity);
+
+		// (b_base / w_base) / (b_quote / w_quote)
+		Rate::checked_from_rational(*b_base, *b_quote)
+			.zip(Rate::checked_from_rational(w_quote.deconstruct(), w_base.deconstruct()))
+			.and_then(|(balance_ratio, weight_ratio)| balance_ratio.checked_mul(&weight_ratio))
+			.ok_or_else(|| ArithmeticError::Overflow.into())
+	}
+
+	pub(crate) fn get_exchange_value(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let a_sent = T::Convert::convert(in_asset.amount);
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_i, b_i) = pool_assets.get(&in_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_o, b_o) = pool_assets.get(&out_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_out_given_in::<_>(*w_i, *w_o, *b_i, *b_o, a_sent, fee)?;
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(amm_pair.value));
+		let a_sent = AssetAmount::new(in_asset.asset_id, in_asset.amount);
+		let fee = pool
+			.fee_config
+			.calculate_fees(in_asset.asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((a_out, a_sent, fee))
+	}
+
+	pub(crate) fn do_buy(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let a_out = T::Convert::convert(out_asset.amount);
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_o, b_o) = pool_assets.get(&out_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_i, b_i) = pool_assets.get(&in_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_in_given_out(*w_i, *w_o, *b_i, *b_o, a_out, fee)?;
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool.fee_config.calculate_fees(in_asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+}
@@ -28,6 +28,7 @@ pub const USDT: CurrencyId = currency::USDT::ID;
 pub const USDC: CurrencyId = 4;
 pub const LP_TOKEN_ID: CurrencyId = 100;
 pub const TWAP_INTERVAL_BLOCKS: Moment = 10;
+pub const MAX_TWAP_OBSERVATIONS: u32 = 10;
 
 pub const MILLISECS_PER_BLOCK: u64 = composable_tests_helpers::test::block::MILLISECS_PER_BLOCK;
 
@@ -158,6 +159,9 @@ parameter_types! {
 	pub MaxInitialWeight: Permill = Permill::from_percent(95);
 	pub MinFinalWeight: Permill = Permill::from_percent(5);
 	pub const TWAPInterval: Moment = MILLISECS_PER_BLOCK * TWAP_INTERVAL_BLOCKS;
+	pub const TWAPObservationInterval: Moment = MILLISECS_PER_BLOCK;
+	pub const MaxTWAPObservations: u32 = MAX_TWAP_OBSERVATIONS;
+	pub const MinPositionLiquidity: u128 = 1_000_000;
 }
 
 parameter_types! {
@@ -239,6 +243,7 @@ impl pallet_staking_rewards::Config for Test {
 	type TreasuryAccount = TreasuryAccountId;
 	type ExistentialDeposits = ExistentialDeposits;
 	type AssetsTransactor = AssetsTransactor;
+	type MaxAutoCompoundsPerBlock = frame_support::traits::ConstU32<2>;
 }
 
 ord_parameter_types! {
@@ -260,10 +265,16 @@ impl pablo::Config for Test {
 		EnsureRoot<AccountId>,                  // for benchmarks
 	>;
 	type EnableTwapOrigin = EnsureRoot<AccountId>;
+	type ManageFeesOrigin = EnsureRoot<AccountId>;
 	type Time = Timestamp;
 	type TWAPInterval = TWAPInterval;
+	type TWAPObservationInterval = TWAPObservationInterval;
+	type MaxTWAPObservations = MaxTWAPObservations;
 	type WeightInfo = ();
 	type LPTokenExistentialDeposit = LPTokenED;
+	type FinancialNft = pablo::mock_fnft::MockFnft;
+	type FinancialNftInstanceId = u64;
+	type MinPositionLiquidity = MinPositionLiquidity;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -1,18 +1,31 @@
+use codec::Encode;
 use composable_traits::fnft::FinancialNft;
 use frame_support::{
 	dispatch::DispatchResult,
+	ensure,
+	storage::unhashed,
 	traits::tokens::nonfungibles::{Create, Inspect, Mutate},
 };
-use sp_runtime::DispatchError;
+use sp_runtime::{DispatchError, TokenError};
 
+/// Minimal financial NFT implementation, storing owners and instance counters directly in the
+/// test externalities so that they are reset with them.
 pub struct MockFnft;
 
+const COLLECTION_PREFIX: &[u8] = b"mock_fnft:collection";
+const OWNER_PREFIX: &[u8] = b"mock_fnft:owner";
+const NEXT_ID_PREFIX: &[u8] = b"mock_fnft:next_id";
+
+fn key<K: Encode>(prefix: &[u8], k: K) -> Vec<u8> {
+	[prefix, &k.encode()].concat()
+}
+
 impl Inspect<u128> for MockFnft {
 	type ItemId = u64;
 	type CollectionId = u128;
 
-	fn owner(_collection: &Self::CollectionId, _item: &Self::ItemId) -> Option<u128> {
-		todo!()
+	fn owner(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<u128> {
+		unhashed::get(&key(OWNER_PREFIX, (collection, item)))
 	}
 }
 
@@ -21,19 +34,44 @@ impl FinancialNft<u128> for MockFnft {
 		todo!()
 	}
 
-	fn get_next_nft_id(_collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError> {
-		todo!()
+	fn get_next_nft_id(collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError> {
+		let key = key(NEXT_ID_PREFIX, collection);
+		let id = unhashed::get_or_default::<u64>(&key);
+		unhashed::put(&key, &(id + 1));
+		Ok(id)
 	}
 }
 
 impl Create<u128> for MockFnft {
 	fn create_collection(
-		_collection: &Self::CollectionId,
+		collection: &Self::CollectionId,
 		_who: &u128,
 		_admin: &u128,
 	) -> DispatchResult {
+		unhashed::put(&key(COLLECTION_PREFIX, collection), &());
 		Ok(())
 	}
 }
 
-impl Mutate<u128> for MockFnft {}
+impl Mutate<u128> for MockFnft {
+	fn mint_into(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		who: &u128,
+	) -> DispatchResult {
+		ensure!(unhashed::exists(&key(COLLECTION_PREFIX, collection)), TokenError::UnknownAsset);
+		ensure!(Self::owner(collection, item).is_none(), TokenError::CannotCreate);
+		unhashed::put(&key(OWNER_PREFIX, (collection, item)), who);
+		Ok(())
+	}
+
+	fn burn(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		_maybe_check_owner: Option<&u128>,
+	) -> DispatchResult {
+		ensure!(Self::owner(collection, item).is_some(), TokenError::UnknownAsset);
+		unhashed::kill(&key(OWNER_PREFIX, (collection, item)));
+		Ok(())
+	}
+}
@@ -0,0 +1,395 @@
+use crate::{
+	create_lpt_asset, AssetIdOf, Config, Error, LPTNonce, Pallet, PoolConfiguration, PoolCount,
+	Pools,
+};
+use composable_maths::dex::{
+	constant_product::{
+		compute_deposit_lp, compute_first_deposit_lp, compute_in_given_out, compute_out_given_in,
+		compute_redeemed_for_lp, compute_single_asset_redeemed_for_lp,
+	},
+	PoolWeightMathExt,
+};
+use composable_support::{
+	abstractions::utils::increment::Increment, collections::vec::bounded::BiBoundedVec,
+	math::safe::SafeAdd,
+};
+use composable_traits::dex::{
+	normalize_asset_deposit_infos_to_min_ratio, AssetAmount, AssetDepositInfo,
+	AssetDepositNormalizationError, BasicPoolInfo, Fee, FeeConfig,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungibles::{Inspect, Mutate, Transfer},
+};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	ArithmeticError, BoundedBTreeMap, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// Maximum number of assets in a multi asset pool.
+pub(crate) const MAX_MULTI_ASSET_POOL_ASSETS: u32 = 8;
+
+pub(crate) type MaxMultiAssetPoolAssets = ConstU32<MAX_MULTI_ASSET_POOL_ASSETS>;
+
+// Balancer V1 Weighted Pool with up to `MAX_MULTI_ASSET_POOL_ASSETS` assets
+pub(crate) struct MultiAssetConstantProduct<T>(PhantomData<T>);
+
+impl<T: Config> MultiAssetConstantProduct<T> {
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		fee_config: FeeConfig,
+		assets_weights: BoundedBTreeMap<T::AssetId, Permill, MaxMultiAssetPoolAssets>,
+		lp_token_id: Option<AssetIdOf<T>>,
+	) -> Result<(T::PoolId, AssetIdOf<T>), DispatchError> {
+		ensure!(assets_weights.len() >= 2, Error::<T>::InvalidPair);
+		ensure!(assets_weights.values().non_zero_weights(), Error::<T>::WeightsMustBeNonZero);
+		ensure!(
+			assets_weights
+				.values()
+				.sum_weights()
+				.map(|total_weight| total_weight.is_one())
+				// If `None`, `sum_weights` overflowed - weights are not normalized
+				.unwrap_or(false),
+			Error::<T>::WeightsMustSumToOne
+		);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+
+		let (pool_id, lp_token) = PoolCount::<T>::try_mutate(
+			|pool_count| -> Result<(T::PoolId, T::AssetId), DispatchError> {
+				let pool_id = *pool_count;
+				let lp_token = match lp_token_id {
+					Some(lp_token) => lp_token,
+					None => create_lpt_asset::<T>(
+						LPTNonce::<T>::increment().expect("Does not exceed u64::MAX"),
+					)?,
+				};
+				Pools::<T>::insert(
+					pool_id,
+					PoolConfiguration::MultiAssetConstantProduct(BasicPoolInfo {
+						owner: who.clone(),
+						assets_weights,
+						lp_token,
+						fee_config,
+					}),
+				);
+				*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+				Ok((pool_id, lp_token))
+			},
+		)?;
+
+		Ok((pool_id, lp_token))
+	}
+
+	/// WARNING! This is not a cheap function to call; it does (at least) one storage read per asset
+	/// in the pool!
+	fn get_pool_balances<S: Get<u32>>(
+		pool: &BasicPoolInfo<T::AccountId, T::AssetId, S>,
+		pool_account: &T::AccountId,
+	) -> BTreeMap<T::AssetId, (Permill, u128)> {
+		pool.assets_weights
+			.iter()
+			.map(|(asset_id, weight)| {
+				(
+					*asset_id,
+					(
+						*weight,
+						T::Convert::convert(Pallet::<T>::pool_balance(*asset_id, pool_account)),
+					),
+				)
+			})
+			.collect::<BTreeMap<_, _>>()
+	}
+
+	pub(crate) fn add_liquidity(
+		who: &T::AccountId,
+		pool: BasicPoolInfo<T::AccountId, T::AssetId, MaxMultiAssetPoolAssets>,
+		pool_account: T::AccountId,
+		assets: BiBoundedVec<
+			AssetAmount<T::AssetId, T::Balance>,
+			1,
+			{ MAX_MULTI_ASSET_POOL_ASSETS as usize },
+		>,
+		min_mint_amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		let (amount_of_lp_token_to_mint, actual_amounts_deposited, fee) =
+			Self::compute_add_liquidity(&pool, &pool_account, assets)?;
+
+		ensure!(
+			amount_of_lp_token_to_mint >= min_mint_amount,
+			Error::<T>::CannotRespectMinimumRequested
+		);
+
+		for (asset_id, amount) in &actual_amounts_deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		T::Assets::mint_into(pool.lp_token, who, amount_of_lp_token_to_mint)?;
+
+		Ok((amount_of_lp_token_to_mint, actual_amounts_deposited, fee))
+	}
+
+	/// Computes the LP tokens minted for depositing `assets` into the pool, the amounts actually
+	/// deposited and the fee charged, without transferring anything.
+	pub(crate) fn compute_add_liquidity(
+		pool: &BasicPoolInfo<T::AccountId, T::AssetId, MaxMultiAssetPoolAssets>,
+		pool_account: &T::AccountId,
+		assets: BiBoundedVec<
+			AssetAmount<T::AssetId, T::Balance>,
+			1,
+			{ MAX_MULTI_ASSET_POOL_ASSETS as usize },
+		>,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		let mut pool_assets = Self::get_pool_balances(pool, pool_account);
+
+		let assets_with_balances = assets.try_mapped(|asset_amount| {
+			if asset_amount.amount.is_zero() {
+				return Err(Error::<T>::InvalidAmount)
+			};
+
+			let (weight, balance) =
+				pool_assets.remove(&asset_amount.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+			Ok(AssetDepositInfo {
+				asset_id: asset_amount.asset_id,
+				deposit_amount: T::Convert::convert(asset_amount.amount),
+				existing_balance: balance,
+				asset_weight: weight,
+			})
+		})?;
+
+		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
+
+		let (amount_of_lp_token_to_mint, deposits, fee): (_, Vec<AssetDepositInfo<T::AssetId>>, _) =
+			if let [single] = assets_with_balances.as_slice() {
+				ensure!(
+					!lp_total_issuance.is_zero(),
+					Error::<T>::InitialDepositMustContainAllAssets
+				);
+
+				let single_deposit = compute_deposit_lp(
+					lp_total_issuance,
+					single.deposit_amount,
+					single.existing_balance,
+					single.asset_weight,
+					pool.fee_config.fee_rate,
+				)?;
+				let fee = Pallet::<T>::deposit_fee(single.asset_id, single_deposit.fee);
+
+				(single_deposit.value, assets_with_balances.into(), fee)
+			} else {
+				// deposits of more than one asset must be balanced over all of the pool's assets
+				ensure!(
+					pool_assets.is_empty(),
+					if lp_total_issuance.is_zero() {
+						Error::<T>::InitialDepositMustContainAllAssets
+					} else {
+						Error::<T>::UnsupportedOperation
+					}
+				);
+
+				if lp_total_issuance.is_zero() {
+					let lp_to_mint = compute_first_deposit_lp(
+						assets_with_balances
+							.iter()
+							.map(|adi| (adi.asset_id, adi.deposit_amount, adi.asset_weight)),
+						Permill::zero(),
+					)?
+					.value;
+
+					(lp_to_mint, assets_with_balances.into(), None)
+				} else {
+					let normalized_deposits = match normalize_asset_deposit_infos_to_min_ratio(
+						assets_with_balances.into(),
+					) {
+						Ok(normalized_assets) => normalized_assets,
+						Err(AssetDepositNormalizationError::ArithmeticOverflow) =>
+							return Err(DispatchError::Arithmetic(ArithmeticError::Overflow)),
+						Err(AssetDepositNormalizationError::NotEnoughAssets) => unreachable!(
+							"at least two assets were provided to the normalization function; qed;"
+						),
+					};
+
+					// since the asset deposits were normalized, the lp_to_mint will be the same for
+					// all asset deposits
+					let asset_to_calculate_with =
+						normalized_deposits.first().expect("at least 2 assets in the vec; qed;");
+
+					// pass 1 as weight since adding liquidity for all assets with normalized
+					// deposits see docs on compute_deposit_lp_ for more information
+					let lp_to_mint = compute_deposit_lp(
+						lp_total_issuance,
+						asset_to_calculate_with.deposit_amount,
+						asset_to_calculate_with.existing_balance,
+						Permill::one(),
+						Zero::zero(),
+					)?
+					.value;
+
+					(lp_to_mint, normalized_deposits, None)
+				}
+			};
+
+		let actual_amounts_deposited = deposits
+			.into_iter()
+			.map(|adi| (adi.asset_id, T::Convert::convert(adi.deposit_amount)))
+			.collect::<BTreeMap<_, T::Balance>>();
+
+		Ok((T::Convert::convert(amount_of_lp_token_to_mint), actual_amounts_deposited, fee))
+	}
+
+	pub(crate) fn remove_liquidity(
+		who: &T::AccountId,
+		pool: BasicPoolInfo<T::AccountId, T::AssetId, MaxMultiAssetPoolAssets>,
+		pool_account: T::AccountId,
+		lp_amount: T::Balance,
+		mut min_receive: BoundedBTreeMap<T::AssetId, T::Balance, MaxMultiAssetPoolAssets>,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
+
+		let redeemed_assets = Self::get_pool_balances(&pool, &pool_account)
+			.into_iter()
+			.map(|(id, (_, balance))| {
+				let redeemed_amount = compute_redeemed_for_lp(
+					lp_total_issuance,
+					T::Convert::convert(lp_amount),
+					balance,
+					Permill::one(),
+				)?;
+
+				if let Some(min_amount) = min_receive.remove(&id) {
+					ensure!(
+						redeemed_amount >= T::Convert::convert(min_amount),
+						Error::<T>::CannotRespectMinimumRequested
+					);
+				}
+
+				Ok::<_, DispatchError>((id, T::Convert::convert(redeemed_amount)))
+			})
+			.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (id, amount) in &redeemed_assets {
+			T::Assets::transfer(
+				*id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		T::Assets::burn_from(pool.lp_token, who, lp_amount)?;
+
+		Ok(redeemed_assets)
+	}
+
+	/// Redeems `lp_amount` for a single asset of a weighted pool.
+	///
+	/// Also used by `DualAssetConstantProduct` pools as they share the same invariant.
+	pub(crate) fn remove_liquidity_single_asset<S: Get<u32>>(
+		who: &T::AccountId,
+		pool: BasicPoolInfo<T::AccountId, T::AssetId, S>,
+		pool_account: T::AccountId,
+		lp_amount: T::Balance,
+		min_receive: AssetAmount<T::AssetId, T::Balance>,
+	) -> Result<(AssetAmount<T::AssetId, T::Balance>, Fee<T::AssetId, T::Balance>), DispatchError> {
+		let pool_assets = Self::get_pool_balances(&pool, &pool_account);
+		let (w_k, b_k) = pool_assets.get(&min_receive.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
+
+		let redeemed = compute_single_asset_redeemed_for_lp(
+			lp_total_issuance,
+			T::Convert::convert(lp_amount),
+			*b_k,
+			*w_k,
+			pool.fee_config.fee_rate,
+		)?;
+
+		let redeemed_amount = T::Convert::convert(redeemed.value);
+		ensure!(redeemed_amount >= min_receive.amount, Error::<T>::CannotRespectMinimumRequested);
+
+		T::Assets::transfer(
+			min_receive.asset_id,
+			&pool_account,
+			who,
+			redeemed_amount,
+			false, // pool account doesn't need to be kept alive
+		)?;
+
+		T::Assets::burn_from(pool.lp_token, who, lp_amount)?;
+
+		let fee = pool
+			.fee_config
+			.calculate_fees(min_receive.asset_id, T::Convert::convert(redeemed.fee));
+
+		Ok((AssetAmount::new(min_receive.asset_id, redeemed_amount), fee))
+	}
+
+	pub(crate) fn get_exchange_value(
+		pool: &BasicPoolInfo<T::AccountId, T::AssetId, MaxMultiAssetPoolAssets>,
+		pool_account: &T::AccountId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let a_sent = T::Convert::convert(in_asset.amount);
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_i, b_i) = pool_assets.get(&in_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_o, b_o) = pool_assets.get(&out_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_out_given_in::<_>(*w_i, *w_o, *b_i, *b_o, a_sent, fee)?;
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(amm_pair.value));
+		let a_sent = AssetAmount::new(in_asset.asset_id, in_asset.amount);
+		let fee = pool
+			.fee_config
+			.calculate_fees(in_asset.asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((a_out, a_sent, fee))
+	}
+
+	pub(crate) fn do_buy(
+		pool: &BasicPoolInfo<T::AccountId, T::AssetId, MaxMultiAssetPoolAssets>,
+		pool_account: &T::AccountId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let a_out = T::Convert::convert(out_asset.amount);
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_o, b_o) = pool_assets.get(&out_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_i, b_i) = pool_assets.get(&in_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_in_given_out(*w_i, *w_o, *b_i, *b_o, a_out, fee)?;
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool.fee_config.calculate_fees(in_asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+}
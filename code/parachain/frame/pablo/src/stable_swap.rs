@@ -0,0 +1,320 @@
+use crate::{
+	create_lpt_asset, AssetIdOf, Config, Error, LPTNonce, Pallet, PoolConfiguration, PoolCount,
+	Pools,
+};
+use composable_maths::dex::{
+	constant_product::compute_redeemed_for_lp,
+	stable_swap::{
+		compute_deposit_lp, compute_first_deposit_lp, compute_in_given_out, compute_out_given_in,
+		compute_spot_price,
+	},
+};
+use composable_support::{
+	abstractions::utils::increment::Increment, collections::vec::bounded::BiBoundedVec,
+	math::safe::SafeAdd,
+};
+use composable_traits::{
+	defi::Rate,
+	dex::{AssetAmount, BasicPoolInfo, Fee, FeeConfig, StableSwapPoolInfo},
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungibles::{Inspect, Mutate, Transfer},
+};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	BoundedBTreeMap, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+// Curve StableSwap Pool
+pub(crate) struct StableSwap<T>(PhantomData<T>);
+
+impl<T: Config> StableSwap<T> {
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		fee_config: FeeConfig,
+		assets_weights: BoundedBTreeMap<T::AssetId, Permill, ConstU32<2>>,
+		amplification_coefficient: u16,
+		lp_token_id: Option<AssetIdOf<T>>,
+	) -> Result<(T::PoolId, AssetIdOf<T>), DispatchError> {
+		ensure!(assets_weights.len() == 2, Error::<T>::InvalidPair);
+		ensure!(amplification_coefficient > 0, Error::<T>::AmpFactorMustBeGreaterThanZero);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+
+		let (pool_id, lp_token) = PoolCount::<T>::try_mutate(
+			|pool_count| -> Result<(T::PoolId, T::AssetId), DispatchError> {
+				let pool_id = *pool_count;
+				let lp_token = match lp_token_id {
+					Some(lp_token) => lp_token,
+					None => create_lpt_asset::<T>(
+						LPTNonce::<T>::increment().expect("Does not exceed u64::MAX"),
+					)?,
+				};
+				Pools::<T>::insert(
+					pool_id,
+					PoolConfiguration::StableSwap(StableSwapPoolInfo {
+						pool_info: BasicPoolInfo {
+							owner: who.clone(),
+							assets_weights,
+							lp_token,
+							fee_config,
+						},
+						amplification_coefficient,
+					}),
+				);
+				*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+				Ok((pool_id, lp_token))
+			},
+		)?;
+
+		Ok((pool_id, lp_token))
+	}
+
+	/// WARNING! This is not a cheap function to call; it does (at least) one storage read per asset
+	/// in the pool!
+	fn get_pool_balances(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+	) -> BTreeMap<T::AssetId, u128> {
+		pool.pool_info
+			.assets_weights
+			.keys()
+			.map(|asset_id| {
+				(*asset_id, T::Convert::convert(Pallet::<T>::pool_balance(*asset_id, pool_account)))
+			})
+			.collect::<BTreeMap<_, _>>()
+	}
+
+	pub(crate) fn add_liquidity(
+		who: &T::AccountId,
+		pool: StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: T::AccountId,
+		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
+		min_mint_amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		let (amount_of_lp_token_to_mint, actual_amounts_deposited, fee) =
+			Self::compute_add_liquidity(&pool, &pool_account, assets)?;
+
+		ensure!(
+			amount_of_lp_token_to_mint >= min_mint_amount,
+			Error::<T>::CannotRespectMinimumRequested
+		);
+
+		for (asset_id, amount) in &actual_amounts_deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		T::Assets::mint_into(pool.pool_info.lp_token, who, amount_of_lp_token_to_mint)?;
+
+		Ok((amount_of_lp_token_to_mint, actual_amounts_deposited, fee))
+	}
+
+	/// Computes the LP tokens minted for depositing `assets` into the pool, the amounts actually
+	/// deposited and the fee charged, without transferring anything.
+	pub(crate) fn compute_add_liquidity(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
+	) -> Result<
+		(T::Balance, BTreeMap<T::AssetId, T::Balance>, Option<Fee<T::AssetId, T::Balance>>),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let amp = u128::from(pool.amplification_coefficient);
+
+		let deposits = assets
+			.into_iter()
+			.map(|asset_amount| -> Result<_, Error<T>> {
+				ensure!(!asset_amount.amount.is_zero(), Error::<T>::InvalidAmount);
+				ensure!(
+					pool_assets.contains_key(&asset_amount.asset_id),
+					Error::<T>::AssetNotFound
+				);
+				Ok((asset_amount.asset_id, T::Convert::convert(asset_amount.amount)))
+			})
+			.collect::<Result<BTreeMap<T::AssetId, u128>, _>>()?;
+
+		let lp_total_issuance =
+			T::Convert::convert(T::Assets::total_issuance(pool.pool_info.lp_token));
+
+		// the fee charged on imbalanced deposits is withheld from the minted LP tokens
+		let (amount_of_lp_token_to_mint, fee) = if lp_total_issuance.is_zero() {
+			ensure!(
+				deposits.len() == pool_assets.len(),
+				Error::<T>::InitialDepositMustContainAllAssets
+			);
+
+			(
+				compute_first_deposit_lp(amp, &deposits.values().copied().collect::<Vec<_>>())?
+					.value,
+				None,
+			)
+		} else {
+			let balances_and_deposits = pool_assets
+				.iter()
+				.map(|(asset_id, balance)| {
+					(*balance, deposits.get(asset_id).copied().unwrap_or_else(Zero::zero))
+				})
+				.collect::<Vec<_>>();
+
+			let deposit = compute_deposit_lp(
+				lp_total_issuance,
+				amp,
+				&balances_and_deposits,
+				pool.pool_info.fee_config.fee_rate,
+			)?;
+			(deposit.value, Pallet::<T>::deposit_fee(pool.pool_info.lp_token, deposit.fee))
+		};
+
+		let actual_amounts_deposited = deposits
+			.into_iter()
+			.map(|(asset_id, amount)| (asset_id, T::Convert::convert(amount)))
+			.collect::<BTreeMap<_, T::Balance>>();
+
+		Ok((T::Convert::convert(amount_of_lp_token_to_mint), actual_amounts_deposited, fee))
+	}
+
+	pub(crate) fn remove_liquidity(
+		who: &T::AccountId,
+		pool: StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: T::AccountId,
+		lp_amount: T::Balance,
+		mut min_receive: BoundedBTreeMap<T::AssetId, T::Balance, ConstU32<2>>,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		let lp_total_issuance =
+			T::Convert::convert(T::Assets::total_issuance(pool.pool_info.lp_token));
+
+		// withdrawals are always balanced, so the share of each asset is the share of LP redeemed
+		let redeemed_assets = Self::get_pool_balances(&pool, &pool_account)
+			.into_iter()
+			.map(|(id, balance)| {
+				let redeemed_amount = compute_redeemed_for_lp(
+					lp_total_issuance,
+					T::Convert::convert(lp_amount),
+					balance,
+					Permill::one(),
+				)?;
+
+				if let Some(min_amount) = min_receive.remove(&id) {
+					ensure!(
+						redeemed_amount >= T::Convert::convert(min_amount),
+						Error::<T>::CannotRespectMinimumRequested
+					);
+				}
+
+				Ok::<_, DispatchError>((id, T::Convert::convert(redeemed_amount)))
+			})
+			.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (id, amount) in &redeemed_assets {
+			T::Assets::transfer(
+				*id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		T::Assets::burn_from(pool.pool_info.lp_token, who, lp_amount)?;
+
+		Ok(redeemed_assets)
+	}
+
+	/// Price of `quote_asset` in terms of `base_asset`, derived from the invariant.
+	pub(crate) fn spot_price(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		base_asset: T::AssetId,
+		quote_asset: T::AssetId,
+	) -> Result<Rate, DispatchError> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let b_base = pool_assets.get(&base_asset).ok_or(Error::<T>::AssetNotFound)?;
+		let b_quote = pool_assets.get(&quote_asset).ok_or(Error::<T>::AssetNotFound)?;
+		ensure!(!b_base.is_zero() && !b_quote.is_zero(), Error::<T>::NotEnoughLiquidity);
+
+		Ok(compute_spot_price(u128::from(pool.amplification_coefficient), *b_quote, *b_base)?)
+	}
+
+	pub(crate) fn get_exchange_value(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let a_sent = T::Convert::convert(in_asset.amount);
+		let fee = if apply_fees { pool.pool_info.fee_config.fee_rate } else { Permill::zero() };
+		let b_i = pool_assets.get(&in_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let b_o = pool_assets.get(&out_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_out_given_in(
+			u128::from(pool.amplification_coefficient),
+			*b_i,
+			*b_o,
+			a_sent,
+			fee,
+		)?;
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(amm_pair.value));
+		let a_sent = AssetAmount::new(in_asset.asset_id, in_asset.amount);
+		let fee = pool
+			.pool_info
+			.fee_config
+			.calculate_fees(in_asset.asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((a_out, a_sent, fee))
+	}
+
+	pub(crate) fn do_buy(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let a_out = T::Convert::convert(out_asset.amount);
+		let fee = if apply_fees { pool.pool_info.fee_config.fee_rate } else { Permill::zero() };
+		let b_o = pool_assets.get(&out_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let b_i = pool_assets.get(&in_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_in_given_out(
+			u128::from(pool.amplification_coefficient),
+			*b_i,
+			*b_o,
+			a_out,
+			fee,
+		)?;
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool
+			.pool_info
+			.fee_config
+			.calculate_fees(in_asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+}
@@ -2,7 +2,10 @@ use crate::{
 	mock,
 	mock::{Pablo, *},
 	Config,
-	PoolConfiguration::DualAssetConstantProduct,
+	PoolConfiguration::{
+		ConcentratedLiquidity, DualAssetConstantProduct, LiquidityBootstrapping,
+		MultiAssetConstantProduct, StableSwap,
+	},
 	PoolInitConfiguration,
 };
 use composable_traits::dex::AssetAmount;
@@ -41,7 +44,7 @@ pub fn dual_asset_pool_weights_vec(
 /// `expected_lp_check` takes base_amount, quote_amount and lp_tokens in order and returns
 /// true if lp_tokens are expected for given base_amount, quote_amount.
 pub fn common_add_remove_lp(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
 	first_asset_amount: Balance,
 	second_asset_amount: Balance,
 	next_first_asset_amount: Balance,
@@ -82,6 +85,10 @@ pub fn common_add_remove_lp(
 	let pool = Pablo::pools(actual_pool_id).expect("pool not found");
 	let lp_token = match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		MultiAssetConstantProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => unreachable!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => unreachable!("concentrated liquidity pools have no lp token"),
 	};
 	// Mint the tokens
 	assert_ok!(Tokens::mint_into(pair[0], &BOB, next_first_asset_amount));
@@ -121,7 +128,9 @@ pub fn common_add_remove_lp(
 	assert_eq!(lp, 0_u128);
 }
 
-pub fn get_pair(init_config: PoolInitConfiguration<AccountId, AssetId>) -> [AssetId; 2] {
+pub fn get_pair(
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
+) -> [AssetId; 2] {
 	match init_config {
 		PoolInitConfiguration::DualAssetConstantProduct { assets_weights, .. } => assets_weights
 			.into_iter()
@@ -129,6 +138,16 @@ pub fn get_pair(init_config: PoolInitConfiguration<AccountId, AssetId>) -> [Asse
 			.collect::<Vec<_>>()
 			.try_into()
 			.expect("pool should have exactly 2 assets; qed;"),
+		PoolInitConfiguration::StableSwap { assets, .. } =>
+			assets.try_into().expect("pool should have exactly 2 assets; qed;"),
+		PoolInitConfiguration::MultiAssetConstantProduct { assets_weights, .. } => assets_weights
+			.into_iter()
+			.map(|(k, _v)| k)
+			.collect::<Vec<_>>()
+			.try_into()
+			.expect("pool should have exactly 2 assets; qed;"),
+		PoolInitConfiguration::LiquidityBootstrapping { pair, .. } |
+		PoolInitConfiguration::ConcentratedLiquidity { pair, .. } => [pair.base, pair.quote],
 	}
 }
 
@@ -136,7 +155,7 @@ pub fn get_pair(init_config: PoolInitConfiguration<AccountId, AssetId>) -> [Asse
 /// `pool_base_amount` and `pool_quote_amount` parameters and returns amount of expected new
 /// lp_tokens.
 pub fn common_add_lp_with_min_mint_amount(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
 	init_first_asset_amount: Balance,
 	init_second_asset_amount: Balance,
 	first_asset_amount: Balance,
@@ -157,6 +176,10 @@ pub fn common_add_lp_with_min_mint_amount(
 
 	let lp_token = match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		MultiAssetConstantProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => unreachable!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => unreachable!("concentrated liquidity pools have no lp token"),
 	};
 
 	let [first_asset, second_asset] = get_pair(init_config);
@@ -219,7 +242,7 @@ pub fn common_add_lp_with_min_mint_amount(
 }
 
 pub fn common_remove_lp_failure(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
 	init_base_amount: Balance,
 	init_quote_amount: Balance,
 	base_amount: Balance,
@@ -245,6 +268,10 @@ pub fn common_remove_lp_failure(
 	let pool = Pablo::pools(pool_id).expect("pool not found");
 	let lp_token = match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		MultiAssetConstantProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => unreachable!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => unreachable!("concentrated liquidity pools have no lp token"),
 	};
 	// Mint the tokens
 	assert_ok!(Tokens::mint_into(pair[0], &BOB, base_amount));
@@ -289,7 +316,7 @@ pub fn common_remove_lp_failure(
 }
 
 pub fn common_exchange_failure(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
 	init_first_amount: AssetAmount<AssetId, Balance>,
 	init_second_amount: AssetAmount<AssetId, Balance>,
 	exchange_first_amount: AssetAmount<AssetId, Balance>,
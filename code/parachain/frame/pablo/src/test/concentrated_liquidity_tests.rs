@@ -0,0 +1,537 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	mock_fnft::MockFnft,
+	ConcentratedLiquidityStates, Error, Event, PoolConfiguration, PoolInitConfiguration, Positions,
+	TickBitmap, Ticks,
+};
+use composable_maths::dex::concentrated_liquidity::MAX_TICK;
+use composable_tests_helpers::test::{currency::PICA, helper::RuntimeTrait};
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount},
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungibles::{Inspect, Mutate},
+		tokens::nonfungibles::Inspect as NonFungiblesInspect,
+	},
+};
+use sp_runtime::{FixedPointNumber, FixedU128, Permill};
+use sp_std::collections::btree_map::BTreeMap;
+
+const TICK_SPACING: u32 = 10;
+const INITIAL_AMOUNT: Balance = 1_000_000_000_000_000;
+
+fn concentrated_liquidity_init_config(
+	tick_spacing: u32,
+	initial_tick: i32,
+) -> PoolInitConfiguration<AccountId, AssetId, BlockNumber> {
+	PoolInitConfiguration::ConcentratedLiquidity {
+		owner: ALICE,
+		pair: CurrencyPair::new(PICA::ID, USDT),
+		tick_spacing,
+		initial_tick,
+		fee: Permill::from_percent(1),
+	}
+}
+
+/// Creates a PICA/USDT pool at a price of 1, positions being minted in `LP_TOKEN_ID`.
+fn create_pool() -> PoolId {
+	System::set_block_number(1);
+	Pablo::do_create_pool(concentrated_liquidity_init_config(TICK_SPACING, 0), Some(LP_TOKEN_ID))
+		.unwrap()
+}
+
+/// Mints `amount` of both assets to `who` and opens a position with them, returning the position
+/// instance, its liquidity and the deposited amounts.
+fn create_position(
+	who: AccountId,
+	pool_id: PoolId,
+	lower_tick: i32,
+	upper_tick: i32,
+	amount: Balance,
+) -> (u64, u128, BTreeMap<AssetId, Balance>) {
+	assert_ok!(Tokens::mint_into(PICA::ID, &who, amount));
+	assert_ok!(Tokens::mint_into(USDT, &who, amount));
+	Test::assert_extrinsic_event_with(
+		Pablo::create_position(
+			RuntimeOrigin::signed(who),
+			pool_id,
+			lower_tick,
+			upper_tick,
+			BTreeMap::from([(PICA::ID, amount), (USDT, amount)]),
+			0,
+			false,
+		),
+		|e| match e {
+			Event::PositionCreated { fnft_instance_id, liquidity, asset_amounts, .. } =>
+				Some((fnft_instance_id, liquidity, asset_amounts)),
+			_ => None,
+		},
+	)
+}
+
+fn swap(who: AccountId, pool_id: PoolId, in_asset: AssetAmount<AssetId, Balance>) -> Balance {
+	let out_asset_id = if in_asset.asset_id == PICA::ID { USDT } else { PICA::ID };
+	assert_ok!(Tokens::mint_into(in_asset.asset_id, &who, in_asset.amount));
+	<Pablo as Amm>::do_swap(&who, pool_id, in_asset, AssetAmount::new(out_asset_id, 0), false)
+		.unwrap()
+		.value
+		.amount
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_create_pool_with_position_collection() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			Test::assert_extrinsic_event(
+				Pablo::create(RuntimeOrigin::root(), concentrated_liquidity_init_config(10, 0)),
+				Event::PoolCreated {
+					pool_id: 0,
+					owner: ALICE,
+					asset_weights: BTreeMap::from([
+						(PICA::ID, Permill::from_percent(50)),
+						(USDT, Permill::from_percent(50)),
+					]),
+					lp_token_id: match Pablo::pools(0).unwrap() {
+						PoolConfiguration::ConcentratedLiquidity(pool) =>
+							pool.position_collection_id,
+						_ => panic!("expected a ConcentratedLiquidity pool"),
+					},
+				},
+			);
+
+			let pool_id = create_pool();
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::ConcentratedLiquidity(pool) => {
+					assert_eq!(pool.owner, ALICE);
+					assert_eq!(pool.tick_spacing, TICK_SPACING);
+					assert_eq!(pool.position_collection_id, LP_TOKEN_ID);
+					assert_eq!(pool.fee_config.fee_rate, Permill::from_percent(1));
+				},
+				_ => panic!("expected a ConcentratedLiquidity pool"),
+			}
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert_eq!(state.tick, 0);
+			assert_eq!(state.sqrt_price, FixedU128::from_u32(1));
+			assert_eq!(state.liquidity, 0);
+			assert_noop!(<Pablo as Amm>::lp_token(pool_id), Error::<Test>::UnsupportedOperation);
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_invalid_ticks() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(concentrated_liquidity_init_config(0, 0), None),
+				Error::<Test>::InvalidTickSpacing
+			);
+			assert_noop!(
+				Pablo::do_create_pool(
+					concentrated_liquidity_init_config(MAX_TICK.unsigned_abs() + 1, 0),
+					None
+				),
+				Error::<Test>::InvalidTickSpacing
+			);
+			assert_noop!(
+				Pablo::do_create_pool(concentrated_liquidity_init_config(10, MAX_TICK + 1), None),
+				Error::<Test>::InvalidTick
+			);
+		});
+	}
+}
+
+mod positions {
+	use super::*;
+
+	#[test]
+	fn should_mint_position_to_its_owner() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let pool_account = Pablo::account_id(&pool_id);
+			let (instance, liquidity, deposited) =
+				create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+
+			assert_eq!(MockFnft::owner(&LP_TOKEN_ID, &instance), Some(BOB));
+			let position = Positions::<Test>::get(LP_TOKEN_ID, instance).unwrap();
+			assert_eq!(position.liquidity, liquidity);
+			assert_eq!((position.lower_tick, position.upper_tick), (-1000, 1000));
+			// the price is in range, both assets are deposited
+			for asset_id in [PICA::ID, USDT] {
+				let amount = deposited[&asset_id];
+				assert!(amount > 0 && amount <= INITIAL_AMOUNT);
+				assert_eq!(Tokens::balance(asset_id, &pool_account), amount);
+				assert_eq!(Tokens::balance(asset_id, &BOB), INITIAL_AMOUNT - amount);
+			}
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert_eq!(state.liquidity, liquidity);
+			assert_eq!(Ticks::<Test>::iter_prefix(pool_id).count(), 2);
+		});
+	}
+
+	#[test]
+	fn should_only_deposit_base_asset_above_current_price() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let (_, _, deposited) = create_position(BOB, pool_id, 100, 1000, INITIAL_AMOUNT);
+
+			assert_eq!(deposited.keys().copied().collect::<Vec<_>>(), vec![PICA::ID]);
+			assert!(deposited[&PICA::ID] <= INITIAL_AMOUNT);
+			// out of range liquidity is not active
+			assert_eq!(ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap().liquidity, 0);
+		});
+	}
+
+	#[test]
+	fn should_not_create_position_with_invalid_range() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			assert_ok!(Tokens::mint_into(PICA::ID, &BOB, INITIAL_AMOUNT));
+			assert_ok!(Tokens::mint_into(USDT, &BOB, INITIAL_AMOUNT));
+			let amounts = BTreeMap::from([(PICA::ID, INITIAL_AMOUNT), (USDT, INITIAL_AMOUNT)]);
+
+			for (lower_tick, upper_tick) in [(-1005, 1000), (1000, -1000), (100, 100)] {
+				assert_noop!(
+					Pablo::create_position(
+						RuntimeOrigin::signed(BOB),
+						pool_id,
+						lower_tick,
+						upper_tick,
+						amounts.clone(),
+						0,
+						false,
+					),
+					Error::<Test>::InvalidTick
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn should_not_create_dust_positions() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			assert_ok!(Tokens::mint_into(PICA::ID, &BOB, 10));
+			assert_ok!(Tokens::mint_into(USDT, &BOB, 10));
+			assert_noop!(
+				Pablo::create_position(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					-1000,
+					1000,
+					BTreeMap::from([(PICA::ID, 10), (USDT, 10)]),
+					0,
+					false,
+				),
+				Error::<Test>::PositionLiquidityTooLow
+			);
+
+			// positions can't be emptied down to dust either
+			let (instance, liquidity, _) =
+				create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			assert_noop!(
+				Pablo::remove_position_liquidity(
+					RuntimeOrigin::signed(BOB),
+					LP_TOKEN_ID,
+					instance,
+					liquidity - MinPositionLiquidity::get() + 1,
+					BTreeMap::new(),
+				),
+				Error::<Test>::PositionLiquidityTooLow
+			);
+		});
+	}
+
+	#[test]
+	fn fungible_liquidity_is_not_supported() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					BTreeMap::from([(PICA::ID, INITIAL_AMOUNT), (USDT, INITIAL_AMOUNT)]),
+					0,
+					false,
+				),
+				Error::<Test>::UnsupportedOperation
+			);
+			assert_noop!(
+				Pablo::remove_liquidity(RuntimeOrigin::signed(BOB), pool_id, 1, BTreeMap::new()),
+				Error::<Test>::UnsupportedOperation
+			);
+		});
+	}
+
+	#[test]
+	fn should_close_position_once_emptied() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let (instance, liquidity, deposited) =
+				create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+
+			assert_noop!(
+				Pablo::remove_position_liquidity(
+					RuntimeOrigin::signed(CHARLIE),
+					LP_TOKEN_ID,
+					instance,
+					liquidity,
+					BTreeMap::new(),
+				),
+				Error::<Test>::NotPositionOwner
+			);
+			assert_noop!(
+				Pablo::remove_position_liquidity(
+					RuntimeOrigin::signed(BOB),
+					LP_TOKEN_ID,
+					instance,
+					liquidity + 1,
+					BTreeMap::new(),
+				),
+				Error::<Test>::InvalidAmount
+			);
+
+			assert_ok!(Pablo::remove_position_liquidity(
+				RuntimeOrigin::signed(BOB),
+				LP_TOKEN_ID,
+				instance,
+				liquidity / 2,
+				BTreeMap::new(),
+			));
+			assert_eq!(
+				Positions::<Test>::get(LP_TOKEN_ID, instance).unwrap().liquidity,
+				liquidity - liquidity / 2
+			);
+
+			assert_ok!(Pablo::remove_position_liquidity(
+				RuntimeOrigin::signed(BOB),
+				LP_TOKEN_ID,
+				instance,
+				liquidity - liquidity / 2,
+				BTreeMap::new(),
+			));
+			assert!(Positions::<Test>::get(LP_TOKEN_ID, instance).is_none());
+			assert_eq!(MockFnft::owner(&LP_TOKEN_ID, &instance), None);
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert_eq!(state.liquidity, 0);
+			assert_eq!(Ticks::<Test>::iter_prefix(pool_id).count(), 0);
+			assert_eq!(TickBitmap::<Test>::iter_prefix(pool_id).count(), 0);
+			// withdrawals are rounded down, at most one unit per removal is left in the pool
+			for asset_id in [PICA::ID, USDT] {
+				let returned = Tokens::balance(asset_id, &BOB) + deposited[&asset_id];
+				assert!(returned <= INITIAL_AMOUNT && returned + 2 >= INITIAL_AMOUNT);
+			}
+		});
+	}
+}
+
+mod swaps {
+	use super::*;
+
+	#[test]
+	fn should_cross_ticks() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let (_, wide_liquidity, _) = create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			let (_, narrow_liquidity, _) =
+				create_position(CHARLIE, pool_id, -100, 100, INITIAL_AMOUNT);
+			assert_eq!(
+				ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap().liquidity,
+				wide_liquidity + narrow_liquidity
+			);
+
+			// selling PICA moves the price down, out of the narrow position
+			let received = swap(ALICE, pool_id, AssetAmount::new(PICA::ID, INITIAL_AMOUNT * 3 / 2));
+			assert!(received > 0 && received < INITIAL_AMOUNT * 3 / 2);
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert!(state.tick < -100 && state.tick >= -1000);
+			assert_eq!(state.liquidity, wide_liquidity);
+
+			// buying PICA back brings the narrow position in range again
+			swap(ALICE, pool_id, AssetAmount::new(USDT, received));
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert!(state.tick >= -100);
+			assert_eq!(state.liquidity, wide_liquidity + narrow_liquidity);
+		});
+	}
+
+	#[test]
+	fn should_cross_ticks_of_many_positions() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let mut total_liquidity = 0;
+			for i in 1..=300 {
+				let (_, liquidity, _) =
+					create_position(BOB, pool_id, -10 * i, 10 * i, INITIAL_AMOUNT / 1_000);
+				total_liquidity += liquidity;
+			}
+			// the bounds of the positions span several words of the tick bitmap
+			assert_eq!(Ticks::<Test>::iter_prefix(pool_id).count(), 600);
+			assert!(TickBitmap::<Test>::iter_prefix(pool_id).count() > 2);
+			assert_eq!(
+				ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap().liquidity,
+				total_liquidity
+			);
+
+			// selling PICA moves the price out of the narrowest positions
+			swap(ALICE, pool_id, AssetAmount::new(PICA::ID, INITIAL_AMOUNT / 10));
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert!(state.tick < -10);
+			assert!(state.liquidity < total_liquidity);
+			// crossing a tick records the fee growth on its other side
+			assert_ne!(
+				Ticks::<Test>::get(pool_id, -10).unwrap().fee_growth_outside,
+				Default::default()
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_swap_past_available_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let amount = INITIAL_AMOUNT * 3;
+			assert_ok!(Tokens::mint_into(PICA::ID, &ALICE, amount));
+			let swap = || {
+				<Pablo as Amm>::do_swap(
+					&ALICE,
+					pool_id,
+					AssetAmount::new(PICA::ID, amount),
+					AssetAmount::new(USDT, 0),
+					false,
+				)
+			};
+
+			assert_noop!(swap(), Error::<Test>::NotEnoughLiquidity);
+			create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			assert_noop!(swap(), Error::<Test>::NotEnoughLiquidity);
+		});
+	}
+
+	#[test]
+	fn spot_price_should_follow_the_pool_price() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			let spot_price = || {
+				<Pablo as Amm>::spot_price(pool_id, AssetAmount::new(PICA::ID, 1_000), USDT, false)
+					.unwrap()
+					.value
+					.amount
+			};
+
+			// the spot price is simulated against the pool liquidity, it is rounded down
+			let initial_price = spot_price();
+			assert!((999..=1_000).contains(&initial_price));
+			swap(ALICE, pool_id, AssetAmount::new(PICA::ID, INITIAL_AMOUNT / 10));
+			assert!(spot_price() < initial_price);
+		});
+	}
+}
+
+mod fees {
+	use super::*;
+
+	#[test]
+	fn should_accrue_fees_proportionally_to_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let (bob_position, bob_liquidity, _) =
+				create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			let (charlie_position, charlie_liquidity, _) =
+				create_position(CHARLIE, pool_id, -1000, 1000, INITIAL_AMOUNT * 2);
+			assert!(charlie_liquidity.abs_diff(bob_liquidity * 2) <= 2);
+
+			let received = swap(ALICE, pool_id, AssetAmount::new(PICA::ID, INITIAL_AMOUNT / 10));
+			swap(ALICE, pool_id, AssetAmount::new(USDT, received));
+
+			assert_noop!(
+				Pablo::collect_position_fees(RuntimeOrigin::signed(BOB), LP_TOKEN_ID, 42),
+				Error::<Test>::PositionNotFound
+			);
+			assert_noop!(
+				Pablo::collect_position_fees(
+					RuntimeOrigin::signed(BOB),
+					LP_TOKEN_ID,
+					charlie_position
+				),
+				Error::<Test>::NotPositionOwner
+			);
+
+			let collect = |who, position| {
+				Test::assert_extrinsic_event_with(
+					Pablo::collect_position_fees(RuntimeOrigin::signed(who), LP_TOKEN_ID, position),
+					|e| match e {
+						Event::PositionFeesCollected { fees, .. } => Some(fees),
+						_ => None,
+					},
+				)
+			};
+			let bob_fees = collect(BOB, bob_position);
+			let charlie_fees = collect(CHARLIE, charlie_position);
+			for asset_id in [PICA::ID, USDT] {
+				assert!(bob_fees[&asset_id] > 0);
+				assert!(charlie_fees[&asset_id].abs_diff(bob_fees[&asset_id] * 2) <= 2);
+			}
+
+			// fees are only paid once
+			assert_eq!(collect(BOB, bob_position), BTreeMap::new());
+		});
+	}
+
+	#[test]
+	fn should_not_accrue_fees_out_of_range() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			let (charlie_position, _, _) =
+				create_position(CHARLIE, pool_id, 100, 1000, INITIAL_AMOUNT);
+
+			swap(ALICE, pool_id, AssetAmount::new(PICA::ID, INITIAL_AMOUNT / 10));
+
+			let fees = Test::assert_extrinsic_event_with(
+				Pablo::collect_position_fees(
+					RuntimeOrigin::signed(CHARLIE),
+					LP_TOKEN_ID,
+					charlie_position,
+				),
+				|e| match e {
+					Event::PositionFeesCollected { fees, .. } => Some(fees),
+					_ => None,
+				},
+			);
+			assert_eq!(fees, BTreeMap::new());
+		});
+	}
+
+	#[test]
+	fn should_pay_fees_when_closing_position() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let (instance, liquidity, deposited) =
+				create_position(BOB, pool_id, -1000, 1000, INITIAL_AMOUNT);
+			let received = swap(ALICE, pool_id, AssetAmount::new(PICA::ID, INITIAL_AMOUNT / 10));
+			swap(ALICE, pool_id, AssetAmount::new(USDT, received));
+
+			assert_ok!(Pablo::remove_position_liquidity(
+				RuntimeOrigin::signed(BOB),
+				LP_TOKEN_ID,
+				instance,
+				liquidity,
+				BTreeMap::new(),
+			));
+			// the round trip left fees in both assets on top of the initial deposit
+			let total_returned: Balance = [PICA::ID, USDT]
+				.into_iter()
+				.map(|asset_id| Tokens::balance(asset_id, &BOB) + deposited[&asset_id])
+				.sum();
+			assert!(total_returned > INITIAL_AMOUNT * 2);
+		});
+	}
+}
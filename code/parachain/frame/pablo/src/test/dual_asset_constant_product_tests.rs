@@ -9,7 +9,10 @@ use crate::{
 	pallet,
 	test::common_test_functions::*,
 	Error,
-	PoolConfiguration::DualAssetConstantProduct,
+	PoolConfiguration::{
+		ConcentratedLiquidity, DualAssetConstantProduct, LiquidityBootstrapping,
+		MultiAssetConstantProduct, StableSwap,
+	},
 	PoolInitConfiguration,
 };
 use composable_maths::dex::constant_product::{compute_deposit_lp, compute_first_deposit_lp};
@@ -84,6 +87,7 @@ fn create_pool(
 fn get_pool(pool_id: PoolId) -> BasicPoolInfo<AccountId, AssetId, ConstU32<2>> {
 	match Pablo::pools(pool_id).expect("pool not found") {
 		DualAssetConstantProduct(pool) => pool,
+		_ => panic!("expected a dual asset constant product pool"),
 	}
 }
 
@@ -203,7 +207,7 @@ pub fn valid_pool_init_config(
 	first_asset_weight: Permill,
 	second_asset: AssetId,
 	fee: Permill,
-) -> PoolInitConfiguration<AccountId, AssetId> {
+) -> PoolInitConfiguration<AccountId, AssetId, BlockNumber> {
 	PoolInitConfiguration::DualAssetConstantProduct {
 		owner: *owner,
 		assets_weights: dual_asset_pool_weights_vec(first_asset, first_asset_weight, second_asset),
@@ -258,7 +262,7 @@ fn test_redeemable_assets() {
 	});
 }
 
-pub fn create_pool_from_config(init_config: PoolInitConfiguration<u128, u128>) -> u128 {
+pub fn create_pool_from_config(init_config: PoolInitConfiguration<u128, u128, u64>) -> u128 {
 	Test::assert_extrinsic_event_with(Pablo::create(RuntimeOrigin::root(), init_config), |event| {
 		match event {
 			crate::Event::PoolCreated { pool_id, .. } => Some(pool_id),
@@ -415,6 +419,10 @@ pub fn lp_token_of_pool(pool_id: u128) -> u128 {
 
 	match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		MultiAssetConstantProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => unreachable!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => unreachable!("concentrated liquidity pools have no lp token"),
 	}
 }
 
@@ -964,7 +972,10 @@ mod twap {
 	use crate::types::TimeWeightedAveragePrice;
 	use composable_tests_helpers::test::block::process_and_progress_blocks;
 	use composable_traits::defi::Rate;
-	use sp_runtime::traits::One;
+	use sp_runtime::{
+		traits::{One, Saturating},
+		FixedPointNumber,
+	};
 
 	#[test]
 	fn twap_asset_prices_change_after_twap_interval() {
@@ -1151,6 +1162,160 @@ mod twap {
 			assert!(twap.quote_twap < max_quote_price);
 		});
 	}
+
+	fn create_twap_pool() -> PoolId {
+		let unit = 1_000_000_000_000_u128;
+		let pool_id = create_pool(
+			BTC,
+			USDT,
+			100_u128 * unit,
+			100_u128 * unit,
+			LP_TOKEN_ID,
+			Permill::zero(),
+			Permill::zero(),
+		);
+		System::set_block_number(0);
+		assert_ok!(Pablo::enable_twap(RuntimeOrigin::root(), pool_id));
+		pool_id
+	}
+
+	fn swap_usdt_in(pool_id: PoolId) {
+		let usdt_value = 1_000_000_000_000_u128;
+		assert_ok!(Tokens::mint_into(USDT, &BOB, usdt_value));
+		assert_ok!(Pablo::swap(
+			RuntimeOrigin::signed(BOB),
+			pool_id,
+			AssetAmount::new(USDT, usdt_value),
+			AssetAmount::new(BTC, 0),
+			false
+		));
+	}
+
+	fn window_of(blocks: u64) -> u64 {
+		blocks * MILLISECS_PER_BLOCK / 1_000
+	}
+
+	fn assert_rate_approx_eq(actual: Rate, expected: Rate) {
+		let difference = actual.max(expected).saturating_sub(actual.min(expected));
+		assert!(
+			difference < Rate::saturating_from_rational(1_u128, 10_000_u128),
+			"{actual:?} != {expected:?}"
+		);
+	}
+
+	#[test]
+	fn twap_observations_overwrite_the_oldest_ones() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_twap_pool();
+			assert_eq!(Pablo::twap_observations(pool_id).map(|o| o.len()), Some(1));
+
+			for _ in 0..2 * MAX_TWAP_OBSERVATIONS {
+				process_and_progress_blocks::<Pablo, Test>(1);
+				swap_usdt_in(pool_id);
+			}
+
+			let observations =
+				Pablo::twap_observations(pool_id).expect("twap observations not found");
+			assert_eq!(
+				observations.iter().map(|o| o.timestamp).collect::<Vec<_>>(),
+				(MAX_TWAP_OBSERVATIONS + 1..=2 * MAX_TWAP_OBSERVATIONS)
+					.map(|block| u64::from(block) * MILLISECS_PER_BLOCK)
+					.collect::<Vec<_>>()
+			);
+			assert_eq!(observations.last(), Pablo::price_cumulative(pool_id).as_ref());
+		});
+	}
+
+	#[test]
+	fn twap_over_window_interpolates_between_observations() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_twap_pool();
+			// USDT is the base of the ordered pair of the pool as its id is the lowest
+			let twap = |window| <Pablo as Amm>::twap_over_window(pool_id, USDT, BTC, window);
+			let rate = || {
+				Pablo::do_get_exchange_rate(pool_id, crate::PriceRatio::NotSwapped)
+					.expect("exchange rate")
+			};
+
+			// the price cumulative is updated with the price after the swap
+			process_and_progress_blocks::<Pablo, Test>(5);
+			swap_usdt_in(pool_id);
+			let first_rate = rate();
+			process_and_progress_blocks::<Pablo, Test>(5);
+			swap_usdt_in(pool_id);
+			let second_rate = rate();
+			process_and_progress_blocks::<Pablo, Test>(5);
+			assert!(first_rate < second_rate);
+
+			assert_rate_approx_eq(twap(window_of(3)).expect("twap"), second_rate);
+			assert_rate_approx_eq(
+				twap(window_of(15)).expect("twap"),
+				(first_rate * Rate::saturating_from_integer(5_u128) +
+					second_rate * Rate::saturating_from_integer(10_u128)) /
+					Rate::saturating_from_integer(15_u128),
+			);
+			// starts in between the observations of blocks 0 and 5
+			assert_rate_approx_eq(
+				twap(window_of(12)).expect("twap"),
+				(first_rate * Rate::saturating_from_integer(2_u128) +
+					second_rate * Rate::saturating_from_integer(10_u128)) /
+					Rate::saturating_from_integer(12_u128),
+			);
+			// pair order selects the inverse price
+			assert_rate_approx_eq(
+				<Pablo as Amm>::twap_over_window(pool_id, BTC, USDT, window_of(3)).expect("twap"),
+				Pablo::do_get_exchange_rate(pool_id, crate::PriceRatio::Swapped)
+					.expect("exchange rate"),
+			);
+		});
+	}
+
+	#[test]
+	fn twap_over_window_fails_when_not_covered() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool(
+				BTC,
+				USDT,
+				1_000_000_000_000,
+				1_000_000_000_000,
+				LP_TOKEN_ID,
+				Permill::zero(),
+				Permill::zero(),
+			);
+			process_and_progress_blocks::<Pablo, Test>(3);
+			// the first observation is recorded when TWAP is enabled
+			assert_ok!(Pablo::enable_twap(RuntimeOrigin::root(), pool_id));
+			process_and_progress_blocks::<Pablo, Test>(5);
+
+			assert_ok!(<Pablo as Amm>::twap_over_window(pool_id, USDT, BTC, window_of(5)));
+			assert_noop!(
+				<Pablo as Amm>::twap_over_window(pool_id, USDT, BTC, window_of(6)),
+				Error::<Test>::TwapWindowNotCovered
+			);
+			assert_noop!(
+				<Pablo as Amm>::twap_over_window(pool_id, USDT, BTC, 0),
+				Error::<Test>::InvalidTwapWindow
+			);
+			assert_noop!(
+				<Pablo as Amm>::twap_over_window(pool_id, USDT, USDC, window_of(5)),
+				Error::<Test>::AssetNotFound
+			);
+
+			let other_pool_id = create_pool(
+				BTC,
+				USDC,
+				1_000_000_000_000,
+				1_000_000_000_000,
+				LP_TOKEN_ID + 1,
+				Permill::zero(),
+				Permill::zero(),
+			);
+			assert_noop!(
+				<Pablo as Amm>::twap_over_window(other_pool_id, USDC, BTC, window_of(5)),
+				Error::<Test>::TwapNotEnabled
+			);
+		});
+	}
 }
 
 #[test]
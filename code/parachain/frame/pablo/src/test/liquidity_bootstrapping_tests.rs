@@ -0,0 +1,402 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	Error, Event, PoolConfiguration, PoolInitConfiguration,
+};
+use composable_maths::dex::constant_product::compute_out_given_in;
+use composable_tests_helpers::test::{currency::PICA, helper::RuntimeTrait};
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount, Sale, SaleEndBehaviour},
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungibles::{Inspect, Mutate},
+};
+use sp_runtime::{FixedPointNumber, FixedU128, Permill};
+use sp_std::collections::btree_map::BTreeMap;
+
+const INITIAL_PROJECT_AMOUNT: Balance = 1_000_000_000_000_000;
+const INITIAL_COLLATERAL_AMOUNT: Balance = 100_000_000_000_000;
+const SALE_START: BlockNumber = 10;
+const SALE_END: BlockNumber = 110;
+
+/// PICA is sold for USDC, the weight of PICA goes from 90% down to 50%
+fn sale() -> Sale<BlockNumber> {
+	Sale {
+		start: SALE_START,
+		end: SALE_END,
+		initial_weight: Permill::from_percent(90),
+		final_weight: Permill::from_percent(50),
+	}
+}
+
+fn lbp_init_config(
+	sale: Sale<BlockNumber>,
+	sale_end_behaviour: SaleEndBehaviour,
+) -> PoolInitConfiguration<AccountId, AssetId, BlockNumber> {
+	PoolInitConfiguration::LiquidityBootstrapping {
+		owner: ALICE,
+		pair: CurrencyPair::new(PICA::ID, USDC),
+		sale,
+		sale_end_behaviour,
+		fee: Permill::from_perthousand(1),
+	}
+}
+
+fn create_pool_with_liquidity(sale_end_behaviour: SaleEndBehaviour) -> PoolId {
+	System::set_block_number(1);
+	let pool_id = Test::assert_extrinsic_event_with(
+		Pablo::create(RuntimeOrigin::root(), lbp_init_config(sale(), sale_end_behaviour)),
+		|e| match e {
+			Event::LiquidityBootstrappingPoolCreated { pool_id, .. } => Some(pool_id),
+			_ => None,
+		},
+	);
+
+	assert_ok!(Tokens::mint_into(PICA::ID, &ALICE, INITIAL_PROJECT_AMOUNT));
+	assert_ok!(Tokens::mint_into(USDC, &ALICE, INITIAL_COLLATERAL_AMOUNT));
+	assert_ok!(Pablo::add_liquidity(
+		RuntimeOrigin::signed(ALICE),
+		pool_id,
+		BTreeMap::from([(PICA::ID, INITIAL_PROJECT_AMOUNT), (USDC, INITIAL_COLLATERAL_AMOUNT)]),
+		0,
+		false,
+	));
+
+	pool_id
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_create_pool_without_lp_token() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				Pablo::do_create_pool(lbp_init_config(sale(), SaleEndBehaviour::Close), None)
+					.unwrap();
+
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::LiquidityBootstrapping(pool) => {
+					assert_eq!(pool.owner, ALICE);
+					assert_eq!(pool.sale, sale());
+					assert_eq!(pool.fee_config.fee_rate, Permill::from_perthousand(1));
+				},
+				_ => panic!("expected a LiquidityBootstrapping pool"),
+			}
+			assert_noop!(<Pablo as Amm>::lp_token(pool_id), Error::<Test>::NoLpTokenForLbp);
+			assert_noop!(
+				<Pablo as Amm>::redeemable_assets_for_lp_tokens(pool_id, 1),
+				Error::<Test>::NoLpTokenForLbp
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_lp_token() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					lbp_init_config(sale(), SaleEndBehaviour::Close),
+					Some(LP_TOKEN_ID)
+				),
+				Error::<Test>::NoLpTokenForLbp
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_invalid_sale() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(SALE_START + 1);
+			// sale already started
+			assert_noop!(
+				Pablo::do_create_pool(lbp_init_config(sale(), SaleEndBehaviour::Close), None),
+				Error::<Test>::InvalidSaleState
+			);
+			// sale ends before it starts
+			assert_noop!(
+				Pablo::do_create_pool(
+					lbp_init_config(
+						Sale { start: SALE_END, end: SALE_START, ..sale() },
+						SaleEndBehaviour::Close
+					),
+					None
+				),
+				Error::<Test>::InvalidSaleState
+			);
+			assert_noop!(
+				Pablo::do_create_pool(
+					lbp_init_config(
+						Sale { start: SALE_END, end: SALE_END, ..sale() },
+						SaleEndBehaviour::Close
+					),
+					None
+				),
+				Error::<Test>::InvalidSaleState
+			);
+			assert_noop!(
+				Pablo::do_create_pool(
+					lbp_init_config(
+						Sale {
+							start: SALE_END,
+							end: SALE_END + 10,
+							final_weight: Permill::one(),
+							..sale()
+						},
+						SaleEndBehaviour::Close
+					),
+					None
+				),
+				Error::<Test>::WeightsMustBeNonZero
+			);
+		});
+	}
+}
+
+mod liquidity {
+	use super::*;
+
+	#[test]
+	fn only_owner_can_add_liquidity_before_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			let pool_account = Pablo::account_id(&pool_id);
+			assert_eq!(Tokens::balance(PICA::ID, &pool_account), INITIAL_PROJECT_AMOUNT);
+			assert_eq!(Tokens::balance(USDC, &pool_account), INITIAL_COLLATERAL_AMOUNT);
+
+			assert_ok!(Tokens::mint_into(USDC, &BOB, INITIAL_COLLATERAL_AMOUNT));
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					BTreeMap::from([(USDC, INITIAL_COLLATERAL_AMOUNT)]),
+					0,
+					false,
+				),
+				Error::<Test>::MustBeOwner
+			);
+
+			System::set_block_number(SALE_START);
+			assert_ok!(Tokens::mint_into(USDC, &ALICE, INITIAL_COLLATERAL_AMOUNT));
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					BTreeMap::from([(USDC, INITIAL_COLLATERAL_AMOUNT)]),
+					0,
+					false,
+				),
+				Error::<Test>::InvalidSaleState
+			);
+		});
+	}
+
+	#[test]
+	fn owner_can_withdraw_after_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			let pool_account = Pablo::account_id(&pool_id);
+
+			System::set_block_number(SALE_END - 1);
+			assert_noop!(
+				Pablo::remove_liquidity(RuntimeOrigin::signed(ALICE), pool_id, 0, BTreeMap::new()),
+				Error::<Test>::InvalidSaleState
+			);
+
+			System::set_block_number(SALE_END);
+			assert_noop!(
+				Pablo::remove_liquidity(RuntimeOrigin::signed(BOB), pool_id, 0, BTreeMap::new()),
+				Error::<Test>::MustBeOwner
+			);
+			assert_ok!(Pablo::remove_liquidity(
+				RuntimeOrigin::signed(ALICE),
+				pool_id,
+				0,
+				BTreeMap::from([(USDC, INITIAL_COLLATERAL_AMOUNT)]),
+			));
+
+			assert_eq!(Tokens::balance(PICA::ID, &ALICE), INITIAL_PROJECT_AMOUNT);
+			assert_eq!(Tokens::balance(USDC, &ALICE), INITIAL_COLLATERAL_AMOUNT);
+			assert_eq!(Tokens::balance(PICA::ID, &pool_account), 0);
+			assert_eq!(Tokens::balance(USDC, &pool_account), 0);
+		});
+	}
+
+	#[test]
+	fn single_asset_withdrawal_is_not_supported() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			System::set_block_number(SALE_END);
+
+			assert_noop!(
+				Pablo::remove_liquidity_single_asset(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					0,
+					AssetAmount::new(USDC, 0),
+				),
+				Error::<Test>::UnsupportedOperation
+			);
+		});
+	}
+}
+
+mod sale {
+	use super::*;
+
+	#[test]
+	fn weights_should_move_over_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+
+			for (block, project_weight) in [(1, 90), (SALE_START, 90), (60, 70), (SALE_END, 50)] {
+				System::set_block_number(block);
+				assert_eq!(
+					<Pablo as Amm>::assets(pool_id).unwrap(),
+					BTreeMap::from([
+						(PICA::ID, Permill::from_percent(project_weight)),
+						(USDC, Permill::from_percent(100 - project_weight)),
+					])
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn should_swap_with_current_weights() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			let amount = INITIAL_COLLATERAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDC, &BOB, amount));
+
+			System::set_block_number(60);
+			let expected = compute_out_given_in(
+				Permill::from_percent(30),
+				Permill::from_percent(70),
+				INITIAL_COLLATERAL_AMOUNT,
+				INITIAL_PROJECT_AMOUNT,
+				amount,
+				Permill::from_perthousand(1),
+			)
+			.unwrap();
+			let swap_result = <Pablo as Amm>::do_swap(
+				&BOB,
+				pool_id,
+				AssetAmount::new(USDC, amount),
+				AssetAmount::new(PICA::ID, 0),
+				false,
+			)
+			.unwrap();
+
+			assert_eq!(swap_result.value.amount, expected.value);
+			assert_eq!(swap_result.fee.amount, expected.fee);
+			assert_eq!(Tokens::balance(PICA::ID, &BOB), expected.value);
+		});
+	}
+
+	#[test]
+	fn project_token_price_should_decrease_over_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			let price_at = |block| {
+				System::set_block_number(block);
+				<Pablo as Amm>::spot_price(pool_id, AssetAmount::new(PICA::ID, 1_000), USDC, false)
+					.unwrap()
+					.value
+					.amount
+			};
+
+			let start_price = price_at(SALE_START);
+			let mid_price = price_at(60);
+			let end_price = price_at(SALE_END);
+			assert!(start_price > mid_price);
+			assert!(mid_price > end_price);
+		});
+	}
+
+	#[test]
+	fn exchange_rate_should_be_weighted_by_current_weights() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			let rate_at = |block| {
+				System::set_block_number(block);
+				Pablo::do_get_exchange_rate(pool_id, crate::PriceRatio::NotSwapped).unwrap()
+			};
+
+			// USDC in PICA: (1_000 PICA / 90%) / (100 USDC / 10%)
+			let expected_start_rate = FixedU128::saturating_from_rational(10_u128, 9_u128);
+			assert!(
+				rate_at(SALE_START).into_inner().abs_diff(expected_start_rate.into_inner()) <= 1
+			);
+			// equal weights at the end of the sale
+			assert_eq!(rate_at(SALE_END), FixedU128::saturating_from_integer(10_u128));
+		});
+	}
+
+	#[test]
+	fn should_not_trade_outside_of_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Close);
+			let amount = INITIAL_COLLATERAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDC, &BOB, amount));
+
+			for block in [SALE_START - 1, SALE_END] {
+				System::set_block_number(block);
+				assert_noop!(
+					<Pablo as Amm>::do_swap(
+						&BOB,
+						pool_id,
+						AssetAmount::new(USDC, amount),
+						AssetAmount::new(PICA::ID, 0),
+						false,
+					),
+					Error::<Test>::InvalidSaleState
+				);
+				assert_noop!(
+					<Pablo as Amm>::do_buy(
+						&BOB,
+						pool_id,
+						USDC,
+						AssetAmount::new(PICA::ID, amount),
+						false,
+					),
+					Error::<Test>::InvalidSaleState
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn should_keep_trading_with_final_weights_if_configured() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(SaleEndBehaviour::Continue);
+			let amount = INITIAL_COLLATERAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDC, &BOB, amount));
+
+			System::set_block_number(SALE_END + 100);
+			let expected = compute_out_given_in(
+				Permill::from_percent(50),
+				Permill::from_percent(50),
+				INITIAL_COLLATERAL_AMOUNT,
+				INITIAL_PROJECT_AMOUNT,
+				amount,
+				Permill::from_perthousand(1),
+			)
+			.unwrap();
+			let swap_result = <Pablo as Amm>::do_swap(
+				&BOB,
+				pool_id,
+				AssetAmount::new(USDC, amount),
+				AssetAmount::new(PICA::ID, 0),
+				false,
+			)
+			.unwrap();
+
+			assert_eq!(swap_result.value.amount, expected.value);
+		});
+	}
+}
@@ -1,4 +1,8 @@
 mod common_test_functions;
+mod concentrated_liquidity_tests;
 mod dual_asset_constant_product_tests;
 mod dual_asset_constant_product_tests_new;
+mod liquidity_bootstrapping_tests;
+mod multi_asset_constant_product_tests;
 mod pablo_tests;
+mod stable_swap_tests;
@@ -0,0 +1,455 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	test::common_test_functions::dual_asset_pool_weights_vec,
+	Error, Event, PoolConfiguration, PoolInitConfiguration,
+};
+use composable_tests_helpers::test::{
+	currency::PICA,
+	helper::{default_acceptable_computation_error, RuntimeTrait},
+};
+use composable_traits::dex::{Amm, AssetAmount};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungibles::{Inspect, Mutate},
+};
+use sp_runtime::Permill;
+use sp_std::collections::btree_map::BTreeMap;
+
+const INITIAL_AMOUNT: Balance = 1_000_000_000_000_000;
+
+fn multi_asset_init_config(
+	assets_weights: Vec<(AssetId, Permill)>,
+) -> PoolInitConfiguration<AccountId, AssetId, BlockNumber> {
+	PoolInitConfiguration::MultiAssetConstantProduct {
+		owner: ALICE,
+		assets_weights,
+		fee: Permill::from_perthousand(1),
+	}
+}
+
+/// PICA 50%, BTC 25%, USDC 25%
+fn three_asset_init_config() -> PoolInitConfiguration<AccountId, AssetId, BlockNumber> {
+	multi_asset_init_config(Vec::from([
+		(PICA::ID, Permill::from_percent(50)),
+		(BTC, Permill::from_percent(25)),
+		(USDC, Permill::from_percent(25)),
+	]))
+}
+
+fn create_pool_with_liquidity(
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
+) -> PoolId {
+	System::set_block_number(1);
+	let pool_id =
+		Test::assert_extrinsic_event_with(Pablo::create(RuntimeOrigin::root(), init_config), |e| {
+			match e {
+				Event::PoolCreated { pool_id, .. } => Some(pool_id),
+				_ => None,
+			}
+		});
+
+	let deposits = <Pablo as Amm>::assets(pool_id)
+		.unwrap()
+		.into_keys()
+		.map(|asset_id| {
+			assert_ok!(Tokens::mint_into(asset_id, &ALICE, INITIAL_AMOUNT));
+			(asset_id, INITIAL_AMOUNT)
+		})
+		.collect::<BTreeMap<_, _>>();
+	assert_ok!(Pablo::add_liquidity(RuntimeOrigin::signed(ALICE), pool_id, deposits, 0, false));
+
+	pool_id
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_create_pool_with_more_than_two_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id =
+				Pablo::do_create_pool(three_asset_init_config(), Some(LP_TOKEN_ID)).unwrap();
+
+			assert_eq!(
+				<Pablo as Amm>::assets(pool_id).unwrap(),
+				BTreeMap::from([
+					(PICA::ID, Permill::from_percent(50)),
+					(BTC, Permill::from_percent(25)),
+					(USDC, Permill::from_percent(25)),
+				])
+			);
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::MultiAssetConstantProduct(pool) => {
+					assert_eq!(pool.lp_token, LP_TOKEN_ID);
+					assert_eq!(pool.fee_config.fee_rate, Permill::from_perthousand(1));
+				},
+				_ => panic!("expected a MultiAssetConstantProduct pool"),
+			}
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_single_asset() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					multi_asset_init_config(Vec::from([(BTC, Permill::one())])),
+					None
+				),
+				Error::<Test>::InvalidPair
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_more_than_max_assets() {
+		new_test_ext().execute_with(|| {
+			let assets_weights = (1..=9)
+				.map(|asset_id| (asset_id, Permill::from_rational(1_u32, 9)))
+				.collect::<Vec<_>>();
+
+			assert_noop!(
+				Pablo::do_create_pool(multi_asset_init_config(assets_weights), None),
+				Error::<Test>::IncorrectPoolConfig
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_invalid_weights() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					multi_asset_init_config(Vec::from([
+						(PICA::ID, Permill::from_percent(50)),
+						(BTC, Permill::from_percent(25)),
+						(USDC, Permill::from_percent(20)),
+					])),
+					None
+				),
+				Error::<Test>::WeightsMustSumToOne
+			);
+			assert_noop!(
+				Pablo::do_create_pool(
+					multi_asset_init_config(Vec::from([
+						(PICA::ID, Permill::from_percent(50)),
+						(BTC, Permill::from_percent(50)),
+						(USDC, Permill::zero()),
+					])),
+					None
+				),
+				Error::<Test>::WeightsMustBeNonZero
+			);
+		});
+	}
+}
+
+mod liquidity {
+	use super::*;
+
+	#[test]
+	fn initial_deposit_must_contain_all_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id =
+				Pablo::do_create_pool(three_asset_init_config(), Some(LP_TOKEN_ID)).unwrap();
+			assert_ok!(Tokens::mint_into(PICA::ID, &ALICE, INITIAL_AMOUNT));
+			assert_ok!(Tokens::mint_into(BTC, &ALICE, INITIAL_AMOUNT));
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					BTreeMap::from([(PICA::ID, INITIAL_AMOUNT), (BTC, INITIAL_AMOUNT)]),
+					0,
+					false,
+				),
+				Error::<Test>::InitialDepositMustContainAllAssets
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_add_liquidity_for_subset_of_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			assert_ok!(Tokens::mint_into(PICA::ID, &BOB, INITIAL_AMOUNT));
+			assert_ok!(Tokens::mint_into(BTC, &BOB, INITIAL_AMOUNT));
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					BTreeMap::from([(PICA::ID, INITIAL_AMOUNT), (BTC, INITIAL_AMOUNT)]),
+					0,
+					false,
+				),
+				Error::<Test>::UnsupportedOperation
+			);
+		});
+	}
+
+	#[test]
+	fn add_balanced_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let alice_lp = Tokens::balance(lp_token, &ALICE);
+
+			let deposit = INITIAL_AMOUNT / 10;
+			for asset_id in [PICA::ID, BTC, USDC] {
+				assert_ok!(Tokens::mint_into(asset_id, &BOB, deposit));
+			}
+			assert_ok!(Pablo::add_liquidity(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				BTreeMap::from([(PICA::ID, deposit), (BTC, deposit), (USDC, deposit)]),
+				0,
+				false,
+			));
+
+			assert_ok!(default_acceptable_computation_error(
+				Tokens::balance(lp_token, &BOB),
+				alice_lp / 10
+			));
+			for asset_id in [PICA::ID, BTC, USDC] {
+				assert_eq!(Tokens::balance(asset_id, &BOB), 0);
+			}
+		});
+	}
+
+	#[test]
+	fn add_single_sided_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+
+			let deposit = INITIAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDC, &BOB, deposit));
+			assert_ok!(Pablo::add_liquidity(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				BTreeMap::from([(USDC, deposit)]),
+				0,
+				false,
+			));
+
+			assert_eq!(Tokens::balance(USDC, &BOB), 0);
+			assert_eq!(
+				Tokens::balance(USDC, &Pablo::account_id(&pool_id)),
+				INITIAL_AMOUNT + deposit
+			);
+			assert!(Tokens::balance(lp_token, &BOB) > 0);
+		});
+	}
+
+	#[test]
+	fn remove_balanced_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let lp_amount = Tokens::balance(lp_token, &ALICE) / 2;
+			let redeemable =
+				<Pablo as Amm>::redeemable_assets_for_lp_tokens(pool_id, lp_amount).unwrap();
+
+			let redeemed =
+				<Pablo as Amm>::remove_liquidity(&ALICE, pool_id, lp_amount, BTreeMap::new())
+					.unwrap();
+
+			assert_eq!(redeemable, redeemed);
+			assert_eq!(redeemed.len(), 3);
+			for (asset_id, amount) in redeemed {
+				assert_ok!(default_acceptable_computation_error(amount, INITIAL_AMOUNT / 2));
+				assert_eq!(Tokens::balance(asset_id, &ALICE), amount);
+			}
+		});
+	}
+}
+
+mod remove_liquidity_single_asset {
+	use super::*;
+
+	#[test]
+	fn should_redeem_single_asset() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let alice_lp = Tokens::balance(lp_token, &ALICE);
+			let lp_amount = alice_lp / 10;
+
+			assert_ok!(Pablo::remove_liquidity_single_asset(
+				RuntimeOrigin::signed(ALICE),
+				pool_id,
+				lp_amount,
+				AssetAmount::new(PICA::ID, 0),
+			));
+
+			// 1 - 0.9^(1 / 0.5) = 0.19 of the balance is redeemed, minus the fee on half of it
+			let received = Tokens::balance(PICA::ID, &ALICE);
+			assert_ok!(default_acceptable_computation_error(
+				received,
+				INITIAL_AMOUNT / 1_000_000 * 189_905
+			));
+			assert_eq!(Tokens::balance(BTC, &ALICE), 0);
+			assert_eq!(Tokens::balance(USDC, &ALICE), 0);
+			assert_eq!(Tokens::balance(lp_token, &ALICE), alice_lp - lp_amount);
+			Test::assert_last_event(Event::LiquidityRemoved {
+				who: ALICE,
+				pool_id,
+				asset_amounts: BTreeMap::from([(PICA::ID, received)]),
+			});
+		});
+	}
+
+	#[test]
+	fn should_redeem_single_asset_from_dual_asset_pool() {
+		new_test_ext().execute_with(|| {
+			let pool_id =
+				create_pool_with_liquidity(PoolInitConfiguration::DualAssetConstantProduct {
+					owner: ALICE,
+					assets_weights: dual_asset_pool_weights_vec(
+						BTC,
+						Permill::from_percent(50),
+						USDC,
+					),
+					fee: Permill::zero(),
+				});
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let lp_amount = Tokens::balance(lp_token, &ALICE) / 10;
+
+			let redeemed = Pablo::do_remove_liquidity_single_asset(
+				&ALICE,
+				pool_id,
+				lp_amount,
+				AssetAmount::new(USDC, 0),
+			)
+			.unwrap();
+
+			assert_ok!(default_acceptable_computation_error(
+				redeemed.amount,
+				INITIAL_AMOUNT / 100 * 19
+			));
+			assert_eq!(Tokens::balance(USDC, &ALICE), redeemed.amount);
+			assert_eq!(Tokens::balance(BTC, &ALICE), 0);
+		});
+	}
+
+	#[test]
+	fn should_respect_min_receive() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let lp_amount = Tokens::balance(lp_token, &ALICE) / 10;
+
+			assert_noop!(
+				Pablo::remove_liquidity_single_asset(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					lp_amount,
+					AssetAmount::new(PICA::ID, INITIAL_AMOUNT / 5),
+				),
+				Error::<Test>::CannotRespectMinimumRequested
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_for_asset_not_in_pool() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let lp_amount = Tokens::balance(lp_token, &ALICE) / 10;
+
+			assert_noop!(
+				Pablo::remove_liquidity_single_asset(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					lp_amount,
+					AssetAmount::new(USDT, 0),
+				),
+				Error::<Test>::AssetNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_be_supported_by_stable_swap_pools() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(
+				PoolInitConfiguration::StableSwap {
+					owner: ALICE,
+					assets: Vec::from([USDT, USDC]),
+					amplification_coefficient: 100,
+					fee: Permill::zero(),
+				},
+				Some(LP_TOKEN_ID),
+			)
+			.unwrap();
+
+			assert_noop!(
+				Pablo::remove_liquidity_single_asset(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					1,
+					AssetAmount::new(USDC, 0),
+				),
+				Error::<Test>::UnsupportedOperation
+			);
+		});
+	}
+}
+
+mod swap {
+	use super::*;
+
+	#[test]
+	fn should_swap_between_any_two_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let pool_account = Pablo::account_id(&pool_id);
+			let amount = INITIAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(BTC, &BOB, amount));
+
+			let expected =
+				<Pablo as Amm>::spot_price(pool_id, AssetAmount::new(BTC, amount), PICA::ID, true)
+					.unwrap();
+			let swap_result = <Pablo as Amm>::do_swap(
+				&BOB,
+				pool_id,
+				AssetAmount::new(BTC, amount),
+				AssetAmount::new(PICA::ID, 0),
+				false,
+			)
+			.unwrap();
+
+			assert_eq!(swap_result, expected);
+			// BTC has half the weight of PICA, so roughly half as much PICA is received
+			assert!(swap_result.value.amount < amount / 2);
+			assert_eq!(Tokens::balance(PICA::ID, &BOB), swap_result.value.amount);
+			assert_eq!(Tokens::balance(BTC, &pool_account), INITIAL_AMOUNT + amount);
+			assert_eq!(Tokens::balance(USDC, &pool_account), INITIAL_AMOUNT);
+		});
+	}
+
+	#[test]
+	fn should_buy_from_pool() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(three_asset_init_config());
+			let amount = INITIAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDC, &BOB, 2 * amount));
+
+			assert_ok!(<Pablo as Amm>::do_buy(
+				&BOB,
+				pool_id,
+				USDC,
+				AssetAmount::new(BTC, amount),
+				false,
+			));
+
+			assert_eq!(Tokens::balance(BTC, &BOB), amount);
+			// both assets have the same weight and balance, so the price is slightly above 1
+			assert!(Tokens::balance(USDC, &BOB) < amount);
+		});
+	}
+}
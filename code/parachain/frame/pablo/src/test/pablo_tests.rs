@@ -35,13 +35,16 @@ mod create {
 mod simulate {
 	use super::*;
 
-	use composable_traits::dex::Amm;
-	use frame_support::traits::fungibles::Mutate;
+	use composable_traits::dex::{Amm, AssetAmount};
+	use frame_support::{
+		assert_noop,
+		traits::fungibles::{Inspect, Mutate},
+	};
 	use sp_runtime::Permill;
 
 	use crate::{
 		mock::{new_test_ext, Pablo, RuntimeOrigin, System, Test, ALICE},
-		Event, PoolInitConfiguration,
+		Error, Event, PoolInitConfiguration,
 	};
 
 	#[test]
@@ -116,4 +119,435 @@ mod simulate {
 			assert_eq!(remove_simulation_result, remove_result);
 		})
 	}
+
+	const INITIAL_AMOUNT: Balance = 1_000_000_000_000_000;
+
+	fn create_pool_with_liquidity() -> PoolId {
+		System::set_block_number(1);
+		let pool_id = Pablo::do_create_pool(
+			PoolInitConfiguration::DualAssetConstantProduct {
+				owner: ALICE,
+				assets_weights: dual_asset_pool_weights_vec(USDT, Permill::from_percent(50), USDC),
+				fee: Permill::from_percent(1),
+			},
+			Some(LP_TOKEN_ID),
+		)
+		.unwrap();
+		assert_ok!(Tokens::mint_into(USDT, &ALICE, INITIAL_AMOUNT));
+		assert_ok!(Tokens::mint_into(USDC, &ALICE, INITIAL_AMOUNT));
+		assert_ok!(Pablo::add_liquidity(
+			RuntimeOrigin::signed(ALICE),
+			pool_id,
+			[(USDT, INITIAL_AMOUNT), (USDC, INITIAL_AMOUNT)].into_iter().collect(),
+			0,
+			false,
+		));
+		pool_id
+	}
+
+	#[test]
+	fn swap_simulation_same_as_actual() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let in_asset = AssetAmount::new(USDT, INITIAL_AMOUNT / 100);
+
+			let simulation = crate::simulate_swap::<Test>(pool_id, in_asset, USDC).unwrap();
+
+			assert_ok!(Tokens::mint_into(USDT, &BOB, in_asset.amount));
+			let fee = Test::assert_extrinsic_event_with(
+				Pablo::swap(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					in_asset,
+					AssetAmount::new(USDC, 0),
+					false,
+				),
+				|e| match e {
+					Event::Swapped { fee, .. } => Some(fee),
+					_ => None,
+				},
+			);
+
+			assert_eq!(simulation.amount_in, in_asset);
+			assert_eq!(simulation.amount_out, AssetAmount::new(USDC, Tokens::balance(USDC, &BOB)));
+			assert_eq!(simulation.fee, fee);
+		});
+	}
+
+	#[test]
+	fn buy_simulation_same_as_actual() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let out_asset = AssetAmount::new(USDC, INITIAL_AMOUNT / 100);
+
+			let simulation = crate::simulate_buy::<Test>(pool_id, USDT, out_asset).unwrap();
+
+			assert_ok!(Tokens::mint_into(USDT, &BOB, INITIAL_AMOUNT));
+			let fee = Test::assert_extrinsic_event_with(
+				Pablo::buy(RuntimeOrigin::signed(BOB), pool_id, USDT, out_asset, false),
+				|e| match e {
+					Event::Swapped { fee, .. } => Some(fee),
+					_ => None,
+				},
+			);
+
+			assert_eq!(
+				simulation.amount_in,
+				AssetAmount::new(USDT, INITIAL_AMOUNT - Tokens::balance(USDT, &BOB))
+			);
+			assert_eq!(simulation.amount_out, out_asset);
+			assert_eq!(simulation.fee, fee);
+		});
+	}
+
+	#[test]
+	fn price_impact_grows_with_the_amount_swapped() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let price_impact = |amount| {
+				crate::simulate_swap::<Test>(pool_id, AssetAmount::new(USDT, amount), USDC)
+					.unwrap()
+					.price_impact
+			};
+
+			assert!(price_impact(INITIAL_AMOUNT / 1_000_000) < Permill::from_perthousand(1));
+			// swapping x, fees excluded, in a constant product pool of reserve r has a price impact
+			// of x / (r + x), i.e. about 9% here
+			let price_impact_of_ten_percent = price_impact(INITIAL_AMOUNT / 10);
+			assert!(price_impact_of_ten_percent > Permill::from_rational(85_u32, 1_000));
+			assert!(price_impact_of_ten_percent < Permill::from_rational(95_u32, 1_000));
+		});
+	}
+
+	#[test]
+	fn liquidity_simulations_report_fees_and_price_impact() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let amount = INITIAL_AMOUNT / 10;
+			assert_ok!(Tokens::mint_into(USDT, &BOB, amount));
+			assert_ok!(Tokens::mint_into(USDC, &BOB, amount));
+
+			let balanced = crate::simulate_add_liquidity::<Test>(
+				&BOB,
+				pool_id,
+				[(USDT, amount), (USDC, amount)].into_iter().collect(),
+			)
+			.unwrap();
+			assert_eq!(balanced.assets, [(USDT, amount), (USDC, amount)].into_iter().collect());
+			assert_eq!(balanced.fee, None);
+			assert!(balanced.price_impact < Permill::from_perthousand(1));
+
+			let single = crate::simulate_add_liquidity::<Test>(
+				&BOB,
+				pool_id,
+				[(USDT, amount)].into_iter().collect(),
+			)
+			.unwrap();
+			let fee = single.fee.unwrap();
+			assert_eq!(fee.asset_id, USDT);
+			assert!(fee.fee > 0);
+			assert!(single.lp_amount < balanced.lp_amount);
+			// depositing a single asset swaps half of it for the other one
+			assert!(single.price_impact > Permill::from_percent(1));
+
+			let lp_amount = Tokens::balance(LP_TOKEN_ID, &ALICE) / 2;
+			let removal = crate::simulate_remove_liquidity::<Test>(
+				&ALICE,
+				pool_id,
+				lp_amount,
+				[(USDT, 0), (USDC, 0)].into_iter().collect(),
+			)
+			.unwrap();
+			assert_eq!(removal.lp_amount, lp_amount);
+			assert!(removal.assets[&USDT] > 0);
+			assert_eq!(removal.fee, None);
+			assert!(removal.price_impact < Permill::from_perthousand(1));
+
+			// nothing was actually deposited nor withdrawn
+			assert_eq!(Tokens::balance(USDT, &BOB), amount);
+			assert_eq!(Tokens::balance(USDC, &BOB), amount);
+			assert_eq!(Tokens::balance(USDT, &ALICE), 0);
+		});
+	}
+
+	#[test]
+	fn simulations_fail_like_actual_exchanges() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+
+			assert_noop!(
+				crate::simulate_swap::<Test>(pool_id, AssetAmount::new(USDT, 1_000), USDT),
+				Error::<Test>::CannotSwapSameAsset
+			);
+			assert_noop!(
+				crate::simulate_buy::<Test>(pool_id, USDC, AssetAmount::new(USDC, 1_000)),
+				Error::<Test>::CannotBuyAssetWithItself
+			);
+			assert_noop!(
+				crate::simulate_swap::<Test>(pool_id + 1, AssetAmount::new(USDT, 1_000), USDC),
+				Error::<Test>::PoolNotFound
+			);
+			assert_noop!(
+				crate::simulate_add_liquidity::<Test>(
+					&BOB,
+					pool_id,
+					[(USDT, 1_000), (LP_TOKEN_ID, 1_000)].into_iter().collect(),
+				),
+				Error::<Test>::AssetNotFound
+			);
+			assert_noop!(
+				crate::simulate_remove_liquidity::<Test>(
+					&ALICE,
+					pool_id,
+					1_000,
+					[(USDT, INITIAL_AMOUNT)].into_iter().collect(),
+				),
+				Error::<Test>::CannotRespectMinimumRequested
+			);
+		});
+	}
+
+	#[test]
+	fn liquidity_simulations_do_not_need_funds() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let amount = INITIAL_AMOUNT / 10;
+			assert_eq!(Tokens::balance(USDT, &BOB), 0);
+			assert_eq!(Tokens::balance(LP_TOKEN_ID, &BOB), 0);
+
+			let deposit = crate::simulate_add_liquidity::<Test>(
+				&BOB,
+				pool_id,
+				[(USDT, amount), (USDC, amount)].into_iter().collect(),
+			)
+			.unwrap();
+			assert!(deposit.lp_amount > 0);
+
+			let withdrawal = crate::simulate_remove_liquidity::<Test>(
+				&BOB,
+				pool_id,
+				deposit.lp_amount,
+				[(USDT, 0), (USDC, 0)].into_iter().collect(),
+			)
+			.unwrap();
+			assert!(withdrawal.assets[&USDT] > 0);
+			assert!(withdrawal.assets[&USDC] > 0);
+		});
+	}
+}
+
+mod fees {
+	use super::*;
+
+	use composable_traits::dex::{Amm, AssetAmount, Fee, FeeConfig};
+	use frame_support::{
+		assert_noop,
+		traits::fungibles::{Inspect, Mutate},
+	};
+	use sp_runtime::{DispatchError, PerThing};
+
+	use crate::{Error, Event, OwnerFees, PoolInitConfiguration, ProtocolFees};
+
+	const INITIAL_AMOUNT: Balance = 1_000_000_000_000_000;
+	const SWAP_AMOUNT: Balance = INITIAL_AMOUNT / 100;
+	const TREASURY: AccountId = 42;
+
+	fn fee_config() -> FeeConfig {
+		FeeConfig {
+			fee_rate: Permill::from_percent(1),
+			owner_fee_rate: Permill::from_percent(20),
+			protocol_fee_rate: Permill::from_percent(50),
+		}
+	}
+
+	fn create_pool_with_liquidity() -> PoolId {
+		System::set_block_number(1);
+		let pool_id = Pablo::do_create_pool(
+			PoolInitConfiguration::DualAssetConstantProduct {
+				owner: ALICE,
+				assets_weights: dual_asset_pool_weights_vec(USDT, Permill::from_percent(50), USDC),
+				fee: Permill::from_percent(1),
+			},
+			Some(LP_TOKEN_ID),
+		)
+		.unwrap();
+		assert_ok!(Pablo::update_fee_config(RuntimeOrigin::root(), pool_id, fee_config()));
+
+		assert_ok!(Tokens::mint_into(USDT, &ALICE, INITIAL_AMOUNT));
+		assert_ok!(Tokens::mint_into(USDC, &ALICE, INITIAL_AMOUNT));
+		assert_ok!(Pablo::add_liquidity(
+			RuntimeOrigin::signed(ALICE),
+			pool_id,
+			[(USDT, INITIAL_AMOUNT), (USDC, INITIAL_AMOUNT)].into_iter().collect(),
+			0,
+			false,
+		));
+		pool_id
+	}
+
+	fn swap(pool_id: PoolId) -> Fee<AssetId, Balance> {
+		assert_ok!(Tokens::mint_into(USDT, &BOB, SWAP_AMOUNT));
+		Test::assert_extrinsic_event_with(
+			Pablo::swap(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				AssetAmount::new(USDT, SWAP_AMOUNT),
+				AssetAmount::new(USDC, 0),
+				false,
+			),
+			|e| match e {
+				Event::Swapped { fee, .. } => Some(fee),
+				_ => None,
+			},
+		)
+	}
+
+	#[test]
+	fn should_update_fee_config() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let new_fee_config = FeeConfig { fee_rate: Permill::from_percent(2), ..fee_config() };
+
+			assert_noop!(
+				Pablo::update_fee_config(RuntimeOrigin::signed(ALICE), pool_id, new_fee_config),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Pablo::update_fee_config(
+					RuntimeOrigin::root(),
+					pool_id,
+					FeeConfig::default_from(Permill::one())
+				),
+				Error::<Test>::InvalidFees
+			);
+			assert_noop!(
+				Pablo::update_fee_config(
+					RuntimeOrigin::root(),
+					pool_id,
+					FeeConfig {
+						owner_fee_rate: Permill::from_percent(60),
+						protocol_fee_rate: Permill::from_percent(50),
+						..new_fee_config
+					}
+				),
+				Error::<Test>::InvalidFees
+			);
+			assert_noop!(
+				Pablo::update_fee_config(RuntimeOrigin::root(), pool_id + 1, new_fee_config),
+				Error::<Test>::PoolNotFound
+			);
+
+			Test::assert_extrinsic_event(
+				Pablo::update_fee_config(RuntimeOrigin::root(), pool_id, new_fee_config),
+				Event::FeeConfigUpdated { pool_id, fee_config: new_fee_config },
+			);
+			assert_eq!(*Pablo::pools(pool_id).unwrap().fee_config_mut(), new_fee_config);
+			let fee = swap(pool_id).fee;
+			assert!(fee.abs_diff(Permill::from_percent(2).mul_floor(SWAP_AMOUNT)) <= 1);
+		});
+	}
+
+	#[test]
+	fn should_accrue_fees_outside_of_pool_reserves() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let fee = swap(pool_id);
+			assert!(fee.owner_fee > 0 && fee.protocol_fee > 0);
+			assert_eq!(OwnerFees::<Test>::get(pool_id, USDT), Some(fee.owner_fee));
+			assert_eq!(ProtocolFees::<Test>::get(pool_id, USDT), Some(fee.protocol_fee));
+			assert_eq!(OwnerFees::<Test>::get(pool_id, USDC), None);
+
+			// liquidity providers can't withdraw the accrued fees
+			let lp_amount = Tokens::balance(LP_TOKEN_ID, &ALICE);
+			let redeemable =
+				<Pablo as Amm>::redeemable_assets_for_lp_tokens(pool_id, lp_amount).unwrap();
+			assert!(
+				redeemable[&USDT] <=
+					Tokens::balance(USDT, &Pablo::account_id(&pool_id)) -
+						fee.owner_fee - fee.protocol_fee
+			);
+		});
+	}
+
+	#[test]
+	fn should_accrue_fees_of_single_asset_removal() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let fee = swap(pool_id);
+
+			assert_ok!(Pablo::remove_liquidity_single_asset(
+				RuntimeOrigin::signed(ALICE),
+				pool_id,
+				Tokens::balance(LP_TOKEN_ID, &ALICE) / 10,
+				AssetAmount::new(USDT, 0),
+			));
+			assert!(OwnerFees::<Test>::get(pool_id, USDT).unwrap() > fee.owner_fee);
+			assert!(ProtocolFees::<Test>::get(pool_id, USDT).unwrap() > fee.protocol_fee);
+			assert_eq!(OwnerFees::<Test>::get(pool_id, USDC), None);
+		});
+	}
+
+	#[test]
+	fn should_claim_fees() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let owner_fee = swap(pool_id).owner_fee + swap(pool_id).owner_fee;
+			let protocol_fee = ProtocolFees::<Test>::get(pool_id, USDT).unwrap();
+
+			assert_noop!(
+				Pablo::claim_fees(RuntimeOrigin::signed(BOB), pool_id, BOB),
+				Error::<Test>::MustBeOwner
+			);
+
+			Test::assert_extrinsic_event(
+				Pablo::claim_fees(RuntimeOrigin::signed(ALICE), pool_id, CHARLIE),
+				Event::OwnerFeesClaimed {
+					pool_id,
+					owner: ALICE,
+					beneficiary: CHARLIE,
+					fees: [(USDT, owner_fee)].into_iter().collect(),
+				},
+			);
+			assert_eq!(Tokens::balance(USDT, &CHARLIE), owner_fee);
+			assert_eq!(OwnerFees::<Test>::get(pool_id, USDT), None);
+			assert_noop!(
+				Pablo::claim_fees(RuntimeOrigin::signed(ALICE), pool_id, CHARLIE),
+				Error::<Test>::NoFeesToClaim
+			);
+
+			Test::assert_extrinsic_event(
+				Pablo::claim_fees(RuntimeOrigin::root(), pool_id, TREASURY),
+				Event::ProtocolFeesClaimed {
+					pool_id,
+					beneficiary: TREASURY,
+					fees: [(USDT, protocol_fee)].into_iter().collect(),
+				},
+			);
+			assert_eq!(Tokens::balance(USDT, &TREASURY), protocol_fee);
+			assert_eq!(ProtocolFees::<Test>::get(pool_id, USDT), None);
+		});
+	}
+
+	#[test]
+	fn should_keep_fees_claimable_once_liquidity_is_removed() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity();
+			let fee = swap(pool_id);
+
+			assert_ok!(Pablo::remove_liquidity(
+				RuntimeOrigin::signed(ALICE),
+				pool_id,
+				Tokens::balance(LP_TOKEN_ID, &ALICE),
+				[(USDT, 0), (USDC, 0)].into_iter().collect(),
+			));
+			assert_eq!(
+				Tokens::balance(USDT, &Pablo::account_id(&pool_id)),
+				fee.owner_fee + fee.protocol_fee
+			);
+
+			assert_ok!(Pablo::claim_fees(RuntimeOrigin::signed(ALICE), pool_id, ALICE));
+			assert_ok!(Pablo::claim_fees(RuntimeOrigin::root(), pool_id, TREASURY));
+			assert_eq!(Tokens::balance(USDT, &Pablo::account_id(&pool_id)), 0);
+		});
+	}
 }
@@ -0,0 +1,291 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	test::common_test_functions::dual_asset_pool_weights_vec,
+	Error, Event, PoolConfiguration, PoolInitConfiguration,
+};
+use composable_tests_helpers::test::helper::RuntimeTrait;
+use composable_traits::dex::{Amm, AssetAmount};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungibles::{Inspect, Mutate},
+};
+use sp_runtime::{FixedPointNumber, FixedU128, Permill};
+use sp_std::collections::btree_map::BTreeMap;
+
+const INITIAL_AMOUNT: Balance = 1_000_000_000_000_000;
+
+fn stable_swap_init_config(
+	amplification_coefficient: u16,
+) -> PoolInitConfiguration<AccountId, AssetId, BlockNumber> {
+	PoolInitConfiguration::StableSwap {
+		owner: ALICE,
+		assets: Vec::from([USDT, USDC]),
+		amplification_coefficient,
+		fee: Permill::from_perthousand(1),
+	}
+}
+
+fn create_pool_with_liquidity(
+	init_config: PoolInitConfiguration<AccountId, AssetId, BlockNumber>,
+) -> PoolId {
+	System::set_block_number(1);
+	let pool_id =
+		Test::assert_extrinsic_event_with(Pablo::create(RuntimeOrigin::root(), init_config), |e| {
+			match e {
+				Event::PoolCreated { pool_id, .. } => Some(pool_id),
+				_ => None,
+			}
+		});
+
+	assert_ok!(Tokens::mint_into(USDT, &ALICE, INITIAL_AMOUNT));
+	assert_ok!(Tokens::mint_into(USDC, &ALICE, INITIAL_AMOUNT));
+	assert_ok!(Pablo::add_liquidity(
+		RuntimeOrigin::signed(ALICE),
+		pool_id,
+		BTreeMap::from([(USDT, INITIAL_AMOUNT), (USDC, INITIAL_AMOUNT)]),
+		0,
+		false,
+	));
+
+	pool_id
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_create_pool_with_equal_weights() {
+		new_test_ext().execute_with(|| {
+			let pool_id =
+				Pablo::do_create_pool(stable_swap_init_config(100), Some(LP_TOKEN_ID)).unwrap();
+
+			assert_eq!(
+				<Pablo as Amm>::assets(pool_id).unwrap(),
+				BTreeMap::from([
+					(USDT, Permill::from_percent(50)),
+					(USDC, Permill::from_percent(50))
+				])
+			);
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::StableSwap(pool) => {
+					assert_eq!(pool.amplification_coefficient, 100);
+					assert_eq!(pool.pool_info.lp_token, LP_TOKEN_ID);
+				},
+				_ => panic!("expected a StableSwap pool"),
+			}
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_zero_amplification_coefficient() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(stable_swap_init_config(0), None),
+				Error::<Test>::AmpFactorMustBeGreaterThanZero
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_create_pool_with_single_asset() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					PoolInitConfiguration::StableSwap {
+						owner: ALICE,
+						assets: Vec::from([USDT, USDT]),
+						amplification_coefficient: 100,
+						fee: Permill::zero(),
+					},
+					None
+				),
+				Error::<Test>::InvalidPair
+			);
+		});
+	}
+}
+
+mod liquidity {
+	use super::*;
+
+	#[test]
+	fn initial_deposit_must_contain_all_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id =
+				Pablo::do_create_pool(stable_swap_init_config(100), Some(LP_TOKEN_ID)).unwrap();
+			assert_ok!(Tokens::mint_into(USDT, &ALICE, INITIAL_AMOUNT));
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					BTreeMap::from([(USDT, INITIAL_AMOUNT)]),
+					0,
+					false,
+				),
+				Error::<Test>::InitialDepositMustContainAllAssets
+			);
+		});
+	}
+
+	#[test]
+	fn add_single_sided_and_remove_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(stable_swap_init_config(100));
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			assert_eq!(Tokens::balance(lp_token, &ALICE), 2 * INITIAL_AMOUNT);
+
+			let deposit = INITIAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDT, &BOB, deposit));
+			assert_ok!(Pablo::add_liquidity(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				BTreeMap::from([(USDT, deposit)]),
+				0,
+				false,
+			));
+
+			// a small single sided deposit into a balanced pool is worth almost its face value
+			let lp = Tokens::balance(lp_token, &BOB);
+			assert!(lp < deposit);
+			assert!(lp > deposit * 999 / 1000);
+
+			let redeemed =
+				<Pablo as Amm>::remove_liquidity(&BOB, pool_id, lp, BTreeMap::new()).unwrap();
+			assert_eq!(Tokens::balance(lp_token, &BOB), 0);
+			let total_redeemed: Balance = redeemed.values().sum();
+			assert!(total_redeemed < deposit);
+			assert!(total_redeemed > deposit * 999 / 1000);
+		});
+	}
+}
+
+mod swap {
+	use super::*;
+
+	#[test]
+	fn should_have_less_slippage_than_constant_product() {
+		new_test_ext().execute_with(|| {
+			let stable_pool_id = create_pool_with_liquidity(stable_swap_init_config(100));
+			let constant_product_pool_id =
+				create_pool_with_liquidity(PoolInitConfiguration::DualAssetConstantProduct {
+					owner: ALICE,
+					assets_weights: dual_asset_pool_weights_vec(
+						USDT,
+						Permill::from_percent(50),
+						USDC,
+					),
+					fee: Permill::from_perthousand(1),
+				});
+
+			let in_asset = AssetAmount::new(USDT, INITIAL_AMOUNT / 10);
+			let stable_out =
+				<Pablo as Amm>::spot_price(stable_pool_id, in_asset, USDC, true).unwrap();
+			let constant_product_out =
+				<Pablo as Amm>::spot_price(constant_product_pool_id, in_asset, USDC, true).unwrap();
+
+			assert!(stable_out.value.amount > constant_product_out.value.amount);
+			assert!(stable_out.value.amount > in_asset.amount * 99 / 100);
+			assert_eq!(stable_out.fee, constant_product_out.fee);
+		});
+	}
+
+	#[test]
+	fn exchange_rate_should_follow_the_invariant() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(stable_swap_init_config(100));
+			let rate =
+				|| Pablo::do_get_exchange_rate(pool_id, crate::PriceRatio::NotSwapped).unwrap();
+			assert_eq!(rate(), FixedU128::one());
+
+			let amount = INITIAL_AMOUNT / 2;
+			assert_ok!(Tokens::mint_into(USDT, &BOB, amount));
+			<Pablo as Amm>::do_swap(
+				&BOB,
+				pool_id,
+				AssetAmount::new(USDT, amount),
+				AssetAmount::new(USDC, 0),
+				false,
+			)
+			.unwrap();
+
+			// the price moves away from one, but far less than the ratio of the balances
+			let pool_account = Pablo::account_id(&pool_id);
+			let usdt_balance = Tokens::balance(USDT, &pool_account);
+			let usdc_balance = Tokens::balance(USDC, &pool_account);
+			let balance_ratios = [
+				FixedU128::saturating_from_rational(usdt_balance, usdc_balance),
+				FixedU128::saturating_from_rational(usdc_balance, usdt_balance),
+			];
+			let rate = rate();
+			assert_ne!(rate, FixedU128::one());
+			if rate > FixedU128::one() {
+				assert!(rate < balance_ratios[0].max(balance_ratios[1]));
+			} else {
+				assert!(rate > balance_ratios[0].min(balance_ratios[1]));
+			}
+			assert_eq!(
+				Pablo::do_get_exchange_rate(pool_id, crate::PriceRatio::Swapped)
+					.unwrap()
+					.saturating_mul(rate)
+					.round(),
+				FixedU128::one()
+			);
+		});
+	}
+
+	#[test]
+	fn swap_and_buy() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(stable_swap_init_config(100));
+			let amount = INITIAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDT, &BOB, 3 * amount));
+
+			let swap_result = <Pablo as Amm>::do_swap(
+				&BOB,
+				pool_id,
+				AssetAmount::new(USDT, amount),
+				AssetAmount::new(USDC, 0),
+				false,
+			)
+			.unwrap();
+			assert_eq!(Tokens::balance(USDC, &BOB), swap_result.value.amount);
+			assert_eq!(Tokens::balance(USDT, &BOB), 2 * amount);
+
+			let buy_result = <Pablo as Amm>::do_buy(
+				&BOB,
+				pool_id,
+				USDT,
+				AssetAmount::new(USDC, swap_result.value.amount),
+				false,
+			)
+			.unwrap();
+			assert_eq!(buy_result.value, AssetAmount::new(USDC, swap_result.value.amount));
+			assert_eq!(Tokens::balance(USDC, &BOB), 2 * swap_result.value.amount);
+			// buying the same amount again costs more as the pool is now imbalanced
+			assert!(Tokens::balance(USDT, &BOB) < amount);
+		});
+	}
+
+	#[test]
+	fn should_respect_min_receive() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(stable_swap_init_config(100));
+			let amount = INITIAL_AMOUNT / 100;
+			assert_ok!(Tokens::mint_into(USDT, &BOB, amount));
+
+			assert_noop!(
+				Pablo::swap(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDT, amount),
+					AssetAmount::new(USDC, amount),
+					false,
+				),
+				Error::<Test>::CannotRespectMinimumRequested
+			);
+		});
+	}
+}
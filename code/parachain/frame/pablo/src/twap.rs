@@ -1,13 +1,15 @@
 use crate::{
 	types::{PriceCumulative, TimeWeightedAveragePrice},
-	Config, MomentOf, PriceCumulativeStateOf, PriceRatio, TWAPStateOf,
+	Config, Error, MomentOf, PriceCumulativeState, PriceCumulativeStateOf, PriceRatio,
+	TWAPObservations, TWAPStateOf,
 };
 use composable_maths::dex::price::{compute_next_price_cumulative, compute_twap};
+use composable_support::math::safe::safe_multiply_by_rational;
 use composable_traits::defi::Rate;
 use frame_support::{pallet_prelude::*, traits::Time};
 use sp_runtime::{
-	traits::{Saturating, Zero},
-	DispatchError,
+	traits::{CheckedSub, Convert, ConvertInto, Saturating, Zero},
+	DispatchError, SaturatedConversion,
 };
 
 pub(crate) fn get_next_price_cumulative<T: Config>(
@@ -106,3 +108,95 @@ pub(crate) fn update_twap_state<T: Config>(
 	}
 	Ok(())
 }
+
+/// Records `price_cumulative` as the latest observation of the pool, unless the previous one is
+/// less than `TWAPObservationInterval` old. Once `MaxTWAPObservations` are recorded, the oldest
+/// observation is overwritten.
+pub(crate) fn record_observation<T: Config>(
+	pool_id: T::PoolId,
+	price_cumulative: &PriceCumulativeStateOf<T>,
+) {
+	TWAPObservations::<T>::mutate(pool_id, |observations| {
+		let observations = observations.get_or_insert_with(Default::default);
+		if let Some(latest) = observations.last() {
+			if price_cumulative.timestamp <
+				latest.timestamp.saturating_add(T::TWAPObservationInterval::get())
+			{
+				return
+			}
+		}
+		if !observations.is_empty() && observations.len() >= T::MaxTWAPObservations::get() as usize
+		{
+			observations.remove(0);
+		}
+		// can only fail if `MaxTWAPObservations` is zero, in which case nothing is recorded
+		let _ = observations.try_push(price_cumulative.clone());
+	});
+}
+
+/// Computes the TWAP of the pool over the last `window`, the cumulative price at the start of the
+/// window being linearly interpolated between the two observations surrounding it.
+///
+/// `price_ratio` selects the `base_twap` (`NotSwapped`) or `quote_twap` (`Swapped`) of the pool.
+pub(crate) fn twap_over_window<T: Config>(
+	pool_id: T::PoolId,
+	price_ratio: PriceRatio,
+	window: MomentOf<T>,
+) -> Result<Rate, DispatchError> {
+	ensure!(!window.is_zero(), Error::<T>::InvalidTwapWindow);
+	let price_cumulative =
+		PriceCumulativeState::<T>::get(pool_id).ok_or(Error::<T>::TwapNotEnabled)?;
+	let current_timestamp = T::Time::now();
+	let start = current_timestamp.checked_sub(&window).ok_or(Error::<T>::TwapWindowNotCovered)?;
+	let cumulative_of = |observation: &PriceCumulativeStateOf<T>| -> u128 {
+		<T::Convert as Convert<T::Balance, u128>>::convert(match price_ratio {
+			PriceRatio::NotSwapped => observation.base_price_cumulative,
+			PriceRatio::Swapped => observation.quote_price_cumulative,
+		})
+	};
+
+	// the price cumulative state may be more recent than the latest observation, and both are
+	// extended up to now with the current price.
+	let current_price_cumulative = if current_timestamp > price_cumulative.timestamp {
+		let (base_price_cumulative, quote_price_cumulative) =
+			get_next_price_cumulative::<T>(pool_id, &price_cumulative)?;
+		PriceCumulative {
+			timestamp: current_timestamp,
+			base_price_cumulative,
+			quote_price_cumulative,
+		}
+	} else {
+		price_cumulative.clone()
+	};
+	let mut observations = TWAPObservations::<T>::get(pool_id)
+		.map(BoundedVec::into_inner)
+		.unwrap_or_default();
+	observations.push(price_cumulative);
+	observations.push(current_price_cumulative.clone());
+
+	let before_index = observations
+		.iter()
+		.rposition(|observation| observation.timestamp <= start)
+		.ok_or(Error::<T>::TwapWindowNotCovered)?;
+	let before = observations.get(before_index).ok_or(Error::<T>::TwapWindowNotCovered)?;
+	let start_cumulative = if before.timestamp == start {
+		cumulative_of(before)
+	} else {
+		// as `start` is in the past, a more recent observation always exists
+		let after = observations.get(before_index + 1).ok_or(Error::<T>::TwapWindowNotCovered)?;
+		let elapsed = start.saturating_sub(before.timestamp).saturated_into::<u128>();
+		let interval = after.timestamp.saturating_sub(before.timestamp).saturated_into::<u128>();
+		let increase = safe_multiply_by_rational(
+			cumulative_of(after).saturating_sub(cumulative_of(before)),
+			elapsed,
+			interval,
+		)?;
+		cumulative_of(before).saturating_add(increase)
+	};
+
+	compute_twap::<ConvertInto, u128, _>(
+		cumulative_of(&current_price_cumulative),
+		start_cumulative,
+		window,
+	)
+}
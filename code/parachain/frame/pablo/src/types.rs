@@ -1,7 +1,7 @@
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, MaxEncodedLen};
 use composable_traits::defi::Rate;
 use scale_info::TypeInfo;
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{FixedPointNumber, FixedU128, RuntimeDebug};
 
 #[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
 pub struct TimeWeightedAveragePrice<Timestamp, Balance> {
@@ -11,9 +11,86 @@ pub struct TimeWeightedAveragePrice<Timestamp, Balance> {
 	pub base_twap: Rate,
 	pub quote_twap: Rate,
 }
-#[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
 pub struct PriceCumulative<Timestamp, Balance> {
 	pub timestamp: Timestamp,
 	pub base_price_cumulative: Balance,
 	pub quote_price_cumulative: Balance,
 }
+
+/// Fee growth per unit of liquidity, for each asset of a concentrated liquidity pool.
+///
+/// Only differences between two values are meaningful, arithmetic on them wraps around.
+#[derive(
+	Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug,
+)]
+pub struct FeeGrowth {
+	pub base: FixedU128,
+	pub quote: FixedU128,
+}
+
+impl FeeGrowth {
+	pub fn wrapping_add(self, other: Self) -> Self {
+		Self {
+			base: FixedU128::from_inner(
+				self.base.into_inner().wrapping_add(other.base.into_inner()),
+			),
+			quote: FixedU128::from_inner(
+				self.quote.into_inner().wrapping_add(other.quote.into_inner()),
+			),
+		}
+	}
+
+	pub fn wrapping_sub(self, other: Self) -> Self {
+		Self {
+			base: FixedU128::from_inner(
+				self.base.into_inner().wrapping_sub(other.base.into_inner()),
+			),
+			quote: FixedU128::from_inner(
+				self.quote.into_inner().wrapping_sub(other.quote.into_inner()),
+			),
+		}
+	}
+}
+
+/// An initialized tick of a concentrated liquidity pool, i.e. a tick used as a bound by at least
+/// one position.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct TickInfo {
+	/// Total liquidity of the positions bounded by this tick.
+	pub liquidity_gross: u128,
+	/// Liquidity added to the pool when the tick is crossed upwards, removed when crossed
+	/// downwards.
+	pub liquidity_net: i128,
+	/// Fee growth on the other side of this tick, relative to the current tick of the pool.
+	pub fee_growth_outside: FeeGrowth,
+}
+
+/// Swap state of a concentrated liquidity pool.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ConcentratedLiquidityState {
+	/// Square root of the current price, in quote per base.
+	pub sqrt_price: FixedU128,
+	/// Greatest tick whose price is lower than or equal to the current price.
+	pub tick: i32,
+	/// Liquidity of the positions in range.
+	pub liquidity: u128,
+	/// Fee growth of the pool since its creation.
+	pub fee_growth_global: FeeGrowth,
+}
+
+/// A position of a concentrated liquidity pool, owned by the holder of the matching financial
+/// NFT.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct Position<PoolId> {
+	pub pool_id: PoolId,
+	pub lower_tick: i32,
+	pub upper_tick: i32,
+	pub liquidity: u128,
+	/// Fee growth inside of the range of the position when it was last updated.
+	pub fee_growth_inside_last: FeeGrowth,
+	/// Fees accrued to the position and not collected yet, in base asset.
+	pub fees_owed_base: u128,
+	/// Fees accrued to the position and not collected yet, in quote asset.
+	pub fees_owed_quote: u128,
+}
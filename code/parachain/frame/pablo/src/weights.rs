@@ -13,6 +13,12 @@ pub trait WeightInfo {
 	fn buy() -> Weight;
 	fn swap() -> Weight;
 	fn do_create_pool() -> Weight;
+	fn remove_liquidity_single_asset() -> Weight;
+	fn create_position() -> Weight;
+	fn remove_position_liquidity() -> Weight;
+	fn collect_position_fees() -> Weight;
+	fn update_fee_config() -> Weight;
+	fn claim_fees() -> Weight;
 }
 
 // For backwards compatibility and tests
@@ -34,5 +40,23 @@ impl WeightInfo for () {
   }
 	fn do_create_pool() -> Weight {
     Weight::from_ref_time(10_000 )
+  }
+	fn remove_liquidity_single_asset() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn create_position() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn remove_position_liquidity() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn collect_position_fees() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn update_fee_config() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn claim_fees() -> Weight {
+    Weight::from_ref_time(10_000 )
   }
 }
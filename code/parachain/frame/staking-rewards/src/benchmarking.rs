@@ -229,6 +229,41 @@ benchmarks! {
 
 	}: _(OriginFor::<T>::signed(user), fnft_collection_id, instance_id, ratio)
 
+	merge {
+		let r in 1 .. T::MaxRewardConfigsPerPool::get();
+		let user: T::AccountId = account("user", 0, 0);
+
+		frame_system::Pallet::<T>::set_block_number(1.into());
+		Pallet::<T>::create_reward_pool(
+			OriginFor::<T>::root(),
+			get_reward_pool::<T>(user.clone(), r)
+		).expect("creating reward pool should succeed");
+
+		frame_system::Pallet::<T>::set_block_number(frame_system::Pallet::<T>::current_block_number() + T::BlockNumber::one());
+
+		<T::AssetsTransactor as Mutate<T::AccountId>>::mint_into(
+			BASE_ASSET_ID.into(),
+			&user,
+			100_000_000_000.into(),
+		).expect("minting should succeed");
+
+		let fnft_collection_id = RewardPools::<T>::get(T::AssetId::from(BASE_ASSET_ID))
+			.expect("Pool exists")
+			.financial_nft_asset_id;
+		let instance_id = stake_and_assert::<T>(
+			user.clone(),
+			BASE_ASSET_ID.into(),
+			100_000_000.into(),
+			ONE_HOUR,
+		);
+		let merged_instance_id = stake_and_assert::<T>(
+			user.clone(),
+			BASE_ASSET_ID.into(),
+			100_000_000.into(),
+			ONE_HOUR,
+		);
+	}: _(OriginFor::<T>::signed(user), fnft_collection_id, instance_id, merged_instance_id)
+
 	reward_accumulation_hook_reward_update_calculation {
 		let now = T::UnixTime::now().as_secs();
 		let user: T::AccountId = account("user", 0, 0);
@@ -330,5 +365,40 @@ benchmarks! {
 
 	}: _(OriginFor::<T>::signed(user), pool_id,  asset_id, amount, true)
 
+	set_auto_compound {
+		frame_system::Pallet::<T>::set_block_number(1.into());
+		let user: T::AccountId = account("user", 0, 0);
+		let pool_id = <Pallet<T> as ManageStaking>::create_staking_pool(get_reward_pool::<T>(user, 1)).unwrap();
+	}: _(OriginFor::<T>::root(), pool_id, true)
+	verify {
+		assert_last_event::<T>(Event::AutoCompoundUpdated { pool_id, enabled: true }.into());
+	}
+
+	compound_stake {
+		let r in 1 .. T::MaxRewardConfigsPerPool::get();
+		let asset_id: T::AssetId = BASE_ASSET_ID.into();
+		let amount = 100_500_u128.into();
+		let staker = whitelisted_caller();
+		let pool_owner: T::AccountId = account("owner", 0, 0);
+
+		frame_system::Pallet::<T>::set_block_number(1.into());
+		<Pallet<T>>::create_reward_pool(OriginFor::<T>::root(), get_reward_pool::<T>(pool_owner.clone(), r))?;
+		<T::AssetsTransactor as Mutate<T::AccountId>>::mint_into(asset_id, &staker, amount * 2.into())?;
+		<T::AssetsTransactor as Mutate<T::AccountId>>::mint_into(asset_id, &pool_owner, amount * 2.into())?;
+		let fnft_collection_id = RewardPools::<T>::get(asset_id)
+			.expect("Pool exists")
+			.financial_nft_asset_id;
+
+		frame_system::Pallet::<T>::set_block_number(2.into());
+		<Pallet<T>>::stake(OriginFor::<T>::signed(staker), asset_id, amount, ONE_HOUR)?;
+		<Pallet<T>>::add_to_rewards_pot(OriginFor::<T>::signed(pool_owner), asset_id, asset_id, amount, false)?;
+		RewardPools::<T>::mutate(asset_id, |pool| {
+			let reward = pool.as_mut().expect("Pool exists").rewards.get_mut(&asset_id).expect("Reward exists");
+			reward.total_rewards += amount;
+		});
+	}: {
+		Pallet::<T>::compound_stake(&asset_id, &fnft_collection_id, &FNFT_INSTANCE_ID_BASE.into())?;
+	}
+
 	impl_benchmark_test_suite!(Pallet, crate::test::new_test_ext(), crate::runtime::Test);
 }
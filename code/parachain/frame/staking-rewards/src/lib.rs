@@ -79,7 +79,7 @@ use sp_runtime::{
 	helpers_128bit::multiply_by_rational_with_rounding, traits::CheckedSub, ArithmeticError,
 	Rounding,
 };
-use sp_std::collections::btree_map::BTreeMap;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 use crate::prelude::*;
 
@@ -130,14 +130,15 @@ pub mod pallet {
 		Permill,
 	};
 	use sp_runtime::{
+		helpers_128bit::multiply_by_rational_with_rounding,
 		traits::{AccountIdConversion, BlockNumberProvider, One},
-		ArithmeticError, PerThing,
+		ArithmeticError, PerThing, Rounding,
 	};
 	use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, ops::Mul, vec, vec::Vec};
 
 	use crate::{
-		accumulate_rewards_hook, add_to_rewards_pot, claim_of_stake, prelude::*,
-		update_rewards_pool, validation::ValidSplitRatio,
+		accumulate_rewards_hook, add_to_rewards_pot, auto_compound_hook, claim_of_stake,
+		prelude::*, update_rewards_pool, validation::ValidSplitRatio,
 	};
 	use composable_support::abstractions::utils::increment::Increment;
 
@@ -206,6 +207,17 @@ pub mod pallet {
 		SplitPosition {
 			positions: Vec<(T::AssetId, T::FinancialNftInstanceId, BalanceOf<T>)>,
 		},
+		/// Two staking positions were merged.
+		MergedPositions {
+			/// FNFT Collection Id
+			fnft_collection_id: T::AssetId,
+			/// FNFT Instance Id of the position the other one was merged into
+			fnft_instance_id: T::FinancialNftInstanceId,
+			/// FNFT Instance Id of the merged position, which was burnt
+			merged_fnft_instance_id: T::FinancialNftInstanceId,
+			/// Staked amount of the resulting position
+			stake: BalanceOf<T>,
+		},
 		/// Reward transfer event.
 		RewardTransferred {
 			from: T::AccountId,
@@ -237,6 +249,20 @@ pub mod pallet {
 			pool_id: T::AssetId,
 			asset_id: T::AssetId,
 		},
+		/// Auto-compounding was enabled or disabled for a pool.
+		AutoCompoundUpdated {
+			pool_id: T::AssetId,
+			enabled: bool,
+		},
+		/// The rewards of a stake in the staked asset were compounded into the stake.
+		StakeCompounded {
+			/// FNFT Collection Id
+			fnft_collection_id: T::AssetId,
+			/// FNFT Instance Id
+			fnft_instance_id: T::FinancialNftInstanceId,
+			/// Amount added to the stake
+			amount: T::Balance,
+		},
 	}
 
 	#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
@@ -295,6 +321,10 @@ pub mod pallet {
 		StakedAmountTooLowAfterSplit,
 		/// Some operation resulted in an arithmetic overflow.
 		ArithmeticError,
+		/// Only two different positions of the same pool and lock duration can be merged.
+		StakesNotMergeable,
+		/// Auto-compounding is only possible for pools rewarding their staked asset.
+		AutoCompoundRequiresStakedAssetReward,
 	}
 
 	pub(crate) type AssetIdOf<T> = <T as Config>::AssetId;
@@ -402,6 +432,10 @@ pub mod pallet {
 		type TreasuryAccount: Get<Self::AccountId>;
 
 		type ExistentialDeposits: GetByKey<Self::AssetId, Self::Balance>;
+
+		/// Maximum number of stakes compounded per auto-compounding pool in a single block.
+		#[pallet::constant]
+		type MaxAutoCompoundsPerBlock: Get<u32>;
 	}
 
 	/// Abstraction over RewardPoolConfiguration type
@@ -463,11 +497,22 @@ pub mod pallet {
 	pub type ShareAssetNonce<T: Config> =
 		StorageValue<_, u64, ValueQuery, Nonce<OneInit, SafeIncrement>>;
 
+	/// Pools whose rewards in their staked asset are compounded into the stakes every block.
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	pub type AutoCompoundPools<T: Config> = StorageMap<_, Blake2_128Concat, T::AssetId, ()>;
+
+	/// The last stake compounded in an auto-compounding pool, if the previous block did not get
+	/// through all of its stakes.
+	#[pallet::storage]
+	pub type AutoCompoundCursor<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, FinancialNftInstanceIdOf<T>>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		/// Weight: see `begin_block`
 		fn on_initialize(_: T::BlockNumber) -> Weight {
-			accumulate_rewards_hook::<T>()
+			accumulate_rewards_hook::<T>().saturating_add(auto_compound_hook::<T>())
 		}
 	}
 
@@ -631,6 +676,65 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			add_to_rewards_pot::<T>(&who, pool_id, asset_id, amount, keep_alive)
 		}
+
+		/// Merge a stake into another one of the same pool and lock duration, burning its fNFT. The
+		/// lock of the resulting stake ends at the average of both lock ends, weighted by their
+		/// stake.
+		///
+		/// Emits `MergedPositions` when successful.
+		#[pallet::weight(T::WeightInfo::merge(T::MaxRewardConfigsPerPool::get()))]
+		#[pallet::call_index(9)]
+		pub fn merge(
+			origin: OriginFor<T>,
+			fnft_collection_id: T::AssetId,
+			fnft_instance_id: T::FinancialNftInstanceId,
+			merged_fnft_instance_id: T::FinancialNftInstanceId,
+		) -> DispatchResult {
+			let who = Self::ensure_stake_owner(
+				ensure_signed(origin)?,
+				&fnft_collection_id,
+				&fnft_instance_id,
+			)?;
+			let who = Self::ensure_stake_owner(who, &fnft_collection_id, &merged_fnft_instance_id)?;
+			<Self as Staking>::merge(
+				&who,
+				&(fnft_collection_id, fnft_instance_id),
+				&(fnft_collection_id, merged_fnft_instance_id),
+			)
+		}
+
+		/// Enable or disable auto-compounding for a pool rewarding its staked asset. While
+		/// enabled, the rewards in the staked asset are added to the stakes every block, without
+		/// restarting their locks.
+		///
+		/// Emits `AutoCompoundUpdated` when successful.
+		#[pallet::weight(T::WeightInfo::set_auto_compound())]
+		#[pallet::call_index(10)]
+		pub fn set_auto_compound(
+			origin: OriginFor<T>,
+			pool_id: T::AssetId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::RewardPoolUpdateOrigin::ensure_origin(origin)?;
+
+			let rewards_pool =
+				RewardPools::<T>::get(pool_id).ok_or(Error::<T>::RewardsPoolNotFound)?;
+			ensure!(
+				rewards_pool.rewards.contains_key(&pool_id),
+				Error::<T>::AutoCompoundRequiresStakedAssetReward
+			);
+
+			if enabled {
+				AutoCompoundPools::<T>::insert(pool_id, ());
+			} else {
+				AutoCompoundPools::<T>::remove(pool_id);
+				AutoCompoundCursor::<T>::remove(pool_id);
+			}
+
+			Self::deposit_event(Event::<T>::AutoCompoundUpdated { pool_id, enabled });
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> ManageStaking for Pallet<T> {
@@ -897,35 +1001,7 @@ pub mod pallet {
 
 					let new_shares = Self::boosted_amount(reward_multiplier, amount)?;
 
-					let total_shares =
-						T::AssetsTransactor::total_issuance(rewards_pool.share_asset_id);
-
-					for (reward_asset_id, reward) in &mut rewards_pool.rewards {
-						let new_inflation = if total_shares.is_zero() {
-							T::Balance::zero()
-						} else {
-							reward.total_rewards.safe_mul(&new_shares)?.safe_div(&total_shares)?
-						};
-
-						reward.total_rewards = reward.total_rewards.safe_add(&new_inflation)?;
-						reward.total_dilution_adjustment =
-							reward.total_dilution_adjustment.safe_add(&new_inflation)?;
-
-						match stake.reductions.get_mut(reward_asset_id) {
-							Some(previous_inflation_and_claims) => {
-								*previous_inflation_and_claims =
-									previous_inflation_and_claims.safe_add(&new_inflation)?;
-							},
-							None => {
-								// REVIEW(benluelo): Is this an invariant we expect? In
-								// ProtocolStaking::transfer_reward assets can be added (and is
-								// currently the only way to add a new reward asset to a pool),
-								// but they are not added to all existing stakes so this
-								// invariant is not upheld
-								defensive!("stake.reductions should contain the same assets as reward_pool.rewards");
-							},
-						}
-					}
+					Self::inflate_rewards(rewards_pool, stake, new_shares)?;
 
 					let fnft_asset_account =
 						T::FinancialNft::asset_account(&fnft_collection_id, &fnft_instance_id);
@@ -1162,6 +1238,94 @@ pub mod pallet {
 			Ok((*fnft_collection_id, new_fnft_instance_id))
 		}
 
+		#[transactional]
+		fn merge(
+			who: &Self::AccountId,
+			(fnft_collection_id, fnft_instance_id): &Self::PositionId,
+			(merged_fnft_collection_id, merged_fnft_instance_id): &Self::PositionId,
+		) -> DispatchResult {
+			ensure!(
+				fnft_collection_id == merged_fnft_collection_id &&
+					fnft_instance_id != merged_fnft_instance_id,
+				Error::<T>::StakesNotMergeable
+			);
+			let merged_position = Stakes::<T>::take(fnft_collection_id, merged_fnft_instance_id)
+				.ok_or(Error::<T>::StakeNotFound)?;
+
+			Stakes::<T>::try_mutate(
+				fnft_collection_id,
+				fnft_instance_id,
+				|maybe_position| -> DispatchResult {
+					let position = maybe_position.as_mut().ok_or(Error::<T>::StakeNotFound)?;
+					ensure!(
+						position.reward_pool_id == merged_position.reward_pool_id,
+						Error::<T>::StakesNotMergeable
+					);
+					let rewards_pool = RewardPools::<T>::get(position.reward_pool_id)
+						.ok_or(Error::<T>::RewardsPoolNotFound)?;
+
+					position.lock = Self::merged_lock(
+						&position.lock,
+						position.stake,
+						&merged_position.lock,
+						merged_position.stake,
+					)?;
+
+					// reductions are the part of the rewards a position is not entitled to, so they
+					// add up like the shares do
+					for (reward_asset_id, reduction) in merged_position.reductions {
+						match position.reductions.get_mut(&reward_asset_id) {
+							Some(existing_reduction) =>
+								*existing_reduction = existing_reduction.safe_add(&reduction)?,
+							None => {
+								position
+									.reductions
+									.try_insert(reward_asset_id, reduction)
+									.map_err(|_| Error::<T>::TooManyRewardAssetTypes)?;
+							},
+						}
+					}
+
+					let fnft_asset_account =
+						T::FinancialNft::asset_account(fnft_collection_id, fnft_instance_id);
+					let merged_fnft_asset_account =
+						T::FinancialNft::asset_account(fnft_collection_id, merged_fnft_instance_id);
+
+					position.stake = position.stake.safe_add(&merged_position.stake)?;
+					position.share = position.share.safe_add(&merged_position.share)?;
+
+					// staked asset
+					Self::merge_lock(
+						position.reward_pool_id,
+						&merged_fnft_asset_account,
+						&fnft_asset_account,
+						merged_position.stake,
+						position.stake,
+					)?;
+
+					// share asset (x-token)
+					Self::merge_lock(
+						rewards_pool.share_asset_id,
+						&merged_fnft_asset_account,
+						&fnft_asset_account,
+						merged_position.share,
+						position.share,
+					)?;
+
+					T::FinancialNft::burn(fnft_collection_id, merged_fnft_instance_id, Some(who))?;
+
+					Self::deposit_event(Event::<T>::MergedPositions {
+						fnft_collection_id: *fnft_collection_id,
+						fnft_instance_id: *fnft_instance_id,
+						merged_fnft_instance_id: *merged_fnft_instance_id,
+						stake: position.stake,
+					});
+
+					Ok(())
+				},
+			)
+		}
+
 		#[transactional]
 		fn claim(
 			who: &Self::AccountId,
@@ -1270,6 +1434,74 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Moves the locked `merged_account_amount` of `asset_id` from the account of a merged
+		/// position to the account of the position it is merged into, which then locks
+		/// `total_amount`.
+		pub(crate) fn merge_lock(
+			asset_id: T::AssetId,
+			merged_fnft_asset_account: &T::AccountId,
+			fnft_asset_account: &T::AccountId,
+			merged_account_amount: T::Balance,
+			total_amount: T::Balance,
+		) -> DispatchResult {
+			T::AssetsTransactor::remove_lock(
+				T::LockId::get(),
+				asset_id,
+				merged_fnft_asset_account,
+			)?;
+			T::AssetsTransactor::transfer(
+				asset_id,
+				merged_fnft_asset_account,
+				fnft_asset_account,
+				merged_account_amount,
+				false, // not a user account, doesn't need to be kept alive
+			)?;
+			T::AssetsTransactor::set_lock(
+				T::LockId::get(),
+				asset_id,
+				fnft_asset_account,
+				total_amount,
+			)
+		}
+
+		/// The lock of two merged positions. It ends at the average of both lock ends weighted by
+		/// the stake of the positions, rounded up, and keeps the highest penalty of both.
+		///
+		/// Both locks must have the same duration, as the shares of the positions were computed
+		/// with the reward multiplier of their duration.
+		pub(crate) fn merged_lock(
+			lock: &lock::Lock,
+			stake: T::Balance,
+			other_lock: &lock::Lock,
+			other_stake: T::Balance,
+		) -> Result<lock::Lock, DispatchError> {
+			ensure!(lock.duration == other_lock.duration, Error::<T>::StakesNotMergeable);
+			let end = lock.started_at.safe_add(&lock.duration)?;
+			let other_end = other_lock.started_at.safe_add(&other_lock.duration)?;
+			let (earlier_end, (later_end, later_stake)) = if end <= other_end {
+				(end, (other_end, other_stake))
+			} else {
+				(other_end, (end, stake))
+			};
+			let total_stake: u128 = stake.safe_add(&other_stake)?.into();
+			let weighted_end = earlier_end.safe_add(
+				&multiply_by_rational_with_rounding(
+					later_end.safe_sub(&earlier_end)?.into(),
+					later_stake.into(),
+					total_stake,
+					Rounding::Up,
+				)
+				.and_then(|delay| u64::try_from(delay).ok())
+				.ok_or(ArithmeticError::Overflow)?,
+			)?;
+
+			Ok(lock::Lock {
+				started_at: weighted_end.saturating_sub(lock.duration),
+				duration: lock.duration,
+				unlock_penalty: lock.unlock_penalty.max(other_lock.unlock_penalty),
+			})
+		}
+
 		/// Transfers the rewards a staker has earned while updating the provided `rewards_pool`.
 		///
 		/// # Params
@@ -1323,6 +1555,133 @@ pub mod pallet {
 			Ok(claimed_amounts)
 		}
 
+		/// Inflates the rewards of the pool by the amount that `new_shares` would be able to claim,
+		/// adding that inflation to the reductions of `stake` so that it can't be claimed.
+		fn inflate_rewards(
+			rewards_pool: &mut RewardPoolOf<T>,
+			stake: &mut StakeOf<T>,
+			new_shares: T::Balance,
+		) -> DispatchResult {
+			let total_shares = T::AssetsTransactor::total_issuance(rewards_pool.share_asset_id);
+
+			for (reward_asset_id, reward) in &mut rewards_pool.rewards {
+				let new_inflation = if total_shares.is_zero() {
+					T::Balance::zero()
+				} else {
+					reward.total_rewards.safe_mul(&new_shares)?.safe_div(&total_shares)?
+				};
+
+				reward.total_rewards = reward.total_rewards.safe_add(&new_inflation)?;
+				reward.total_dilution_adjustment =
+					reward.total_dilution_adjustment.safe_add(&new_inflation)?;
+
+				match stake.reductions.get_mut(reward_asset_id) {
+					Some(previous_inflation_and_claims) => {
+						*previous_inflation_and_claims =
+							previous_inflation_and_claims.safe_add(&new_inflation)?;
+					},
+					None => {
+						// REVIEW(benluelo): Is this an invariant we expect? In
+						// ProtocolStaking::transfer_reward assets can be added (and is
+						// currently the only way to add a new reward asset to a pool),
+						// but they are not added to all existing stakes so this
+						// invariant is not upheld
+						defensive!(
+							"stake.reductions should contain the same assets as reward_pool.rewards"
+						);
+					},
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Claims the rewards of a stake in the staked asset of its pool and adds them to the
+		/// stake, allocating the corresponding shares. Unlike `extend`, the lock is not restarted.
+		///
+		/// Returns the compounded amount.
+		#[transactional]
+		pub(crate) fn compound_stake(
+			pool_id: &T::AssetId,
+			fnft_collection_id: &T::AssetId,
+			fnft_instance_id: &T::FinancialNftInstanceId,
+		) -> Result<T::Balance, DispatchError> {
+			Stakes::<T>::try_mutate(fnft_collection_id, fnft_instance_id, |maybe_stake| {
+				let stake = maybe_stake.as_mut().ok_or(Error::<T>::StakeNotFound)?;
+
+				RewardPools::<T>::try_mutate(pool_id, |maybe_rewards_pool| {
+					let rewards_pool =
+						maybe_rewards_pool.as_mut().ok_or(Error::<T>::RewardsPoolNotFound)?;
+					let share_asset_id = rewards_pool.share_asset_id;
+
+					let reward = rewards_pool
+						.rewards
+						.get_mut(pool_id)
+						.ok_or(Error::<T>::RewardAssetNotFound)?;
+					let claim = sp_std::cmp::min(
+						claim_of_stake::<T>(stake, &share_asset_id, reward, pool_id)?,
+						reward.total_rewards.safe_sub(&reward.claimed_rewards)?,
+					);
+
+					if claim.is_zero() {
+						return Ok(claim)
+					}
+
+					reward.claimed_rewards = reward.claimed_rewards.safe_add(&claim)?;
+					if let Some(inflation) = stake.reductions.get_mut(pool_id) {
+						*inflation = inflation.safe_add(&claim)?;
+					}
+
+					// SAFETY: The duration preset on an existing stake should be valid in the
+					// pool since it's currently not possible to modify the presets after pool
+					// creation.
+					let reward_multiplier = rewards_pool
+						.lock
+						.duration_multipliers
+						.multiplier(stake.lock.duration)
+						.copied()
+						.defensive_unwrap_or_else(|| {
+							FixedU64::one().try_into_validated().expect("1 is >= 1")
+						});
+
+					let new_shares = Self::boosted_amount(reward_multiplier, claim)?;
+
+					Self::inflate_rewards(rewards_pool, stake, new_shares)?;
+
+					let fnft_asset_account =
+						T::FinancialNft::asset_account(fnft_collection_id, fnft_instance_id);
+
+					T::AssetsTransactor::transfer(
+						*pool_id,
+						&Self::pool_account_id(pool_id),
+						&fnft_asset_account,
+						claim,
+						false, // pallet account doesn't need to be kept alive
+					)?;
+
+					Self::allocate_shares(pool_id, &fnft_asset_account, rewards_pool, new_shares)?;
+
+					stake.stake = stake.stake.safe_add(&claim)?;
+					stake.share = stake.share.safe_add(&new_shares)?;
+
+					T::AssetsTransactor::set_lock(
+						T::LockId::get(),
+						*pool_id,
+						&fnft_asset_account,
+						stake.stake,
+					)?;
+					T::AssetsTransactor::set_lock(
+						T::LockId::get(),
+						share_asset_id,
+						&fnft_asset_account,
+						stake.share,
+					)?;
+
+					Ok(claim)
+				})
+			})
+		}
+
 		pub(crate) fn pool_account_id(pool_id: &T::AssetId) -> T::AccountId {
 			T::PalletId::get().into_sub_account_truncating(pool_id)
 		}
@@ -1549,6 +1908,58 @@ pub(crate) fn accumulate_rewards_hook<T: Config>() -> Weight {
 	total_weight
 }
 
+/// Compounds the staked asset rewards of up to `MaxAutoCompoundsPerBlock` stakes of every
+/// auto-compounding pool, continuing from where the previous block left off.
+pub(crate) fn auto_compound_hook<T: Config>() -> Weight {
+	let max_compounds = T::MaxAutoCompoundsPerBlock::get();
+	let mut total_weight = Weight::zero();
+
+	for pool_id in AutoCompoundPools::<T>::iter_keys() {
+		// pool id, reward pool and cursor
+		total_weight += T::DbWeight::get().reads(3);
+
+		let fnft_collection_id = match RewardPools::<T>::get(pool_id) {
+			Some(rewards_pool) => rewards_pool.financial_nft_asset_id,
+			None => continue,
+		};
+
+		let batch = match AutoCompoundCursor::<T>::get(pool_id) {
+			Some(previous_instance_id) => Stakes::<T>::iter_key_prefix_from(
+				fnft_collection_id,
+				Stakes::<T>::hashed_key_for(fnft_collection_id, previous_instance_id),
+			),
+			None => Stakes::<T>::iter_key_prefix(fnft_collection_id),
+		}
+		.take(max_compounds as usize)
+		.collect::<Vec<_>>();
+
+		for fnft_instance_id in &batch {
+			total_weight += T::WeightInfo::compound_stake(T::MaxRewardConfigsPerPool::get());
+
+			match Pallet::<T>::compound_stake(&pool_id, &fnft_collection_id, fnft_instance_id) {
+				Ok(amount) if amount.is_zero() => {},
+				Ok(amount) => Pallet::<T>::deposit_event(Event::<T>::StakeCompounded {
+					fnft_collection_id,
+					fnft_instance_id: *fnft_instance_id,
+					amount,
+				}),
+				Err(error) => log::error!(
+					"failed to compound stake {fnft_instance_id:?} of pool {pool_id:?}: {error:?}"
+				),
+			}
+		}
+
+		total_weight += T::DbWeight::get().writes(1);
+		match batch.last() {
+			Some(last_instance_id) if batch.len() == max_compounds as usize =>
+				AutoCompoundCursor::<T>::insert(pool_id, last_instance_id),
+			_ => AutoCompoundCursor::<T>::remove(pool_id),
+		}
+	}
+
+	total_weight
+}
+
 /// Accumulates all of the rewards in the provided pool, updating them in-place. Returns the weight
 /// of the calculations.
 #[must_use = "the calculated weight does nothing on it's own"]
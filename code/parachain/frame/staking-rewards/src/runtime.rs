@@ -261,6 +261,7 @@ parameter_types! {
 	pub const StakingRewardsLockId: LockIdentifier = *b"stk_lock";
 	// REVIEW(benluelo): Use a better value for this?
 	pub const TreasuryAccountId: AccountId = sr25519::Public([10_u8; 32]);
+	pub const MaxAutoCompoundsPerBlock: u32 = 2;
 }
 
 impl crate::Config for Test {
@@ -281,6 +282,7 @@ impl crate::Config for Test {
 	type AssetsTransactor = Assets;
 	type LockId = StakingRewardsLockId;
 	type TreasuryAccount = TreasuryAccountId;
+	type MaxAutoCompoundsPerBlock = MaxAutoCompoundsPerBlock;
 }
 
 impl InstanceFilter<RuntimeCall> for ProxyType {
@@ -11,7 +11,8 @@ use crate::{
 		add_to_rewards_pot_and_assert, create_rewards_pool_and_assert, split_and_assert,
 		stake_and_assert, unstake_and_assert,
 	},
-	FinancialNftInstanceIdOf, Pallet, RewardPoolConfigurationOf, RewardPools, Stakes,
+	AutoCompoundCursor, FinancialNftInstanceIdOf, Pallet, RewardPoolConfigurationOf, RewardPools,
+	Stakes,
 };
 
 use composable_support::validation::TryIntoValidated;
@@ -1244,6 +1245,299 @@ fn split_positions_accrue_same_as_original_position() {
 	assert_eq!(accrued_with_split, accrued_without_split);
 }
 
+#[test]
+fn merged_positions_accrue_same_as_separate_positions() {
+	fn create_pool_and_stake_twice() -> (u128, [FinancialNftInstanceIdOf<Test>; 2]) {
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			reward_configs: [(
+				USDT::ID,
+				RewardConfig { reward_rate: RewardRate::per_second(USDT::units(1)) },
+			)]
+			.into_iter()
+			.try_collect()
+			.unwrap(),
+			lock: default_lock_config(),
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+
+		process_and_progress_blocks::<StakingRewards, Test>(1);
+
+		mint_assets([BOB], [USDT::ID], USDT::units(1_000));
+		add_to_rewards_pot_and_assert::<Test>(BOB, PICA::ID, USDT::ID, USDT::units(1_000), false);
+
+		mint_assets([ALICE], [PICA::ID], PICA::units(2000));
+		let first = stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(1_000), ONE_HOUR);
+		process_and_progress_blocks::<StakingRewards, Test>(10);
+		let second = stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(500), ONE_HOUR);
+
+		let fnft_collection_id =
+			RewardPools::<Test>::get(PICA::ID).expect("Pool exists").financial_nft_asset_id;
+		(fnft_collection_id, [first, second])
+	}
+
+	const BLOCKS_TO_ACCRUE_FOR: usize = 150;
+
+	let accrued_without_merge = new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		let (fnft_collection_id, positions) = create_pool_and_stake_twice();
+
+		process_and_progress_blocks::<StakingRewards, Test>(BLOCKS_TO_ACCRUE_FOR);
+
+		for fnft_instance_id in positions {
+			crate::Pallet::<Test>::claim(
+				RuntimeOrigin::signed(ALICE),
+				fnft_collection_id,
+				fnft_instance_id,
+			)
+			.unwrap();
+		}
+
+		Tokens::balance(USDT::ID, &ALICE)
+	});
+
+	let accrued_with_merge = new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		let (fnft_collection_id, [first, second]) = create_pool_and_stake_twice();
+
+		assert_ok!(StakingRewards::merge(
+			RuntimeOrigin::signed(ALICE),
+			fnft_collection_id,
+			first,
+			second
+		));
+
+		process_and_progress_blocks::<StakingRewards, Test>(BLOCKS_TO_ACCRUE_FOR);
+
+		crate::Pallet::<Test>::claim(RuntimeOrigin::signed(ALICE), fnft_collection_id, first)
+			.unwrap();
+
+		Tokens::balance(USDT::ID, &ALICE)
+	});
+
+	// the claims of the separate positions are rounded down individually
+	assert!(accrued_with_merge.abs_diff(accrued_without_merge) <= 1);
+}
+
+#[test]
+fn merge_should_combine_positions_and_weight_lock_end() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_default_reward_pool();
+		process_and_progress_blocks::<StakingRewards, Test>(1);
+
+		Timestamp::set_timestamp(ONE_HOUR * 24 * 1_000);
+		mint_assets([ALICE], [PICA::ID], PICA::units(5_000));
+		let first = stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(1_000), ONE_HOUR);
+		Timestamp::set_timestamp((ONE_HOUR * 24 + 4 * ONE_MINUTE) * 1_000);
+		let second = stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(3_000), ONE_HOUR);
+		let fnft_collection_id =
+			RewardPools::<Test>::get(PICA::ID).expect("Pool exists").financial_nft_asset_id;
+		let first_stake = Stakes::<Test>::get(fnft_collection_id, first).unwrap();
+		let second_stake = Stakes::<Test>::get(fnft_collection_id, second).unwrap();
+
+		assert_ok!(StakingRewards::merge(
+			RuntimeOrigin::signed(ALICE),
+			fnft_collection_id,
+			first,
+			second
+		));
+		Test::assert_last_event(RuntimeEvent::StakingRewards(crate::Event::MergedPositions {
+			fnft_collection_id,
+			fnft_instance_id: first,
+			merged_fnft_instance_id: second,
+			stake: PICA::units(4_000),
+		}));
+
+		let merged = Stakes::<Test>::get(fnft_collection_id, first).unwrap();
+		assert_eq!(merged.stake, PICA::units(4_000));
+		assert_eq!(merged.share, first_stake.share + second_stake.share);
+		for (asset_id, reduction) in &merged.reductions {
+			assert_eq!(
+				*reduction,
+				first_stake.reductions[asset_id] + second_stake.reductions[asset_id]
+			);
+		}
+		// 3/4 of the stake is locked 4 minutes later than the other 1/4
+		assert_eq!(
+			merged.lock,
+			Lock {
+				started_at: first_stake.lock.started_at + 3 * ONE_MINUTE,
+				duration: ONE_HOUR,
+				unlock_penalty: first_stake.lock.unlock_penalty,
+			}
+		);
+
+		assert!(Stakes::<Test>::get(fnft_collection_id, second).is_none());
+		assert_eq!(FinancialNft::items(&fnft_collection_id).collect::<Vec<_>>(), vec![first]);
+		let fnft_asset_account = FinancialNft::asset_account(&fnft_collection_id, &first);
+		assert_eq!(Tokens::balance(PICA::ID, &fnft_asset_account), PICA::units(4_000));
+		assert_eq!(Tokens::reducible_balance(PICA::ID, &fnft_asset_account, false), 0);
+		let merged_fnft_asset_account = FinancialNft::asset_account(&fnft_collection_id, &second);
+		assert_eq!(Tokens::balance(PICA::ID, &merged_fnft_asset_account), 0);
+	});
+}
+
+#[test]
+fn merge_should_not_allow_different_lock_durations() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_default_reward_pool();
+		process_and_progress_blocks::<StakingRewards, Test>(1);
+
+		mint_assets([ALICE], [PICA::ID], PICA::units(5_000));
+		let first = stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(1_000), ONE_HOUR);
+		let second = stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(3_000), ONE_MINUTE);
+		let fnft_collection_id =
+			RewardPools::<Test>::get(PICA::ID).expect("Pool exists").financial_nft_asset_id;
+
+		// the one minute stake would earn rewards with the multiplier of an hour
+		assert_noop!(
+			StakingRewards::merge(RuntimeOrigin::signed(ALICE), fnft_collection_id, first, second),
+			crate::Error::<Test>::StakesNotMergeable
+		);
+	});
+}
+
+#[test]
+fn merge_should_not_allow_non_owner_or_same_position() {
+	with_stake(
+		ALICE,
+		100_500,
+		ONE_HOUR,
+		100,
+		false,
+		|_pool_id,
+		 _unlock_penalty,
+		 _stake_duration,
+		 _staked_asset_id,
+		 fnft_collection_id,
+		 fnft_instance_id| {
+			assert_noop!(
+				StakingRewards::merge(
+					RuntimeOrigin::signed(BOB),
+					fnft_collection_id,
+					fnft_instance_id,
+					fnft_instance_id
+				),
+				crate::Error::<Test>::OnlyStakeOwnerCanInteractWithStake
+			);
+			assert_noop!(
+				StakingRewards::merge(
+					RuntimeOrigin::signed(ALICE),
+					fnft_collection_id,
+					fnft_instance_id,
+					fnft_instance_id
+				),
+				crate::Error::<Test>::StakesNotMergeable
+			);
+		},
+	);
+}
+
+#[test]
+fn auto_compound_should_fold_rewards_into_stakes_in_batches() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			reward_configs: bounded_btree_map! {
+				PICA::ID => RewardConfig { reward_rate: RewardRate::per_second(PICA::units(1)) }
+			},
+			lock: default_lock_config(),
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+		process_and_progress_blocks::<StakingRewards, Test>(1);
+
+		mint_assets([BOB], [PICA::ID], PICA::units(1_000));
+		add_to_rewards_pot_and_assert::<Test>(BOB, PICA::ID, PICA::ID, PICA::units(1_000), false);
+
+		mint_assets([ALICE], [PICA::ID], PICA::units(3_000));
+		let positions = [(); 3]
+			.map(|_| stake_and_assert::<Test>(ALICE, PICA::ID, PICA::units(1_000), ONE_HOUR));
+		let fnft_collection_id =
+			RewardPools::<Test>::get(PICA::ID).expect("Pool exists").financial_nft_asset_id;
+		let stakes_before =
+			positions.map(|position| Stakes::<Test>::get(fnft_collection_id, position).unwrap());
+
+		Test::assert_extrinsic_event(
+			StakingRewards::set_auto_compound(RuntimeOrigin::root(), PICA::ID, true),
+			crate::Event::<Test>::AutoCompoundUpdated { pool_id: PICA::ID, enabled: true },
+		);
+
+		let compounded_positions = || {
+			positions
+				.iter()
+				.filter(|&&position| {
+					Stakes::<Test>::get(fnft_collection_id, position).unwrap().stake >
+						PICA::units(1_000)
+				})
+				.count()
+		};
+
+		// only `MaxAutoCompoundsPerBlock` stakes are compounded per block
+		process_and_progress_blocks::<StakingRewards, Test>(1);
+		assert_eq!(compounded_positions(), 2);
+		assert!(AutoCompoundCursor::<Test>::get(PICA::ID).is_some());
+
+		process_and_progress_blocks::<StakingRewards, Test>(1);
+		assert_eq!(compounded_positions(), 3);
+		assert!(AutoCompoundCursor::<Test>::get(PICA::ID).is_none());
+
+		for (position, stake_before) in positions.iter().zip(stakes_before) {
+			let stake = Stakes::<Test>::get(fnft_collection_id, position).unwrap();
+			assert!(stake.share > stake_before.share);
+			assert_eq!(stake.lock, stake_before.lock);
+
+			let fnft_asset_account = FinancialNft::asset_account(&fnft_collection_id, position);
+			assert_eq!(Tokens::balance(PICA::ID, &fnft_asset_account), stake.stake);
+			assert_eq!(Tokens::reducible_balance(PICA::ID, &fnft_asset_account, false), 0);
+		}
+		assert_eq!(Tokens::balance(PICA::ID, &ALICE), 0);
+
+		Test::assert_extrinsic_event(
+			StakingRewards::set_auto_compound(RuntimeOrigin::root(), PICA::ID, false),
+			crate::Event::<Test>::AutoCompoundUpdated { pool_id: PICA::ID, enabled: false },
+		);
+		let stakes_after =
+			positions.map(|position| Stakes::<Test>::get(fnft_collection_id, position).unwrap());
+		process_and_progress_blocks::<StakingRewards, Test>(2);
+		assert_eq!(
+			positions.map(|position| Stakes::<Test>::get(fnft_collection_id, position).unwrap()),
+			stakes_after
+		);
+	});
+}
+
+#[test]
+fn set_auto_compound_requires_pool_rewarding_staked_asset() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			StakingRewards::set_auto_compound(RuntimeOrigin::root(), PICA::ID, true),
+			crate::Error::<Test>::RewardsPoolNotFound
+		);
+
+		create_default_reward_pool();
+
+		assert_noop!(
+			StakingRewards::set_auto_compound(RuntimeOrigin::signed(ALICE), PICA::ID, true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			StakingRewards::set_auto_compound(RuntimeOrigin::root(), PICA::ID, true),
+			crate::Error::<Test>::AutoCompoundRequiresStakedAssetReward
+		);
+	});
+}
+
 #[test]
 fn claim_with_insufficient_pot_funds() {
 	init_logger();
@@ -6,11 +6,14 @@ pub trait WeightInfo {
 	fn extend(r: u32) -> Weight;
 	fn unstake(r: u32) -> Weight;
 	fn split(r: u32) -> Weight;
+	fn merge(r: u32) -> Weight;
 	fn reward_accumulation_hook_reward_update_calculation() -> Weight;
 	fn unix_time_now() -> Weight;
 	fn update_rewards_pool(r: u32) -> Weight;
 	fn claim(r: u32) -> Weight;
 	fn add_to_rewards_pot() -> Weight;
+	fn set_auto_compound() -> Weight;
+	fn compound_stake(r: u32) -> Weight;
 }
 
 impl WeightInfo for () {
@@ -34,6 +37,10 @@ impl WeightInfo for () {
 		Weight::from_ref_time(10_000)
 	}
 
+	fn merge(_r: u32) -> Weight {
+		Weight::from_ref_time(10_000)
+	}
+
 	fn reward_accumulation_hook_reward_update_calculation() -> Weight {
 		Weight::from_ref_time(10_000)
 	}
@@ -53,4 +60,12 @@ impl WeightInfo for () {
 	fn add_to_rewards_pot() -> Weight {
 		Weight::from_ref_time(10_000)
 	}
+
+	fn set_auto_compound() -> Weight {
+		Weight::from_ref_time(10_000)
+	}
+
+	fn compound_stake(_r: u32) -> Weight {
+		Weight::from_ref_time(10_000)
+	}
 }
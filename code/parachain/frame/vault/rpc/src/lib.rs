@@ -0,0 +1,141 @@
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::{
+	defi::Rate,
+	time::DurationSeconds,
+	vault::{SharePriceCheckpoint, StrategyReport},
+};
+use core::{fmt::Display, str::FromStr};
+use frame_support::{pallet_prelude::MaybeSerializeDeserialize, Parameter};
+use jsonrpsee::{
+	core::{Error as RpcError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{traits::Block as BlockT, DispatchError, FixedI128};
+use sp_std::sync::Arc;
+use vault_runtime_api::VaultRuntimeApi;
+
+#[rpc(client, server)]
+pub trait VaultApi<BlockHash, VaultId, AccountId, Balance, BlockNumber>
+where
+	VaultId: FromStr + Display,
+	Balance: FromStr + Display,
+{
+	#[method(name = "vault_sharePrice")]
+	fn share_price(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<SafeRpcWrapper<Rate>>;
+
+	#[method(name = "vault_sharePriceHistory")]
+	fn share_price_history(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<SharePriceCheckpoint<BlockNumber, SafeRpcWrapper<Rate>>>>;
+
+	#[method(name = "vault_realizedApy")]
+	fn realized_apy(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		window: DurationSeconds,
+		at: Option<BlockHash>,
+	) -> RpcResult<SafeRpcWrapper<FixedI128>>;
+
+	#[method(name = "vault_strategies")]
+	fn strategies(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<StrategyReport<AccountId, SafeRpcWrapper<Balance>, SafeRpcWrapper<FixedI128>>>>;
+}
+
+pub struct Vault<C, Block> {
+	client: Arc<C>,
+	_marker: sp_std::marker::PhantomData<Block>,
+}
+
+impl<C, M> Vault<C, M> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(e: impl core::fmt::Debug) -> RpcError {
+	RpcError::Call(CallError::Custom(ErrorObject::owned(
+		9876,
+		"Something wrong",
+		Some(format!("{:?}", e)),
+	)))
+}
+
+fn dispatch_error(e: DispatchError) -> RpcError {
+	RpcError::Call(CallError::Custom(ErrorObject::owned(
+		9877,
+		"Query failed",
+		Some(format!("{:?}", e)),
+	)))
+}
+
+impl<C, Block, VaultId, AccountId, Balance, BlockNumber>
+	VaultApiServer<<Block as BlockT>::Hash, VaultId, AccountId, Balance, BlockNumber>
+	for Vault<C, (Block, VaultId, AccountId, Balance, BlockNumber)>
+where
+	Block: BlockT,
+	VaultId: Send + Sync + 'static + Codec + FromStr + Display,
+	AccountId: Send + Sync + Parameter + MaybeSerializeDeserialize + 'static,
+	Balance: Send + Sync + 'static + Codec + FromStr + Display,
+	BlockNumber: Send + Sync + Parameter + MaybeSerializeDeserialize + 'static,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: VaultRuntimeApi<Block, VaultId, AccountId, Balance, BlockNumber>,
+{
+	fn share_price(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SafeRpcWrapper<Rate>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.share_price(at, vault_id.0).map_err(runtime_error)?.map_err(dispatch_error)
+	}
+
+	fn share_price_history(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<SharePriceCheckpoint<BlockNumber, SafeRpcWrapper<Rate>>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.share_price_history(at, vault_id.0).map_err(runtime_error)
+	}
+
+	fn realized_apy(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		window: DurationSeconds,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<SafeRpcWrapper<FixedI128>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.realized_apy(at, vault_id.0, window)
+			.map_err(runtime_error)?
+			.map_err(dispatch_error)
+	}
+
+	fn strategies(
+		&self,
+		vault_id: SafeRpcWrapper<VaultId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<StrategyReport<AccountId, SafeRpcWrapper<Balance>, SafeRpcWrapper<FixedI128>>>>
+	{
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.strategies(at, vault_id.0).map_err(runtime_error)?.map_err(dispatch_error)
+	}
+}
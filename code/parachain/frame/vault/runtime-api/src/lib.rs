@@ -0,0 +1,37 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::{
+	defi::Rate,
+	time::DurationSeconds,
+	vault::{SharePriceCheckpoint, StrategyReport},
+};
+use sp_runtime::{DispatchError, FixedI128};
+use sp_std::vec::Vec;
+
+// Vault Runtime API declaration. Implemented for each runtime at
+// `runtime/<runtime-name>/src/lib.rs`.
+sp_api::decl_runtime_apis! {
+	pub trait VaultRuntimeApi<VaultId, AccountId, Balance, BlockNumber>
+	where
+		VaultId: Codec,
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Retrieve the amount of underlying assets one LP token of `vault_id` is worth.
+		fn share_price(vault_id: VaultId) -> Result<SafeRpcWrapper<Rate>, DispatchError>;
+
+		/// Retrieve the share price checkpoints of `vault_id`, oldest first.
+		fn share_price_history(vault_id: VaultId) -> Vec<SharePriceCheckpoint<BlockNumber, SafeRpcWrapper<Rate>>>;
+
+		/// Retrieve the annualized return of a share of `vault_id` over the last `window` seconds.
+		fn realized_apy(vault_id: VaultId, window: DurationSeconds) -> Result<SafeRpcWrapper<FixedI128>, DispatchError>;
+
+		/// Retrieve the allocation, balance and return of every strategy of `vault_id`.
+		fn strategies(
+			vault_id: VaultId,
+		) -> Result<Vec<StrategyReport<AccountId, SafeRpcWrapper<Balance>, SafeRpcWrapper<FixedI128>>>, DispatchError>;
+	}
+}
@@ -128,6 +128,23 @@ benchmarks! {
 		}.into())
 	}
 
+	cancel_queued_withdrawal {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::MinimumQueuedWithdrawal::get().max(T::CreationDeposit::get() * 10u32.into());
+		let (vault, _) = create_vault::<T>(A, caller.clone());
+		T::Currency::mint_into(recode_unwrap_u128(A), &caller, amount * 2u32.into())?;
+		T::NativeCurrency::mint_into(&caller, amount * 2u32.into())?;
+		<Vault<T> as VaultTrait>::deposit(&vault, &caller, amount)?;
+		Vault::<T>::do_queue_withdrawal(&vault, &caller, amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), vault)
+	verify {
+		assert_last_event::<T>(Event::QueuedWithdrawalCancelled {
+			vault,
+			account: caller,
+			lp_amount: amount
+		}.into())
+	}
+
 	emergency_shutdown {
 		let caller: T::AccountId = whitelisted_caller();
 		let (vault, _) = create_vault::<T>(A, caller);
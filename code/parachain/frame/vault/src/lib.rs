@@ -60,9 +60,9 @@ pub mod pallet {
 	use core::ops::AddAssign;
 
 	use crate::{
-		models::StrategyOverview,
+		models::{QueuedWithdrawal, StrategyOverview},
 		rent::{self, Verdict},
-		traits::{CurrencyFactory, StrategicVault},
+		traits::{CurrencyFactory, StrategicVault, VaultStrategy},
 		validation::{ValidateCreationDeposit, ValidateMaxStrategies},
 		weights::WeightInfo,
 	};
@@ -71,26 +71,31 @@ pub mod pallet {
 	use composable_traits::{
 		currency::RangeId,
 		defi::Rate,
+		time::{DurationSeconds, SECONDS_PER_YEAR_NAIVE},
 		vault::{
-			CapabilityVault, Deposit, FundsAvailability, ReportableStrategicVault, Vault,
-			VaultConfig,
+			CapabilityVault, Deposit, FundsAvailability, ReportableStrategicVault,
+			SharePriceCheckpoint, StrategyReport, Vault, VaultConfig,
 		},
 	};
 	use frame_support::{
 		dispatch::DispatchResultWithPostInfo,
 		ensure,
 		pallet_prelude::*,
+		storage::{with_transaction, TransactionOutcome},
 		traits::{
 			fungible::{
 				Inspect as InspectNative, Mutate as MutateNative, MutateHold as MutateHoldNative,
 				Transfer as TransferNative,
 			},
 			fungibles::{Inspect, Mutate, MutateHold, Transfer},
+			UnixTime,
 		},
 		transactional, PalletId,
 	};
 	use frame_system::{
-		ensure_root, ensure_signed, pallet_prelude::OriginFor, Config as SystemConfig,
+		ensure_root, ensure_signed,
+		pallet_prelude::{BlockNumberFor, OriginFor},
+		Config as SystemConfig,
 	};
 	use num_traits::{One, SaturatingSub};
 	use scale_info::TypeInfo;
@@ -101,9 +106,9 @@ pub mod pallet {
 			AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Convert,
 			Zero,
 		},
-		ArithmeticError, DispatchError, FixedPointNumber, Perquintill,
+		ArithmeticError, DispatchError, FixedI128, FixedPointNumber, Perquintill,
 	};
-	use sp_std::{cmp::Ordering, fmt::Debug};
+	use sp_std::{cmp::Ordering, fmt::Debug, vec::Vec};
 
 	#[allow(missing_docs)]
 	pub type AssetIdOf<T> =
@@ -120,6 +125,13 @@ pub mod pallet {
 	pub type VaultInfo<T> =
 		crate::models::VaultInfo<AccountIdOf<T>, BalanceOf<T>, AssetIdOf<T>, BlockNumberOf<T>>;
 
+	#[allow(missing_docs)]
+	pub type SharePriceCheckpointOf<T> = SharePriceCheckpoint<BlockNumberOf<T>, Rate>;
+
+	#[allow(missing_docs)]
+	pub type QueuedWithdrawalOf<T> =
+		crate::models::QueuedWithdrawal<AccountIdOf<T>, BalanceOf<T>, BlockNumberOf<T>>;
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		#[allow(missing_docs)]
@@ -218,6 +230,34 @@ pub mod pallet {
 		/// avoid name collisions with other pallets and vaults.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
+
+		/// Strategies implemented on-chain, which vaults rebalance when idle. Strategies not
+		/// managed by it are left to poll the vault and rebalance themselves.
+		type Strategies: VaultStrategy<Self::AccountId, Self::AssetId, Self::Balance>;
+
+		/// The minimum number of blocks between two rebalancings of the strategies of a vault.
+		#[pallet::constant]
+		type RebalanceInterval: Get<Self::BlockNumber>;
+
+		/// Time used to annualize the returns of vaults.
+		type UnixTime: UnixTime;
+
+		/// The minimum number of blocks between two checkpoints of the share price of a vault.
+		#[pallet::constant]
+		type CheckpointInterval: Get<Self::BlockNumber>;
+
+		/// The number of share price checkpoints kept per vault, older ones are dropped.
+		#[pallet::constant]
+		type MaxCheckpoints: Get<u32>;
+
+		/// The number of withdrawals which can be queued per vault while it lacks liquidity.
+		#[pallet::constant]
+		type MaxQueuedWithdrawals: Get<u32>;
+
+		/// The minimum amount of LP tokens of a queued withdrawal, so that the queue can not be
+		/// filled with dust.
+		#[pallet::constant]
+		type MinimumQueuedWithdrawal: Get<Self::Balance>;
 	}
 
 	#[pallet::pallet]
@@ -263,6 +303,42 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The block at which the strategies of each vault were last rebalanced.
+	#[pallet::storage]
+	#[pallet::getter(fn last_rebalance)]
+	pub type LastRebalance<T: Config> =
+		StorageMap<_, Twox64Concat, T::VaultId, T::BlockNumber, OptionQuery>;
+
+	/// Share price of each vault, checkpointed every [`Config::CheckpointInterval`] blocks, oldest
+	/// first.
+	#[pallet::storage]
+	#[pallet::getter(fn share_price_checkpoints)]
+	#[allow(clippy::disallowed_types)] // a vault without checkpoints has an empty history
+	pub type SharePriceCheckpoints<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::VaultId,
+		BoundedVec<SharePriceCheckpointOf<T>, T::MaxCheckpoints>,
+		ValueQuery,
+	>;
+
+	/// Withdrawals exceeding the funds held by each vault, in the order they were requested.
+	#[pallet::storage]
+	#[pallet::getter(fn withdrawal_queue)]
+	#[allow(clippy::disallowed_types)] // a vault without queued withdrawals has an empty queue
+	pub type WithdrawalQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::VaultId,
+		BoundedVec<QueuedWithdrawalOf<T>, T::MaxQueuedWithdrawals>,
+		ValueQuery,
+	>;
+
+	/// The last vault maintained by `on_idle`, if the previous block did not get through all of
+	/// the vaults.
+	#[pallet::storage]
+	pub type MaintenanceCursor<T: Config> = StorageValue<_, T::VaultId, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -303,6 +379,55 @@ pub mod pallet {
 			/// The ID of the vault.
 			vault: T::VaultId,
 		},
+		/// Emitted after the yield of a strategy is accounted in the assets under management of
+		/// the vault.
+		YieldHarvested {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account of the strategy.
+			strategy: T::AccountId,
+			/// The balance of the strategy gained since its last report.
+			amount: T::Balance,
+		},
+		/// Emitted after funds were moved between a vault and one of its strategies.
+		StrategyRebalanced {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account of the strategy.
+			strategy: T::AccountId,
+			/// The funds moved, `Withdrawable` when pushed to the strategy, `Depositable` or
+			/// `MustLiquidate` when pulled back.
+			funds: FundsAvailability<T::Balance>,
+		},
+		/// Emitted after a withdrawal exceeding the funds held by the vault was queued.
+		WithdrawalQueued {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account ID making the withdrawal.
+			account: T::AccountId,
+			/// Amount of LP tokens held until the withdrawal is settled.
+			lp_amount: T::Balance,
+		},
+		/// Emitted after a queued withdrawal was served.
+		WithdrawalSettled {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account ID which made the withdrawal.
+			account: T::AccountId,
+			/// Amount of LP tokens exchanged for the withdrawal.
+			lp_amount: T::Balance,
+			/// Assets received in exchange for the withdrawal.
+			asset_amount: T::Balance,
+		},
+		/// Emitted after the withdrawals queued by an account were cancelled.
+		QueuedWithdrawalCancelled {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account ID which made the withdrawals.
+			account: T::AccountId,
+			/// Amount of LP tokens released.
+			lp_amount: T::Balance,
+		},
 	}
 
 	#[allow(missing_docs)]
@@ -364,6 +489,25 @@ pub mod pallet {
 		TombstoneDurationNotExceeded,
 		/// Existentially funded vaults do not require extra funds.
 		InvalidAddSurcharge,
+		/// Share price checkpoints could not be stored, as `MaxCheckpoints` is zero.
+		TooManyCheckpoints,
+		/// Returns can not be computed before a share price checkpoint was taken in the window.
+		NotEnoughCheckpoints,
+		/// The vault already queued [`MaxQueuedWithdrawals`](Config::MaxQueuedWithdrawals)
+		/// withdrawals.
+		WithdrawalQueueFull,
+		/// Withdrawals of less than
+		/// [`MinimumQueuedWithdrawal`](Config::MinimumQueuedWithdrawal) LP tokens are not queued.
+		QueuedWithdrawalTooSmall,
+		/// The account has no withdrawal queued in the vault.
+		NoQueuedWithdrawal,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::maintain_vaults(now, remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -532,6 +676,17 @@ pub mod pallet {
 						false,
 					)?;
 					LpTokensToVaults::<T>::remove(vault.asset_id);
+					LastRebalance::<T>::remove(dest);
+					SharePriceCheckpoints::<T>::remove(dest);
+					// the LP tokens are worthless once the vault is gone
+					for withdrawal in WithdrawalQueue::<T>::take(dest) {
+						T::Currency::release(
+							vault.lp_token_id,
+							&withdrawal.account,
+							withdrawal.lp_amount,
+							true,
+						)?;
+					}
 					v.take();
 				}
 				Ok(().into())
@@ -558,15 +713,19 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// Withdraw funds
+		/// Withdraw funds. If the vault does not hold enough funds, or other withdrawals are
+		/// already queued, the withdrawal is queued and its LP tokens are held until the
+		/// strategies returned enough funds to settle it.
 		///
 		/// # Emits
 		///  - Event::Withdrawn
+		///  - Event::WithdrawalQueued
 		///
 		/// # Errors
 		///  - When the origin is not signed.
 		///  - When `lp_amount < MinimumWithdrawal`.
-		///  - When the vault has insufficient amounts reserved.
+		///  - When the withdrawal is queued and `lp_amount < MinimumQueuedWithdrawal`.
+		///  - When the vault already queued `MaxQueuedWithdrawals` withdrawals.
 		#[pallet::call_index(5)]
 		#[pallet::weight(<T as Config>::WeightInfo::withdraw())]
 		pub fn withdraw(
@@ -575,8 +734,43 @@ pub mod pallet {
 			lp_amount: T::Balance,
 		) -> DispatchResultWithPostInfo {
 			let to = ensure_signed(origin)?;
-			let asset_amount = <Self as Vault>::withdraw(&vault, &to, lp_amount)?;
-			Self::deposit_event(Event::Withdrawn { account: to, lp_amount, asset_amount });
+			if WithdrawalQueue::<T>::decode_len(vault).unwrap_or_default().is_zero() {
+				match <Self as Vault>::withdraw(&vault, &to, lp_amount) {
+					Ok(asset_amount) => {
+						Self::deposit_event(Event::Withdrawn {
+							account: to,
+							lp_amount,
+							asset_amount,
+						});
+						return Ok(().into())
+					},
+					Err(error) if error != DispatchError::from(Error::<T>::NotEnoughLiquidity) =>
+						return Err(error.into()),
+					Err(_) => {},
+				}
+			}
+			Self::do_queue_withdrawal(&vault, &to, lp_amount)?;
+			Self::deposit_event(Event::WithdrawalQueued { vault, account: to, lp_amount });
+			Ok(().into())
+		}
+
+		/// Cancel the withdrawals queued by the caller in `vault`, releasing their LP tokens.
+		///
+		/// # Emits
+		///  - Event::QueuedWithdrawalCancelled
+		///
+		/// # Errors
+		///  - When the origin is not signed.
+		///  - When the caller has no withdrawal queued in `vault`.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_queued_withdrawal())]
+		pub fn cancel_queued_withdrawal(
+			origin: OriginFor<T>,
+			vault: T::VaultId,
+		) -> DispatchResultWithPostInfo {
+			let account = ensure_signed(origin)?;
+			let lp_amount = Self::do_cancel_queued_withdrawals(&vault, &account)?;
+			Self::deposit_event(Event::QueuedWithdrawalCancelled { vault, account, lp_amount });
 			Ok(().into())
 		}
 
@@ -744,8 +938,10 @@ pub mod pallet {
 
 			let lp_shares_value_amount = Self::do_lp_share_value(vault_id, &vault, lp_amount)?;
 
+			// the funds owed to queued withdrawals are reserved for them
 			let vault_owned_amount =
-				T::Currency::balance(vault.asset_id, &Self::account_id(vault_id));
+				T::Currency::balance(vault.asset_id, &Self::account_id(vault_id))
+					.saturating_sub(&Self::queued_withdrawals_value(vault_id, &vault)?);
 
 			// TODO(hussein-aitlahcen): should we provide what we can to reduce the available
 			// liquidity in order to force strategies to rebalance?
@@ -773,6 +969,154 @@ pub mod pallet {
 			Ok(lp_shares_value_amount)
 		}
 
+		pub(crate) fn do_queue_withdrawal(
+			vault_id: &T::VaultId,
+			to: &T::AccountId,
+			lp_amount: T::Balance,
+		) -> DispatchResult {
+			ensure!(
+				lp_amount > T::MinimumWithdrawal::get(),
+				Error::<T>::AmountMustGteMinimumWithdrawal
+			);
+			ensure!(
+				lp_amount >= T::MinimumQueuedWithdrawal::get(),
+				Error::<T>::QueuedWithdrawalTooSmall
+			);
+			let vault = Self::vault_info(vault_id)?;
+			ensure!(vault.capabilities.withdrawals_allowed(), Error::<T>::WithdrawalsHalted);
+			ensure!(
+				(WithdrawalQueue::<T>::decode_len(vault_id).unwrap_or_default() as u32) <
+					T::MaxQueuedWithdrawals::get(),
+				Error::<T>::WithdrawalQueueFull
+			);
+			T::Currency::hold(vault.lp_token_id, to, lp_amount)
+				.map_err(|_| Error::<T>::InsufficientLpTokens)?;
+			WithdrawalQueue::<T>::try_append(
+				vault_id,
+				QueuedWithdrawal {
+					account: to.clone(),
+					lp_amount,
+					queued_at: frame_system::Pallet::<T>::block_number(),
+				},
+			)
+			.map_err(|_| Error::<T>::WithdrawalQueueFull.into())
+		}
+
+		/// Removes the withdrawals queued by `account` in `vault_id` and releases their LP tokens.
+		/// Returns the amount of LP tokens released.
+		fn do_cancel_queued_withdrawals(
+			vault_id: &T::VaultId,
+			account: &T::AccountId,
+		) -> Result<T::Balance, DispatchError> {
+			let vault = Self::vault_info(vault_id)?;
+			WithdrawalQueue::<T>::try_mutate(vault_id, |queue| {
+				let lp_amount = queue
+					.iter()
+					.filter(|withdrawal| withdrawal.account == *account)
+					.try_fold(T::Balance::zero(), |sum, withdrawal| {
+						sum.checked_add(&withdrawal.lp_amount).ok_or(ArithmeticError::Overflow)
+					})?;
+				ensure!(!lp_amount.is_zero(), Error::<T>::NoQueuedWithdrawal);
+				queue.retain(|withdrawal| withdrawal.account != *account);
+				T::Currency::release(vault.lp_token_id, account, lp_amount, false)?;
+				Ok::<_, DispatchError>(lp_amount)
+			})
+		}
+
+		/// Settles the queued withdrawals of `vault_id` in order, until one exceeds the funds held
+		/// by the vault or `remaining_weight` does not allow more. Returns the weight consumed.
+		fn settle_withdrawals(
+			vault_id: &T::VaultId,
+			vault: &VaultInfo<T>,
+			remaining_weight: Weight,
+		) -> Weight {
+			let mut consumed_weight = Weight::zero();
+			let mut queue = Self::withdrawal_queue(vault_id);
+			let queued_before = queue.len();
+			while let Some(withdrawal) = queue.first().cloned() {
+				let weight = <T as Config>::WeightInfo::settle_withdrawal();
+				if consumed_weight.saturating_add(weight).any_gt(remaining_weight) {
+					break
+				}
+				consumed_weight = consumed_weight.saturating_add(weight);
+				let result = with_transaction(|| {
+					match Self::do_settle_withdrawal(vault_id, vault, &withdrawal) {
+						Ok(settled) => TransactionOutcome::Commit(Ok(settled)),
+						Err(error) => TransactionOutcome::Rollback(Err(error)),
+					}
+				});
+				match result {
+					Ok(Some(asset_amount)) => {
+						queue.remove(0);
+						Self::deposit_event(Event::WithdrawalSettled {
+							vault: *vault_id,
+							account: withdrawal.account,
+							lp_amount: withdrawal.lp_amount,
+							asset_amount,
+						});
+					},
+					Ok(None) => break,
+					Err(error) => {
+						log::warn!(
+							"Failed to settle withdrawal of {:?} from vault {:?}: {:?}",
+							withdrawal.account,
+							vault_id,
+							error
+						);
+						break
+					},
+				}
+			}
+			if queue.len() != queued_before {
+				WithdrawalQueue::<T>::insert(vault_id, queue);
+				consumed_weight = consumed_weight.saturating_add(T::DbWeight::get().writes(1));
+			}
+			consumed_weight
+		}
+
+		/// Exchanges the held LP tokens of `withdrawal` for underlying assets, `None` if the vault
+		/// does not hold enough funds yet.
+		fn do_settle_withdrawal(
+			vault_id: &T::VaultId,
+			vault: &VaultInfo<T>,
+			withdrawal: &QueuedWithdrawalOf<T>,
+		) -> Result<Option<T::Balance>, DispatchError> {
+			let asset_amount = Self::do_lp_share_value(vault_id, vault, withdrawal.lp_amount)?;
+			let from = Self::account_id(vault_id);
+			if asset_amount > T::Currency::reducible_balance(vault.asset_id, &from, true) {
+				return Ok(None)
+			}
+			T::Currency::release(
+				vault.lp_token_id,
+				&withdrawal.account,
+				withdrawal.lp_amount,
+				false,
+			)?;
+			T::Currency::burn_from(vault.lp_token_id, &withdrawal.account, withdrawal.lp_amount)
+				.map_err(|_| Error::<T>::InsufficientLpTokens)?;
+			T::Currency::transfer(vault.asset_id, &from, &withdrawal.account, asset_amount, true)
+				.map_err(|_| Error::<T>::TransferFromFailed)?;
+			Ok(Some(asset_amount))
+		}
+
+		/// Value of the LP tokens held by the withdrawals queued in `vault_id`.
+		fn queued_withdrawals_value(
+			vault_id: &T::VaultId,
+			vault: &VaultInfo<T>,
+		) -> Result<T::Balance, DispatchError> {
+			let lp_amount = Self::withdrawal_queue(vault_id).iter().try_fold(
+				T::Balance::zero(),
+				|sum, withdrawal| {
+					sum.checked_add(&withdrawal.lp_amount).ok_or(ArithmeticError::Overflow)
+				},
+			)?;
+			if lp_amount.is_zero() {
+				Ok(lp_amount)
+			} else {
+				Self::do_lp_share_value(vault_id, vault, lp_amount)
+			}
+		}
+
 		fn do_deposit(
 			vault_id: &T::VaultId,
 			from: &T::AccountId,
@@ -888,6 +1232,227 @@ pub mod pallet {
 			Ok(owned + outstanding)
 		}
 
+		/// Rebalances the strategies managed by [`Config::Strategies`] of every vault which was not
+		/// rebalanced for [`Config::RebalanceInterval`] blocks, settles its queued withdrawals,
+		/// then checkpoints its share price if [`Config::CheckpointInterval`] elapsed, as long as
+		/// `remaining_weight` allows it. Returns the weight consumed.
+		///
+		/// Vaults are maintained in turn, the next block resumes after the last vault maintained
+		/// so that the last vaults are not starved when the weight runs out.
+		pub(crate) fn maintain_vaults(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let mut consumed_weight = T::DbWeight::get().reads_writes(1, 1);
+			if consumed_weight.any_gt(remaining_weight) {
+				return Weight::zero()
+			}
+			let vaults = match MaintenanceCursor::<T>::get() {
+				Some(last_vault_id) =>
+					Vaults::<T>::iter_from(Vaults::<T>::hashed_key_for(last_vault_id)),
+				None => Vaults::<T>::iter(),
+			};
+			let mut last_maintained = None;
+			let mut all_maintained = true;
+			'vaults: for (vault_id, vault) in vaults {
+				consumed_weight = consumed_weight.saturating_add(T::DbWeight::get().reads(4));
+				if consumed_weight.any_gt(remaining_weight) {
+					all_maintained = false;
+					break
+				}
+				if Self::last_rebalance(vault_id)
+					.map_or(true, |last| now >= last + T::RebalanceInterval::get())
+				{
+					for strategy in CapitalStructure::<T>::iter_key_prefix(vault_id) {
+						if !T::Strategies::manages(&strategy) {
+							continue
+						}
+						let weight = <T as Config>::WeightInfo::rebalance_strategy();
+						if consumed_weight.saturating_add(weight).any_gt(remaining_weight) {
+							// the vault is rebalanced again from its first strategy next time
+							all_maintained = false;
+							break 'vaults
+						}
+						consumed_weight = consumed_weight.saturating_add(weight);
+						let result = with_transaction(|| {
+							match Self::do_rebalance_strategy(&vault_id, &vault, &strategy) {
+								Ok(()) => TransactionOutcome::Commit(Ok(())),
+								Err(error) => TransactionOutcome::Rollback(Err(error)),
+							}
+						});
+						if let Err(error) = result {
+							log::warn!(
+								"Failed to rebalance strategy {:?} of vault {:?}: {:?}",
+								strategy,
+								vault_id,
+								error
+							);
+						}
+					}
+					LastRebalance::<T>::insert(vault_id, now);
+					consumed_weight = consumed_weight.saturating_add(T::DbWeight::get().writes(1));
+				}
+				if vault.capabilities.withdrawals_allowed() {
+					consumed_weight = consumed_weight.saturating_add(Self::settle_withdrawals(
+						&vault_id,
+						&vault,
+						remaining_weight.saturating_sub(consumed_weight),
+					));
+				}
+				// taken after rebalancing, so that harvested yield is part of the checkpointed
+				// price
+				if Self::share_price_checkpoints(vault_id)
+					.last()
+					.map_or(true, |last| now >= last.block + T::CheckpointInterval::get())
+				{
+					let weight = <T as Config>::WeightInfo::checkpoint_share_price();
+					if consumed_weight.saturating_add(weight).any_gt(remaining_weight) {
+						all_maintained = false;
+						break
+					}
+					consumed_weight = consumed_weight.saturating_add(weight);
+					if let Err(error) = Self::checkpoint_share_price(&vault_id, now) {
+						log::warn!(
+							"Failed to checkpoint share price of vault {:?}: {:?}",
+							vault_id,
+							error
+						);
+					}
+				}
+				last_maintained = Some(vault_id);
+			}
+			if all_maintained {
+				// the next block starts over from the first vault
+				MaintenanceCursor::<T>::kill();
+			} else if let Some(last_vault_id) = last_maintained {
+				MaintenanceCursor::<T>::put(last_vault_id);
+			}
+			consumed_weight
+		}
+
+		/// Records the current share price of `vault_id`, dropping the oldest checkpoint once
+		/// [`Config::MaxCheckpoints`] are kept.
+		fn checkpoint_share_price(vault_id: &T::VaultId, now: T::BlockNumber) -> DispatchResult {
+			let checkpoint = SharePriceCheckpoint {
+				block: now,
+				timestamp: T::UnixTime::now().as_secs(),
+				price: <Self as Vault>::stock_dilution_rate(vault_id)?,
+			};
+			SharePriceCheckpoints::<T>::try_mutate(vault_id, |checkpoints| -> DispatchResult {
+				if checkpoints.len() as u32 >= T::MaxCheckpoints::get() && !checkpoints.is_empty() {
+					checkpoints.remove(0);
+				}
+				checkpoints.try_push(checkpoint).map_err(|_| Error::<T>::TooManyCheckpoints)?;
+				Ok(())
+			})
+		}
+
+		/// The return of a share of `vault_id` over the last `window` seconds, annualized without
+		/// compounding. Measured from the oldest checkpoint within the window up to the current
+		/// share price.
+		pub fn realized_apy(
+			vault_id: &T::VaultId,
+			window: DurationSeconds,
+		) -> Result<FixedI128, DispatchError> {
+			let now = T::UnixTime::now().as_secs();
+			let start = Self::share_price_checkpoints(vault_id)
+				.into_iter()
+				.find(|checkpoint| checkpoint.timestamp >= now.saturating_sub(window))
+				.ok_or(Error::<T>::NotEnoughCheckpoints)?;
+			let elapsed = now.saturating_sub(start.timestamp);
+			ensure!(!elapsed.is_zero() && !start.price.is_zero(), Error::<T>::NotEnoughCheckpoints);
+			let price = <Self as Vault>::stock_dilution_rate(vault_id)?;
+			let growth =
+				FixedI128::checked_from_rational(price.into_inner(), start.price.into_inner())
+					.and_then(|rate| rate.checked_sub(&FixedI128::one()))
+					.ok_or(ArithmeticError::Overflow)?;
+			Ok(growth
+				.checked_mul(&FixedI128::saturating_from_rational(SECONDS_PER_YEAR_NAIVE, elapsed))
+				.ok_or(ArithmeticError::Overflow)?)
+		}
+
+		/// Allocation, balance and return of every strategy of `vault_id`. The return is `None`
+		/// for strategies which never withdrew funds from the vault.
+		pub fn strategy_reports(
+			vault_id: &T::VaultId,
+		) -> Result<Vec<StrategyReport<T::AccountId, T::Balance, FixedI128>>, DispatchError> {
+			Self::vault_info(vault_id)?;
+			Ok(CapitalStructure::<T>::iter_prefix(vault_id)
+				.map(|(strategy, overview)| {
+					let returned =
+						<T::Convert as Convert<T::Balance, u128>>::convert(overview.balance)
+							.saturating_add(<T::Convert as Convert<T::Balance, u128>>::convert(
+								overview.lifetime_deposited,
+							));
+					let withdrawn = <T::Convert as Convert<T::Balance, u128>>::convert(
+						overview.lifetime_withdrawn,
+					);
+					StrategyReport {
+						strategy,
+						allocation: overview.allocation,
+						balance: overview.balance,
+						lifetime_withdrawn: overview.lifetime_withdrawn,
+						lifetime_deposited: overview.lifetime_deposited,
+						return_rate: FixedI128::checked_from_rational(returned, withdrawn)
+							.and_then(|rate| rate.checked_sub(&FixedI128::one())),
+					}
+				})
+				.collect())
+		}
+
+		/// Harvests the yield of `strategy` and reports its balance, then moves funds between the
+		/// vault and the strategy so that it holds its allocation of the assets under management.
+		fn do_rebalance_strategy(
+			vault_id: &T::VaultId,
+			vault: &VaultInfo<T>,
+			strategy: &T::AccountId,
+		) -> DispatchResult {
+			T::Strategies::harvest(strategy, vault.asset_id)?;
+			let balance = T::Strategies::balance(strategy, vault.asset_id)?;
+			let reported = CapitalStructure::<T>::get(vault_id, strategy).balance;
+			if balance > reported {
+				Self::deposit_event(Event::YieldHarvested {
+					vault: *vault_id,
+					strategy: strategy.clone(),
+					amount: balance - reported,
+				});
+			}
+			<Self as ReportableStrategicVault>::update_strategy_report(
+				vault_id, strategy, &balance,
+			)?;
+
+			let funds = match <Self as StrategicVault>::available_funds(vault_id, strategy)? {
+				FundsAvailability::Withdrawable(amount) => {
+					// other strategies may not have returned their excess yet
+					let amount = amount.min(T::Currency::reducible_balance(
+						vault.asset_id,
+						&Self::account_id(vault_id),
+						true,
+					));
+					if amount.is_zero() {
+						return Ok(())
+					}
+					<Self as StrategicVault>::withdraw(vault_id, strategy, amount)?;
+					T::Strategies::deposit(strategy, vault.asset_id, amount)?;
+					FundsAvailability::Withdrawable(amount)
+				},
+				FundsAvailability::Depositable(amount) => {
+					T::Strategies::withdraw(strategy, vault.asset_id, amount)?;
+					<Self as StrategicVault>::deposit(vault_id, strategy, amount)?;
+					FundsAvailability::Depositable(amount)
+				},
+				FundsAvailability::MustLiquidate if !balance.is_zero() => {
+					T::Strategies::withdraw(strategy, vault.asset_id, balance)?;
+					<Self as StrategicVault>::deposit(vault_id, strategy, balance)?;
+					FundsAvailability::MustLiquidate
+				},
+				FundsAvailability::MustLiquidate | FundsAvailability::None => return Ok(()),
+			};
+			Self::deposit_event(Event::StrategyRebalanced {
+				vault: *vault_id,
+				strategy: strategy.clone(),
+				funds,
+			});
+			Ok(())
+		}
+
 		/// Tries to fetch a stored [VaultInfo] through its index.
 		fn vault_info(vault_idx: &T::VaultId) -> Result<VaultInfo<T>, DispatchError> {
 			Ok(Vaults::<T>::try_get(vault_idx).map_err(|_err| Error::<T>::VaultDoesNotExist)?)
@@ -1015,7 +1580,10 @@ pub mod pallet {
 				(Ok(vault), Ok(StrategyOverview { allocation, balance, .. }))
 					if !vault.capabilities.is_stopped() && !vault.capabilities.is_tombstoned() =>
 				{
-					let aum = Self::assets_under_management(vault_id)?;
+					// funds owed to queued withdrawals are not allocated, so that strategies return
+					// them to the vault
+					let aum = Self::assets_under_management(vault_id)?
+						.saturating_sub(&Self::queued_withdrawals_value(vault_id, &vault)?);
 					let max_allowed = <T::Convert as Convert<u128, T::Balance>>::convert(
 						allocation
 							.mul_floor(<T::Convert as Convert<T::Balance, u128>>::convert(aum)),
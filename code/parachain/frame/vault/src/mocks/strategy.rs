@@ -1,11 +1,14 @@
 //! An example pallet showing how a `strategy` could be implemented as a secondary pallet. The
-//! extrinsics show how to interact with the `vault` pallet.
+//! extrinsics show how to interact with the `vault` pallet, while the `VaultStrategy`
+//! implementation lets the vault rebalance it.
 
 pub use pallet::*;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use crate::traits::{FundsAvailability, ReportableStrategicVault, StrategicVault};
+	use crate::traits::{
+		FundsAvailability, ReportableStrategicVault, StrategicVault, VaultStrategy,
+	};
 	use composable_traits::vault::Vault;
 	use frame_support::{
 		pallet_prelude::*,
@@ -13,10 +16,12 @@ pub mod pallet {
 		PalletId,
 	};
 	use frame_system::{ensure_root, pallet_prelude::OriginFor, Config as SystemConfig};
-	use sp_runtime::traits::AccountIdConversion;
+	use sp_runtime::traits::{AccountIdConversion, Zero};
 
 	type BalanceOf<T> =
 		<<T as Config>::Currency as Inspect<<T as SystemConfig>::AccountId>>::Balance;
+	type AssetIdOf<T> =
+		<<T as Config>::Currency as Inspect<<T as SystemConfig>::AccountId>>::AssetId;
 	type VaultIdOf<T> = <<T as Config>::Vault as Vault>::VaultId;
 	type ReportOf<T> = <<T as Config>::Vault as ReportableStrategicVault>::Report;
 
@@ -59,11 +64,48 @@ pub mod pallet {
 	pub struct Pallet<T>(_);
 
 	impl<T: Config> Pallet<T> {
-		fn account_id() -> T::AccountId {
+		pub fn account_id() -> T::AccountId {
 			T::PalletId::get().into_account_truncating()
 		}
 	}
 
+	/// Funds are kept idle in the account of the pallet, revenue is minted straight into it.
+	impl<T: Config> VaultStrategy<T::AccountId, AssetIdOf<T>, BalanceOf<T>> for Pallet<T> {
+		fn manages(strategy: &T::AccountId) -> bool {
+			*strategy == Self::account_id()
+		}
+
+		fn harvest(
+			_strategy: &T::AccountId,
+			_asset_id: AssetIdOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			Ok(Zero::zero())
+		}
+
+		fn balance(
+			strategy: &T::AccountId,
+			asset_id: AssetIdOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			Ok(T::Currency::balance(asset_id, strategy))
+		}
+
+		fn deposit(
+			_strategy: &T::AccountId,
+			_asset_id: AssetIdOf<T>,
+			_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			Ok(())
+		}
+
+		fn withdraw(
+			_strategy: &T::AccountId,
+			_asset_id: AssetIdOf<T>,
+			_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			Ok(())
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Mints new tokens and sends them to self, mocking the generating of revenue through DeFi.
@@ -88,6 +88,11 @@ parameter_types! {
 	pub const MinimumDeposit: Balance = 0;
 	pub const MinimumWithdrawal: Balance = 0;
 	pub const TombstoneDuration: BlockNumber = 10;
+	pub const RebalanceInterval: BlockNumber = 5;
+	pub const CheckpointInterval: BlockNumber = 10;
+	pub const MaxCheckpoints: u32 = 3;
+	pub const MaxQueuedWithdrawals: u32 = 2;
+	pub const MinimumQueuedWithdrawal: Balance = 100;
 }
 
 impl pallet_vault::Config for Test {
@@ -108,6 +113,24 @@ impl pallet_vault::Config for Test {
 	type TombstoneDuration = TombstoneDuration;
 	type VaultId = u64;
 	type WeightInfo = ();
+	type Strategies = Strategy;
+	type RebalanceInterval = RebalanceInterval;
+	type UnixTime = Timestamp;
+	type CheckpointInterval = CheckpointInterval;
+	type MaxCheckpoints = MaxCheckpoints;
+	type MaxQueuedWithdrawals = MaxQueuedWithdrawals;
+	type MinimumQueuedWithdrawal = MinimumQueuedWithdrawal;
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 5;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
 }
 
 parameter_type_with_key! {
@@ -170,6 +193,7 @@ construct_runtime!(
 		Factory: crate::mocks::currency_factory::{Pallet, Call, Storage, Event<T>},
 		Strategy: crate::mocks::strategy::{Pallet, Call, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 	}
 );
 
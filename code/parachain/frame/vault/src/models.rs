@@ -24,3 +24,14 @@ pub struct StrategyOverview<Balance> {
 	/// Sum of all deposited funds.
 	pub lifetime_deposited: Balance,
 }
+
+/// A withdrawal which could not be served from the funds held by the vault when requested.
+#[derive(Copy, Clone, Encode, Decode, MaxEncodedLen, Debug, PartialEq, Eq, TypeInfo)]
+pub struct QueuedWithdrawal<AccountId, Balance, BlockNumber> {
+	/// The account redeeming LP tokens, which are held until the withdrawal is settled.
+	pub account: AccountId,
+	/// Amount of LP tokens to exchange for underlying assets.
+	pub lp_amount: Balance,
+	/// The block the withdrawal was requested at.
+	pub queued_at: BlockNumber,
+}
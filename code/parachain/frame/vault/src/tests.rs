@@ -2,12 +2,14 @@ use crate::{
 	mocks::{
 		currency_factory::MockCurrencyId,
 		tests::{
-			AccountId, Balance, Balances, BlockNumber, CreationDeposit, ExistentialDeposit,
-			ExtBuilder, RuntimeEvent, RuntimeOrigin, System, Test, Tokens, TombstoneDuration,
-			Vaults, ACCOUNT_FREE_START, ALICE, BOB, CHARLIE, MINIMUM_BALANCE,
+			AccountId, Balance, Balances, BlockNumber, CheckpointInterval, CreationDeposit,
+			ExistentialDeposit, ExtBuilder, MaxCheckpoints, MaxQueuedWithdrawals,
+			RebalanceInterval, RuntimeEvent, RuntimeOrigin, Strategy, System, Test, Timestamp,
+			Tokens, TombstoneDuration, Vaults, ACCOUNT_FREE_START, ALICE, BOB, CHARLIE,
+			MINIMUM_BALANCE,
 		},
 	},
-	models::VaultInfo,
+	models::{QueuedWithdrawal, VaultInfo},
 	*,
 };
 use composable_support::{math::safe::safe_multiply_by_rational, validation::Validated};
@@ -17,16 +19,22 @@ use composable_tests_helpers::{
 };
 use composable_traits::{
 	defi::Rate,
+	time::{ONE_HOUR, SECONDS_PER_YEAR_NAIVE},
 	vault::{
-		Deposit, FundsAvailability, ReportableStrategicVault, StrategicVault, Vault, VaultConfig,
+		Deposit, FundsAvailability, ReportableStrategicVault, SharePriceCheckpoint, StrategicVault,
+		StrategyReport, Vault, VaultConfig,
 	},
 };
 use frame_support::{
 	assert_noop, assert_ok,
-	traits::fungibles::{Inspect, Mutate},
+	traits::{
+		fungibles::{Inspect, Mutate},
+		Hooks,
+	},
+	weights::Weight,
 };
 use proptest::prelude::*;
-use sp_runtime::{ArithmeticError, FixedPointNumber, Perbill, Perquintill};
+use sp_runtime::{ArithmeticError, FixedI128, FixedPointNumber, Perbill, Perquintill};
 
 const DEFAULT_STRATEGY_SHARE: Perquintill = Perquintill::from_percent(90);
 // dependent on the previous value, both should be changed
@@ -981,3 +989,302 @@ fn do_liquidate_strategy_successfully_liquidates_a_strategy_account(
 		assert_eq!(Tokens::balance(currency_id, &strategy_account_id), 0);
 	});
 }
+
+#[test]
+fn on_idle_rebalances_managed_strategies_and_reports_yield() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let strategy = Strategy::account_id();
+		let (id, _) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+
+		Vaults::on_idle(1, Weight::MAX);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &strategy), 900);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 100);
+		assert_eq!(CapitalStructure::<Test>::get(id, strategy).balance, 900);
+		assert_eq!(Vaults::last_rebalance(id), Some(1));
+		System::assert_last_event(RuntimeEvent::Vaults(crate::Event::StrategyRebalanced {
+			vault: id,
+			strategy,
+			funds: FundsAvailability::Withdrawable(900),
+		}));
+
+		assert_ok!(Strategy::generate_revenue(RuntimeOrigin::root(), id, 90));
+		// not rebalanced again before the interval elapsed
+		Vaults::on_idle(RebalanceInterval::get(), Weight::MAX);
+		assert_eq!(CapitalStructure::<Test>::get(id, strategy).balance, 900);
+
+		Vaults::on_idle(1 + RebalanceInterval::get(), Weight::MAX);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::YieldHarvested {
+			vault: id,
+			strategy,
+			amount: 90,
+		}));
+		// 90% of the 1090 under management stay in the strategy
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &strategy), 981);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 109);
+		assert_eq!(Vaults::lp_share_value(&id, 1000), Ok(1090));
+	});
+}
+
+#[test]
+fn on_idle_liquidates_strategies_of_stopped_vaults_and_ignores_unmanaged_ones() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let strategy = Strategy::account_id();
+		let (managed, _) = create_vault(strategy, MockCurrencyId::A);
+		let (unmanaged, _) = create_vault(BOB, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 2000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), managed, 1000));
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), unmanaged, 1000));
+
+		Vaults::on_idle(1, Weight::MAX);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &strategy), 900);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &BOB), 0);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&unmanaged)), 1000);
+
+		assert_ok!(Vaults::emergency_shutdown(RuntimeOrigin::root(), managed));
+		Vaults::on_idle(1 + RebalanceInterval::get(), Weight::MAX);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &strategy), 0);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&managed)), 1000);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::StrategyRebalanced {
+			vault: managed,
+			strategy,
+			funds: FundsAvailability::MustLiquidate,
+		}));
+	});
+}
+
+#[test]
+fn on_idle_does_not_exceed_remaining_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		let strategy = Strategy::account_id();
+		let (id, _) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+
+		// the mock runtime does not charge for storage access, so only the strategy is accounted
+		let remaining_weight = <Test as Config>::WeightInfo::rebalance_strategy()
+			.saturating_sub(Weight::from_ref_time(1));
+		assert!(Vaults::on_idle(1, remaining_weight).all_lte(remaining_weight));
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &strategy), 0);
+		assert_eq!(Vaults::last_rebalance(id), None);
+	});
+}
+
+#[test]
+fn on_idle_resumes_after_the_last_vault_maintained() {
+	ExtBuilder::default().build().execute_with(|| {
+		let strategy = Strategy::account_id();
+		let (a, _) = create_vault(strategy, MockCurrencyId::A);
+		let (b, _) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 2000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), a, 1000));
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), b, 1000));
+
+		// enough for a single vault to be rebalanced and checkpointed
+		let remaining_weight = <Test as Config>::WeightInfo::rebalance_strategy()
+			.saturating_add(<Test as Config>::WeightInfo::checkpoint_share_price());
+		Vaults::on_idle(1, remaining_weight);
+		// vaults are not iterated in order of id
+		let (first, second) = if Vaults::last_rebalance(a).is_some() { (a, b) } else { (b, a) };
+		assert_eq!(Vaults::last_rebalance(first), Some(1));
+		assert_eq!(Vaults::last_rebalance(second), None);
+		assert_eq!(MaintenanceCursor::<Test>::get(), Some(first));
+
+		Vaults::on_idle(2, remaining_weight);
+		assert_eq!(Vaults::last_rebalance(second), Some(2));
+		assert_eq!(MaintenanceCursor::<Test>::get(), None);
+	});
+}
+
+#[test]
+fn on_idle_checkpoints_share_price_and_drops_oldest_checkpoints() {
+	ExtBuilder::default().build().execute_with(|| {
+		let (id, _) = create_vault(BOB, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		Timestamp::set_timestamp(6000);
+
+		Vaults::on_idle(1, Weight::MAX);
+		assert_eq!(
+			Vaults::share_price_checkpoints(id).into_inner(),
+			vec![SharePriceCheckpoint { block: 1, timestamp: 6, price: Rate::from(1) }]
+		);
+		// not checkpointed again before the interval elapsed
+		Vaults::on_idle(CheckpointInterval::get(), Weight::MAX);
+		assert_eq!(Vaults::share_price_checkpoints(id).len(), 1);
+
+		for n in 1..=MaxCheckpoints::get() as u64 {
+			Vaults::on_idle(1 + n * CheckpointInterval::get(), Weight::MAX);
+		}
+		let checkpoints = Vaults::share_price_checkpoints(id);
+		assert_eq!(checkpoints.len(), MaxCheckpoints::get() as usize);
+		assert_eq!(checkpoints.first().map(|checkpoint| checkpoint.block), Some(11));
+	});
+}
+
+#[test]
+fn realized_apy_annualizes_share_price_growth_within_window() {
+	ExtBuilder::default().build().execute_with(|| {
+		let (id, _) = create_vault(BOB, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_noop!(
+			Vaults::realized_apy(&id, SECONDS_PER_YEAR_NAIVE),
+			Error::<Test>::NotEnoughCheckpoints
+		);
+
+		Vaults::on_idle(1, Weight::MAX);
+		Timestamp::set_timestamp(SECONDS_PER_YEAR_NAIVE / 2 * 1000);
+		Tokens::mint_into(MockCurrencyId::A, &Vaults::account_id(&id), 100).unwrap();
+
+		// 10% over half a year
+		assert_eq!(
+			Vaults::realized_apy(&id, SECONDS_PER_YEAR_NAIVE),
+			Ok(FixedI128::saturating_from_rational(20, 100))
+		);
+		assert_noop!(Vaults::realized_apy(&id, ONE_HOUR), Error::<Test>::NotEnoughCheckpoints);
+	});
+}
+
+#[test]
+fn strategy_reports_return_allocation_and_return_of_strategies() {
+	ExtBuilder::default().build().execute_with(|| {
+		let strategy = Strategy::account_id();
+		let (id, _) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_eq!(
+			Vaults::strategy_reports(&id),
+			Ok(vec![StrategyReport {
+				strategy,
+				allocation: DEFAULT_STRATEGY_SHARE,
+				balance: 0,
+				lifetime_withdrawn: 0,
+				lifetime_deposited: 0,
+				return_rate: None,
+			}])
+		);
+
+		Vaults::on_idle(1, Weight::MAX);
+		assert_ok!(Strategy::generate_revenue(RuntimeOrigin::root(), id, 90));
+		assert_ok!(Strategy::report(RuntimeOrigin::root(), id));
+		assert_eq!(
+			Vaults::strategy_reports(&id),
+			Ok(vec![StrategyReport {
+				strategy,
+				allocation: DEFAULT_STRATEGY_SHARE,
+				balance: 990,
+				lifetime_withdrawn: 900,
+				lifetime_deposited: 0,
+				return_rate: Some(FixedI128::saturating_from_rational(10, 100)),
+			}])
+		);
+	});
+}
+
+#[test]
+fn withdrawals_exceeding_vault_funds_are_queued_and_settled_once_strategies_return_funds() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let strategy = Strategy::account_id();
+		let (id, vault) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		Vaults::on_idle(1, Weight::MAX);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 100);
+
+		assert_ok!(Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 500));
+		System::assert_last_event(RuntimeEvent::Vaults(crate::Event::WithdrawalQueued {
+			vault: id,
+			account: ALICE,
+			lp_amount: 500,
+		}));
+		assert_eq!(
+			Vaults::withdrawal_queue(id).into_inner(),
+			vec![QueuedWithdrawal { account: ALICE, lp_amount: 500, queued_at: 1 }]
+		);
+		assert_eq!(Tokens::reducible_balance(vault.lp_token_id, &ALICE, false), 500);
+		// the strategy keeps 90% of the funds not owed to the queued withdrawal
+		assert_eq!(
+			<Vaults as StrategicVault>::available_funds(&id, &strategy),
+			Ok(FundsAvailability::Depositable(450))
+		);
+
+		Vaults::on_idle(1 + RebalanceInterval::get(), Weight::MAX);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::WithdrawalSettled {
+			vault: id,
+			account: ALICE,
+			lp_amount: 500,
+			asset_amount: 500,
+		}));
+		assert!(Vaults::withdrawal_queue(id).is_empty());
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &ALICE), 500);
+		assert_eq!(Tokens::balance(vault.lp_token_id, &ALICE), 500);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 50);
+	});
+}
+
+#[test]
+fn withdrawals_are_queued_behind_pending_ones_up_to_max_queued_withdrawals() {
+	ExtBuilder::default().build().execute_with(|| {
+		let strategy = Strategy::account_id();
+		let (id, _) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		Vaults::on_idle(1, Weight::MAX);
+
+		assert_ok!(Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 500));
+		// the funds held by the vault are reserved for the queued withdrawal
+		assert_noop!(
+			<Vaults as Vault>::withdraw(&id, &ALICE, 60),
+			Error::<Test>::NotEnoughLiquidity
+		);
+		assert_noop!(
+			Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 60),
+			Error::<Test>::QueuedWithdrawalTooSmall
+		);
+		// could be served from the funds held by the vault, but the queue goes first
+		assert_ok!(Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 100));
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &ALICE), 0);
+		assert_eq!(Vaults::withdrawal_queue(id).len(), MaxQueuedWithdrawals::get() as usize);
+
+		// the first withdrawal can not be settled yet, so neither can the second one
+		Vaults::on_idle(2, Weight::MAX);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &ALICE), 0);
+		assert_noop!(
+			Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 100),
+			Error::<Test>::WithdrawalQueueFull
+		);
+	});
+}
+
+#[test]
+fn cancelling_queued_withdrawals_releases_their_lp_tokens() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let strategy = Strategy::account_id();
+		let (id, vault) = create_vault(strategy, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		Vaults::on_idle(1, Weight::MAX);
+		assert_ok!(Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 500));
+		assert_ok!(Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 300));
+		assert_eq!(Tokens::reducible_balance(vault.lp_token_id, &ALICE, false), 200);
+
+		assert_noop!(
+			Vaults::cancel_queued_withdrawal(RuntimeOrigin::signed(BOB), id),
+			Error::<Test>::NoQueuedWithdrawal
+		);
+		assert_ok!(Vaults::cancel_queued_withdrawal(RuntimeOrigin::signed(ALICE), id));
+		System::assert_last_event(RuntimeEvent::Vaults(crate::Event::QueuedWithdrawalCancelled {
+			vault: id,
+			account: ALICE,
+			lp_amount: 800,
+		}));
+		assert!(Vaults::withdrawal_queue(id).is_empty());
+		assert_eq!(Tokens::reducible_balance(vault.lp_token_id, &ALICE, false), 1000);
+	});
+}
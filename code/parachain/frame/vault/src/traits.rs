@@ -2,5 +2,5 @@
 
 pub use composable_traits::{
 	currency::CurrencyFactory,
-	vault::{FundsAvailability, ReportableStrategicVault, StrategicVault},
+	vault::{FundsAvailability, ReportableStrategicVault, StrategicVault, VaultStrategy},
 };
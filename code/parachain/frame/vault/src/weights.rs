@@ -18,6 +18,10 @@ pub trait WeightInfo {
 	fn add_surcharge() -> Weight;
 	fn claim_surcharge() -> Weight;
 	fn delete_tombstoned() -> Weight;
+	fn rebalance_strategy() -> Weight;
+	fn checkpoint_share_price() -> Weight;
+	fn settle_withdrawal() -> Weight;
+	fn cancel_queued_withdrawal() -> Weight;
 }
 
 pub struct SubstrateWeight<T>(PhantomData<T>);
@@ -52,6 +56,34 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(8_u64))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Tokens TotalIssuance (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:0)
+	// Storage: Vault CapitalStructure (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Vault SharePriceCheckpoints (r:1 w:1)
+	fn checkpoint_share_price() -> Weight {
+		Weight::from_ref_time(38_504_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	// Storage: Vault CapitalStructure (r:1 w:0)
+	// Storage: Tokens Accounts (r:3 w:3)
+	// Storage: Tokens Reserves (r:1 w:0)
+	fn settle_withdrawal() -> Weight {
+		Weight::from_ref_time(71_236_000_u64)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault WithdrawalQueue (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	fn cancel_queued_withdrawal() -> Weight {
+		Weight::from_ref_time(38_114_000_u64)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
 	// Storage: Vault Vaults (r:1 w:1)
 	fn emergency_shutdown() -> Weight {
 		Weight::from_ref_time(25_497_000_u64)
@@ -86,6 +118,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
+	// Storage: Vault CapitalStructure (r:4 w:2)
+	// Storage: Vault Vaults (r:2 w:0)
+	// Storage: Tokens Accounts (r:2 w:2)
+	fn rebalance_strategy() -> Weight {
+		Weight::from_ref_time(96_412_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
 }
 
 impl WeightInfo for () {
@@ -119,6 +159,34 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(8_u64))
 			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Tokens TotalIssuance (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:0)
+	// Storage: Vault CapitalStructure (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Vault SharePriceCheckpoints (r:1 w:1)
+	fn checkpoint_share_price() -> Weight {
+		Weight::from_ref_time(38_504_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	// Storage: Vault CapitalStructure (r:1 w:0)
+	// Storage: Tokens Accounts (r:3 w:3)
+	// Storage: Tokens Reserves (r:1 w:0)
+	fn settle_withdrawal() -> Weight {
+		Weight::from_ref_time(71_236_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault WithdrawalQueue (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	fn cancel_queued_withdrawal() -> Weight {
+		Weight::from_ref_time(38_114_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
 	// Storage: Vault Vaults (r:1 w:1)
 	fn emergency_shutdown() -> Weight {
 		Weight::from_ref_time(25_497_000_u64)
@@ -153,4 +221,12 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
+	// Storage: Vault CapitalStructure (r:4 w:2)
+	// Storage: Vault Vaults (r:2 w:0)
+	// Storage: Tokens Accounts (r:2 w:2)
+	fn rebalance_strategy() -> Weight {
+		Weight::from_ref_time(96_412_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
 }
@@ -22,8 +22,9 @@ use crate::{
 	runtime::{
 		assets::ExtendWithAssetsApi, cosmwasm::ExtendWithCosmwasmApi,
 		crowdloan_rewards::ExtendWithCrowdloanRewardsApi, farming::ExtendWithFarmingApi,
-		ibc::ExtendWithIbcApi, lending::ExtendWithLendingApi, pablo::ExtendWithPabloApi,
-		staking_rewards::ExtendWithStakingRewardsApi, BaseHostRuntimeApis,
+		ibc::ExtendWithIbcApi, lending::ExtendWithLendingApi, oracle::ExtendWithOracleApi,
+		pablo::ExtendWithPabloApi, staking_rewards::ExtendWithStakingRewardsApi,
+		vault::ExtendWithVaultApi, BaseHostRuntimeApis,
 	},
 };
 
@@ -69,6 +70,8 @@ where
 			+ ExtendWithPabloApi<RuntimeApi, Executor>
 			+ ExtendWithFarmingApi<RuntimeApi, Executor>
 			+ ExtendWithLendingApi<RuntimeApi, Executor>
+			+ ExtendWithVaultApi<RuntimeApi, Executor>
+			+ ExtendWithOracleApi<RuntimeApi, Executor>
 			+ ExtendWithCosmwasmApi<RuntimeApi, Executor>
 			+ ExtendWithIbcApi<RuntimeApi, Executor>,
 {
@@ -106,6 +109,16 @@ where
 		deps.clone(),
 	)?;
 
+	<FullClient<RuntimeApi, Executor> as ProvideRuntimeApi<OpaqueBlock>>::Api::extend_with_vault_api(
+		&mut io,
+		deps.clone(),
+	)?;
+
+	<FullClient<RuntimeApi, Executor> as ProvideRuntimeApi<OpaqueBlock>>::Api::extend_with_oracle_api(
+		&mut io,
+		deps.clone(),
+	)?;
+
 	<FullClient<RuntimeApi, Executor> as ProvideRuntimeApi<OpaqueBlock>>::Api::extend_with_cosmwasm_api(
 		&mut io, deps.clone(),
 	)?;
@@ -4,6 +4,7 @@ use cosmwasm_rpc::{Cosmwasm, CosmwasmApiServer};
 use crowdloan_rewards_rpc::{CrowdloanRewards, CrowdloanRewardsApiServer};
 use cumulus_primitives_core::CollectCollationInfo;
 use ibc_rpc::{IbcApiServer, IbcRpcHandler};
+use oracle_rpc::{Oracle, OracleApiServer};
 use pablo_rpc::{Pablo, PabloApiServer};
 use pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi;
 use reward_rpc::{Reward, RewardApiServer};
@@ -15,6 +16,7 @@ use sp_runtime::traits::BlakeTwo256;
 use sp_session::SessionKeys;
 use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
 use substrate_frame_rpc_system::AccountNonceApi;
+use vault_rpc::{Vault, VaultApiServer};
 
 /// Consider this a trait alias.
 pub trait BaseHostRuntimeApis:
@@ -221,6 +223,34 @@ define_trait! {
 		impl for picasso_runtime {}
 	}
 
+	mod vault {
+		pub trait ExtendWithVaultApi {
+			fn extend_with_vault_api(io, deps);
+		}
+
+		impl for composable_runtime {}
+
+		impl for picasso_runtime {
+			fn (io, deps) {
+				io.merge(Vault::new(deps.client).into_rpc())
+			}
+		}
+	}
+
+	mod oracle {
+		pub trait ExtendWithOracleApi {
+			fn extend_with_oracle_api(io, deps);
+		}
+
+		impl for composable_runtime {}
+
+		impl for picasso_runtime {
+			fn (io, deps) {
+				io.merge(Oracle::new(deps.client).into_rpc())
+			}
+		}
+	}
+
 	mod cosmwasm {
 		pub trait ExtendWithCosmwasmApi {
 			fn extend_with_cosmwasm_api(io, deps);
@@ -38,7 +38,7 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
 					c,
 					RuntimeCall::Pablo(..) |
 						RuntimeCall::FarmingRewards(..) |
-						RuntimeCall::Farming(..)
+						RuntimeCall::Farming(..) | RuntimeCall::Vault(..)
 				)
 			},
 			ProxyType::Oracle => {
@@ -53,7 +53,11 @@ use common::{
 use composable_support::rpc_helpers::SafeRpcWrapper;
 use composable_traits::{
 	assets::Asset,
-	dex::{Amm, PriceAggregate},
+	defi::Rate,
+	dex::{Amm, AssetAmount, Fee, LiquiditySimulation, PriceAggregate, SwapSimulation},
+	oracle::{OracleAssetInfo, Price, RewardTracker, SignerStats, SubmittedPrice},
+	time::DurationSeconds,
+	vault::{SharePriceCheckpoint, StrategyReport},
 };
 use pallet_ibc::ics20_fee::FlatFeeConverter;
 use primitives::currency::ForeignAssetId;
@@ -242,7 +246,10 @@ impl assets_registry::Config for Runtime {
 parameter_types! {
 	pub PabloPalletId: PalletId = PalletId(*b"pal_pblo");
 	pub TWAPInterval: u64 = (MILLISECS_PER_BLOCK as u64) * 10;
+	pub TWAPObservationInterval: u64 = (MILLISECS_PER_BLOCK as u64) * 5;
+	pub const MaxTWAPObservations: u32 = 90;
 	pub LPTokenExistentialDeposit: Balance = 100;
+	pub const MinPositionLiquidity: u128 = 1_000_000_000;
 }
 
 impl pablo::Config for Runtime {
@@ -256,10 +263,34 @@ impl pablo::Config for Runtime {
 	type PalletId = PabloPalletId;
 	type PoolCreationOrigin = EnsureRootOrTwoThirdNativeCouncil;
 	type EnableTwapOrigin = EnsureRootOrTwoThirdNativeCouncil;
+	type ManageFeesOrigin = EnsureRootOrTwoThirdNativeCouncil;
 	type Time = Timestamp;
 	type TWAPInterval = TWAPInterval;
+	type TWAPObservationInterval = TWAPObservationInterval;
+	type MaxTWAPObservations = MaxTWAPObservations;
 	type WeightInfo = weights::pablo::WeightInfo<Runtime>;
 	type LPTokenExistentialDeposit = LPTokenExistentialDeposit;
+	type FinancialNft = Fnft;
+	type FinancialNftInstanceId = FinancialNftInstanceId;
+	type MinPositionLiquidity = MinPositionLiquidity;
+}
+
+parameter_types! {
+	pub const FnftPalletId: PalletId = PalletId(*b"pal_fnft");
+}
+
+pub type FinancialNftInstanceId = u64;
+
+impl fnft::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxProperties = ConstU32<16>;
+	type FinancialNftCollectionId = CurrencyId;
+	type FinancialNftInstanceId = FinancialNftInstanceId;
+	type ProxyType = composable_traits::account_proxy::ProxyType;
+	type AccountProxy = composable_traits::account_proxy::AccountProxyWrapper<Runtime>;
+	type ProxyTypeSelector = composable_traits::fnft::FnftAccountProxyType;
+	type PalletId = FnftPalletId;
+	type WeightInfo = ();
 }
 
 impl assets_transactor_router::Config for Runtime {
@@ -332,6 +363,7 @@ parameter_types! {
 	// cspell:disable-next
 	pub const OraclePalletId: PalletId = PalletId(*b"plt_orac");
 	pub const MsPerBlock: u64 = MILLISECS_PER_BLOCK as u64;
+	pub const MaxPriceSourceHops: u32 = 4;
 }
 
 impl oracle::Config for Runtime {
@@ -360,6 +392,9 @@ impl oracle::Config for Runtime {
 	type Moment = Moment;
 	type Time = Timestamp;
 	type PalletId = OraclePalletId;
+	type PoolId = PoolId;
+	type Dex = Pablo;
+	type MaxPriceSourceHops = MaxPriceSourceHops;
 }
 
 parameter_types! {
@@ -786,6 +821,49 @@ impl bonded_finance::Config for Runtime {
 	type WeightInfo = weights::bonded_finance::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	pub const VaultPalletId: PalletId = PalletId(*b"pal_vlts");
+	pub const MaxStrategies: usize = 255;
+	pub VaultCreationDeposit: Balance = 10 * CurrencyId::unit::<Balance>();
+	pub VaultRentPerBlock: Balance = CurrencyId::milli::<Balance>();
+	pub const VaultMinimumDeposit: Balance = 0;
+	pub const VaultMinimumWithdrawal: Balance = 0;
+	pub const TombstoneDuration: BlockNumber = DAYS;
+	pub const RebalanceInterval: BlockNumber = HOURS;
+	pub const CheckpointInterval: BlockNumber = HOURS;
+	// a week of hourly checkpoints
+	pub const MaxCheckpoints: u32 = 168;
+	pub const MaxQueuedWithdrawals: u32 = 64;
+	pub MinimumQueuedWithdrawal: Balance = CurrencyId::unit::<Balance>();
+}
+
+impl vault::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type CurrencyFactory = CurrencyFactory;
+	type AssetId = CurrencyId;
+	type NativeCurrency = Balances;
+	type Currency = AssetsTransactorRouter;
+	type VaultId = u64;
+	type WeightInfo = vault::weights::SubstrateWeight<Runtime>;
+	type Convert = ConvertInto;
+	type MaxStrategies = MaxStrategies;
+	type MinimumDeposit = VaultMinimumDeposit;
+	type MinimumWithdrawal = VaultMinimumWithdrawal;
+	type CreationDeposit = VaultCreationDeposit;
+	type ExistentialDeposit = NativeExistentialDeposit;
+	type TombstoneDuration = TombstoneDuration;
+	type RentPerBlock = VaultRentPerBlock;
+	type PalletId = VaultPalletId;
+	type Strategies = ();
+	type RebalanceInterval = RebalanceInterval;
+	type UnixTime = Timestamp;
+	type CheckpointInterval = CheckpointInterval;
+	type MaxCheckpoints = MaxCheckpoints;
+	type MaxQueuedWithdrawals = MaxQueuedWithdrawals;
+	type MinimumQueuedWithdrawal = MinimumQueuedWithdrawal;
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -850,6 +928,8 @@ construct_runtime!(
 		AssetsTransactorRouter: assets_transactor_router = 61,
 		FarmingRewards: reward::<Instance1> = 62,
 		Farming: farming = 63,
+		Fnft: fnft = 64,
+		Vault: vault = 65,
 
 		CallFilter: call_filter = 100,
 
@@ -922,6 +1002,7 @@ mod benches {
 		[pablo, Pablo]
 		[democracy, Democracy]
 		[oracle, Oracle]
+		[vault, Vault]
 		[pallet_ibc, Ibc]
 	);
 }
@@ -955,6 +1036,47 @@ cumulus_pallet_parachain_system::register_validate_block!(
 	CheckInherents = CheckInherents,
 );
 
+fn wrap_fee(
+	fee: Fee<CurrencyId, Balance>,
+) -> Fee<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>> {
+	Fee {
+		fee: SafeRpcWrapper(fee.fee),
+		lp_fee: SafeRpcWrapper(fee.lp_fee),
+		owner_fee: SafeRpcWrapper(fee.owner_fee),
+		protocol_fee: SafeRpcWrapper(fee.protocol_fee),
+		asset_id: SafeRpcWrapper(fee.asset_id),
+	}
+}
+
+fn wrap_swap_simulation(
+	simulation: SwapSimulation<CurrencyId, Balance>,
+) -> SwapSimulation<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>> {
+	let wrap_asset_amount = |asset: AssetAmount<CurrencyId, Balance>| {
+		AssetAmount::new(SafeRpcWrapper(asset.asset_id), SafeRpcWrapper(asset.amount))
+	};
+	SwapSimulation {
+		amount_in: wrap_asset_amount(simulation.amount_in),
+		amount_out: wrap_asset_amount(simulation.amount_out),
+		fee: wrap_fee(simulation.fee),
+		price_impact: simulation.price_impact,
+	}
+}
+
+fn wrap_liquidity_simulation(
+	simulation: LiquiditySimulation<CurrencyId, Balance>,
+) -> LiquiditySimulation<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>> {
+	LiquiditySimulation {
+		assets: simulation
+			.assets
+			.into_iter()
+			.map(|(asset_id, amount)| (SafeRpcWrapper(asset_id), SafeRpcWrapper(amount)))
+			.collect(),
+		lp_amount: SafeRpcWrapper(simulation.lp_amount),
+		fee: simulation.fee.map(wrap_fee),
+		price_impact: simulation.price_impact,
+	}
+}
+
 impl_runtime_apis! {
 	impl assets_runtime_api::AssetsRuntimeApi<Block, CurrencyId, AccountId, Balance, ForeignAssetId> for Runtime {
 		fn balance_of(SafeRpcWrapper(asset_id): SafeRpcWrapper<CurrencyId>, account_id: AccountId) -> SafeRpcWrapper<Balance> /* Balance */ {
@@ -1031,15 +1153,13 @@ impl_runtime_apis! {
 			who: SafeRpcWrapper<AccountId>,
 			pool_id: SafeRpcWrapper<PoolId>,
 			amounts: BTreeMap<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>,
-		) -> SafeRpcWrapper<Balance> {
-			SafeRpcWrapper(
-				<Pablo as Amm>::simulate_add_liquidity(
-					&who.0,
-					pool_id.0,
-					amounts.iter().map(|(k, v)| (k.0, v.0)).collect(),
-				)
-				.unwrap_or_else(|_| Zero::zero())
+		) -> Result<LiquiditySimulation<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>, DispatchError> {
+			pablo::simulate_add_liquidity::<Runtime>(
+				&who.0,
+				pool_id.0,
+				amounts.iter().map(|(k, v)| (k.0, v.0)).collect(),
 			)
+			.map(wrap_liquidity_simulation)
 		}
 
 		fn simulate_remove_liquidity(
@@ -1047,23 +1167,163 @@ impl_runtime_apis! {
 			pool_id: SafeRpcWrapper<PoolId>,
 			lp_amount: SafeRpcWrapper<Balance>,
 			min_expected_amounts: BTreeMap<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>,
-		) -> BTreeMap<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>> {
-			<Pablo as Amm>::simulate_remove_liquidity(
+		) -> Result<LiquiditySimulation<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>, DispatchError> {
+			pablo::simulate_remove_liquidity::<Runtime>(
 				&who.0,
 				pool_id.0,
 				lp_amount.0,
-				min_expected_amounts
-					.iter()
-					.map(|(k, v)| (k.0, v.0))
-					.collect()
-				)
-				.map(|simulation_result| {
-					simulation_result
-						.into_iter()
-						.map(|(k, v)| (SafeRpcWrapper(k), SafeRpcWrapper(v)))
-						.collect()
+				min_expected_amounts.iter().map(|(k, v)| (k.0, v.0)).collect(),
+			)
+			.map(wrap_liquidity_simulation)
+		}
+
+		fn simulate_swap(
+			pool_id: SafeRpcWrapper<PoolId>,
+			in_asset: AssetAmount<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>,
+			out_asset_id: SafeRpcWrapper<CurrencyId>,
+		) -> Result<SwapSimulation<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>, DispatchError> {
+			pablo::simulate_swap::<Runtime>(
+				pool_id.0,
+				AssetAmount::new(in_asset.asset_id.0, in_asset.amount.0),
+				out_asset_id.0,
+			)
+			.map(wrap_swap_simulation)
+		}
+
+		fn simulate_buy(
+			pool_id: SafeRpcWrapper<PoolId>,
+			in_asset_id: SafeRpcWrapper<CurrencyId>,
+			out_asset: AssetAmount<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>,
+		) -> Result<SwapSimulation<SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>, DispatchError> {
+			pablo::simulate_buy::<Runtime>(
+				pool_id.0,
+				in_asset_id.0,
+				AssetAmount::new(out_asset.asset_id.0, out_asset.amount.0),
+			)
+			.map(wrap_swap_simulation)
+		}
+
+		fn twap(
+			pool_id: SafeRpcWrapper<PoolId>,
+			base_asset_id: SafeRpcWrapper<CurrencyId>,
+			quote_asset_id: SafeRpcWrapper<CurrencyId>,
+			window_seconds: SafeRpcWrapper<u64>,
+		) -> Option<SafeRpcWrapper<Rate>> {
+			<Pablo as Amm>::twap_over_window(
+				pool_id.0,
+				base_asset_id.0,
+				quote_asset_id.0,
+				window_seconds.0,
+			)
+			.ok()
+			.map(SafeRpcWrapper)
+		}
+	}
+
+	impl oracle_runtime_api::OracleRuntimeApi<Block, CurrencyId, AccountId, BlockNumber, Balance, Moment> for Runtime {
+		fn get_price(
+			asset_id: CurrencyId,
+			amount: Balance,
+		) -> Result<Price<SafeRpcWrapper<Balance>, BlockNumber>, DispatchError> {
+			<Oracle as composable_traits::oracle::Oracle>::get_price(asset_id, amount)
+				.map(|Price { price, block }| Price { price: SafeRpcWrapper(price), block })
+		}
+
+		fn get_twap(asset_id: CurrencyId, amount: Balance) -> Result<SafeRpcWrapper<Balance>, DispatchError> {
+			<Oracle as composable_traits::oracle::Oracle>::get_twap_for_amount(asset_id, amount)
+				.map(SafeRpcWrapper)
+		}
+
+		fn price_history(asset_id: CurrencyId) -> Vec<Price<SafeRpcWrapper<Balance>, BlockNumber>> {
+			Oracle::price_history(asset_id)
+				.into_iter()
+				.map(|Price { price, block }| Price { price: SafeRpcWrapper(price), block })
+				.collect()
+		}
+
+		fn pre_prices(asset_id: CurrencyId) -> Vec<SubmittedPrice<AccountId, SafeRpcWrapper<Balance>, BlockNumber>> {
+			Oracle::submitted_prices(asset_id)
+				.into_iter()
+				.map(|SubmittedPrice { who, price, block }| SubmittedPrice { who, price: SafeRpcWrapper(price), block })
+				.collect()
+		}
+
+		fn assets_info() -> Vec<OracleAssetInfo<SafeRpcWrapper<CurrencyId>, BlockNumber, SafeRpcWrapper<Balance>>> {
+			Oracle::assets_info()
+				.into_iter()
+				.map(|info| OracleAssetInfo {
+					asset_id: SafeRpcWrapper(info.asset_id),
+					threshold: info.threshold,
+					min_answers: info.min_answers,
+					max_answers: info.max_answers,
+					block_interval: info.block_interval,
+					reward_weight: SafeRpcWrapper(info.reward_weight),
+					slash: SafeRpcWrapper(info.slash),
+					emit_price_changes: info.emit_price_changes,
+				})
+				.collect()
+		}
+
+		fn signer_stats(signer: AccountId) -> Option<SignerStats<AccountId, SafeRpcWrapper<Balance>, BlockNumber>> {
+			Oracle::signer_stats(signer).map(|stats| SignerStats {
+				signer: stats.signer,
+				controller: stats.controller,
+				stake: SafeRpcWrapper(stats.stake),
+				answer_in_transit: SafeRpcWrapper(stats.answer_in_transit),
+				declared_withdraw: stats.declared_withdraw.map(|(stake, unlock_block)| (SafeRpcWrapper(stake), unlock_block)),
+				total_rewarded: SafeRpcWrapper(stats.total_rewarded),
+				total_slashed: SafeRpcWrapper(stats.total_slashed),
+			})
+		}
+
+		fn reward_tracker() -> Option<RewardTracker<SafeRpcWrapper<Balance>, Moment>> {
+			Oracle::reward_tracker_store().map(|tracker| RewardTracker {
+				period: tracker.period,
+				start: tracker.start,
+				total_already_rewarded: SafeRpcWrapper(tracker.total_already_rewarded),
+				current_block_reward: SafeRpcWrapper(tracker.current_block_reward),
+				total_reward_weight: SafeRpcWrapper(tracker.total_reward_weight),
+			})
+		}
+	}
+
+	impl vault_runtime_api::VaultRuntimeApi<Block, u64, AccountId, Balance, BlockNumber> for Runtime {
+		fn share_price(vault_id: u64) -> Result<SafeRpcWrapper<Rate>, DispatchError> {
+			<Vault as composable_traits::vault::Vault>::stock_dilution_rate(&vault_id)
+				.map(SafeRpcWrapper)
+		}
+
+		fn share_price_history(vault_id: u64) -> Vec<SharePriceCheckpoint<BlockNumber, SafeRpcWrapper<Rate>>> {
+			Vault::share_price_checkpoints(vault_id)
+				.into_iter()
+				.map(|SharePriceCheckpoint { block, timestamp, price }| SharePriceCheckpoint {
+					block,
+					timestamp,
+					price: SafeRpcWrapper(price),
 				})
-				.unwrap_or_default()
+				.collect()
+		}
+
+		fn realized_apy(vault_id: u64, window: DurationSeconds) -> Result<SafeRpcWrapper<FixedI128>, DispatchError> {
+			Vault::realized_apy(&vault_id, window).map(SafeRpcWrapper)
+		}
+
+		fn strategies(
+			vault_id: u64,
+		) -> Result<Vec<StrategyReport<AccountId, SafeRpcWrapper<Balance>, SafeRpcWrapper<FixedI128>>>, DispatchError> {
+			Vault::strategy_reports(&vault_id).map(|reports| {
+				reports
+					.into_iter()
+					.map(|report| StrategyReport {
+						strategy: report.strategy,
+						allocation: report.allocation,
+						balance: SafeRpcWrapper(report.balance),
+						lifetime_withdrawn: SafeRpcWrapper(report.lifetime_withdrawn),
+						lifetime_deposited: SafeRpcWrapper(report.lifetime_deposited),
+						return_rate: report.return_rate.map(SafeRpcWrapper),
+					})
+					.collect()
+			})
 		}
 	}
 
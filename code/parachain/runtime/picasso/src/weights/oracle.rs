@@ -201,4 +201,69 @@ impl<T: frame_system::Config> oracle::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(4))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	/// Storage: Pablo Pools (r:1 w:0)
+	/// Proof Skipped: Pablo Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Oracle PriceSources (r:0 w:1)
+	/// Proof: Oracle PriceSources (max_values: None, max_size: Some(180), added: 2655, mode: MaxEncodedLen)
+	/// The range of component `p` is `[0, 4]`.
+	fn set_price_source(p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + p * (97 ±0)`
+		//  Estimated: `0 + p * (2572 ±0)`
+		// Minimum execution time: 21_314 nanoseconds.
+		Weight::from_ref_time(22_068_412)
+			// Standard Error: 9_861
+			.saturating_add(Weight::from_ref_time(3_118_244).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(Weight::from_proof_size(2572).saturating_mul(p.into()))
+	}
+	/// Storage: Oracle AssetsInfo (r:1 w:0)
+	/// Proof: Oracle AssetsInfo (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: Oracle UpdateTriggers (r:0 w:1)
+	/// Proof: Oracle UpdateTriggers (max_values: None, max_size: Some(29), added: 2504, mode: MaxEncodedLen)
+	fn set_update_trigger() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `2553`
+		// Minimum execution time: 19_207 nanoseconds.
+		Weight::from_ref_time(19_874_000)
+			.saturating_add(Weight::from_proof_size(2553))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Oracle SignerToController (r:26 w:0)
+	/// Proof: Oracle SignerToController (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: Oracle AssetsInfo (r:1 w:0)
+	/// Proof: Oracle AssetsInfo (max_values: None, max_size: Some(78), added: 2553, mode: MaxEncodedLen)
+	/// Storage: Oracle Prices (r:1 w:1)
+	/// Proof: Oracle Prices (max_values: None, max_size: Some(52), added: 2527, mode: MaxEncodedLen)
+	/// Storage: Oracle OracleStake (r:25 w:0)
+	/// Proof: Oracle OracleStake (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: Oracle AnswerInTransit (r:25 w:25)
+	/// Proof: Oracle AnswerInTransit (max_values: None, max_size: Some(64), added: 2539, mode: MaxEncodedLen)
+	/// Storage: Oracle UpdateTriggers (r:1 w:0)
+	/// Proof: Oracle UpdateTriggers (max_values: None, max_size: Some(29), added: 2504, mode: MaxEncodedLen)
+	/// Storage: Oracle PrePrices (r:1 w:1)
+	/// Proof: Oracle PrePrices (max_values: None, max_size: Some(2113), added: 4588, mode: MaxEncodedLen)
+	/// Storage: Oracle PriceHistory (r:1 w:1)
+	/// Proof: Oracle PriceHistory (max_values: None, max_size: Some(433), added: 2908, mode: MaxEncodedLen)
+	/// Storage: Oracle RewardTrackerStore (r:1 w:0)
+	/// Proof: Oracle RewardTrackerStore (max_values: Some(1), max_size: Some(64), added: 559, mode: MaxEncodedLen)
+	/// The range of component `p` is `[1, 25]`.
+	fn submit_report(p: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `414 + p * (166 ±0)`
+		//  Estimated: `15593 + p * (7633 ±0)`
+		// Minimum execution time: 112_402 nanoseconds.
+		Weight::from_ref_time(58_113_517)
+			.saturating_add(Weight::from_proof_size(15593))
+			// Standard Error: 41_202
+			.saturating_add(Weight::from_ref_time(68_476_920).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+			.saturating_add(Weight::from_proof_size(7633).saturating_mul(p.into()))
+	}
 }
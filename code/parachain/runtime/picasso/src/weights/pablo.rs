@@ -154,4 +154,132 @@ impl<T: frame_system::Config> pablo::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(2))
 	}
+	/// Storage: Pablo Pools (r:1 w:0)
+	/// Proof: Pablo Pools (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: AssetsRegistry LocalToForeign (r:2 w:0)
+	/// Proof: AssetsRegistry LocalToForeign (max_values: None, max_size: Some(2081), added: 4556, mode: MaxEncodedLen)
+	/// Storage: Tokens TotalIssuance (r:1 w:1)
+	/// Proof: Tokens TotalIssuance (max_values: None, max_size: Some(40), added: 2515, mode: MaxEncodedLen)
+	/// Storage: Tokens Accounts (r:4 w:4)
+	/// Proof: Tokens Accounts (max_values: None, max_size: Some(120), added: 2595, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Pablo PriceCumulativeState (r:1 w:0)
+	/// Proof Skipped: Pablo PriceCumulativeState (max_values: None, max_size: None, mode: Measured)
+	fn remove_liquidity_single_asset() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1738`
+		//  Estimated: `33988`
+		// Minimum execution time: 161_942 nanoseconds.
+		Weight::from_ref_time(167_355_000)
+			.saturating_add(Weight::from_proof_size(33988))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: Pablo Pools (r:1 w:0)
+	/// Proof: Pablo Pools (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Pablo ConcentratedLiquidityStates (r:1 w:1)
+	/// Proof: Pablo ConcentratedLiquidityStates (max_values: None, max_size: Some(17464), added: 19939, mode: MaxEncodedLen)
+	/// Storage: Tokens Accounts (r:4 w:4)
+	/// Proof: Tokens Accounts (max_values: None, max_size: Some(120), added: 2595, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Fnft FinancialNftId (r:1 w:1)
+	/// Proof Skipped: Fnft FinancialNftId (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Fnft Instance (r:1 w:1)
+	/// Proof Skipped: Fnft Instance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Fnft Collection (r:1 w:0)
+	/// Proof Skipped: Fnft Collection (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Fnft OwnerInstances (r:1 w:1)
+	/// Proof Skipped: Fnft OwnerInstances (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Proxy Proxies (r:1 w:1)
+	/// Proof: Proxy Proxies (max_values: None, max_size: Some(845), added: 3320, mode: MaxEncodedLen)
+	/// Storage: Pablo Positions (r:0 w:1)
+	/// Proof: Pablo Positions (max_values: None, max_size: Some(124), added: 2599, mode: MaxEncodedLen)
+	fn create_position() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2214`
+		//  Estimated: `54163`
+		// Minimum execution time: 238_513 nanoseconds.
+		Weight::from_ref_time(245_072_000)
+			.saturating_add(Weight::from_proof_size(54163))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
+	/// Storage: Fnft Instance (r:1 w:1)
+	/// Proof Skipped: Fnft Instance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Pablo Positions (r:1 w:1)
+	/// Proof: Pablo Positions (max_values: None, max_size: Some(124), added: 2599, mode: MaxEncodedLen)
+	/// Storage: Pablo Pools (r:1 w:0)
+	/// Proof: Pablo Pools (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Pablo ConcentratedLiquidityStates (r:1 w:1)
+	/// Proof: Pablo ConcentratedLiquidityStates (max_values: None, max_size: Some(17464), added: 19939, mode: MaxEncodedLen)
+	/// Storage: Tokens Accounts (r:4 w:4)
+	/// Proof: Tokens Accounts (max_values: None, max_size: Some(120), added: 2595, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Fnft OwnerInstances (r:1 w:1)
+	/// Proof Skipped: Fnft OwnerInstances (max_values: None, max_size: None, mode: Measured)
+	fn remove_position_liquidity() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2610`
+		//  Estimated: `49817`
+		// Minimum execution time: 201_337 nanoseconds.
+		Weight::from_ref_time(208_460_000)
+			.saturating_add(Weight::from_proof_size(49817))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
+	/// Storage: Fnft Instance (r:1 w:0)
+	/// Proof Skipped: Fnft Instance (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Pablo Positions (r:1 w:1)
+	/// Proof: Pablo Positions (max_values: None, max_size: Some(124), added: 2599, mode: MaxEncodedLen)
+	/// Storage: Pablo Pools (r:1 w:0)
+	/// Proof: Pablo Pools (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Pablo ConcentratedLiquidityStates (r:1 w:0)
+	/// Proof: Pablo ConcentratedLiquidityStates (max_values: None, max_size: Some(17464), added: 19939, mode: MaxEncodedLen)
+	/// Storage: Tokens Accounts (r:4 w:4)
+	/// Proof: Tokens Accounts (max_values: None, max_size: Some(120), added: 2595, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn collect_position_fees() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `2541`
+		//  Estimated: `44311`
+		// Minimum execution time: 148_904 nanoseconds.
+		Weight::from_ref_time(153_216_000)
+			.saturating_add(Weight::from_proof_size(44311))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: Pablo Pools (r:1 w:1)
+	/// Proof: Pablo Pools (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	fn update_fee_config() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1153`
+		//  Estimated: `2609`
+		// Minimum execution time: 31_874 nanoseconds.
+		Weight::from_ref_time(32_651_000)
+			.saturating_add(Weight::from_proof_size(2609))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Pablo Pools (r:1 w:0)
+	/// Proof: Pablo Pools (max_values: None, max_size: Some(134), added: 2609, mode: MaxEncodedLen)
+	/// Storage: Pablo OwnerFees (r:3 w:2)
+	/// Proof: Pablo OwnerFees (max_values: None, max_size: Some(80), added: 2555, mode: MaxEncodedLen)
+	/// Storage: Tokens Accounts (r:4 w:4)
+	/// Proof: Tokens Accounts (max_values: None, max_size: Some(120), added: 2595, mode: MaxEncodedLen)
+	/// Storage: System Account (r:1 w:0)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn claim_fees() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1842`
+		//  Estimated: `23977`
+		// Minimum execution time: 96_137 nanoseconds.
+		Weight::from_ref_time(99_402_000)
+			.saturating_add(Weight::from_proof_size(23977))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
 }
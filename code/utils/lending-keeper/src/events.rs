@@ -0,0 +1,46 @@
+//! Events of the lending pallet the keeper keeps its index of positions up to date with.
+
+use scale_codec::Decode;
+use subxt::{events::StaticEvent, utils::AccountId32};
+
+/// Market ids are SCALE encoded as their inner `u32`.
+pub type MarketId = u32;
+pub type Balance = u128;
+
+#[derive(Decode, Debug)]
+pub struct Borrowed {
+	pub sender: AccountId32,
+	pub market_id: MarketId,
+	pub amount: Balance,
+}
+
+impl StaticEvent for Borrowed {
+	const PALLET: &'static str = "Lending";
+	const EVENT: &'static str = "Borrowed";
+}
+
+#[derive(Decode, Debug)]
+pub struct BorrowRepaid {
+	pub sender: AccountId32,
+	pub market_id: MarketId,
+	pub beneficiary: AccountId32,
+	pub amount: Balance,
+}
+
+impl StaticEvent for BorrowRepaid {
+	const PALLET: &'static str = "Lending";
+	const EVENT: &'static str = "BorrowRepaid";
+}
+
+#[derive(Decode, Debug)]
+pub struct PositionLiquidated {
+	pub market_id: MarketId,
+	pub account: AccountId32,
+	pub repaid_amount: Balance,
+	pub seized_collateral: Balance,
+}
+
+impl StaticEvent for PositionLiquidated {
+	const PALLET: &'static str = "Lending";
+	const EVENT: &'static str = "PositionLiquidated";
+}
@@ -0,0 +1,198 @@
+use crate::{
+	events::{BorrowRepaid, Borrowed, MarketId, PositionLiquidated},
+	positions::{batches, Positions},
+};
+use futures::StreamExt;
+use scale_codec::{Decode, Encode};
+use std::collections::BTreeMap;
+use subxt::{
+	dynamic::Value,
+	ext::sp_core::{sr25519, Bytes},
+	rpc::rpc_params,
+	tx::PairSigner,
+	utils::AccountId32,
+	OnlineClient, SubstrateConfig,
+};
+
+type Hash = <SubstrateConfig as subxt::Config>::Hash;
+
+/// Storage key of `Lending::DebtIndex`: prefix, then `Twox64Concat` hashed market id and account.
+type DebtIndexKey = ([u8; 32], [u8; 8], MarketId, [u8; 8], AccountId32);
+
+const STORAGE_PAGE_SIZE: u32 = 1000;
+
+/// Number of finalized blocks after which a liquidation still unhealthy is submitted again.
+const RESUBMIT_AFTER_BLOCKS: u64 = 10;
+
+/// Watches lending positions and liquidates the unhealthy ones.
+pub struct Keeper {
+	api: OnlineClient<SubstrateConfig>,
+	signer: PairSigner<SubstrateConfig, sr25519::Pair>,
+	batch_size: usize,
+	positions: Positions,
+	/// Finalized blocks seen so far.
+	blocks: u64,
+	/// Block at which the liquidation of a position was last submitted.
+	submitted: BTreeMap<(MarketId, AccountId32), u64>,
+}
+
+impl Keeper {
+	pub async fn new(
+		composable_node_url: String,
+		liquidator: sr25519::Pair,
+	) -> Result<Self, subxt::Error> {
+		let api = OnlineClient::<SubstrateConfig>::from_url(composable_node_url).await?;
+		let max_batch_size = api
+			.constants()
+			.at(&subxt::dynamic::constant("Lending", "MaxLiquidationBatchSize"))?;
+		let batch_size = u32::decode(&mut max_batch_size.encoded())? as usize;
+		Ok(Self {
+			api,
+			signer: PairSigner::new(liquidator),
+			batch_size,
+			positions: <_>::default(),
+			blocks: 0,
+			submitted: <_>::default(),
+		})
+	}
+
+	/// Indexes all positions having debt, then liquidates unhealthy positions on each finalized
+	/// block, keeping the index up to date with the events of the block.
+	pub async fn run(mut self) -> Result<(), subxt::Error> {
+		self.index_positions().await?;
+		let mut block_sub = self.api.blocks().subscribe_finalized().await?;
+		while let Some(block) = block_sub.next().await {
+			let block = block?;
+			self.blocks += 1;
+			let events = block.events().await?;
+			for event in events.iter() {
+				let event = event?;
+				if let Some(Borrowed { sender, market_id, .. }) = event.as_event::<Borrowed>()? {
+					self.positions.insert(market_id, sender);
+				} else if let Some(BorrowRepaid { market_id, beneficiary, .. }) =
+					event.as_event::<BorrowRepaid>()?
+				{
+					self.refresh_position(block.hash(), market_id, beneficiary).await?;
+				} else if let Some(PositionLiquidated { market_id, account, .. }) =
+					event.as_event::<PositionLiquidated>()?
+				{
+					self.refresh_position(block.hash(), market_id, account).await?;
+				}
+			}
+			self.liquidate_unhealthy(block.hash()).await?;
+		}
+		Ok(())
+	}
+
+	/// Builds the index from `Lending::DebtIndex`, which has an entry for each position with debt.
+	async fn index_positions(&mut self) -> Result<(), subxt::Error> {
+		let debt_index = subxt::dynamic::storage_root("Lending", "DebtIndex");
+		let mut iter =
+			self.api.storage().at(None).await?.iter(debt_index, STORAGE_PAGE_SIZE).await?;
+		while let Some((key, _)) = iter.next().await? {
+			let (_, _, market_id, _, account) = DebtIndexKey::decode(&mut key.0.as_ref())?;
+			self.positions.insert(market_id, account);
+		}
+		log::info!("indexed {} positions", self.positions.len());
+		Ok(())
+	}
+
+	/// Drops the position from the index once it has no debt left.
+	async fn refresh_position(
+		&mut self,
+		at: Hash,
+		market_id: MarketId,
+		account: AccountId32,
+	) -> Result<(), subxt::Error> {
+		let debt_index = subxt::dynamic::storage(
+			"Lending",
+			"DebtIndex",
+			vec![market_id_value(market_id), account_value(&account)],
+		);
+		self.submitted.remove(&(market_id, account.clone()));
+		if self.api.storage().at(Some(at)).await?.fetch(&debt_index).await?.is_none() {
+			self.positions.remove(market_id, &account);
+		}
+		Ok(())
+	}
+
+	/// Checks all indexed positions at once with the `unhealthy_positions` runtime API.
+	async fn unhealthy_positions(
+		&self,
+		at: Hash,
+	) -> Result<Vec<(MarketId, AccountId32)>, subxt::Error> {
+		let positions = self
+			.positions
+			.iter()
+			.map(|(market_id, account)| (market_id, account.clone()))
+			.collect::<Vec<_>>();
+		if positions.is_empty() {
+			return Ok(positions)
+		}
+		let unhealthy = self
+			.api
+			.rpc()
+			.request::<Bytes>(
+				"state_call",
+				rpc_params!["LendingRuntimeApi_unhealthy_positions", Bytes(positions.encode()), at],
+			)
+			.await?;
+		Ok(Vec::decode(&mut unhealthy.0.as_ref())?)
+	}
+
+	async fn liquidate_unhealthy(&mut self, at: Hash) -> Result<(), subxt::Error> {
+		let mut unhealthy = BTreeMap::<MarketId, Vec<AccountId32>>::new();
+		for (market_id, account) in self.unhealthy_positions(at).await? {
+			let pending = self
+				.submitted
+				.get(&(market_id, account.clone()))
+				.map_or(false, |block| self.blocks.saturating_sub(*block) < RESUBMIT_AFTER_BLOCKS);
+			if !pending {
+				unhealthy.entry(market_id).or_default().push(account);
+			}
+		}
+
+		for (market_id, borrowers) in batches(unhealthy, self.batch_size) {
+			log::info!("liquidating {} positions of market {:?}", borrowers.len(), market_id);
+			let tx = subxt::dynamic::tx(
+				"Lending",
+				"liquidate",
+				vec![
+					("market_id", market_id_value(market_id)),
+					(
+						"borrowers",
+						Value::unnamed_composite(vec![Value::unnamed_composite(
+							borrowers.iter().map(account_value),
+						)]),
+					),
+				],
+			);
+			// batches are not awaited in blocks, positions still unhealthy once
+			// `RESUBMIT_AFTER_BLOCKS` passed are submitted again
+			match self.api.tx().sign_and_submit_default(&tx, &self.signer).await {
+				Ok(hash) => {
+					log::info!("submitted liquidation {:?} of market {:?}", hash, market_id);
+					for account in borrowers {
+						self.submitted.insert((market_id, account), self.blocks);
+					}
+				},
+				Err(error) => log::error!(
+					"liquidation submission failed, market_id: {:?}, error: {:?}",
+					market_id,
+					error
+				),
+			}
+		}
+		Ok(())
+	}
+}
+
+/// `MarketId` is a newtype around `u32`.
+fn market_id_value(market_id: MarketId) -> Value {
+	Value::unnamed_composite(vec![Value::u128(market_id.into())])
+}
+
+/// `AccountId32` is a newtype around `[u8; 32]`.
+fn account_value(account: &AccountId32) -> Value {
+	Value::unnamed_composite(vec![Value::from_bytes(account.0)])
+}
@@ -0,0 +1,35 @@
+mod events;
+mod keeper;
+mod opts;
+mod positions;
+
+use crate::{keeper::Keeper, opts::Opts};
+use clap::Parser;
+use subxt::ext::sp_core::{sr25519, Pair};
+
+#[tokio::main]
+async fn main() {
+	env_logger::init();
+
+	let opts = Opts::parse();
+
+	let liquidator = sr25519::Pair::from_string(&opts.liquidator_suri, None)
+		.map_err(|e| {
+			log::error!("invalid liquidator secret uri: {:?}", e);
+			std::process::exit(1);
+		})
+		.unwrap();
+
+	let keeper = Keeper::new(opts.composable_node, liquidator)
+		.await
+		.map_err(|e| {
+			log::error!("{:?}", e);
+			std::process::exit(1);
+		})
+		.unwrap();
+
+	if let Err(e) = keeper.run().await {
+		log::error!("{:?}", e);
+		std::process::exit(1);
+	}
+}
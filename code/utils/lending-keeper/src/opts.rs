@@ -0,0 +1,13 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[clap(version = "1.0", author = "Composable")]
+pub struct Opts {
+	/// Host address of the composable node.
+	#[clap(long, default_value = "ws://127.0.0.1:9988")]
+	pub composable_node: String,
+
+	/// Secret URI of the account signing `liquidate` calls.
+	#[clap(long, default_value = "//Alice")]
+	pub liquidator_suri: String,
+}
@@ -0,0 +1,85 @@
+use crate::events::MarketId;
+use std::collections::{BTreeMap, BTreeSet};
+use subxt::utils::AccountId32;
+
+/// Accounts having debt, indexed by the market they borrowed from.
+#[derive(Default, Debug)]
+pub struct Positions {
+	borrowers: BTreeMap<MarketId, BTreeSet<AccountId32>>,
+}
+
+impl Positions {
+	pub fn insert(&mut self, market_id: MarketId, account: AccountId32) {
+		self.borrowers.entry(market_id).or_default().insert(account);
+	}
+
+	pub fn remove(&mut self, market_id: MarketId, account: &AccountId32) {
+		if let Some(accounts) = self.borrowers.get_mut(&market_id) {
+			accounts.remove(account);
+			if accounts.is_empty() {
+				self.borrowers.remove(&market_id);
+			}
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.borrowers.values().map(BTreeSet::len).sum()
+	}
+
+	/// All indexed positions, market by market.
+	pub fn iter(&self) -> impl Iterator<Item = (MarketId, &AccountId32)> {
+		self.borrowers.iter().flat_map(|(market_id, accounts)| {
+			accounts.iter().map(move |account| (*market_id, account))
+		})
+	}
+}
+
+/// Splits the accounts to liquidate of each market into batches of at most `batch_size` accounts.
+pub fn batches(
+	unhealthy: BTreeMap<MarketId, Vec<AccountId32>>,
+	batch_size: usize,
+) -> Vec<(MarketId, Vec<AccountId32>)> {
+	unhealthy
+		.into_iter()
+		.flat_map(|(market_id, accounts)| {
+			accounts
+				.chunks(batch_size.max(1))
+				.map(|batch| (market_id, batch.to_vec()))
+				.collect::<Vec<_>>()
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn account(seed: u8) -> AccountId32 {
+		AccountId32([seed; 32])
+	}
+
+	#[test]
+	fn positions_are_indexed_by_market() {
+		let mut positions = Positions::default();
+		positions.insert(1, account(1));
+		positions.insert(1, account(2));
+		positions.insert(1, account(1));
+		positions.insert(2, account(1));
+		assert_eq!(positions.len(), 3);
+
+		positions.remove(1, &account(1));
+		positions.remove(2, &account(1));
+		positions.remove(3, &account(1));
+		assert_eq!(positions.iter().collect::<Vec<_>>(), vec![(1, &account(2))]);
+	}
+
+	#[test]
+	fn batches_do_not_exceed_batch_size() {
+		let unhealthy =
+			BTreeMap::from([(1, vec![account(1), account(2), account(3)]), (2, vec![account(4)])]);
+		assert_eq!(
+			batches(unhealthy, 2),
+			vec![(1, vec![account(1), account(2)]), (1, vec![account(3)]), (2, vec![account(4)]),]
+		);
+	}
+}